@@ -0,0 +1,952 @@
+//! Proof-of concept implementation of a graph executor component that is executed in a topological order.
+//! The graph is represented as a directed acyclic graph (DAG) where each node is executed once and the edges
+//! represent the order of execution. The goal of this component is the efficient splitting of the computations
+//! associated with each node onto multiple CPU cores using multiple threads and processes with the help of
+//! shared memory and cross-process synchronisation.
+
+use anyhow::anyhow;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use graph_executor_core::{
+    graph_structure::{
+        graph::DirectedAcyclicGraph, graph_diff::GraphDiff, graph_limits::GraphLimits,
+        order_spec::OrderSpec, parse_mode::ParseMode, template,
+    },
+    scheduler::{
+        CriticalPathScheduler, FifoScheduler, PriorityScheduler, RandomScheduler, Scheduler,
+    },
+};
+use graph_executor_shm::shared_memory::{lock_audit, semaphore::SyncBackend};
+#[cfg(feature = "dbus")]
+use graph_executor_shm::shared_memory_graph_execution::control_bus::{
+    ControlBus, CONTROL_BUS_NAME,
+};
+use graph_executor_shm::shared_memory_graph_execution::{
+    artifact_store::open_artifact_store,
+    bottleneck::{recorded_durations, BottleneckReport},
+    control_socket::{ControlServer, CONTROL_METHODS},
+    environment::EnvironmentManifest,
+    execution_options::ExecutionOptions,
+    execution_report::RunFailed,
+    executor::{ExecutionMode, Executor},
+    external_events,
+    fault_injection::FaultPlan,
+    graph_cat::{self, CatFormat, GraphFilter},
+    lifecycle::{LifecycleEvent, LifecycleListener},
+    mode_check::{check_execution_modes, ModeCheckOptions},
+    preemption::PreemptionPolicy,
+    run_manifest::retry_failed,
+    run_status::RunStatus,
+    shell::RunShell,
+    shm_dump::ShmDump,
+    simulation::simulate,
+    systemd::SystemdUnits,
+    watch_run::{watch_run, DEFAULT_DEBOUNCE},
+    watchdog::{teardown, watch, WatchdogOptions},
+    worker_table::WorkerTable,
+};
+#[cfg(feature = "sqlite")]
+use graph_executor_shm::shared_memory_graph_execution::{
+    flakiness::{QuarantineReport, DEFAULT_FAILURE_RATE_THRESHOLD, DEFAULT_QUARANTINE_RETRIES},
+    sqlite_export,
+};
+use std::{
+    collections::BTreeMap, io::IsTerminal, ops::ControlFlow, process::exit, str::FromStr,
+    sync::Arc, time::Duration,
+};
+
+/// Executes a graph with the processes of this binary sharing it via POSIX shared memory, or inspects, controls and
+/// supervises its runs.
+///
+/// Without a command, the arguments are those of `run`, e.g.
+/// `graph-executor ./resources/example-printed-dot-digraph.dot test_filename_suffix`.
+#[derive(Debug, Parser)]
+#[command(
+    name = "graph-executor",
+    version,
+    args_conflicts_with_subcommands = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    run: Option<RunArgs>,
+    /// Mechanism of the semaphores created by any command, which all processes of a run should share
+    #[arg(long, global = true, value_name = "semaphore|futex", value_parser = SyncBackend::from_str)]
+    sync: Option<SyncBackend>,
+    /// Validate the shared memory lock under real workloads, aborting with a diagnostic dump on a violation
+    #[arg(long, global = true)]
+    lock_audit: bool,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Execute a graph as a worker of the run with the filename suffix, together with all other workers of the run
+    Run(Box<RunArgs>),
+    /// Print the status of a run in shared memory
+    Status { filename_suffix: String },
+    /// Print the workers attached to a run in shared memory
+    Workers { filename_suffix: String },
+    /// Print the raw shared memory of a run, e.g. when it fails to deserialize
+    ShmDump { filename_suffix: String },
+    /// Print the lifecycle events of a run as they are published, until the run completed
+    Events { filename_suffix: String },
+    /// Simulate the execution of a graph with a scheduling policy, assuming every node takes the same time
+    Simulate {
+        digraph_file: String,
+        worker_count: usize,
+        #[arg(value_enum, default_value_t = SimulationScheduler::Fifo)]
+        scheduler: SimulationScheduler,
+    },
+    /// Print the nodes whose attempts failed too often across the runs exported into a SQLite database
+    Quarantine {
+        database: String,
+        failure_rate_threshold: Option<f64>,
+    },
+    /// Check that a graph file parses into a valid DAG, printing its size and any structural anti-patterns
+    Validate {
+        digraph_file: String,
+        #[command(flatten)]
+        limits: LimitArgs,
+    },
+    /// Print structural anti-patterns of a graph
+    Lint {
+        digraph_file: String,
+        /// Print the anti-patterns as YAML for other tools
+        #[arg(long)]
+        yaml: bool,
+        #[command(flatten)]
+        limits: LimitArgs,
+    },
+    /// Render the nodes of a graph file or live run selected by filters as DOT, annotated with their status
+    Viz(CatArgs),
+    /// Print the nodes and edges added, removed or changed between two graph files, exiting with 1 if there are any
+    Diff {
+        before: String,
+        after: String,
+        #[arg(long, value_enum, default_value_t = DiffFormat::Human)]
+        format: DiffFormat,
+        #[arg(long, value_enum, default_value_t = Color::Auto)]
+        color: Color,
+    },
+    /// Print the nodes of a graph file or live run selected by filters, as a table or DOT
+    Cat {
+        #[command(flatten)]
+        args: CatArgs,
+        #[arg(long, value_name = "table|dot", default_value = "table", value_parser = CatFormat::from_str)]
+        format: CatFormat,
+    },
+    /// Supervise a run from a separate process until it ends
+    Watchdog {
+        filename_suffix: String,
+        /// Time without a heartbeat after which a worker is detached
+        #[arg(long, value_name = "SECONDS", value_parser = seconds)]
+        stale_after: Option<Duration>,
+        /// Command started in place of a detached worker
+        #[arg(long, value_name = "COMMAND")]
+        restart: Option<String>,
+        #[arg(long, value_name = "COUNT")]
+        max_restarts: Option<usize>,
+    },
+    /// Remove the shared memory and semaphores of a run, e.g. after stopping all of its workers
+    #[command(visible_alias = "clean")]
+    Teardown { filename_suffix: String },
+    /// Inspect and change a live run interactively, e.g. to debug a stuck run
+    Shell { filename_suffix: String },
+    /// Notify the iceoryx2 event service of an external event, releasing the nodes awaiting it in every run
+    /// listening for it
+    Signal { event: String },
+    /// Serve the local control API of runs on a Unix domain socket, until the process is killed
+    Serve {
+        #[cfg_attr(feature = "dbus", arg(required_unless_present = "dbus"))]
+        #[cfg_attr(not(feature = "dbus"), arg(required = true))]
+        socket_path: Option<std::path::PathBuf>,
+        /// Serve the control API on the session bus instead
+        #[cfg(feature = "dbus")]
+        #[arg(long, conflicts_with = "socket_path")]
+        dbus: bool,
+    },
+    /// Resume a run which finished with failures, executing only its failed nodes again
+    RetryFailed {
+        /// Filename suffix of the run or its run manifest
+        source: String,
+        #[arg(long, value_name = "COUNT", default_value_t = 1)]
+        workers: usize,
+        #[arg(short, long)]
+        keep_going: bool,
+    },
+    /// Re-execute the nodes whose input files changed (and their descendants) until interrupted
+    WatchRun { digraph_file: String },
+    /// Write systemd units running a fleet of workers and a watchdog for a run
+    Systemd {
+        digraph_file: String,
+        filename_suffix: String,
+        worker_count: usize,
+        /// Directory the unit files are written to
+        #[arg(long, value_name = "DIRECTORY", default_value = ".")]
+        output: std::path::PathBuf,
+        /// Further arguments of the workers, e.g. `--keep-going`
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        worker_args: Vec<String>,
+    },
+    /// Execute a graph sequentially, in threads and in processes of this binary and compare the executions
+    CheckModes {
+        digraph_file: String,
+        #[arg(long, value_name = "COUNT")]
+        workers: Option<usize>,
+        /// Node whose results may differ between the executions
+        #[arg(long, value_name = "NODE")]
+        nondeterministic: Vec<String>,
+    },
+}
+
+/// Arguments of running a graph, with or without the `run` command.
+#[derive(Debug, Args)]
+struct RunArgs {
+    /// DOT, workflow, Makefile or build.ninja file of the graph
+    digraph_file: String,
+    /// Suffix of the names of the run's shared memory objects, shared by all of its workers
+    filename_suffix: String,
+    /// Ignore edges referencing nodes which are not declared
+    #[arg(long)]
+    lenient: bool,
+    /// Preempt running command nodes for higher-priority ones, which implies claiming nodes by priority
+    #[arg(long, value_name = "stop|terminate", value_parser = PreemptionPolicy::from_str)]
+    preemption: Option<PreemptionPolicy>,
+    /// Export the run into a SQLite database
+    #[arg(long, value_name = "DATABASE")]
+    sqlite: Option<String>,
+    /// Cap the nodes executing at the same time across all processes of the run, like build tools
+    #[arg(short, long)]
+    jobs: Option<usize>,
+    /// Keep executing unaffected branches after a failure
+    #[arg(short, long)]
+    keep_going: bool,
+    /// Wall-clock limit of the run, after which its remaining nodes are cancelled
+    #[arg(long, value_name = "SECONDS", value_parser = seconds)]
+    timeout: Option<Duration>,
+    /// Time without a heartbeat after which idle workers requeue the node of a dead worker, unless a watchdog does
+    #[arg(long, value_name = "SECONDS|never", value_parser = reclaim_after)]
+    reclaim_after: Option<ReclaimAfter>,
+    /// Seeded faults injected by this worker for testing the run's recovery, e.g. `seed=42,crash=0.1`
+    #[arg(long, value_name = "PLAN", value_parser = FaultPlan::from_str)]
+    faults: Option<FaultPlan>,
+    /// Retry nodes which failed too often in the runs exported into a SQLite database
+    #[arg(long, value_name = "DATABASE")]
+    retry_flaky: Option<String>,
+    /// Print the nodes which delayed the run most
+    #[arg(long)]
+    bottlenecks: bool,
+    /// Ordering assertions checked against the attempts recorded by the run
+    #[arg(long, value_name = "FILE")]
+    expect_order: Option<String>,
+    /// Whether violated ordering assertions fail the run or only print a warning
+    #[arg(long, value_enum, default_value_t = OrderViolations::Fail)]
+    order_violations: OrderViolations,
+    /// Environment variable whose value is recorded in the run's manifest
+    #[arg(long, value_name = "VARIABLE")]
+    env: Vec<String>,
+    /// Tool version probe whose output is recorded in the run's manifest
+    #[arg(long, value_name = "NAME=COMMAND", value_parser = probe)]
+    probe: Vec<(String, String)>,
+    /// Manifest of the run's environment, written by its last worker
+    #[arg(long, value_name = "FILE")]
+    manifest: Option<String>,
+    /// Refuse running in an environment which differs from the previous run recorded in the manifest
+    #[arg(long, requires = "manifest")]
+    require_clean_env: bool,
+    /// Append-only log of all changes to the run, set by the worker creating it
+    #[arg(long, value_name = "FILE")]
+    audit_log: Option<String>,
+    /// Final state of the run written by its last worker, from which `retry-failed` resumes it
+    #[arg(long, value_name = "FILE")]
+    run_manifest: Option<String>,
+    /// Tarball for issues about the run, written by its last worker if the run failed
+    #[arg(long, value_name = "FILE")]
+    failure_bundle: Option<String>,
+    /// Variable substituted for `${NAME}` in DOT and workflow files
+    #[arg(long, value_name = "NAME=VALUE")]
+    set: Vec<String>,
+    /// Interval after which the priority of waiting nodes is boosted by 1, which implies claiming nodes by priority
+    #[arg(long, value_name = "SECONDS", value_parser = seconds)]
+    priority_aging: Option<Duration>,
+    /// Isolated working directories of command nodes, below the temporary directory unless a root is given
+    #[arg(long, value_name = "ROOT", require_equals = true)]
+    workdirs: Option<Option<std::path::PathBuf>>,
+    /// Keep working directories after successful attempts as well, which implies `--workdirs`
+    #[arg(long)]
+    keep_workdirs: bool,
+    /// Directory below which the standard output and error of command nodes are captured per node
+    #[arg(long, value_name = "DIRECTORY")]
+    output_dir: Option<std::path::PathBuf>,
+    /// Shared memory budget of the run on this worker, result blobs exceeding it are spilled to disk
+    #[arg(long, value_name = "BYTES")]
+    max_shm: Option<usize>,
+    /// Directory spilled result blobs are written to
+    #[arg(long, value_name = "DIRECTORY")]
+    spill_dir: Option<std::path::PathBuf>,
+    /// Cap on the bytes the run writes to disk, exceeding it cancels and fails the run
+    #[arg(long, value_name = "BYTES")]
+    max_disk: Option<usize>,
+    /// Lock the pages of the run's graph into RAM, so that page faults don't add jitter to claims and updates
+    #[arg(long)]
+    mlock: bool,
+    /// Results of nodes shared between runs on any host, read instead of executing nodes whose inputs didn't change
+    #[arg(long, value_name = "DIRECTORY|s3://BUCKET/PREFIX")]
+    remote_cache: Option<String>,
+    // The limits are not flattened from `LimitArgs`, which would keep `Cli` from detecting arguments of `run` without
+    // a command
+    /// Reject graphs with more nodes
+    #[arg(long, value_name = "COUNT")]
+    max_nodes: Option<usize>,
+    /// Reject graphs with more edges
+    #[arg(long, value_name = "COUNT")]
+    max_edges: Option<usize>,
+    /// Reject graphs whose serialization takes more bytes
+    #[arg(long, value_name = "BYTES")]
+    max_graph_bytes: Option<usize>,
+}
+
+/// Filters of the nodes printed by `cat` and `viz`.
+#[derive(Debug, Args)]
+struct CatArgs {
+    /// Graph file or filename suffix of a live run
+    source: String,
+    /// Print only nodes with the status
+    #[arg(long)]
+    status: Vec<String>,
+    /// Print only the node and its descendants
+    #[arg(long, value_name = "NODE")]
+    subtree: Option<String>,
+    /// Print only nodes without parents
+    #[arg(long)]
+    roots_only: bool,
+    /// Replace identifiers, commands and attributes by placeholders, e.g. for sharing the graph in an issue
+    #[arg(long)]
+    anonymize: bool,
+    #[command(flatten)]
+    limits: LimitArgs,
+}
+
+/// Limits of the size of graphs loaded from DOT and workflow files, defaulting to [`GraphLimits::default`].
+#[derive(Debug, Args)]
+struct LimitArgs {
+    /// Reject graphs with more nodes
+    #[arg(long, value_name = "COUNT")]
+    max_nodes: Option<usize>,
+    /// Reject graphs with more edges
+    #[arg(long, value_name = "COUNT")]
+    max_edges: Option<usize>,
+    /// Reject graphs whose serialization takes more bytes
+    #[arg(long, value_name = "BYTES")]
+    max_graph_bytes: Option<usize>,
+}
+
+impl LimitArgs {
+    /// Get the default [`GraphLimits`] with the limits given on the command line.
+    fn graph_limits(&self) -> GraphLimits {
+        let default = GraphLimits::default();
+        GraphLimits {
+            max_nodes: self.max_nodes.unwrap_or(default.max_nodes),
+            max_edges: self.max_edges.unwrap_or(default.max_edges),
+            max_serialized_size: self.max_graph_bytes.unwrap_or(default.max_serialized_size),
+        }
+    }
+}
+
+/// Scheduling policies of `simulate`.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum SimulationScheduler {
+    Fifo,
+    CriticalPath,
+    Random,
+}
+
+/// Formats of `diff`.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum DiffFormat {
+    Human,
+    Json,
+}
+
+/// Whether `diff` colors its output.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Color {
+    /// Color the output if it is a terminal
+    Auto,
+    Always,
+    Never,
+}
+
+/// Whether violated ordering assertions fail a run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum OrderViolations {
+    Fail,
+    Warn,
+}
+
+/// Time after which idle workers requeue the node of a dead worker, [`None`] if they never do.
+#[derive(Clone, Copy, Debug)]
+struct ReclaimAfter(Option<Duration>);
+
+/// Parses a duration given in (fractional) seconds.
+fn seconds(seconds: &str) -> anyhow::Result<Duration> {
+    let parsed = seconds
+        .parse::<f64>()
+        .map_err(|e| anyhow!("Invalid seconds {:?}: {}", seconds, e))?;
+    Duration::try_from_secs_f64(parsed).map_err(|e| anyhow!("Invalid seconds {:?}: {}", seconds, e))
+}
+
+/// Parses the seconds of `--reclaim-after` or `never`.
+fn reclaim_after(value: &str) -> anyhow::Result<ReclaimAfter> {
+    match value {
+        "never" => Ok(ReclaimAfter(None)),
+        value => seconds(value).map(|duration| ReclaimAfter(Some(duration))),
+    }
+}
+
+/// Parses a tool version probe like `<name>=<command>`.
+fn probe(probe: &str) -> anyhow::Result<(String, String)> {
+    match probe.split_once('=') {
+        Some((name, command)) => Ok((name.to_string(), command.to_string())),
+        None => Err(anyhow!(
+            "Invalid probe {:?}: must look like <name>=<command>.",
+            probe
+        )),
+    }
+}
+
+/// Main function.
+#[cfg(target_family = "unix")]
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    if let Some(backend) = cli.sync {
+        backend.set_process_default().map_err(|e| anyhow!(e))?;
+    }
+    if cli.lock_audit {
+        lock_audit::set_enabled(true);
+    }
+    let command = match (cli.command, cli.run) {
+        (Some(command), _) => command,
+        (None, Some(run)) => Command::Run(Box::new(run)),
+        (None, None) => {
+            Cli::command().print_help()?;
+            exit(2);
+        }
+    };
+
+    match command {
+        Command::Run(args) => run(*args),
+        Command::Status { filename_suffix } => {
+            print!("{}", RunStatus::read(&filename_suffix)?);
+            Ok(())
+        }
+        Command::Workers { filename_suffix } => {
+            print!("{}", WorkerTable::read(&filename_suffix)?);
+            Ok(())
+        }
+        Command::ShmDump { filename_suffix } => {
+            print!("{}", ShmDump::read(&filename_suffix)?);
+            Ok(())
+        }
+        Command::Events { filename_suffix } => {
+            let mut listener = LifecycleListener::subscribe(&filename_suffix)?;
+            let graph = graph_cat::read_graph(&filename_suffix, GraphLimits::default())?;
+            loop {
+                let Some(event) = listener.next_event(Duration::from_secs(1))? else {
+                    continue;
+                };
+                println!("{}", event.describe(&graph));
+                if let LifecycleEvent::RunCompleted { failed } = event {
+                    exit(failed as i32);
+                }
+            }
+        }
+        #[cfg(feature = "dbus")]
+        Command::Serve { dbus: true, .. } => {
+            let _service = ControlBus::register(ExecutionOptions::default())?;
+            println!(
+                "Serving control API as {} on the session bus, methods:\n{}",
+                CONTROL_BUS_NAME, CONTROL_METHODS
+            );
+            loop {
+                std::thread::park();
+            }
+        }
+        Command::Serve { socket_path, .. } => {
+            let socket_path = socket_path.ok_or(anyhow!("Missing socket path."))?;
+            let server = ControlServer::bind(&socket_path, ExecutionOptions::default())?;
+            println!(
+                "Serving control API on {}, methods:\n{}",
+                socket_path.display(),
+                CONTROL_METHODS
+            );
+            server.serve()
+        }
+        Command::Simulate {
+            digraph_file,
+            worker_count,
+            scheduler,
+        } => {
+            let graph = DirectedAcyclicGraph::from_file(&digraph_file)?;
+            let scheduler: Box<dyn Scheduler> = match scheduler {
+                SimulationScheduler::Fifo => Box::new(FifoScheduler),
+                SimulationScheduler::CriticalPath => Box::new(CriticalPathScheduler::new(&graph)),
+                SimulationScheduler::Random => Box::new(RandomScheduler::default()),
+            };
+            print!(
+                "{}",
+                simulate(&graph, &BTreeMap::new(), worker_count, scheduler.as_ref())?
+            );
+            Ok(())
+        }
+        Command::Watchdog {
+            filename_suffix,
+            stale_after,
+            restart,
+            max_restarts,
+        } => {
+            let mut options = WatchdogOptions {
+                restart_command: restart,
+                ..Default::default()
+            };
+            if let Some(stale_after) = stale_after {
+                options.stale_after = stale_after;
+            }
+            if let Some(max_restarts) = max_restarts {
+                options.max_restarts = max_restarts;
+            }
+            println!("{}", watch(&filename_suffix, &options)?);
+            Ok(())
+        }
+        Command::WatchRun { digraph_file } => {
+            let mut graph = DirectedAcyclicGraph::from_file(&digraph_file)?;
+            let filename_suffix = format!("watch-run-{}", std::process::id());
+            watch_run(
+                &mut graph,
+                &filename_suffix,
+                &ExecutionOptions {
+                    graph_source: Some(digraph_file),
+                    ..Default::default()
+                },
+                DEFAULT_DEBOUNCE,
+                |_, result| {
+                    match result {
+                        Ok(report) => println!("{}", report),
+                        Err(e) => eprintln!("{}", e),
+                    }
+                    println!("Watching for changes of node inputs...");
+                    ControlFlow::Continue(())
+                },
+            )
+        }
+        Command::Teardown { filename_suffix } => teardown(&filename_suffix),
+        Command::Shell { filename_suffix } => {
+            let mut shell = RunShell::attach(&filename_suffix)?;
+            println!(
+                "Attached to run {}, type help for a list of commands.",
+                filename_suffix
+            );
+            shell.run(std::io::stdin().lock(), std::io::stdout())?;
+            Ok(())
+        }
+        Command::Signal { event } => {
+            let listeners = external_events::notify(&event)?;
+            println!(
+                "Event {} notified, listening workers: {}.",
+                event, listeners
+            );
+            Ok(())
+        }
+        Command::RetryFailed {
+            source,
+            workers,
+            keep_going,
+        } => {
+            let options = ExecutionOptions {
+                keep_going,
+                ..Default::default()
+            };
+            println!("{}", retry_failed(&source, workers, &options)?);
+            Ok(())
+        }
+        Command::CheckModes {
+            digraph_file,
+            workers,
+            nondeterministic,
+        } => {
+            let graph = DirectedAcyclicGraph::from_file(&digraph_file)?;
+            let mut options = ModeCheckOptions {
+                worker_command: Some(vec![std::env::current_exe()?.display().to_string()]),
+                nondeterministic_nodes: nondeterministic.into_iter().collect(),
+                ..Default::default()
+            };
+            if let Some(workers) = workers {
+                options.workers = workers;
+            }
+            let filename_suffix = format!("check-modes-{}", std::process::id());
+            let report = check_execution_modes(&graph, &filename_suffix, &options)?;
+            println!("{}", report);
+            if !report.is_consistent() {
+                exit(1);
+            }
+            Ok(())
+        }
+        Command::Systemd {
+            digraph_file,
+            filename_suffix,
+            worker_count,
+            output,
+            worker_args,
+        } => {
+            let digraph_file = std::fs::canonicalize(&digraph_file)
+                .map_err(|e| anyhow!("Failed resolving {}: {}", digraph_file, e))?;
+            let units = SystemdUnits {
+                binary: std::env::current_exe()?.display().to_string(),
+                digraph_file: digraph_file.display().to_string(),
+                filename_suffix,
+                worker_count,
+                worker_args,
+                watchdog_sec: Duration::from_secs(30),
+            };
+            for (file_name, content) in units.unit_files() {
+                let path = output.join(file_name);
+                std::fs::write(&path, content)
+                    .map_err(|e| anyhow!("Failed writing {}: {}", path.display(), e))?;
+                println!("{}", path.display());
+            }
+            Ok(())
+        }
+        Command::Validate {
+            digraph_file,
+            limits,
+        } => {
+            let graph = DirectedAcyclicGraph::from_file_with_limits(
+                &digraph_file,
+                ParseMode::Strict,
+                limits.graph_limits(),
+            )?;
+            for warning in graph.lint() {
+                println!("{}", warning);
+            }
+            println!(
+                "{} is a valid graph with {} nodes.",
+                digraph_file,
+                graph.get_node_indices().count()
+            );
+            Ok(())
+        }
+        Command::Lint {
+            digraph_file,
+            yaml,
+            limits,
+        } => {
+            let warnings = DirectedAcyclicGraph::from_file_with_limits(
+                &digraph_file,
+                ParseMode::Strict,
+                limits.graph_limits(),
+            )?
+            .lint();
+            match yaml {
+                true => print!("{}", serde_yaml::to_string(&warnings)?),
+                false => {
+                    for warning in &warnings {
+                        println!("{}", warning);
+                    }
+                }
+            }
+            Ok(())
+        }
+        Command::Diff {
+            before,
+            after,
+            format,
+            color,
+        } => {
+            let diff = GraphDiff::between(
+                &DirectedAcyclicGraph::from_file(&before)?,
+                &DirectedAcyclicGraph::from_file(&after)?,
+            );
+            let color = match color {
+                Color::Auto => std::io::stdout().is_terminal(),
+                Color::Always => true,
+                Color::Never => false,
+            };
+            match format {
+                DiffFormat::Human => print!("{}", diff.render(color)),
+                DiffFormat::Json => print!("{}", diff.to_json()),
+            }
+            if !diff.is_empty() {
+                exit(1);
+            }
+            Ok(())
+        }
+        Command::Cat { args, format } => cat(args, format),
+        Command::Viz(args) => cat(args, CatFormat::Dot),
+        Command::Quarantine {
+            database,
+            failure_rate_threshold,
+        } => {
+            #[cfg(feature = "sqlite")]
+            {
+                let attempt_outcomes =
+                    sqlite_export::attempt_outcomes(std::path::Path::new(&database))?;
+                print!(
+                    "{}",
+                    QuarantineReport::new(
+                        attempt_outcomes,
+                        failure_rate_threshold.unwrap_or(DEFAULT_FAILURE_RATE_THRESHOLD)
+                    )
+                );
+                Ok(())
+            }
+            #[cfg(not(feature = "sqlite"))]
+            {
+                let _ = (database, failure_rate_threshold);
+                Err(anyhow!(
+                    "Reading runs from SQLite requires building with `--features sqlite`."
+                ))
+            }
+        }
+    }
+}
+
+/// Prints the nodes of a graph file or live run selected by the filters of `args` in `format`.
+fn cat(args: CatArgs, format: CatFormat) -> anyhow::Result<()> {
+    let mut filter = GraphFilter {
+        subtree: args.subtree,
+        roots_only: args.roots_only,
+        ..Default::default()
+    };
+    for status in &args.status {
+        filter = filter.with_status(status)?;
+    }
+    print!(
+        "{}",
+        graph_cat::cat(
+            &graph_cat::read_graph(&args.source, args.limits.graph_limits())?,
+            &filter,
+            format,
+            args.anonymize
+        )?
+    );
+    Ok(())
+}
+
+/// Executes a graph as a worker of its run.
+fn run(args: RunArgs) -> anyhow::Result<()> {
+    let RunArgs {
+        digraph_file,
+        filename_suffix,
+        ..
+    } = &args;
+    let parse_mode = match args.lenient {
+        true => ParseMode::Lenient,
+        false => ParseMode::Strict,
+    };
+    let sqlite_database = args.sqlite.as_deref();
+    let flakiness_database = args.retry_flaky.as_deref();
+    if (sqlite_database.is_some() || flakiness_database.is_some()) && !cfg!(feature = "sqlite") {
+        return Err(anyhow!(
+            "Exporting runs to SQLite requires building with `--features sqlite`."
+        ));
+    }
+    let preemption = args.preemption.unwrap_or(PreemptionPolicy::Disabled);
+    let reclaim_after = match args.reclaim_after {
+        Some(ReclaimAfter(reclaim_after)) => reclaim_after,
+        None => Some(WatchdogOptions::default().stale_after),
+    };
+    // Ordering assertions checked against the attempts recorded by the run, failing it (or only warning) if violated
+    let order_spec = match &args.expect_order {
+        Some(file) => Some(OrderSpec::from_str(
+            &std::fs::read_to_string(file)
+                .map_err(|e| anyhow!("Failed reading order specification {}: {}", file, e))?,
+        )?),
+        None => None,
+    };
+    let manifest_file = args.manifest.as_deref();
+    // Kept after successful attempts with `--keep-workdirs` (which implies `--workdirs`), after failed ones always
+    let workdir_root = match &args.workdirs {
+        Some(root) => Some(root.clone().unwrap_or_else(std::env::temp_dir)),
+        None => args.keep_workdirs.then(std::env::temp_dir),
+    };
+    let remote_cache = args
+        .remote_cache
+        .as_deref()
+        .map(open_artifact_store)
+        .transpose()?;
+    let scheduler: Option<Arc<dyn Scheduler>> = match (preemption, args.priority_aging) {
+        (PreemptionPolicy::Disabled, None) => None,
+        (_, None) => Some(Arc::new(PriorityScheduler::default())),
+        (_, Some(interval)) => Some(Arc::new(PriorityScheduler::default().with_aging(interval))),
+    };
+    let template_variables = template::parse_variables(args.set.iter().map(String::as_str))?;
+
+    // Read digraph from file and execute it
+    // Build descriptions and workflows are imported as graphs of command nodes
+    let file_name = std::path::Path::new(digraph_file)
+        .file_name()
+        .and_then(|file_name| file_name.to_str())
+        .unwrap_or_default();
+    let mut graph = match file_name {
+        "Makefile" | "makefile" | "GNUmakefile" => {
+            DirectedAcyclicGraph::from_makefile(digraph_file)?
+        }
+        file_name if file_name.ends_with(".mk") => {
+            DirectedAcyclicGraph::from_makefile(digraph_file)?
+        }
+        file_name if file_name.ends_with(".ninja") => {
+            DirectedAcyclicGraph::from_ninja(digraph_file)?
+        }
+        // DOT files, Argo Workflows and Airflow DAGs with the files they include, rendered with the variables
+        _ => DirectedAcyclicGraph::from_pipeline_file_with_limits(
+            digraph_file,
+            parse_mode,
+            &template_variables,
+            LimitArgs {
+                max_nodes: args.max_nodes,
+                max_edges: args.max_edges,
+                max_graph_bytes: args.max_graph_bytes,
+            }
+            .graph_limits(),
+        )?,
+    };
+    // Retry nodes which failed too often in the runs exported into a SQLite database
+    #[cfg(feature = "sqlite")]
+    if let Some(database) = flakiness_database {
+        let report = QuarantineReport::new(
+            sqlite_export::attempt_outcomes(std::path::Path::new(database))?,
+            DEFAULT_FAILURE_RATE_THRESHOLD,
+        );
+        let raised = report.raise_retries(&mut graph, DEFAULT_QUARANTINE_RETRIES);
+        if !raised.is_empty() {
+            println!(
+                "Retrying quarantined nodes up to {} times: {}",
+                DEFAULT_QUARANTINE_RETRIES,
+                raised.join(", ")
+            );
+        }
+    }
+    if let Some(order_spec) = &order_spec {
+        order_spec.validate(&graph)?;
+    }
+    // Refuse running in an environment which differs from the previous run recorded in the manifest
+    let environment_manifest = match (
+        !args.env.is_empty() || !args.probe.is_empty(),
+        manifest_file,
+    ) {
+        (false, None) => None,
+        _ => Some(EnvironmentManifest::capture(
+            &graph,
+            &args.env,
+            &args.probe,
+        )?),
+    };
+    if let (Some(environment), Some(manifest_file), true) =
+        (&environment_manifest, manifest_file, args.require_clean_env)
+    {
+        if std::path::Path::new(manifest_file).exists() {
+            let previous: EnvironmentManifest =
+                serde_yaml::from_str(&std::fs::read_to_string(manifest_file)?)?;
+            let differences = environment.differences(&previous);
+            if !differences.is_empty() {
+                return Err(anyhow!(
+                    "Environment differs from the run recorded in {}:\n    {}",
+                    manifest_file,
+                    differences.join("\n    ")
+                ));
+            }
+        }
+    }
+    let executor = Executor {
+        mode: ExecutionMode::MultiProcessShm {
+            prefix: filename_suffix.clone(),
+        },
+        options: ExecutionOptions {
+            graph_source: Some(digraph_file.clone()),
+            scheduler,
+            preemption,
+            jobs: args.jobs,
+            keep_going: args.keep_going,
+            timeout: args.timeout,
+            reclaim_after,
+            faults: args.faults.clone(),
+            environment_manifest,
+            audit_log: args.audit_log.clone(),
+            run_manifest: args.run_manifest.clone(),
+            failure_bundle: args.failure_bundle.clone(),
+            workdir_root,
+            keep_workdirs: args.keep_workdirs,
+            output_directory: args.output_dir.clone(),
+            max_shm_bytes: args.max_shm,
+            spill_directory: args.spill_dir.clone(),
+            max_disk_bytes: args.max_disk,
+            lock_memory: args.mlock,
+            remote_cache,
+            ..Default::default()
+        },
+    };
+    // Failed runs are reported along with their failure, which is returned once they were summarized and exported
+    let (report, failure) = match executor.run(&mut graph) {
+        Ok(report) => (report, None),
+        Err(error) => match error.downcast::<RunFailed>() {
+            Ok(RunFailed { report, error }) => (*report, Some(error)),
+            Err(error) => return Err(error),
+        },
+    };
+
+    // The last worker leaving the run summarizes and exports it
+    if report.is_last_worker {
+        println!("{}", report);
+    }
+    if let (Some(manifest_file), Some(environment), true) = (
+        manifest_file,
+        &report.header.environment,
+        report.is_last_worker,
+    ) {
+        std::fs::write(manifest_file, serde_yaml::to_string(environment)?)
+            .map_err(|e| anyhow!("Failed writing manifest {}: {}", manifest_file, e))?;
+    }
+    if args.bottlenecks && report.is_last_worker {
+        print!(
+            "{}",
+            BottleneckReport::new(&graph, &recorded_durations(&graph))
+        );
+    }
+    #[cfg(feature = "sqlite")]
+    if let (Some(database), true) = (sqlite_database, report.is_last_worker) {
+        let run_id = sqlite_export::export_run(
+            std::path::Path::new(database),
+            filename_suffix,
+            &graph,
+            &report,
+        )?;
+        println!(
+            "Exported run {} to {} as run_id {}.",
+            filename_suffix, database, run_id
+        );
+    }
+    if let Some(error) = failure {
+        return Err(error);
+    }
+
+    if let (Some(order_spec), true) = (&order_spec, report.is_last_worker) {
+        let violations = graph.check_order(order_spec)?;
+        for violation in &violations {
+            println!("Order assertion {}", violation);
+        }
+        if !violations.is_empty() && args.order_violations == OrderViolations::Fail {
+            return Err(anyhow!(
+                "Run {} violated {} order assertion(s).",
+                filename_suffix,
+                violations.len()
+            ));
+        }
+    }
+    if report.timed_out {
+        return Err(anyhow!(
+            "Run {} timed out, its remaining nodes were cancelled.",
+            filename_suffix
+        ));
+    }
+    Ok(())
+}
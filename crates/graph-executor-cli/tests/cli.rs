@@ -0,0 +1,207 @@
+use std::{
+    path::PathBuf,
+    process::{Command, Output},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Runs the `graph-executor` binary with `args`.
+fn graph_executor(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_graph-executor"))
+        .args(args)
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .unwrap()
+}
+
+/// Path of the example graph of the repository, whose nodes `a` to `d` print their identifiers.
+fn example_graph() -> String {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../resources/example-typical-dot-digraph.dot")
+        .display()
+        .to_string()
+}
+
+/// Writes `content` to a graph file of the test `name` in the temporary directory.
+fn graph_file(name: &str, content: &str) -> String {
+    let path = std::env::temp_dir().join(format!(
+        "graph-executor-cli-{}-{}.dot",
+        name,
+        std::process::id()
+    ));
+    std::fs::write(&path, content).unwrap();
+    path.display().to_string()
+}
+
+#[test]
+fn subcommands_have_help_and_validated_arguments() {
+    let help = graph_executor(&["--help"]);
+    let help = String::from_utf8_lossy(&help.stdout);
+    for command in ["run", "validate", "status", "teardown", "viz"] {
+        assert!(
+            help.lines()
+                .any(|line| line.trim_start().starts_with(command)),
+            "Help does not list {}:\n{}",
+            command,
+            help
+        );
+    }
+    let validate_help = graph_executor(&["validate", "--help"]);
+    assert!(
+        validate_help.status.success()
+            && String::from_utf8_lossy(&validate_help.stdout)
+                .contains("Usage: graph-executor validate [OPTIONS] <DIGRAPH_FILE>"),
+        "Subcommand has no usage of its own."
+    );
+
+    for args in [
+        &["validate"][..],
+        &["status", "first", "second"],
+        &["run", "graph.dot", "suffix", "--jobs=many"],
+        &["graph.dot", "suffix", "--order-violations=ignore"],
+        &["graph.dot", "suffix", "--require-clean-env"],
+        &["viz", "graph.dot", "--format=dot"],
+    ] {
+        let output = graph_executor(args);
+        assert_eq!(
+            output.status.code(),
+            Some(2),
+            "Invalid arguments {:?} are not rejected.",
+            args
+        );
+        assert!(
+            String::from_utf8_lossy(&output.stderr).contains("--help"),
+            "Invalid arguments {:?} are rejected without pointing to the usage.",
+            args
+        );
+    }
+}
+
+#[test]
+fn validate_and_viz_read_graph_files() {
+    let validated = graph_executor(&["validate", &example_graph()]);
+    assert!(
+        validated.status.success()
+            && String::from_utf8_lossy(&validated.stdout)
+                .ends_with("is a valid graph with 4 nodes.\n"),
+        "Valid graph is not validated: {:?}",
+        validated
+    );
+    let cyclic = graph_file("cyclic", "digraph {\n    a -> b\n    b -> a\n}\n");
+    assert_eq!(
+        graph_executor(&["validate", &cyclic]).status.code(),
+        Some(1),
+        "Cyclic graph is validated."
+    );
+    std::fs::remove_file(&cyclic).unwrap();
+
+    let limited = graph_executor(&["validate", &example_graph(), "--max-nodes=3"]);
+    assert!(
+        limited.status.code() == Some(1)
+            && String::from_utf8_lossy(&limited.stderr).contains("limit of 3 nodes"),
+        "Graph exceeding --max-nodes is validated: {:?}",
+        limited
+    );
+    let large = graph_file(
+        "large",
+        &format!(
+            "digraph {{\n{}}}\n",
+            (0..1_500)
+                .map(|i| format!("    n{}\n", i))
+                .collect::<String>()
+        ),
+    );
+    assert_eq!(
+        graph_executor(&["lint", &large]).status.code(),
+        Some(1),
+        "Graph exceeding the default limits is linted."
+    );
+    assert!(
+        graph_executor(&[
+            "lint",
+            &large,
+            "--max-nodes=2000",
+            "--max-graph-bytes=1000000"
+        ])
+        .status
+        .success(),
+        "Graph within raised limits is not linted."
+    );
+    std::fs::remove_file(&large).unwrap();
+    assert_eq!(
+        graph_executor(&["viz", &example_graph(), "--max-edges=1"])
+            .status
+            .code(),
+        Some(1),
+        "Graph exceeding --max-edges is rendered."
+    );
+
+    let viz = graph_executor(&["viz", &example_graph()]);
+    let dot = String::from_utf8_lossy(&viz.stdout);
+    assert!(
+        viz.status.success()
+            && dot.starts_with("digraph {")
+            && dot.contains("a -> b")
+            && dot.contains("execution_status: Executable"),
+        "Graph is not rendered as annotated DOT:\n{}",
+        dot
+    );
+}
+
+#[test]
+fn run_status_and_clean_manage_runs() {
+    // `run` and the binary without a command execute graphs alike
+    let filename_suffix = format!("test_cli_run_{}", std::process::id());
+    for args in [
+        &["run", &example_graph(), &filename_suffix][..],
+        &[&example_graph(), &filename_suffix, "-j", "2"],
+    ] {
+        let output = graph_executor(args);
+        assert!(
+            output.status.success()
+                && String::from_utf8_lossy(&output.stdout).starts_with("a\nb\nc\nd\n"),
+            "Graph is not executed by {:?}: {:?}",
+            args,
+            output
+        );
+    }
+
+    let graph = graph_file(
+        "sleep",
+        "digraph {\n    sleep [ command = \"sleep 1\" ]\n    done\n    sleep -> done\n}\n",
+    );
+    let filename_suffix = format!("test_cli_status_{}", std::process::id());
+    let mut worker = Command::new(env!("CARGO_BIN_EXE_graph-executor"))
+        .args(["run", &graph, &filename_suffix])
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let started = Instant::now();
+    let status = loop {
+        let status = graph_executor(&["status", &filename_suffix]);
+        if status.status.success() || started.elapsed() > Duration::from_secs(10) {
+            break String::from_utf8_lossy(&status.stdout).to_string();
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+    assert!(
+        status.contains("sleep") && status.contains("done"),
+        "Status of the live run is not printed:\n{}",
+        status
+    );
+    assert!(worker.wait().unwrap().success(), "Run failed.");
+    std::fs::remove_file(&graph).unwrap();
+
+    assert!(
+        !graph_executor(&["status", &filename_suffix])
+            .status
+            .success(),
+        "Status of a finished run is printed."
+    );
+    assert!(
+        graph_executor(&["clean", &filename_suffix])
+            .status
+            .success(),
+        "Cleaning up a finished run fails."
+    );
+}
@@ -1,4 +1,4 @@
-use std::{mem::size_of, slice::from_raw_parts};
+use core::{mem::size_of, slice::from_raw_parts};
 
 /// Trait for casting sized structs as bytes and vice versa.
 pub trait AsFromBytes: Sized + Clone {
@@ -10,7 +10,7 @@ pub trait AsFromBytes: Sized + Clone {
     /// Casts byte representation as the implementing struct's type.
     fn from_bytes(bytes: &[u8]) -> Self {
         // unsafe { core::mem::transmute_copy(&bytes[0]) }
-        unsafe { std::ptr::read(bytes.as_ptr() as *const Self) }
+        unsafe { core::ptr::read(bytes.as_ptr() as *const Self) }
         // unsafe { &*(bytes.as_ptr() as *const Self) }.to_owned() // used to work
     }
 }
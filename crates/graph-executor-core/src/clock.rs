@@ -0,0 +1,100 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+/// Source of time for the sleeps, timeouts and backoffs of workers, so that their timing can be driven virtually
+/// (see [`VirtualClock`]) instead of waiting in real time.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Get the current time, measured from an unspecified origin like `CLOCK_MONOTONIC`.
+    fn now(&self) -> Duration;
+
+    /// Blocks the calling thread until `duration` passed on the clock.
+    fn sleep(&self, duration: Duration);
+
+    /// Get the time passed on the clock since `earlier`, a previous result of [`Clock::now`].
+    fn elapsed(&self, earlier: Duration) -> Duration {
+        self.now().saturating_sub(earlier)
+    }
+
+    /// Checks whether time passes on the clock while the calling thread blocks on something else than the clock,
+    /// e.g. on an event of another process, so that callers know whether they may wait for it instead of sleeping.
+    fn passes_in_real_time(&self) -> bool {
+        true
+    }
+}
+
+/// Real time of `CLOCK_MONOTONIC`, shared by all processes running on the same kernel. This is the default clock.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        let mut time = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        // Like `std::time::Instant::now`, which reads the same clock, a failure is a bug of the platform
+        if unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut time) } == -1 {
+            panic!(
+                "Failed reading CLOCK_MONOTONIC: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+        Duration::new(time.tv_sec as u64, time.tv_nsec as u32)
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// Virtual time for tests, which only passes when it is slept or [advanced](VirtualClock::advance): sleeping
+/// returns right away after advancing the clock by the slept duration, so that timeouts of minutes pass in
+/// milliseconds. Clones share the same time.
+///
+/// The clock starts at the current time of the [`SystemClock`], so that timestamps taken on it remain comparable
+/// to those of real time. Processes don't share virtual time, so only workers of the same process should be driven
+/// by one clock.
+#[derive(Clone, Debug)]
+pub struct VirtualClock {
+    now: Arc<Mutex<Duration>>,
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        VirtualClock::new()
+    }
+}
+
+impl VirtualClock {
+    /// Creates a virtual clock starting at the current time of the [`SystemClock`].
+    pub fn new() -> Self {
+        VirtualClock {
+            now: Arc::new(Mutex::new(SystemClock.now())),
+        }
+    }
+
+    /// Advances the clock by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> Duration {
+        *self.now.lock().unwrap()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+        // Polling loops sleep until other threads progress, which they should get to before the next poll
+        thread::yield_now();
+    }
+
+    fn passes_in_real_time(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,1981 @@
+pub mod attempt;
+#[cfg(feature = "std")]
+pub mod attribute_spec;
+#[cfg(feature = "std")]
+pub mod build_import;
+pub mod display_options;
+#[cfg(feature = "std")]
+pub(crate) mod dot_syntax;
+pub mod duplicate_node_id;
+pub mod edge;
+pub mod execution_record;
+pub mod execution_status;
+pub mod graph;
+#[cfg(feature = "std")]
+pub mod graph_diff;
+pub mod graph_limits;
+#[cfg(feature = "std")]
+pub(crate) mod include;
+pub mod io_priority;
+pub mod isolation;
+pub mod lint;
+pub mod node;
+pub(crate) mod node_ids;
+pub mod node_progress;
+pub mod node_role;
+pub mod order_spec;
+pub mod parse_mode;
+pub mod small_dag;
+#[cfg(feature = "std")]
+pub mod template;
+#[cfg(feature = "std")]
+pub mod workflow_import;
+
+#[cfg(all(test, feature = "std", feature = "serde"))]
+mod tests {
+    use super::{
+        attempt::{Attempt, AttemptOutcome, Hook},
+        attribute_spec::NodeSpec,
+        display_options::DisplayOptions,
+        duplicate_node_id::DuplicateNodeId,
+        edge::{Dependency, Edge},
+        execution_status::ExecutionStatus,
+        graph::DirectedAcyclicGraph,
+        graph_diff::{Change, GraphDiff},
+        graph_limits::GraphLimits,
+        io_priority::IoPriority,
+        lint::{LintThresholds, LintWarning},
+        node::Node,
+        node_progress::NodeProgress,
+        node_role::NodeRole,
+        order_spec::{OrderAssertion, OrderSpec},
+        parse_mode::ParseMode,
+        small_dag::SmallDag,
+        template,
+    };
+    use crate::scheduler::GraphView;
+    use petgraph::graph::NodeIndex;
+    use std::{
+        collections::{BTreeMap, VecDeque},
+        fs::read_to_string,
+        str::FromStr,
+        time::Duration,
+    };
+
+    // `Edge` tests
+
+    #[test]
+    fn edge_compare_equality_from_str_direct_new() {
+        let edge_from_str = Edge::from_str("0 -> 1 [ ]").unwrap();
+        let edge_direct = Edge {
+            parent: String::from("0"),
+            child: String::from("1"),
+            dependency: Dependency::Completion,
+        };
+        let edge_new = Edge::new(String::from("0"), String::from("1"));
+
+        assert_eq!(
+            edge_from_str, edge_direct,
+            "`Edge::from_string()` and `Edge {{}}` initializations are not equal."
+        );
+        assert_eq!(
+            edge_from_str, edge_new,
+            "`Edge::from_string()` and `Edge::new()` initializations are not equal."
+        );
+        assert_eq!(
+            edge_direct, edge_new,
+            "`Edge {{}}` and `Edge::new()` initializations are not equal."
+        );
+    }
+
+    // `Node` tests
+
+    #[test]
+    fn node_compare_equality_from_str_new_default() {
+        let node_from_str =
+            Node::from_str("Struct Node, Node.args: , Node.executed: Executable").unwrap();
+        let node_new = Node::new(String::from(""));
+        let node_default = Node::default();
+
+        assert_eq!(
+            node_from_str, node_new,
+            "`Node::from_string()` and `Node::new()` initializations are not equal."
+        );
+        assert_eq!(
+            node_from_str, node_default,
+            "`Node::from_string()` and `Node::default()` initializations are not equal."
+        );
+        assert_eq!(
+            node_new, node_default,
+            "`Node::new()` and `Node::default()` initializations are not equal."
+        );
+    }
+
+    #[test]
+    fn node_method_execute() {
+        let mut node_executed = Node::new(String::from(""));
+        node_executed.execution_status = ExecutionStatus::Executed;
+        let mut node_executing = Node::new(String::from(""));
+        node_executing.execution_status = ExecutionStatus::Executing;
+        let node_executable = Node::new(String::from(""));
+        let mut node_non_executable = Node::new(String::from(""));
+        node_non_executable.execution_status = ExecutionStatus::NonExecutable;
+
+        let result_executed = node_executed.execute();
+        let result_executing = node_executing.execute();
+        let result_executable = node_executable.execute();
+        let result_non_executable = node_non_executable.execute();
+
+        assert_eq!(
+            result_executed.unwrap_err().to_string(),
+            String::from("Trying to execute node which has already been executed."),
+            "Wrong/no error when trying to execute node which has `ExecutionStatus::Executed`."
+        );
+        assert_eq!(
+            result_executing.unwrap(),
+            (),
+            "Unsuccessful when trying to execute node which has `ExecutionStatus::Executing`."
+        );
+        assert_eq!(
+            result_executable.unwrap_err().to_string(),
+            String::from("Trying to execute node which is not yet set for execution."),
+            "Wrong/no error when trying to execute node which has `ExecutionStatus::Executable`."
+        );
+        assert_eq!(
+            result_non_executable.unwrap_err().to_string(),
+            String::from("Trying to execute node which is not executable."),
+            "Wrong/no error when trying to execute node which has `ExecutionStatus::NonExecutable`."
+        );
+    }
+
+    // `ExecutionStatus` tests
+
+    #[test]
+    fn execution_status_compare_equality_from_str_direct() {
+        let execution_status_from_str = ExecutionStatus::from_str("Executed").unwrap();
+        let execution_status_direct = ExecutionStatus::Executed;
+
+        assert_eq!(
+            execution_status_from_str, execution_status_direct,
+            "`ExecutionStatus::from_string()` and `ExecutionStatus::Executed` initializations are not equal."
+        );
+    }
+
+    // `DirectedAcyclicGraph` tests
+
+    #[test]
+    fn dag_compare_equality_new_from_str_from_bytes() {
+        let graph_new = DirectedAcyclicGraph::new(
+            BTreeMap::from([
+                (
+                    String::from("0"),
+                    Node::new(String::from("Node 0 was just executed")),
+                ),
+                (
+                    String::from("1"),
+                    Node::new(String::from("Node 1 was just executed")),
+                ),
+                (
+                    String::from("2"),
+                    Node::new(String::from("Node 2 was just executed")),
+                ),
+                (
+                    String::from("3"),
+                    Node::new(String::from("Node 3 was just executed")),
+                ),
+            ]),
+            vec![
+                Edge::new(String::from("0"), String::from("1")),
+                Edge::new(String::from("2"), String::from("3")),
+                Edge::new(String::from("1"), String::from("3")),
+            ],
+        )
+        .unwrap();
+
+        let graph_from_str = DirectedAcyclicGraph::from_str(&format!("{}", graph_new)).unwrap();
+        let graph_from_bytes =
+            rmp_serde::from_slice::<DirectedAcyclicGraph>(&rmp_serde::to_vec(&graph_new).unwrap())
+                .unwrap();
+
+        assert_eq!(
+            graph_new, graph_from_str,
+            "`DAG::new()` and `DAG::from_str()` initializations are not equal."
+        );
+        assert_eq!(
+            graph_new, graph_from_bytes,
+            "`DAG::new()` and `DAG::from_bytes()` initializations are not equal."
+        );
+        assert_eq!(
+            graph_from_str, graph_from_bytes,
+            "`DAG::from_str()` and `DAG::from_bytes()` initializations are not equal."
+        );
+    }
+
+    #[test]
+    fn dag_parse_from_string() {
+        let dag_from_file = DirectedAcyclicGraph::from_str(
+            &read_to_string("../../resources/example-printed-dot-digraph.dot").unwrap(),
+        )
+        .unwrap();
+        let dag_initialized = DirectedAcyclicGraph::new(
+            BTreeMap::from([
+                (
+                    String::from("0"),
+                    Node::new(String::from("Node 0 was just executed")),
+                ),
+                (
+                    String::from("1"),
+                    Node::new(String::from("Node 1 was just executed")),
+                ),
+                (
+                    String::from("2"),
+                    Node::new(String::from("Node 2 was just executed")),
+                ),
+                (
+                    String::from("3"),
+                    Node::new(String::from("Node 3 was just executed")),
+                ),
+                (
+                    String::from("4"),
+                    Node::new(String::from("Node 4 was just executed")),
+                ),
+                (
+                    String::from("5"),
+                    Node::new(String::from("Node 5 was just executed")),
+                ),
+                (
+                    String::from("6"),
+                    Node::new(String::from("Node 6 was just executed")),
+                ),
+            ]),
+            vec![
+                Edge::new(String::from("0"), String::from("1")),
+                Edge::new(String::from("1"), String::from("3")),
+                Edge::new(String::from("4"), String::from("3")),
+                Edge::new(String::from("2"), String::from("4")),
+                Edge::new(String::from("6"), String::from("3")),
+                Edge::new(String::from("5"), String::from("4")),
+                Edge::new(String::from("5"), String::from("6")),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            dag_from_file, dag_initialized,
+            "DAG parsed from file and initialized manually not equal"
+        );
+
+        let dag_from_file_2 = DirectedAcyclicGraph::from_str(
+            &read_to_string("../../resources/example-typical-dot-digraph.dot").unwrap(),
+        )
+        .unwrap();
+        let dag_initialized_2 = DirectedAcyclicGraph::new(
+            BTreeMap::from([
+                (String::from("a"), Node::new("a".to_string())),
+                (String::from("b"), Node::new("b".to_string())),
+                (String::from("c"), Node::new("c".to_string())),
+                (String::from("d"), Node::new("d".to_string())),
+            ]),
+            vec![
+                Edge::new(String::from("a"), String::from("b")),
+                Edge::new(String::from("b"), String::from("c")),
+                Edge::new(String::from("b"), String::from("d")),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            dag_from_file_2, dag_initialized_2,
+            "DAG parsed from file and initialized manually not equal"
+        );
+    }
+
+    #[test]
+    fn dag_parse_quoted_unicode_identifiers_and_round_trip() {
+        let dag_from_str = DirectedAcyclicGraph::from_str(
+            "digraph {\n    \"my node\" -> \"b-c\" -> über;\n    \"say \\\"hi\\\", ok\" -> über;\n}",
+        )
+        .unwrap();
+        let dag_initialized = DirectedAcyclicGraph::new(
+            BTreeMap::from([
+                (String::from("my node"), Node::new(String::from("my node"))),
+                (String::from("b-c"), Node::new(String::from("b-c"))),
+                (String::from("über"), Node::new(String::from("über"))),
+                (
+                    String::from("say \"hi\", ok"),
+                    Node::new(String::from("say \"hi\", ok")),
+                ),
+            ]),
+            vec![
+                Edge::new(String::from("my node"), String::from("b-c")),
+                Edge::new(String::from("b-c"), String::from("über")),
+                Edge::new(String::from("say \"hi\", ok"), String::from("über")),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            dag_from_str, dag_initialized,
+            "DAG with quoted identifiers parsed from string and initialized manually not equal"
+        );
+
+        let dag_round_tripped = DirectedAcyclicGraph::from_str(&dag_from_str.to_string()).unwrap();
+        assert_eq!(
+            dag_from_str, dag_round_tripped,
+            "DAG with quoted identifiers not equal after `Display` and `DAG::from_str()` round trip."
+        );
+
+        // Edge statements with an empty attribute list declare their endpoints like any other
+        assert_eq!(
+            DirectedAcyclicGraph::from_str(
+                "digraph {\n    \"my node\" -> \"b-c\" [ ];\n    \"b-c\" -> über [ ];\n    \"say \\\"hi\\\", ok\" -> über [ ];\n}"
+            )
+            .unwrap(),
+            dag_initialized,
+            "Endpoints of edges with an empty attribute list are not declared."
+        );
+    }
+
+    #[test]
+    fn dag_display_options() {
+        let dag = DirectedAcyclicGraph::from_str(
+            "digraph {\n    \"extract data\" [ label = \"token=secret\" ]\n    \"extract data\" -> load [ dependency = streaming ]\n    load -> report\n}",
+        )
+        .unwrap();
+        assert_eq!(
+            dag.display(DisplayOptions::default()).to_string(),
+            dag.to_string(),
+            "Default display options do not print the graph like `Display`."
+        );
+
+        let display = |options: DisplayOptions| dag.display(options).to_string();
+        let labelled = display(DisplayOptions {
+            edge_labels: true,
+            ..Default::default()
+        });
+        assert!(
+            labelled.contains(
+                "\"extract data\" -> load [ label = \"streaming\" dependency = streaming ]"
+            ) && labelled.contains("load -> report [ label = \"completion\" ]"),
+            "Edges are not labelled with their dependency: {}",
+            labelled
+        );
+        assert_eq!(
+            DirectedAcyclicGraph::from_str(&labelled).unwrap(),
+            dag,
+            "Graph printed with edge labels does not parse to the same graph."
+        );
+
+        let hidden = display(DisplayOptions {
+            hide_args: true,
+            compact_ids: true,
+            ..Default::default()
+        });
+        assert!(
+            !hidden.contains("secret") && !hidden.contains("extract data"),
+            "Args or identifiers are printed: {}",
+            hidden
+        );
+        let compact = DirectedAcyclicGraph::from_str(&hidden).unwrap();
+        assert_eq!(
+            compact
+                .get_node_indices()
+                .map(|i| compact.node_id(i))
+                .collect::<Vec<_>>(),
+            ["0", "1", "2"],
+            "Nodes are not identified by their index."
+        );
+        assert_eq!(
+            compact.dependency(
+                compact.node_index("0").unwrap(),
+                compact.node_index("1").unwrap()
+            ),
+            Some(Dependency::Streaming),
+            "Edges are not kept with compact identifiers."
+        );
+    }
+
+    #[test]
+    fn dag_method_get_executable_node_indeces() {
+        let graph = DirectedAcyclicGraph::new(
+            BTreeMap::from([
+                (
+                    String::from("0"),
+                    Node::new(String::from("Node 0 was just executed")),
+                ),
+                (
+                    String::from("1"),
+                    Node::new(String::from("Node 1 was just executed")),
+                ),
+                (
+                    String::from("2"),
+                    Node::new(String::from("Node 2 was just executed")),
+                ),
+                (
+                    String::from("3"),
+                    Node::new(String::from("Node 3 was just executed")),
+                ),
+            ]),
+            vec![
+                Edge::new(String::from("0"), String::from("1")),
+                Edge::new(String::from("2"), String::from("3")),
+                Edge::new(String::from("1"), String::from("3")),
+            ],
+        )
+        .unwrap();
+
+        let executable_nodes_1 = graph.get_executable_node_indices();
+        let executable_nodes_2 = VecDeque::from(vec![NodeIndex::new(0), NodeIndex::new(2)]);
+
+        assert_eq!(
+            executable_nodes_1, executable_nodes_2,
+            "`DAG.get_executable_node_indeces()` method does not return correct node indeces."
+        );
+    }
+
+    #[test]
+    fn dag_fail_directed_cyclic_graph() {
+        let err = DirectedAcyclicGraph::new(
+            BTreeMap::from([
+                (
+                    String::from("0"),
+                    Node::new(String::from("Node 0 was just executed")),
+                ),
+                (
+                    String::from("1"),
+                    Node::new(String::from("Node 1 was just executed")),
+                ),
+            ]),
+            vec![
+                Edge::new(String::from("0"), String::from("1")),
+                Edge::new(String::from("1"), String::from("0")),
+            ],
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            format!("Cyclic graph supplied on NodeIndex(1)"),
+            "Cyclic graph is successfully created (it shouldn't be)."
+        );
+    }
+
+    #[test]
+    fn dag_strict_lenient_parse_mode_unknown_endpoints() {
+        let nodes = BTreeMap::from([
+            (String::from("0"), Node::new(String::from("Node 0"))),
+            (String::from("1"), Node::new(String::from("Node 1"))),
+        ]);
+        let edges = vec![
+            Edge::new(String::from("0"), String::from("1")),
+            Edge::new(String::from("0"), String::from("2")),
+            Edge::new(String::from("3"), String::from("1")),
+        ];
+
+        let err = DirectedAcyclicGraph::new(nodes.clone(), edges.clone()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            String::from("Edges reference nodes which are not declared: {\"2\", \"3\"}"),
+            "Strict mode does not list all unknown edge endpoints."
+        );
+
+        let graph_lenient =
+            DirectedAcyclicGraph::new_with_mode(nodes.clone(), edges, ParseMode::Lenient).unwrap();
+        let graph_without_unknown_edges =
+            DirectedAcyclicGraph::new(nodes, vec![Edge::new(String::from("0"), String::from("1"))])
+                .unwrap();
+        assert_eq!(
+            graph_lenient, graph_without_unknown_edges,
+            "Lenient mode does not drop edges with unknown endpoints."
+        );
+    }
+
+    #[test]
+    fn dag_strict_lenient_parse_mode_unknown_attributes() {
+        let dot = "digraph {\n    a [ command = \"make a\", retires = 2, shape = box ]\n    a -> b [ color = red ]\n}";
+        let err = DirectedAcyclicGraph::from_str(dot).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "DirectedAcyclicGraph::from_str parsing error: Unknown attribute retires of node a, did you mean retries?",
+            "Strict mode accepts a typo'd attribute."
+        );
+        assert!(
+            DirectedAcyclicGraph::from_str("digraph {\n    a -> b [ dependecy = streaming ]\n}")
+                .is_err(),
+            "Strict mode accepts an unknown edge attribute."
+        );
+
+        // Lenient mode ignores the unknown attribute, both accept Graphviz attributes
+        let graph = DirectedAcyclicGraph::from_str_with_mode(dot, ParseMode::Lenient).unwrap();
+        let a = graph.node_index("a").unwrap();
+        assert_eq!(graph[a].command(), Some("make a"));
+        assert_eq!(graph[a].retries(), 0);
+        assert_eq!(
+            NodeSpec::parse(
+                "a",
+                &[(String::from("retries"), String::from("2"))],
+                ParseMode::Strict
+            )
+            .unwrap()
+            .retries,
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn dag_diff_reports_changed_nodes_and_edges() {
+        let before = DirectedAcyclicGraph::from_str(
+            "digraph {\n    a [ command = \"make a\" ]\n    b\n    c\n    a -> b\n    b -> c\n}",
+        )
+        .unwrap();
+        let after = DirectedAcyclicGraph::from_str(
+            "digraph {\n    a [ command = \"make -j4 a\" ]\n    b\n    d [ retries = 2 ]\n    a -> b [ dependency = streaming ]\n    b -> d\n}",
+        )
+        .unwrap();
+        let diff = GraphDiff::between(&before, &after);
+        assert_eq!(
+            diff.nodes
+                .iter()
+                .map(|node| (node.id.as_str(), node.change))
+                .collect::<Vec<_>>(),
+            [
+                ("a", Change::Changed),
+                ("c", Change::Removed),
+                ("d", Change::Added)
+            ],
+            "Node changes are not reported."
+        );
+        assert_eq!(
+            diff.to_string(),
+            "~ node a\n    command: \"make a\" -> \"make -j4 a\"\n- node c\n    label: \"c\" -> (unset)\n    isolation: \"inline\" -> (unset)\n+ node d\n    label: (unset) -> \"d\"\n    retries: (unset) -> \"2\"\n    isolation: (unset) -> \"inline\"\n~ edge a -> b\n    dependency: \"completion\" -> \"streaming\"\n- edge b -> c\n    dependency: \"completion\" -> (unset)\n+ edge b -> d\n    dependency: (unset) -> \"completion\"\n",
+            "Diff is not printed like a unified diff."
+        );
+        assert!(
+            diff.to_json().starts_with(
+                "{\"nodes\":[{\"id\":\"a\",\"change\":\"changed\",\"attributes\":[{\"key\":\"command\",\"before\":\"make a\",\"after\":\"make -j4 a\"}]}"
+            ),
+            "Diff is not printed as JSON: {}",
+            diff.to_json()
+        );
+        assert!(GraphDiff::between(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn dag_check_order_against_recorded_attempts() {
+        let mut graph =
+            DirectedAcyclicGraph::from_str("digraph {\n    a\n    b\n    c\n    d\n}").unwrap();
+        let attempt = |started: u64, finished: u64| Attempt {
+            worker_id: 0,
+            started_after: Duration::from_secs(started),
+            finished_after: Duration::from_secs(finished),
+            outcome: AttemptOutcome::Succeeded,
+            error: None,
+            hook: None,
+        };
+        for (id, started, finished) in [("a", 0, 2), ("b", 2, 3), ("c", 1, 4), ("d", 3, 5)] {
+            let i = graph.node_index(id).unwrap();
+            graph[i].attempts = vec![attempt(started, finished)];
+        }
+
+        let spec = OrderSpec::from_str(
+            "# Domain constraints\na before b\nc before d\na and b never concurrent\n\nc and d never concurrent\n",
+        )
+        .unwrap();
+        let violations = graph.check_order(&spec).unwrap();
+        assert_eq!(
+            violations
+                .iter()
+                .map(|violation| violation.assertion.clone())
+                .collect::<Vec<_>>(),
+            vec![spec.assertions[1].clone(), spec.assertions[3].clone()],
+            "Wrong assertions are reported as violated."
+        );
+        assert_eq!(
+            violations[0].to_string(),
+            "c before d violated: d started at 3s, before c finished at 4s"
+        );
+        assert_eq!(
+            spec.assertions[3],
+            OrderAssertion::NeverConcurrent {
+                first: String::from("c"),
+                second: String::from("d")
+            }
+        );
+
+        for invalid in ["a after b", "a before e"] {
+            assert!(
+                OrderSpec::from_str(invalid)
+                    .and_then(|spec| graph.check_order(&spec))
+                    .is_err(),
+                "Invalid assertion {} is not rejected.",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn dag_fail_duplicate_node_ids() {
+        let err = DirectedAcyclicGraph::new(
+            vec![
+                (String::from("0"), Node::new(String::from("Node 0"))),
+                (String::from("1"), Node::new(String::from("Node 1"))),
+                (String::from("0"), Node::new(String::from("Node 0 again"))),
+                (String::from("1"), Node::new(String::from("Node 1 again"))),
+                (String::from("2"), Node::new(String::from("Node 2"))),
+            ],
+            vec![Edge::new(String::from("0"), String::from("1"))],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<DuplicateNodeId>().unwrap(),
+            DuplicateNodeId {
+                node_ids: vec![String::from("0"), String::from("1")]
+            },
+            "Duplicate node identifiers are not (all) detected."
+        );
+
+        // Repeated declarations in DOT are merged, later attributes taking precedence
+        let graph = DirectedAcyclicGraph::from_str(
+            "digraph {\n    a\n    a -> b\n    a [ cost = 3, retries = 1 ]\n    a [ retries = 2 ]\n}",
+        )
+        .unwrap();
+        let a = graph.node_index("a").unwrap();
+        assert_eq!(
+            (
+                graph.get_node_indices().count(),
+                graph[a].cost(),
+                graph[a].retries()
+            ),
+            (2, Some(3), 2),
+            "Node declared repeatedly in DOT string is not merged."
+        );
+    }
+
+    #[test]
+    fn dag_fail_exceeding_graph_limits() {
+        let limits = GraphLimits {
+            max_nodes: 2,
+            max_edges: 1,
+            max_serialized_size: 1024,
+        };
+        let nodes = vec![
+            (String::from("0"), Node::new(String::from("Node 0"))),
+            (String::from("1"), Node::new(String::from("Node 1"))),
+            (String::from("2"), Node::new(String::from("Node 2"))),
+        ];
+
+        let err =
+            DirectedAcyclicGraph::new_with_limits(nodes.clone(), vec![], ParseMode::Strict, limits)
+                .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            String::from("Graph exceeds the limit of 2 nodes with 3 nodes."),
+            "Graph exceeding `max_nodes` is successfully created (it shouldn't be)."
+        );
+
+        let mut graph = DirectedAcyclicGraph::new_with_limits(
+            nodes[..2].to_vec(),
+            vec![Edge::new(String::from("0"), String::from("1"))],
+            ParseMode::Strict,
+            limits,
+        )
+        .unwrap();
+        assert_eq!(
+            graph
+                .add_node(String::from("2"), Node::new(String::from("Node 2")))
+                .unwrap_err()
+                .to_string(),
+            String::from("Graph exceeds the limit of 2 nodes with 3 nodes."),
+            "Node exceeding `max_nodes` is successfully added (it shouldn't be)."
+        );
+        assert_eq!(
+            graph
+                .add_edge(Edge::new(String::from("1"), String::from("0")))
+                .unwrap_err()
+                .to_string(),
+            String::from("Cyclic graph supplied on NodeIndex(1)"),
+            "Edge introducing a cycle is successfully added (it shouldn't be)."
+        );
+    }
+
+    #[test]
+    fn dag_get_parent_child_node_indeces() {
+        let graph = DirectedAcyclicGraph::new(
+            BTreeMap::from([
+                (
+                    String::from("0"),
+                    Node::new(String::from("Node 0 was just executed")),
+                ),
+                (
+                    String::from("1"),
+                    Node::new(String::from("Node 1 was just executed")),
+                ),
+                (
+                    String::from("2"),
+                    Node::new(String::from("Node 2 was just executed")),
+                ),
+                (
+                    String::from("3"),
+                    Node::new(String::from("Node 3 was just executed")),
+                ),
+            ]),
+            vec![
+                Edge::new(String::from("0"), String::from("1")),
+                Edge::new(String::from("2"), String::from("3")),
+                Edge::new(String::from("1"), String::from("3")),
+            ],
+        )
+        .unwrap();
+
+        let parents = graph
+            .get_parent_node_indices(NodeIndex::new(3))
+            .collect::<Vec<NodeIndex>>();
+        assert_eq!(
+            parents,
+            Vec::from([NodeIndex::new(1), NodeIndex::new(2)]),
+            "Wrong parents of Node 3."
+        );
+
+        let parents = graph
+            .get_parent_node_indices(NodeIndex::new(2))
+            .collect::<Vec<NodeIndex>>();
+        assert_eq!(parents, Vec::new(), "Wrong parents of Node 2.");
+
+        let children = graph
+            .get_child_node_indices(NodeIndex::new(2))
+            .collect::<Vec<NodeIndex>>();
+        assert_eq!(
+            children,
+            Vec::from([NodeIndex::new(3)]),
+            "Wrong children of Node 2."
+        );
+
+        let children = graph
+            .get_child_node_indices(NodeIndex::new(1))
+            .collect::<Vec<NodeIndex>>();
+        assert_eq!(
+            children,
+            Vec::from([NodeIndex::new(3)]),
+            "Wrong children of Node 1."
+        );
+    }
+
+    #[test]
+    fn dag_content_hash_ignores_execution_status() {
+        let graph =
+            DirectedAcyclicGraph::from_file("../../resources/example-typical-dot-digraph.dot")
+                .unwrap();
+        let mut executed_graph = graph.clone();
+        executed_graph[NodeIndex::new(0)].execution_status = ExecutionStatus::Executed;
+        assert_eq!(
+            graph.content_hash(),
+            executed_graph.content_hash(),
+            "Content hash depends on the nodes' execution statuses."
+        );
+
+        let mut changed_graph = graph.clone();
+        changed_graph
+            .add_node(String::from("e"), Node::new(String::from("e")))
+            .unwrap();
+        assert_ne!(
+            graph.content_hash(),
+            changed_graph.content_hash(),
+            "Content hash does not change with the graph's definition."
+        );
+    }
+
+    #[test]
+    fn dag_content_hash_distinguishes_attributes_with_equal_values() {
+        for (first, second) in [
+            ("inputs = \"x\"", "command = \"x\""),
+            ("cost = 5", "priority = 5"),
+            ("nice = 5", "retries = 5"),
+            ("env = \"A=BC\"", "env = \"AB=C\""),
+            ("cwd = \"x\"", "inputs = \"x\""),
+        ] {
+            let hashes = [first, second].map(|attributes| {
+                let graph = DirectedAcyclicGraph::from_str(&format!(
+                    "digraph {{\n    a [ {} ]\n}}",
+                    attributes
+                ))
+                .unwrap();
+                graph.node_content_hash(graph.node_index("a").unwrap())
+            });
+            assert_ne!(
+                hashes[0], hashes[1],
+                "Nodes with {} and {} have the same content hash.",
+                first, second
+            );
+        }
+    }
+
+    #[test]
+    fn dag_anonymized_keeps_only_structure_statuses_and_costs() {
+        let mut graph = DirectedAcyclicGraph::from_str(
+            "digraph {\n    secret_build [ cost = 3, command = \"make secret\", env = \"TOKEN=1\" ]\n    secret_test [ cwd = \"/secret\" ]\n    secret_build -> secret_test\n}",
+        )
+        .unwrap();
+        let build = graph.node_index("secret_build").unwrap();
+        let test = graph.node_index("secret_test").unwrap();
+        graph[build].execution_status = ExecutionStatus::Failed;
+
+        let anonymized = graph.anonymized();
+        for i in [build, test] {
+            let node = &anonymized[i];
+            assert_eq!(
+                anonymized.node_id(i),
+                i.index().to_string(),
+                "Node identifier is not replaced by the node's index."
+            );
+            assert!(
+                node.args().is_empty()
+                    && node.command().is_none()
+                    && node.env().is_empty()
+                    && node.cwd().is_none(),
+                "Attributes of the node are kept: {}",
+                node
+            );
+            assert_eq!(
+                (node.execution_status, node.cost()),
+                (graph[i].execution_status, graph[i].cost()),
+                "Execution status or cost of the node is not kept."
+            );
+            assert_eq!(
+                anonymized.get_child_node_indices(i).collect::<Vec<_>>(),
+                graph.get_child_node_indices(i).collect::<Vec<_>>(),
+                "Children of the node are not kept."
+            );
+        }
+        assert!(
+            !anonymized.to_string().contains("secret"),
+            "Anonymized graph leaks identifiers or attributes:\n{}",
+            anonymized
+        );
+    }
+
+    #[test]
+    fn dag_parse_node_cost_attribute() {
+        let graph =
+            DirectedAcyclicGraph::from_str("digraph {\n    a [ cost = 3 ]\n    a -> b\n}").unwrap();
+        let a = graph.node_index("a").unwrap();
+        let b = graph.node_index("b").unwrap();
+        assert_eq!(graph[a].cost(), Some(3), "Cost attribute is not parsed.");
+        assert_eq!(
+            graph[b].cost(),
+            None,
+            "Node without cost attribute has a cost."
+        );
+
+        let round_tripped = DirectedAcyclicGraph::from_str(&graph.to_string()).unwrap();
+        assert_eq!(
+            graph, round_tripped,
+            "Cost is lost when printing the graph."
+        );
+
+        assert!(
+            DirectedAcyclicGraph::from_str("digraph {\n    a [ cost = cheap ]\n}").is_err(),
+            "Invalid cost attribute does not fail parsing."
+        );
+    }
+
+    #[test]
+    fn dag_parse_node_command_and_priority_attributes() {
+        let graph = DirectedAcyclicGraph::from_str(
+            "digraph {\n    a [ pre = \"mkdir -p tmp\", command = \"printf '%s, %s' a b\", post = \"rm -r tmp\", priority = -2 ]\n    a -> b\n}",
+        )
+        .unwrap();
+        let a = graph.node_index("a").unwrap();
+        let b = graph.node_index("b").unwrap();
+        assert_eq!(
+            graph[a].command(),
+            Some("printf '%s, %s' a b"),
+            "Command attribute is not parsed."
+        );
+        assert_eq!(graph[a].priority(), -2, "Priority attribute is not parsed.");
+        assert_eq!(
+            (graph[a].hook(Hook::Pre), graph[a].hook(Hook::Post)),
+            (Some("mkdir -p tmp"), Some("rm -r tmp")),
+            "Hook attributes are not parsed."
+        );
+        assert_eq!(
+            (graph[b].command(), graph[b].priority()),
+            (None, 0),
+            "Node without attributes has a command or priority."
+        );
+
+        let round_tripped = DirectedAcyclicGraph::from_str(&graph.to_string()).unwrap();
+        assert_eq!(
+            graph, round_tripped,
+            "Command containing commas, hooks or priority are lost when printing the graph."
+        );
+
+        let mut node = Node::new(String::from("a"))
+            .with_command(String::from("exit 3"))
+            .unwrap();
+        node.execution_status = ExecutionStatus::Executing;
+        assert!(
+            node.execute().is_err(),
+            "Failing command does not fail the node."
+        );
+    }
+
+    #[test]
+    fn dag_parse_node_nice_and_ionice_attributes() {
+        let graph = DirectedAcyclicGraph::from_str(
+            "digraph {\n    a [ command = \"test $(nice) -eq 7\", nice = 7, ionice = \"best-effort:6\" ]\n    b [ ionice = idle ]\n}",
+        )
+        .unwrap();
+        let a = graph.node_index("a").unwrap();
+        let b = graph.node_index("b").unwrap();
+        assert_eq!(graph[a].nice(), Some(7), "Nice attribute is not parsed.");
+        assert_eq!(
+            (graph[a].ionice(), graph[b].ionice()),
+            (Some(IoPriority::BestEffort(6)), Some(IoPriority::Idle)),
+            "Ionice attribute is not parsed."
+        );
+        assert_eq!(
+            graph,
+            DirectedAcyclicGraph::from_str(&graph.to_string()).unwrap(),
+            "Nice or ionice is lost when printing the graph."
+        );
+
+        let mut node = graph[a].clone();
+        node.execution_status = ExecutionStatus::Executing;
+        assert!(
+            node.execute().is_ok(),
+            "Nice is not applied to the command."
+        );
+
+        for invalid in ["nice = 20", "ionice = \"best-effort:8\"", "ionice = fast"] {
+            assert!(
+                DirectedAcyclicGraph::from_str(&format!("digraph {{\n    a [ {} ]\n}}", invalid))
+                    .is_err(),
+                "Invalid attribute {} does not fail parsing.",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn dag_parse_node_cwd_and_env_attributes() {
+        let dot = "digraph {\n    a [ command = \"test $(pwd) = / && test $A$B = 12\", cwd = \"/\", env = \"A=1,B=2\" ]\n}";
+        let graph = DirectedAcyclicGraph::from_str(dot).unwrap();
+        let a = graph.node_index("a").unwrap();
+        assert_eq!(graph[a].cwd(), Some("/"), "Cwd attribute is not parsed.");
+        assert_eq!(
+            graph[a].env(),
+            [
+                (String::from("A"), String::from("1")),
+                (String::from("B"), String::from("2"))
+            ],
+            "Env attribute is not parsed."
+        );
+        assert_eq!(
+            graph,
+            DirectedAcyclicGraph::from_str(&graph.to_string()).unwrap(),
+            "Cwd or env is lost when printing the graph."
+        );
+        for (attribute, changed) in [("cwd = \"/\"", "cwd = \"/tmp\""), ("A=1,B=2", "A=1,B=3")] {
+            let changed_graph =
+                DirectedAcyclicGraph::from_str(&dot.replace(attribute, changed)).unwrap();
+            assert_ne!(
+                graph.content_hash(),
+                changed_graph.content_hash(),
+                "Content hash does not change with the node's {}.",
+                changed
+            );
+        }
+
+        let mut node = graph[a].clone();
+        node.execution_status = ExecutionStatus::Executing;
+        assert!(
+            node.execute().is_ok(),
+            "Cwd or env is not applied to the command."
+        );
+
+        for invalid in ["env = \"A\"", "env = \"=1\"", "cwd = \"\""] {
+            assert!(
+                DirectedAcyclicGraph::from_str(&format!("digraph {{\n    a [ {} ]\n}}", invalid))
+                    .is_err(),
+                "Invalid attribute {} does not fail parsing.",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn dag_approval_gates_claiming() {
+        let mut graph = DirectedAcyclicGraph::from_str(
+            "digraph {\n    a [ approval = true ]\n    b [ approval = false ]\n    a -> c\n}",
+        )
+        .unwrap();
+        let (a, b, c) = (
+            graph.node_index("a").unwrap(),
+            graph.node_index("b").unwrap(),
+            graph.node_index("c").unwrap(),
+        );
+        assert!(
+            graph[a].is_awaiting_approval() && !graph[b].requires_approval(),
+            "Approval attribute is not parsed."
+        );
+        let executable = |graph: &DirectedAcyclicGraph| {
+            GraphView::new(graph, Duration::ZERO, 1).executable_node_indices()
+        };
+        assert_eq!(
+            executable(&graph),
+            VecDeque::from([b]),
+            "Node awaiting approval may be claimed."
+        );
+
+        graph[a].approved = true;
+        assert_eq!(
+            executable(&graph),
+            VecDeque::from([a, b]),
+            "Approved node may not be claimed."
+        );
+        assert_eq!(
+            graph,
+            DirectedAcyclicGraph::from_str(&graph.to_string()).unwrap(),
+            "Approval is lost when printing the graph."
+        );
+
+        graph.cancel_node(a);
+        assert_eq!(
+            [a, b, c].map(|i| graph[i].execution_status),
+            [
+                ExecutionStatus::Cancelled,
+                ExecutionStatus::Executable,
+                ExecutionStatus::Skipped
+            ],
+            "Cancelling a node does not skip exactly its descendants."
+        );
+        graph.reset_nodes([a]);
+        assert!(
+            graph[a].is_awaiting_approval(),
+            "Reset node keeps its approval."
+        );
+
+        assert!(
+            DirectedAcyclicGraph::from_str("digraph {\n    a [ approval = maybe ]\n}").is_err(),
+            "Invalid approval does not fail parsing."
+        );
+    }
+
+    #[test]
+    fn dag_external_events_gate_claiming() {
+        let mut graph = DirectedAcyclicGraph::from_str(
+            "digraph {\n    a [ await_event = \"ready\" ]\n    b [ await_event = \"ready\" ]\n    c [ await_event = \"other\" ]\n}",
+        )
+        .unwrap();
+        let (a, b, c) = (
+            graph.node_index("a").unwrap(),
+            graph.node_index("b").unwrap(),
+            graph.node_index("c").unwrap(),
+        );
+        let executable = |graph: &DirectedAcyclicGraph| {
+            GraphView::new(graph, Duration::ZERO, 1).executable_node_indices()
+        };
+        assert_eq!(
+            executable(&graph),
+            VecDeque::new(),
+            "Node awaiting an event may be claimed."
+        );
+
+        assert_eq!(
+            graph.receive_event("ready"),
+            vec![a, b],
+            "Event is not received by exactly the nodes awaiting it."
+        );
+        assert_eq!(
+            executable(&graph),
+            VecDeque::from([a, b]),
+            "Node which received its event may not be claimed."
+        );
+        assert!(
+            graph.receive_event("ready").is_empty() && graph[c].is_awaiting_event(),
+            "Event is received twice or by nodes awaiting another event."
+        );
+        assert_eq!(
+            graph,
+            DirectedAcyclicGraph::from_str(&graph.to_string()).unwrap(),
+            "Awaited event is lost when printing the graph."
+        );
+
+        graph.reset_nodes([a]);
+        assert!(
+            graph[a].is_awaiting_event() && !graph[b].is_awaiting_event(),
+            "Reset node keeps its received event."
+        );
+
+        assert!(
+            DirectedAcyclicGraph::from_str("digraph {\n    a [ await_event = \"not ready\" ]\n}")
+                .is_err(),
+            "Invalid event name does not fail parsing."
+        );
+    }
+
+    #[test]
+    fn dag_node_timeouts_are_parsed_and_printed() {
+        let graph = DirectedAcyclicGraph::from_str(
+            "digraph {\n    a [ timeout = 1.5 ]\n    b [ command = \"make\", timeout = 30 ]\n    c\n}",
+        )
+        .unwrap();
+        assert_eq!(
+            ["a", "b", "c"].map(|id| graph[graph.node_index(id).unwrap()].timeout()),
+            [
+                Some(Duration::from_millis(1500)),
+                Some(Duration::from_secs(30)),
+                None
+            ],
+            "Timeouts are not parsed in seconds."
+        );
+        assert_eq!(
+            graph,
+            DirectedAcyclicGraph::from_str(&graph.to_string()).unwrap(),
+            "Timeout is lost when printing the graph."
+        );
+        assert_ne!(
+            graph.content_hash(),
+            DirectedAcyclicGraph::from_str(
+                "digraph {\n    a\n    b [ command = \"make\" ]\n    c\n}"
+            )
+            .unwrap()
+            .content_hash(),
+            "Timeouts do not change the graph's hash."
+        );
+        for timeout in ["0", "-1", "soon"] {
+            assert!(
+                DirectedAcyclicGraph::from_str(&format!(
+                    "digraph {{\n    a [ timeout = {} ]\n}}",
+                    timeout
+                ))
+                .is_err(),
+                "Invalid timeout {} does not fail parsing.",
+                timeout
+            );
+        }
+    }
+
+    #[test]
+    fn dag_setup_and_teardown_nodes_are_wired_to_roots_and_leaves() {
+        let mut graph = DirectedAcyclicGraph::from_str(
+            "digraph {\n    up [ role = setup ]\n    down [ role = teardown ]\n    a -> b\n    a -> c\n    d\n}",
+        )
+        .unwrap();
+        let id = |graph: &DirectedAcyclicGraph, i| graph.node_id(i).to_string();
+        let index = |id: &str| graph.node_index(id).unwrap();
+        let (up, down, a, b, c, d) = (
+            index("up"),
+            index("down"),
+            index("a"),
+            index("b"),
+            index("c"),
+            index("d"),
+        );
+        assert_eq!(
+            (graph[up].role(), graph[down].role(), graph[a].role()),
+            (Some(NodeRole::Setup), Some(NodeRole::Teardown), None),
+            "Role attribute is not parsed."
+        );
+        assert_eq!(
+            graph
+                .get_child_node_indices(up)
+                .map(|i| id(&graph, i))
+                .collect::<Vec<_>>(),
+            ["a", "d"],
+            "Setup node is not wired to all roots."
+        );
+        assert_eq!(
+            graph
+                .get_parent_node_indices(down)
+                .map(|i| id(&graph, i))
+                .collect::<Vec<_>>(),
+            ["b", "c", "d"],
+            "Teardown node is not wired to all leaves."
+        );
+        assert_eq!(
+            graph,
+            DirectedAcyclicGraph::from_str(&graph.to_string()).unwrap(),
+            "Printed graph doesn't parse to the same graph."
+        );
+        let only_roles = DirectedAcyclicGraph::from_str(
+            "digraph {\n    up [ role = setup ]\n    down [ role = teardown ]\n}",
+        )
+        .unwrap();
+        assert_eq!(
+            only_roles
+                .get_child_node_indices(only_roles.node_index("up").unwrap())
+                .map(|i| id(&only_roles, i))
+                .collect::<Vec<_>>(),
+            ["down"],
+            "Teardown node doesn't follow setup node without other nodes."
+        );
+
+        // Teardown is neither skipped by failures nor cancelled, but executed once all its parents finished
+        for i in [up, a] {
+            graph[i].execution_status = ExecutionStatus::Executed;
+        }
+        for i in [b, c] {
+            graph[i].execution_status = ExecutionStatus::Executing;
+        }
+        graph[d].execution_status = ExecutionStatus::Executable;
+        graph.fail_node(b, String::from("failed"));
+        assert_eq!(
+            graph[down].execution_status,
+            ExecutionStatus::NonExecutable,
+            "Failure skips teardown node or releases it before its parents finished."
+        );
+        assert!(graph.is_teardown_pending());
+        graph.cancel();
+        assert_eq!(
+            [c, d, down].map(|i| graph[i].execution_status),
+            [
+                ExecutionStatus::Cancelled,
+                ExecutionStatus::Skipped,
+                ExecutionStatus::Executable
+            ],
+            "Cancelling the run doesn't release the teardown node."
+        );
+
+        assert!(
+            DirectedAcyclicGraph::from_str("digraph {\n    a [ role = cleanup ]\n}").is_err(),
+            "Invalid role does not fail parsing."
+        );
+    }
+
+    #[test]
+    fn dag_streaming_children_start_on_first_progress_mark() {
+        let mut graph = DirectedAcyclicGraph::from_str(
+            "digraph {\n    producer -> consumer -> sink [ dependency = streaming ]\n    config -> consumer\n}",
+        )
+        .unwrap();
+        let index = |id: &str| graph.node_index(id).unwrap();
+        let (producer, consumer, sink, config) = (
+            index("producer"),
+            index("consumer"),
+            index("sink"),
+            index("config"),
+        );
+        assert_eq!(
+            graph.dependency(producer, consumer),
+            Some(Dependency::Streaming),
+            "Edge attribute doesn't set the dependency of every edge of the chain."
+        );
+        assert_eq!(
+            graph.dependency(config, consumer),
+            Some(Dependency::Completion)
+        );
+        assert_eq!(
+            graph,
+            DirectedAcyclicGraph::from_str(&graph.to_string()).unwrap(),
+            "Printed graph doesn't parse to the same graph."
+        );
+        let completion_only = DirectedAcyclicGraph::from_str(
+            "digraph {\n    producer -> consumer -> sink\n    config -> consumer\n}",
+        )
+        .unwrap();
+        assert_ne!(
+            graph.content_hash(),
+            completion_only.content_hash(),
+            "Content hash doesn't cover the dependency."
+        );
+
+        // The consumer waits for the first mark of the producer and for its other parent to be executed
+        assert!(
+            graph.mark_progress(producer).is_empty(),
+            "Progress of a node which isn't executing is recorded."
+        );
+        graph[producer].execution_status = ExecutionStatus::Executing;
+        assert!(graph.mark_progress(producer).is_empty());
+        assert!(!graph.is_streaming(config, consumer));
+        graph[producer].progress_marks = 0;
+        graph[config].execution_status = ExecutionStatus::Executed;
+        assert_eq!(
+            graph.mark_progress(producer),
+            vec![consumer],
+            "First progress mark doesn't release the streaming child."
+        );
+        assert_eq!(
+            graph[consumer].execution_status,
+            ExecutionStatus::Executable
+        );
+        assert!(graph.mark_progress(producer).is_empty());
+        assert_eq!(graph[producer].progress_marks, 2);
+        assert!(graph.report_progress(
+            producer,
+            NodeProgress::new(0.4257, Some(String::from("chunk 2")))
+        ));
+        assert_eq!(
+            graph[producer].progress.as_ref().map(ToString::to_string),
+            Some(String::from("42.5% (chunk 2)")),
+            "Reported progress is not recorded."
+        );
+        assert!(
+            graph.heartbeat(producer, Duration::from_secs(3))
+                && !graph.heartbeat(config, Duration::from_secs(3)),
+            "Heartbeats of nodes which aren't executing are recorded."
+        );
+        assert!(
+            !graph.report_progress(config, NodeProgress::new(2.0, None))
+                && NodeProgress::new(2.0, None).fraction() == 1.0,
+            "Progress of a node which isn't executing is recorded or fractions are not clamped."
+        );
+
+        // Children streaming from a failed parent are stopped
+        graph[consumer].execution_status = ExecutionStatus::Executing;
+        graph.fail_node(producer, String::from("failed"));
+        assert_eq!(
+            [consumer, sink].map(|i| graph[i].execution_status),
+            [ExecutionStatus::Cancelled, ExecutionStatus::Skipped],
+            "Failure doesn't cancel the streaming child."
+        );
+        assert_eq!(graph.reset_failed_nodes(), vec![consumer, producer, sink]);
+        assert_eq!(
+            (
+                graph[producer].progress_marks,
+                &graph[producer].progress,
+                graph[producer].heartbeat_after
+            ),
+            (0, &None, None),
+            "Reset keeps progress marks."
+        );
+
+        assert!(
+            DirectedAcyclicGraph::from_str("digraph {\n    a -> b [ dependency = eager ]\n}")
+                .is_err(),
+            "Invalid dependency does not fail parsing."
+        );
+    }
+
+    #[test]
+    fn graph_template_substitutes_variables() {
+        let template = "digraph {\n    deploy [ command = \"./deploy.sh ${STAGE} $${HOME} $HOME\", ionice = \"${IONICE}\" ]\n}";
+        let variables =
+            template::parse_variables(["STAGE=prod", "IONICE=best-effort:7=x"]).unwrap();
+        assert_eq!(
+            template::render(template, &variables).unwrap(),
+            "digraph {\n    deploy [ command = \"./deploy.sh prod ${HOME} $HOME\", ionice = \"best-effort:7=x\" ]\n}",
+            "Variables are not substituted or escapes and shell variables are not kept."
+        );
+        // Variables not set explicitly are resolved from the environment
+        let path = std::env::var("PATH").unwrap();
+        assert_eq!(
+            template::render("${PATH}", &variables).unwrap(),
+            path,
+            "Variable is not resolved from the environment."
+        );
+
+        let error = template::render(
+            "${UNSET_GRAPH_VARIABLE_B} ${STAGE} ${UNSET_GRAPH_VARIABLE_A} ${UNSET_GRAPH_VARIABLE_B}",
+            &variables,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(
+            error
+                .ends_with("Unresolved variables: UNSET_GRAPH_VARIABLE_A, UNSET_GRAPH_VARIABLE_B."),
+            "Unresolved variables are not listed: {}",
+            error
+        );
+        assert!(
+            template::render("${NOT CLOSED", &variables).is_err()
+                && template::parse_variables(["=value"]).is_err(),
+            "Invalid references or assignments are accepted."
+        );
+    }
+
+    #[test]
+    fn pipeline_files_include_namespaced_fragments() {
+        let directory = std::env::temp_dir().join("graph_executor_test_includes");
+        std::fs::create_dir_all(directory.join("fragments")).unwrap();
+        let write =
+            |file: &str, content: &str| std::fs::write(directory.join(file), content).unwrap();
+        write(
+            "fragments/common.dot",
+            "digraph {\n    build [ command = \"make ${TARGET}\" ]\n    build -> test\n}",
+        );
+        write(
+            "main.dot",
+            "digraph {\n    include \"fragments/common.dot\"\n    include \"fragments/common.dot\" [ namespace = lib ]\n    common.test -> deploy\n    lib.test -> deploy\n}",
+        );
+        write(
+            "flow.yaml",
+            "imports:\n  - main.dot\n  - file: fragments/common.dot\n    namespace: \"\"\nmy_dag:\n  tasks:\n    final:\n      dependencies: [main.deploy, test]\n",
+        );
+        let variables = BTreeMap::from([(String::from("TARGET"), String::from("all"))]);
+        let path = |file: &str| directory.join(file).display().to_string();
+
+        let graph = DirectedAcyclicGraph::from_pipeline_file(
+            &path("main.dot"),
+            ParseMode::Strict,
+            &variables,
+        )
+        .unwrap();
+        let ids = |graph: &DirectedAcyclicGraph| {
+            graph
+                .get_node_indices()
+                .map(|i| graph.node_id(i).to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ids(&graph),
+            [
+                "common.build",
+                "common.test",
+                "deploy",
+                "lib.build",
+                "lib.test"
+            ],
+            "Included nodes are not namespaced."
+        );
+        let build = graph.node_index("lib.build").unwrap();
+        assert_eq!(
+            (
+                graph[build].command(),
+                graph
+                    .get_child_node_indices(build)
+                    .map(|i| graph.node_id(i))
+                    .collect::<Vec<_>>()
+            ),
+            (Some("make all"), vec!["lib.test"]),
+            "Included file is not rendered or loses its edges."
+        );
+        assert_eq!(
+            graph
+                .get_parent_node_indices(graph.node_index("deploy").unwrap())
+                .count(),
+            2,
+            "Including file can't declare edges to included nodes."
+        );
+
+        let flow = DirectedAcyclicGraph::from_pipeline_file(
+            &path("flow.yaml"),
+            ParseMode::Strict,
+            &variables,
+        )
+        .unwrap();
+        assert_eq!(
+            ids(&flow),
+            [
+                "build",
+                "final",
+                "main.common.build",
+                "main.common.test",
+                "main.deploy",
+                "main.lib.build",
+                "main.lib.test",
+                "test"
+            ],
+            "YAML imports are not included."
+        );
+
+        write(
+            "fragments/cycle.dot",
+            "digraph {\n    include \"../cycle.dot\"\n}",
+        );
+        write(
+            "cycle.dot",
+            "digraph {\n    include \"fragments/cycle.dot\"\n}",
+        );
+        let error = DirectedAcyclicGraph::from_file(&path("cycle.dot"))
+            .unwrap_err()
+            .to_string();
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert!(
+            error.contains("Include cycle"),
+            "Include cycle is not detected: {}",
+            error
+        );
+    }
+
+    #[test]
+    fn dag_import_makefile() {
+        let graph = DirectedAcyclicGraph::from_makefile_str(
+            "# Build the app\nCC := cc\nCFLAGS = -O2\nCFLAGS += -Wall\nOBJS = main.o util.o\n\n.PHONY: all clean\nall: app\n\napp: $(OBJS)\n\t@$(CC) -o $@ $^\n\nmain.o: main.c util.h\n\t$(CC) $(CFLAGS) \\\n\t  -c $< -o $@\nutil.o: util.c\n\t$(CC) $(CFLAGS) -c $< -o $@\nclean: ; -rm -f app $$(ls *.o)\n",
+        )
+        .unwrap();
+        let node = |id| &graph[graph.node_index(id).unwrap()];
+        assert_eq!(
+            ["all", "app", "main.o", "util.o", "clean"].map(|id| node(id).command()),
+            [
+                None,
+                Some("cc -o app main.o util.o"),
+                Some("cc -O2 -Wall -c main.c -o main.o"),
+                Some("cc -O2 -Wall -c util.c -o util.o"),
+                Some("{ rm -f app $(ls *.o); } || true"),
+            ],
+            "Recipes are not imported as commands."
+        );
+        let parents = |id| -> Vec<&str> {
+            graph
+                .get_parent_node_indices(graph.node_index(id).unwrap())
+                .map(|i| graph.node_id(i))
+                .collect()
+        };
+        assert_eq!(
+            parents("all"),
+            vec!["app"],
+            "Target prerequisite is not a parent."
+        );
+        let mut app_parents = parents("app");
+        app_parents.sort();
+        assert_eq!(
+            app_parents,
+            vec!["main.o", "util.o"],
+            "Object files are not parents."
+        );
+        assert!(
+            parents("main.o").is_empty(),
+            "Source files are imported as parents."
+        );
+    }
+
+    #[test]
+    fn dag_import_ninja() {
+        let graph = DirectedAcyclicGraph::from_ninja_str(
+            "cflags = -O2\n\nrule cc\n  command = cc $cflags $extra -c $in -o $out\n  description = CC $out\n\nrule link\n  command = cc -o $out $in\n\nbuild main.o: cc main.c | util.h\n  extra = -DMAIN\nbuild util.o: cc util.c\nbuild my$ app: link main.o util.o\nbuild all: phony my$ app\ndefault all\n",
+        )
+        .unwrap();
+        let node = |id| &graph[graph.node_index(id).unwrap()];
+        assert_eq!(
+            ["main.o", "util.o", "my app", "all"].map(|id| node(id).command()),
+            [
+                Some("cc -O2 -DMAIN -c main.c -o main.o"),
+                Some("cc -O2  -c util.c -o util.o"),
+                Some("cc -o my app main.o util.o"),
+                None,
+            ],
+            "Build statements are not imported as commands."
+        );
+        let parents = |id| -> Vec<&str> {
+            let mut parents: Vec<&str> = graph
+                .get_parent_node_indices(graph.node_index(id).unwrap())
+                .map(|i| graph.node_id(i))
+                .collect();
+            parents.sort();
+            parents
+        };
+        assert_eq!(
+            parents("my app"),
+            vec!["main.o", "util.o"],
+            "Inputs built by other builds are not parents."
+        );
+        assert_eq!(
+            parents("all"),
+            vec!["my app"],
+            "Phony inputs are not parents."
+        );
+
+        assert!(
+            DirectedAcyclicGraph::from_ninja_str("build a: missing b\n").is_err(),
+            "Unknown rule does not fail the import."
+        );
+    }
+
+    #[test]
+    fn dag_import_argo_workflow() {
+        let graph = DirectedAcyclicGraph::from_argo_str(
+            "apiVersion: argoproj.io/v1alpha1
+kind: Workflow
+spec:
+  entrypoint: diamond
+  arguments:
+    parameters:
+    - name: greeting
+      value: hello
+  templates:
+  - name: diamond
+    dag:
+      tasks:
+      - name: A
+        template: echo
+        arguments: {parameters: [{name: message, value: A}]}
+      - name: B
+        dependencies: [A]
+        template: echo
+      - name: C
+        depends: A.Succeeded
+        template: script
+      - name: D
+        depends: (B || C) && !A.Failed
+        template: echo
+        arguments: {parameters: [{name: message, value: it's D}]}
+  - name: echo
+    inputs:
+      parameters:
+      - name: message
+        default: default
+    container:
+      image: alpine:3.7
+      command: [echo, \"{{workflow.parameters.greeting}} {{inputs.parameters.message}}\"]
+  - name: script
+    script:
+      image: python:alpine
+      command: [python]
+      source: |
+        print(1)
+        print(2)
+",
+        )
+        .unwrap();
+        let node = |id| &graph[graph.node_index(id).unwrap()];
+        assert_eq!(
+            ["A", "B", "C", "D"].map(|id| node(id).command()),
+            [
+                Some("echo 'hello A'"),
+                Some("echo 'hello default'"),
+                Some("printf '%s\\n' 'print(1)' 'print(2)' | python"),
+                Some("echo 'hello it'\\''s D'"),
+            ],
+            "Templates are not imported as commands."
+        );
+        let parents = |id| -> Vec<&str> {
+            let mut parents: Vec<&str> = graph
+                .get_parent_node_indices(graph.node_index(id).unwrap())
+                .map(|i| graph.node_id(i))
+                .collect();
+            parents.sort();
+            parents
+        };
+        assert_eq!(
+            [parents("B"), parents("C"), parents("D")],
+            [vec!["A"], vec!["A"], vec!["A", "B", "C"]],
+            "Dependencies are not imported as parents."
+        );
+    }
+
+    #[test]
+    fn dag_import_airflow_dag() {
+        let dag_factory = DirectedAcyclicGraph::from_airflow_str(
+            "default:
+  default_args:
+    owner: me
+example_dag:
+  schedule_interval: '@daily'
+  tasks:
+    extract:
+      operator: airflow.operators.bash_operator.BashOperator
+      bash_command: echo extract
+    transform:
+      operator: airflow.operators.python_operator.PythonOperator
+      python_callable_name: transform
+      dependencies: [extract]
+    load:
+      operator: airflow.operators.bash_operator.BashOperator
+      bash_command: |
+        echo load
+        echo done
+      dependencies: [transform]
+",
+        )
+        .unwrap();
+        let serialized = DirectedAcyclicGraph::from_airflow_str(
+            r#"{"__version": 1, "dag": {"_dag_id": "example_dag", "tasks": [
+                {"task_id": "extract", "bash_command": "echo extract", "downstream_task_ids": ["transform"]},
+                {"__type": "operator", "__var": {"task_id": "transform", "downstream_task_ids": ["load"]}},
+                {"task_id": "load", "bash_command": "echo load\necho done", "downstream_task_ids": []}
+            ]}}"#,
+        )
+        .unwrap();
+
+        for graph in [&dag_factory, &serialized] {
+            let node = |id| &graph[graph.node_index(id).unwrap()];
+            assert_eq!(
+                ["extract", "transform", "load"].map(|id| node(id).command()),
+                [
+                    Some("printf '%s\\n' 'echo extract' | bash"),
+                    None,
+                    Some("printf '%s\\n' 'echo load' 'echo done' | bash"),
+                ],
+                "Bash commands are not imported."
+            );
+            let load = graph.node_index("load").unwrap();
+            assert_eq!(
+                graph
+                    .get_parent_node_indices(load)
+                    .map(|i| graph.node_id(i))
+                    .collect::<Vec<&str>>(),
+                vec!["transform"],
+                "Dependencies are not imported as parents."
+            );
+        }
+    }
+
+    #[test]
+    fn lint_warns_about_anti_patterns() {
+        let node = |args: &str, command: Option<&str>| match command {
+            Some(command) => Node::new(String::from(args))
+                .with_command(String::from(command))
+                .unwrap(),
+            None => Node::new(String::from(args)),
+        };
+        let graph = DirectedAcyclicGraph::new(
+            [
+                ("c1", node("same", Some("true"))),
+                ("c2", node("same", Some("true"))),
+                ("c3", node("c3", Some("true"))),
+                ("h", node("h", Some("true"))),
+                ("o", node("o", Some("true"))),
+                ("t0", node("t0", None)),
+                ("t1", node("t1", None)),
+                ("t2", node("t2", None)),
+            ]
+            .map(|(id, node)| (String::from(id), node)),
+            [
+                ("h", "c1"),
+                ("h", "c2"),
+                ("h", "c3"),
+                ("t0", "t1"),
+                ("t1", "t2"),
+            ]
+            .map(|(parent, child)| Edge::new(String::from(parent), String::from(child)))
+            .to_vec(),
+        )
+        .unwrap();
+
+        let warnings = graph.lint_with(&LintThresholds {
+            max_children: 2,
+            min_trivial_chain_length: 3,
+        });
+        assert_eq!(
+            warnings,
+            vec![
+                LintWarning::ManyChildren {
+                    node_id: String::from("h"),
+                    child_count: 3
+                },
+                LintWarning::TrivialChain {
+                    node_ids: ["t0", "t1", "t2"].map(String::from).to_vec()
+                },
+                LintWarning::OrphanNode {
+                    node_id: String::from("o")
+                },
+                LintWarning::DuplicateArgs {
+                    args: String::from("same"),
+                    node_ids: ["c1", "c2"].map(String::from).to_vec()
+                },
+            ],
+            "Anti-patterns are not reported."
+        );
+        assert_eq!(
+            serde_yaml::to_string(&warnings[2]).unwrap(),
+            "lint: orphan-node\nnode_id: o\n",
+            "Warnings are not machine-readable."
+        );
+        assert!(
+            graph
+                .lint()
+                .iter()
+                .all(|w| w.code() == "orphan-node" || w.code() == "duplicate-args"),
+            "Default thresholds report small hubs or chains."
+        );
+    }
+
+    #[test]
+    fn iteration_order_is_by_node_id() {
+        let mut graph = DirectedAcyclicGraph::new(
+            ["b", "d"].map(|id| (String::from(id), Node::new(String::from(id)))),
+            vec![],
+        )
+        .unwrap();
+        for id in ["c", "a"] {
+            graph
+                .add_node(String::from(id), Node::new(String::from(id)))
+                .unwrap();
+        }
+        for (parent, child) in [("d", "c"), ("b", "c"), ("d", "a"), ("a", "c")] {
+            graph
+                .add_edge(Edge::new(String::from(parent), String::from(child)))
+                .unwrap();
+        }
+        let deserialized =
+            rmp_serde::from_slice::<DirectedAcyclicGraph>(&rmp_serde::to_vec(&graph).unwrap())
+                .unwrap();
+
+        for graph in [&graph, &deserialized] {
+            let ids = |indices: Vec<NodeIndex>| -> Vec<&str> {
+                indices.into_iter().map(|i| graph.node_id(i)).collect()
+            };
+            let c = graph.node_index("c").unwrap();
+            assert_eq!(
+                ids(graph.get_node_indices().collect()),
+                vec!["a", "b", "c", "d"],
+                "Nodes are not ordered by identifier."
+            );
+            assert_eq!(
+                ids(graph.get_parent_node_indices(c).collect()),
+                vec!["a", "b", "d"],
+                "Parents are not ordered by identifier."
+            );
+            assert_eq!(
+                ids(graph.get_executable_node_indices().into()),
+                vec!["b", "d"],
+                "Executable nodes are not ordered by identifier."
+            );
+        }
+    }
+
+    // `SmallDag` tests
+
+    #[test]
+    fn small_dag_claim_complete_fail() {
+        // a -> b -> d, a -> c -> d, e
+        let graph = DirectedAcyclicGraph::new(
+            ["a", "b", "c", "d", "e"].map(|id| (String::from(id), Node::new(String::from(id)))),
+            [("a", "b"), ("a", "c"), ("b", "d"), ("c", "d")]
+                .map(|(parent, child)| Edge::new(String::from(parent), String::from(child)))
+                .to_vec(),
+        )
+        .unwrap();
+        let mut dag = SmallDag::<8>::try_from(&graph).unwrap();
+        assert_eq!(dag.len(), 5, "Nodes are lost when converting the graph.");
+        assert_eq!(dag.executable(), 0b10001, "Only a and e are executable.");
+        assert!(
+            dag.add_edge(3, 0).is_err(),
+            "Edge introducing a cycle is added."
+        );
+
+        assert_eq!(
+            (dag.claim(), dag.claim(), dag.claim()),
+            (Some(0), Some(4), None)
+        );
+        dag.complete(0).unwrap();
+        assert_eq!(
+            [1, 2, 3].map(|i| dag.execution_status(i).unwrap()),
+            [
+                ExecutionStatus::Executable,
+                ExecutionStatus::Executable,
+                ExecutionStatus::NonExecutable
+            ],
+            "Children of an executed node are not executable."
+        );
+        assert!(
+            dag.complete(1).is_err(),
+            "Node completed without being claimed."
+        );
+        assert_eq!(dag.claim(), Some(1));
+        dag.fail(1).unwrap();
+        assert_eq!(
+            dag.execution_status(3).unwrap(),
+            ExecutionStatus::Skipped,
+            "Descendant of a failed node is not skipped."
+        );
+        assert_eq!(dag.claim(), Some(2));
+        dag.complete(2).unwrap();
+        dag.complete(4).unwrap();
+        assert!(dag.is_finished(), "Graph is not finished.");
+
+        dag.reset();
+        assert_eq!(
+            dag.executable(),
+            0b10001,
+            "Reset graph is not executable again."
+        );
+        assert!(
+            SmallDag::<4>::try_from(&graph).is_err(),
+            "Graph exceeding the capacity is converted."
+        );
+    }
+
+    #[test]
+    fn changes_since_generation() {
+        let original = DirectedAcyclicGraph::new(
+            ["a", "b", "c"].map(|id| (String::from(id), Node::new(String::from(id)))),
+            vec![Edge::new(String::from("a"), String::from("b"))],
+        )
+        .unwrap();
+        let (a, b) = (
+            original.node_index("a").unwrap(),
+            original.node_index("b").unwrap(),
+        );
+        let mut graph = original.clone();
+        assert_eq!(
+            (graph.generation(), graph.changes_since(0)),
+            (0, vec![]),
+            "New graph has changes."
+        );
+
+        let before = graph.clone();
+        graph[a].execution_status = ExecutionStatus::Executing;
+        graph.stamp_changes(&before);
+        let before = graph.clone();
+        graph[a].execution_status = ExecutionStatus::Executed;
+        graph[b].execution_status = ExecutionStatus::Executable;
+        graph.stamp_changes(&before);
+        assert_eq!(
+            (graph.generation(), graph.changes_since(1)),
+            (
+                2,
+                vec![
+                    (String::from("a"), ExecutionStatus::Executed),
+                    (String::from("b"), ExecutionStatus::Executable)
+                ]
+            ),
+            "Changes of the latest write are not returned."
+        );
+        assert_eq!(
+            graph.changes_since(2),
+            vec![],
+            "Changes are returned twice."
+        );
+
+        // Writes without changes keep the generation, writes replacing the whole graph advance it
+        let before = graph.clone();
+        graph.stamp_changes(&before);
+        assert_eq!(
+            graph.generation(),
+            2,
+            "Write without changes advances the generation."
+        );
+        let before = graph.clone();
+        graph = original.clone();
+        graph.stamp_changes(&before);
+        assert_eq!(
+            (graph.generation(), graph.changes_since(2)),
+            (
+                3,
+                vec![
+                    (String::from("a"), ExecutionStatus::Executable),
+                    (String::from("b"), ExecutionStatus::NonExecutable)
+                ]
+            ),
+            "Reset graph does not advance the generation."
+        );
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn graph_json_schema() {
+        let schema = serde_yaml::to_value(schemars::schema_for!(DirectedAcyclicGraph)).unwrap();
+        for name in ["Node", "ExecutionStatus", "Attempt", "GraphLimits"] {
+            assert!(
+                schema["$defs"].get(name).is_some(),
+                "Schema of {} is missing.",
+                name
+            );
+        }
+
+        // The schema describes the fields of the graph as serialized by petgraph
+        let graph =
+            DirectedAcyclicGraph::from_file("../../resources/example-typical-dot-digraph.dot")
+                .unwrap();
+        let serialized = serde_yaml::to_value(&graph).unwrap();
+        let fields = |value: &serde_yaml::Value| -> Vec<String> {
+            let mut fields: Vec<String> = value
+                .as_mapping()
+                .unwrap()
+                .keys()
+                .map(|k| k.as_str().unwrap().to_string())
+                .collect();
+            fields.sort();
+            fields
+        };
+        assert_eq!(
+            fields(&serialized),
+            fields(&schema["properties"]),
+            "Schema does not describe the fields of the graph."
+        );
+        assert_eq!(
+            fields(&serialized["graph"]),
+            fields(&schema["$defs"]["SerializedGraph"]["properties"]),
+            "Schema does not describe the fields of the petgraph graph."
+        );
+    }
+}
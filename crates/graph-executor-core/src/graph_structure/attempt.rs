@@ -0,0 +1,128 @@
+use alloc::string::String;
+use anyhow::{anyhow, Error, Result};
+use core::{fmt, str::FromStr, time::Duration};
+
+/// How an [`Attempt`] at executing a [`super::node::Node`] ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum AttemptOutcome {
+    /// The node was executed successfully.
+    Succeeded,
+    /// Executing the node returned an error, it is retried if it has retries left.
+    Failed,
+    /// The attempt exceeded the node's timeout and was aborted, it is retried like a failed attempt if the node has
+    /// retries left, see [`super::node::Node::timeout`].
+    TimedOut,
+    /// The node's command was terminated by preemption and the node requeued.
+    Preempted,
+    /// The node was executing when the run was cancelled.
+    Cancelled,
+    /// The worker executing the node stopped sending heartbeats and the node was requeued by the watchdog. The
+    /// attempt's start is approximated by the worker's last heartbeat.
+    Abandoned,
+    /// The node's outputs were newer than its inputs, so that it was completed without running its command, see
+    /// [`super::node::Node::is_up_to_date`].
+    UpToDate,
+    /// The node's results were read from the run's remote cache, so that it was completed without running it.
+    Cached,
+}
+
+impl fmt::Display for AttemptOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                AttemptOutcome::Succeeded => "Succeeded",
+                AttemptOutcome::Failed => "Failed",
+                AttemptOutcome::TimedOut => "TimedOut",
+                AttemptOutcome::Preempted => "Preempted",
+                AttemptOutcome::Cancelled => "Cancelled",
+                AttemptOutcome::Abandoned => "Abandoned",
+                AttemptOutcome::UpToDate => "UpToDate",
+                AttemptOutcome::Cached => "Cached",
+            }
+        )
+    }
+}
+
+impl FromStr for AttemptOutcome {
+    type Err = Error;
+    /// Parses [`AttemptOutcome`] from a string like: "Succeeded".
+    fn from_str(outcome: &str) -> Result<Self> {
+        match outcome {
+            "Succeeded" => Ok(AttemptOutcome::Succeeded),
+            "Failed" => Ok(AttemptOutcome::Failed),
+            "TimedOut" => Ok(AttemptOutcome::TimedOut),
+            "Preempted" => Ok(AttemptOutcome::Preempted),
+            "Cancelled" => Ok(AttemptOutcome::Cancelled),
+            "Abandoned" => Ok(AttemptOutcome::Abandoned),
+            "UpToDate" => Ok(AttemptOutcome::UpToDate),
+            "Cached" => Ok(AttemptOutcome::Cached),
+            _ => Err(anyhow!(
+                "AttemptOutcome::from_str parsing error: Invalid attempt outcome {}.",
+                outcome
+            )),
+        }
+    }
+}
+
+/// Hook of a [`super::node::Node`], i.e. a shell command run by the executing worker around the node's body.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Hook {
+    /// Runs before the node's body, e.g. to create a temporary directory. The body is not run if it fails.
+    Pre,
+    /// Runs after the node's body finished, successfully or not, e.g. to upload an artifact.
+    Post,
+}
+
+impl fmt::Display for Hook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Hook::Pre => "pre",
+                Hook::Post => "post",
+            }
+        )
+    }
+}
+
+impl FromStr for Hook {
+    type Err = Error;
+    /// Parses [`Hook`] from a string like: "pre".
+    fn from_str(hook: &str) -> Result<Self> {
+        match hook {
+            "pre" => Ok(Hook::Pre),
+            "post" => Ok(Hook::Post),
+            _ => Err(anyhow!(
+                "Hook::from_str parsing error: Invalid hook {}.",
+                hook
+            )),
+        }
+    }
+}
+
+/// Record of a single attempt at executing a [`super::node::Node`], shared with all workers of a run. Retried,
+/// preempted and cancelled executions are kept as separate attempts instead of overwriting each other.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Attempt {
+    /// Index of the worker in the run's worker table which made the attempt.
+    pub worker_id: usize,
+    /// Time after the start of the run at which the attempt started.
+    pub started_after: Duration,
+    /// Time after the start of the run at which the attempt ended.
+    pub finished_after: Duration,
+    /// How the attempt ended.
+    pub outcome: AttemptOutcome,
+    /// Error message of a [`AttemptOutcome::Failed`] or [`AttemptOutcome::TimedOut`] attempt.
+    pub error: Option<String>,
+    /// Hook whose command failed a [`AttemptOutcome::Failed`] attempt, [`None`] if the node's body failed.
+    pub hook: Option<Hook>,
+}
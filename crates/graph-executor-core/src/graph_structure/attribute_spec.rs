@@ -0,0 +1,525 @@
+use super::{
+    attempt::Hook,
+    edge::Dependency,
+    io_priority::IoPriority,
+    isolation::Isolation,
+    node::{parse_env, parse_seconds, Node},
+    node_role::NodeRole,
+    parse_mode::ParseMode,
+};
+use anyhow::{anyhow, Result};
+use std::{fmt, str::FromStr, time::Duration};
+
+/// Documentation of an attribute recognized in the attribute lists of DOT pipeline files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AttributeSpec {
+    /// Key of the attribute, e.g. `retries`.
+    pub key: &'static str,
+    /// Kind of values the attribute takes, e.g. `unsigned integer`.
+    pub value: &'static str,
+    /// What the attribute declares.
+    pub description: &'static str,
+}
+
+impl fmt::Display for AttributeSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.key, self.value, self.description)
+    }
+}
+
+/// Graphviz attributes which only change how a graph is drawn. They are accepted in every attribute list, so that
+/// pipeline files can still be rendered with Graphviz, but have no effect on the execution.
+pub const GRAPHVIZ_ATTRIBUTES: &[&str] = &[
+    "arrowhead",
+    "arrowtail",
+    "color",
+    "comment",
+    "constraint",
+    "dir",
+    "fillcolor",
+    "fixedsize",
+    "fontcolor",
+    "fontname",
+    "fontsize",
+    "group",
+    "headlabel",
+    "height",
+    "penwidth",
+    "shape",
+    "style",
+    "taillabel",
+    "tooltip",
+    "weight",
+    "width",
+    "xlabel",
+];
+
+/// Typed attributes of a node statement like `a [ command = "make a", retries = 2 ]`, see [`NodeSpec::ATTRIBUTES`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NodeSpec {
+    /// Args of the node, or the whole node as printed by [`Node`]'s `Display`.
+    pub label: Option<String>,
+    /// See [`Node::cost`].
+    pub cost: Option<u64>,
+    /// See [`Node::gang`].
+    pub gang: Option<String>,
+    /// See [`Node::mutex`].
+    pub mutex: Option<String>,
+    /// See [`Node::priority`].
+    pub priority: Option<i64>,
+    /// See [`Node::nice`].
+    pub nice: Option<i32>,
+    /// See [`Node::ionice`].
+    pub ionice: Option<IoPriority>,
+    /// See [`Node::retries`].
+    pub retries: Option<u32>,
+    /// See [`Node::timeout`].
+    pub timeout: Option<Duration>,
+    /// See [`Node::inputs`].
+    pub inputs: Option<Vec<String>>,
+    /// See [`Node::outputs`].
+    pub outputs: Option<Vec<String>>,
+    /// See [`Node::requires_approval`].
+    pub approval: bool,
+    /// See [`Node::awaited_event`].
+    pub await_event: Option<String>,
+    /// See [`Node::command`].
+    pub command: Option<String>,
+    /// See [`Node::role`].
+    pub role: Option<NodeRole>,
+    /// See [`Node::isolation`].
+    pub isolation: Option<Isolation>,
+    /// See [`Node::cwd`].
+    pub cwd: Option<String>,
+    /// See [`Node::env`].
+    pub env: Option<Vec<(String, String)>>,
+    /// Shell command of the [`Hook::Pre`] hook.
+    pub pre: Option<String>,
+    /// Shell command of the [`Hook::Post`] hook.
+    pub post: Option<String>,
+}
+
+impl NodeSpec {
+    /// Attributes recognized in node statements.
+    pub const ATTRIBUTES: &'static [AttributeSpec] = &[
+        AttributeSpec {
+            key: "label",
+            value: "string",
+            description: "Args of the node, or the node printed by the graph's Display",
+        },
+        AttributeSpec {
+            key: "cost",
+            value: "unsigned integer",
+            description: "Estimated cost, used by the critical-path scheduler",
+        },
+        AttributeSpec {
+            key: "gang",
+            value: "string",
+            description: "Gang whose members are only claimed together",
+        },
+        AttributeSpec {
+            key: "mutex",
+            value: "string",
+            description: "Concurrency key no two executing nodes share",
+        },
+        AttributeSpec {
+            key: "priority",
+            value: "integer",
+            description: "Priority, used by the priority scheduler",
+        },
+        AttributeSpec {
+            key: "nice",
+            value: "integer from -20 to 19",
+            description: "Niceness of the command's process",
+        },
+        AttributeSpec {
+            key: "ionice",
+            value: "class[:level]",
+            description: "I/O priority of the command's process",
+        },
+        AttributeSpec {
+            key: "retries",
+            value: "unsigned integer",
+            description: "Number of times the node is retried after failing",
+        },
+        AttributeSpec {
+            key: "timeout",
+            value: "seconds",
+            description: "Longest time an attempt may take before it is aborted and fails",
+        },
+        AttributeSpec {
+            key: "inputs",
+            value: "comma-separated paths",
+            description: "Files the node reads, used for incremental builds",
+        },
+        AttributeSpec {
+            key: "outputs",
+            value: "comma-separated paths",
+            description: "Files the node writes, used for incremental builds",
+        },
+        AttributeSpec {
+            key: "approval",
+            value: "true or false",
+            description: "Whether the node waits for approval before it is claimed",
+        },
+        AttributeSpec {
+            key: "await_event",
+            value: "iceoryx2 service name",
+            description: "External event the node waits for before it is claimed",
+        },
+        AttributeSpec {
+            key: "command",
+            value: "string",
+            description: "Shell command executing the node",
+        },
+        AttributeSpec {
+            key: "role",
+            value: "setup or teardown",
+            description: "Role wiring the node to all other nodes",
+        },
+        AttributeSpec {
+            key: "isolation",
+            value: "process or inline",
+            description: "Whether the node runs in a process of its own",
+        },
+        AttributeSpec {
+            key: "cwd",
+            value: "path",
+            description: "Working directory of the command's process",
+        },
+        AttributeSpec {
+            key: "env",
+            value: "comma-separated NAME=value",
+            description: "Environment variables of the command's process",
+        },
+        AttributeSpec {
+            key: "pre",
+            value: "string",
+            description: "Shell command run before the node's command",
+        },
+        AttributeSpec {
+            key: "post",
+            value: "string",
+            description: "Shell command run after the node's command",
+        },
+    ];
+
+    /// Parses the attributes of the node `id`. Unknown attributes which aren't [`GRAPHVIZ_ATTRIBUTES`] either are an
+    /// error in [`ParseMode::Strict`] and are ignored with a warning in [`ParseMode::Lenient`].
+    pub fn parse(id: &str, attributes: &[(String, String)], parse_mode: ParseMode) -> Result<Self> {
+        check_keys(
+            &format!("node {}", id),
+            attributes,
+            NodeSpec::ATTRIBUTES,
+            parse_mode,
+        )?;
+        let mut spec = NodeSpec::default();
+        for (key, value) in attributes {
+            match key.as_str() {
+                "label" => spec.label = Some(value.clone()),
+                "cost" => spec.cost = Some(parse_value(id, "cost", value)?),
+                "gang" => spec.gang = Some(value.clone()),
+                "mutex" => spec.mutex = Some(value.clone()),
+                "priority" => spec.priority = Some(parse_value(id, "priority", value)?),
+                "nice" => spec.nice = Some(parse_value(id, "nice", value)?),
+                "ionice" => spec.ionice = Some(IoPriority::from_str(value)?),
+                "retries" => spec.retries = Some(parse_value(id, "retries", value)?),
+                "timeout" => {
+                    spec.timeout = Some(parse_seconds(value).map_err(|e| {
+                        anyhow!(
+                            "DirectedAcyclicGraph::from_str parsing error: Invalid timeout of node {}: {}",
+                            id,
+                            e
+                        )
+                    })?)
+                }
+                "inputs" => spec.inputs = Some(split_paths(value)),
+                "outputs" => spec.outputs = Some(split_paths(value)),
+                "approval" => spec.approval = parse_value(id, "approval", value)?,
+                "await_event" => spec.await_event = Some(value.clone()),
+                "command" => spec.command = Some(value.clone()),
+                "role" => spec.role = Some(NodeRole::from_str(value)?),
+                "isolation" => spec.isolation = Some(Isolation::from_str(value)?),
+                "cwd" => spec.cwd = Some(value.clone()),
+                "env" => spec.env = Some(parse_env(value)?),
+                "pre" => spec.pre = Some(value.clone()),
+                "post" => spec.post = Some(value.clone()),
+                _ => (),
+            }
+        }
+        Ok(spec)
+    }
+
+    /// Get the attributes `node` would be declared with, e.g. to compare nodes attribute by attribute.
+    pub fn from_node(node: &Node) -> Self {
+        NodeSpec {
+            label: Some(node.args().to_string()),
+            cost: node.cost(),
+            gang: node.gang().map(String::from),
+            mutex: node.mutex().map(String::from),
+            priority: Some(node.priority()).filter(|priority| *priority != 0),
+            nice: node.nice(),
+            ionice: node.ionice(),
+            retries: Some(node.retries()).filter(|retries| *retries > 0),
+            timeout: node.timeout(),
+            inputs: Some(node.inputs().to_vec()).filter(|inputs| !inputs.is_empty()),
+            outputs: Some(node.outputs().to_vec()).filter(|outputs| !outputs.is_empty()),
+            approval: node.requires_approval(),
+            await_event: node.awaited_event().map(String::from),
+            command: node.command().map(String::from),
+            role: node.role(),
+            isolation: Some(node.isolation()),
+            cwd: node.cwd().map(String::from),
+            env: Some(node.env().to_vec()).filter(|env| !env.is_empty()),
+            pre: node.hook(Hook::Pre).map(String::from),
+            post: node.hook(Hook::Post).map(String::from),
+        }
+    }
+
+    /// Returns the key and value of every set attribute, in the order of [`NodeSpec::ATTRIBUTES`].
+    pub fn attributes(&self) -> Vec<(&'static str, String)> {
+        let list = |paths: &Option<Vec<String>>| paths.as_ref().map(|paths| paths.join(","));
+        [
+            ("label", self.label.clone()),
+            ("cost", self.cost.map(|cost| cost.to_string())),
+            ("gang", self.gang.clone()),
+            ("mutex", self.mutex.clone()),
+            (
+                "priority",
+                self.priority.map(|priority| priority.to_string()),
+            ),
+            ("nice", self.nice.map(|nice| nice.to_string())),
+            ("ionice", self.ionice.map(|ionice| ionice.to_string())),
+            ("retries", self.retries.map(|retries| retries.to_string())),
+            (
+                "timeout",
+                self.timeout
+                    .map(|timeout| timeout.as_secs_f64().to_string()),
+            ),
+            ("inputs", list(&self.inputs)),
+            ("outputs", list(&self.outputs)),
+            (
+                "approval",
+                Some(String::from("true")).filter(|_| self.approval),
+            ),
+            ("await_event", self.await_event.clone()),
+            ("command", self.command.clone()),
+            ("role", self.role.map(|role| role.to_string())),
+            (
+                "isolation",
+                self.isolation.map(|isolation| isolation.to_string()),
+            ),
+            ("cwd", self.cwd.clone()),
+            (
+                "env",
+                self.env.as_ref().map(|env| {
+                    env.iter()
+                        .map(|(name, value)| format!("{}={}", name, value))
+                        .collect::<Vec<String>>()
+                        .join(",")
+                }),
+            ),
+            ("pre", self.pre.clone()),
+            ("post", self.post.clone()),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, value?)))
+        .collect()
+    }
+
+    /// Creates the node `id` declared with these attributes.
+    pub fn into_node(self, id: &str) -> Result<Node> {
+        let mut node = match self.label {
+            Some(label) if label.starts_with("Struct Node,") => Node::from_str(&label)?,
+            Some(label) => Node::new(label),
+            None => Node::new(id.to_string()),
+        };
+        if let Some(cost) = self.cost {
+            node = node.with_cost(cost);
+        }
+        if let Some(gang) = self.gang {
+            node = node.with_gang(gang)?;
+        }
+        if let Some(mutex) = self.mutex {
+            node = node.with_mutex(mutex)?;
+        }
+        if let Some(priority) = self.priority {
+            node = node.with_priority(priority);
+        }
+        if let Some(nice) = self.nice {
+            node = node.with_nice(nice)?;
+        }
+        if let Some(ionice) = self.ionice {
+            node = node.with_ionice(ionice);
+        }
+        if let Some(retries) = self.retries {
+            node = node.with_retries(retries);
+        }
+        if let Some(timeout) = self.timeout {
+            node = node.with_timeout(timeout)?;
+        }
+        if let Some(inputs) = self.inputs {
+            node = node.with_inputs(inputs)?;
+        }
+        if let Some(outputs) = self.outputs {
+            node = node.with_outputs(outputs)?;
+        }
+        if self.approval {
+            node = node.with_approval();
+        }
+        if let Some(event) = self.await_event {
+            node = node.with_awaited_event(event)?;
+        }
+        if let Some(command) = self.command {
+            node = node.with_command(command)?;
+        }
+        if let Some(role) = self.role {
+            node = node.with_role(role);
+        }
+        if let Some(isolation) = self.isolation {
+            node = node.with_isolation(isolation);
+        }
+        if let Some(cwd) = self.cwd {
+            node = node.with_cwd(cwd)?;
+        }
+        if let Some(env) = self.env {
+            node = node.with_env(env)?;
+        }
+        for (hook, command) in [(Hook::Pre, self.pre), (Hook::Post, self.post)] {
+            if let Some(command) = command {
+                node = node.with_hook(hook, command)?;
+            }
+        }
+        Ok(node)
+    }
+}
+
+/// Typed attributes of an edge statement like `producer -> consumer [ dependency = streaming ]`, see
+/// [`EdgeSpec::ATTRIBUTES`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EdgeSpec {
+    /// Label printed on the edge, e.g. by [`super::display_options::DisplayOptions::edge_labels`].
+    pub label: Option<String>,
+    /// Dependency of the edge's child on its parent.
+    pub dependency: Dependency,
+}
+
+impl EdgeSpec {
+    /// Attributes recognized in edge statements.
+    pub const ATTRIBUTES: &'static [AttributeSpec] = &[
+        AttributeSpec {
+            key: "label",
+            value: "string",
+            description: "Label printed on the edge, ignored when parsing",
+        },
+        AttributeSpec {
+            key: "dependency",
+            value: "completion or streaming",
+            description: "When the child may start relative to its parent",
+        },
+    ];
+
+    /// Parses the attributes of the edge statement `chain` like [`NodeSpec::parse`].
+    pub fn parse(
+        chain: &str,
+        attributes: &[(String, String)],
+        parse_mode: ParseMode,
+    ) -> Result<Self> {
+        check_keys(
+            &format!("edge {}", chain),
+            attributes,
+            EdgeSpec::ATTRIBUTES,
+            parse_mode,
+        )?;
+        let mut spec = EdgeSpec::default();
+        for (key, value) in attributes {
+            match key.as_str() {
+                "label" => spec.label = Some(value.clone()),
+                "dependency" => spec.dependency = Dependency::from_str(value)?,
+                _ => (),
+            }
+        }
+        Ok(spec)
+    }
+
+    /// Returns the key and value of every set attribute like [`NodeSpec::attributes`].
+    pub fn attributes(&self) -> Vec<(&'static str, String)> {
+        self.label
+            .iter()
+            .map(|label| ("label", label.clone()))
+            .chain([("dependency", self.dependency.to_string())])
+            .collect()
+    }
+}
+
+/// Checks that all keys of `attributes` of `statement` are `known` or [`GRAPHVIZ_ATTRIBUTES`], suggesting the
+/// closest known key for typos like `retires`.
+fn check_keys(
+    statement: &str,
+    attributes: &[(String, String)],
+    known: &[AttributeSpec],
+    parse_mode: ParseMode,
+) -> Result<()> {
+    for (key, _) in attributes {
+        if known.iter().any(|spec| spec.key == key) || GRAPHVIZ_ATTRIBUTES.contains(&key.as_str()) {
+            continue;
+        }
+        let suggestion = known
+            .iter()
+            .map(|spec| (edit_distance(key, spec.key), spec.key))
+            .filter(|(distance, _)| *distance <= 2)
+            .min()
+            .map(|(_, suggestion)| format!(", did you mean {}?", suggestion))
+            .unwrap_or_else(|| String::from("."));
+        let message = format!("Unknown attribute {} of {}{}", key, statement, suggestion);
+        match parse_mode {
+            ParseMode::Strict => {
+                return Err(anyhow!(
+                    "DirectedAcyclicGraph::from_str parsing error: {}",
+                    message
+                ))
+            }
+            ParseMode::Lenient => println!("{} The attribute is ignored.", message),
+        }
+    }
+    Ok(())
+}
+
+/// Parses the `value` of the attribute `key` of node `id`, e.g. a number or a boolean.
+fn parse_value<T: FromStr>(id: &str, key: &str, value: &str) -> Result<T>
+where
+    T::Err: fmt::Display,
+{
+    value.parse().map_err(|e| {
+        anyhow!(
+            "DirectedAcyclicGraph::from_str parsing error: Invalid {} {} of node {}: {}",
+            key,
+            value,
+            id,
+            e
+        )
+    })
+}
+
+/// Splits a comma-separated list of paths like `src/a.c,src/a.h`.
+fn split_paths(paths: &str) -> Vec<String> {
+    paths
+        .split(',')
+        .map(|path| path.trim().to_string())
+        .collect()
+}
+
+/// Number of single-character insertions, deletions and substitutions turning `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
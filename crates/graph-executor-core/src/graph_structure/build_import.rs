@@ -0,0 +1,557 @@
+use super::{edge::Edge, graph::DirectedAcyclicGraph, node::Node};
+use anyhow::{anyhow, Result};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Maximum depth of nested variable references, guarding against self-referencing variables.
+const MAX_EXPANSION_DEPTH: usize = 32;
+
+/// Rule of a Makefile: its prerequisites and (unexpanded) recipe lines.
+#[derive(Default)]
+struct MakeRule {
+    prerequisites: Vec<String>,
+    recipe: Vec<String>,
+}
+
+/// Variable of a Makefile, either expanded when it is used (`=`) or when it is defined (`:=`).
+enum MakeVariable {
+    Recursive(String),
+    Simple(String),
+}
+
+impl DirectedAcyclicGraph {
+    /// Creates [`DirectedAcyclicGraph`] from a path to a Makefile (see [`DirectedAcyclicGraph::from_makefile_str`]).
+    pub fn from_makefile(file_path: &str) -> Result<Self> {
+        DirectedAcyclicGraph::from_makefile_str(&std::fs::read_to_string(file_path)?)
+    }
+
+    /// Creates [`DirectedAcyclicGraph`] from the explicit rules of a Makefile: every target becomes a node running
+    /// its recipe (lines joined with `&&`) and every prerequisite which is itself a target becomes a parent.
+    /// Prerequisites which are not targets (i.e. source files) are ignored.
+    ///
+    /// Supported are variables (`=`, `:=`, `::=`, `?=`, `+=`, falling back to environment variables), the automatic
+    /// variables `$@`, `$<`, `$^` and `$?`, the recipe prefixes `@`, `-` and `+` and special targets like `.PHONY`
+    /// (which are ignored). Pattern rules, functions, conditionals and includes are rejected.
+    pub fn from_makefile_str(makefile: &str) -> Result<Self> {
+        let mut variables: BTreeMap<String, MakeVariable> = BTreeMap::new();
+        let mut rules: BTreeMap<String, MakeRule> = BTreeMap::new();
+        let mut target_order: Vec<String> = vec![];
+        // Targets receiving the following recipe lines, [`None`] before the first rule
+        let mut current_targets: Option<Vec<String>> = None;
+
+        for line in join_continuation_lines(makefile, '\\') {
+            // Recipe lines start with a tab
+            if let Some(recipe_line) = line.strip_prefix('\t') {
+                let targets = current_targets.as_ref().ok_or(anyhow!(
+                    "Makefile parsing error: Recipe line {:?} without rule.",
+                    line
+                ))?;
+                for target in targets {
+                    if let Some(rule) = rules.get_mut(target) {
+                        rule.recipe.push(recipe_line.trim().to_string());
+                    }
+                }
+                continue;
+            }
+
+            let line = match line.find('#') {
+                Some(comment_start) => &line[..comment_start],
+                None => &line,
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let first_word = line.split_whitespace().next().unwrap_or_default();
+            if [
+                "include", "-include", "sinclude", "ifeq", "ifneq", "ifdef", "ifndef", "else",
+                "endif", "define", "endef", "vpath",
+            ]
+            .contains(&first_word)
+            {
+                return Err(anyhow!(
+                    "Makefile parsing error: Unsupported directive {:?}.",
+                    line.trim()
+                ));
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+
+            // Variable assignments like `CC = gcc` or `CFLAGS += -O2`
+            let (equals, colon) = (line.find('='), line.find(':'));
+            if let Some(equals) = equals.filter(|e| colon.is_none_or(|c| c + 3 >= *e)) {
+                let name_and_operator = &line[..equals];
+                let name = name_and_operator.trim_end_matches([':', '?', '+']).trim();
+                let operator = name_and_operator
+                    .trim_start_matches(|c: char| c != ':' && c != '?' && c != '+');
+                let operator = if operator.starts_with(':') {
+                    ":"
+                } else {
+                    operator.trim()
+                };
+                let value = line[equals + 1..].trim().to_string();
+                let variable = match operator {
+                    ":" => MakeVariable::Simple(expand_make(&value, &variables, None, 0)?),
+                    "?" if variables.contains_key(name) => continue,
+                    "+" => match variables.remove(name) {
+                        Some(MakeVariable::Simple(previous)) => MakeVariable::Simple(format!(
+                            "{} {}",
+                            previous,
+                            expand_make(&value, &variables, None, 0)?
+                        )),
+                        Some(MakeVariable::Recursive(previous)) => {
+                            MakeVariable::Recursive(format!("{} {}", previous, value))
+                        }
+                        None => MakeVariable::Recursive(value),
+                    },
+                    _ => MakeVariable::Recursive(value),
+                };
+                variables.insert(name.to_string(), variable);
+                continue;
+            }
+
+            // Rules like `main.o: main.c util.h` or `clean: ; rm -f *.o`
+            let Some(colon) = colon else {
+                return Err(anyhow!(
+                    "Makefile parsing error: Unsupported line {:?}.",
+                    line.trim()
+                ));
+            };
+            let (targets, rest) = (&line[..colon], line[colon + 1..].trim_start_matches(':'));
+            let (prerequisites, inline_recipe) = match rest.split_once(';') {
+                Some((prerequisites, recipe)) => (prerequisites, Some(recipe.trim())),
+                None => (rest, None),
+            };
+            let targets: Vec<String> = expand_make(targets, &variables, None, 0)?
+                .split_whitespace()
+                .map(str::to_string)
+                .collect();
+            if targets.iter().any(|target| target.contains('%')) {
+                return Err(anyhow!(
+                    "Makefile parsing error: Pattern rules are not supported: {:?}.",
+                    line.trim()
+                ));
+            }
+            // Special targets like `.PHONY` or `.SUFFIXES` are ignored together with their recipes
+            let targets: Vec<String> = targets
+                .into_iter()
+                .filter(|target| {
+                    !(target.len() > 1
+                        && target.starts_with('.')
+                        && target[1..]
+                            .chars()
+                            .all(|c| c.is_ascii_uppercase() || c == '_'))
+                })
+                .collect();
+            let prerequisites: Vec<String> = expand_make(prerequisites, &variables, None, 0)?
+                .split_whitespace()
+                .filter(|prerequisite| *prerequisite != "|")
+                .map(str::to_string)
+                .collect();
+            for target in &targets {
+                let rule = rules.entry(target.clone()).or_insert_with(|| {
+                    target_order.push(target.clone());
+                    MakeRule::default()
+                });
+                rule.prerequisites.extend(prerequisites.iter().cloned());
+                if let Some(inline_recipe) = inline_recipe.filter(|r| !r.is_empty()) {
+                    rule.recipe.push(inline_recipe.to_string());
+                }
+            }
+            current_targets = Some(targets);
+        }
+
+        let mut nodes = vec![];
+        let mut edges = vec![];
+        for target in &target_order {
+            let rule = &rules[target];
+            let mut commands = vec![];
+            for recipe_line in &rule.recipe {
+                let expanded = expand_make(
+                    recipe_line,
+                    &variables,
+                    Some((target, &rule.prerequisites)),
+                    0,
+                )?;
+                // Strip the prefixes `@` (silent), `+` (always executed) and `-` (ignore errors)
+                let command = expanded.trim_start_matches(['@', '+', '-', ' ']);
+                let ignore_errors = expanded[..expanded.len() - command.len()].contains('-');
+                let command = command.trim();
+                if !command.is_empty() {
+                    commands.push(match ignore_errors {
+                        true => format!("{{ {}; }} || true", command),
+                        false => command.to_string(),
+                    });
+                }
+            }
+            let node = match commands.is_empty() {
+                true => Node::new(target.clone()),
+                false => Node::new(target.clone()).with_command(commands.join(" && "))?,
+            };
+            nodes.push((target.clone(), node));
+
+            let parents: BTreeSet<&String> = rule
+                .prerequisites
+                .iter()
+                .filter(|prerequisite| rules.contains_key(*prerequisite))
+                .collect();
+            for parent in parents {
+                edges.push(Edge::new(parent.clone(), target.clone()));
+            }
+        }
+
+        DirectedAcyclicGraph::new(nodes, edges)
+    }
+
+    /// Creates [`DirectedAcyclicGraph`] from a path to a `build.ninja` file (see [`DirectedAcyclicGraph::from_ninja_str`]).
+    pub fn from_ninja(file_path: &str) -> Result<Self> {
+        DirectedAcyclicGraph::from_ninja_str(&std::fs::read_to_string(file_path)?)
+    }
+
+    /// Creates [`DirectedAcyclicGraph`] from the build statements of a `build.ninja` file: every build statement
+    /// becomes a node (identified by its first output) running its rule's `command`, and every build producing one of
+    /// its explicit, implicit or order-only inputs becomes a parent. `phony` builds become nodes without a command.
+    ///
+    /// Supported are top-level, rule and build variables, `$in`, `$out` and the escapes `$$`, `$ ` and `$:`.
+    /// `include` and `subninja` are rejected.
+    pub fn from_ninja_str(ninja: &str) -> Result<Self> {
+        /// Statement the indented variable bindings belong to.
+        enum Block {
+            Rule(String),
+            Build(usize),
+            Ignored,
+        }
+        /// Build statement with its outputs, explicit inputs, all inputs, rule and bindings.
+        struct Build {
+            outputs: Vec<String>,
+            explicit_inputs: Vec<String>,
+            inputs: Vec<String>,
+            rule: String,
+            bindings: BTreeMap<String, String>,
+        }
+
+        let mut variables: BTreeMap<String, String> = BTreeMap::new();
+        let mut rules: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+        let mut builds: Vec<Build> = vec![];
+        let mut block = Block::Ignored;
+
+        for line in join_continuation_lines(ninja, '$') {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            // Indented variable bindings of the current rule or build
+            if line.starts_with([' ', '\t']) {
+                let (key, value) = line.trim().split_once('=').ok_or(anyhow!(
+                    "Ninja parsing error: Invalid binding {:?}.",
+                    line.trim()
+                ))?;
+                let (key, value) = (key.trim().to_string(), value.trim().to_string());
+                match block {
+                    Block::Rule(ref rule) => {
+                        rules.entry(rule.clone()).or_default().insert(key, value);
+                    }
+                    Block::Build(build) => {
+                        let expanded = expand_ninja(&value, &|name| {
+                            builds[build]
+                                .bindings
+                                .get(name)
+                                .or(variables.get(name))
+                                .cloned()
+                        })?;
+                        builds[build].bindings.insert(key, expanded);
+                    }
+                    Block::Ignored => (),
+                }
+                continue;
+            }
+
+            let (keyword, rest) = line.split_once(' ').unwrap_or((&line, ""));
+            match keyword {
+                "rule" => {
+                    block = Block::Rule(rest.trim().to_string());
+                    rules.entry(rest.trim().to_string()).or_default();
+                }
+                "build" => {
+                    let lookup = |name: &str| variables.get(name).cloned();
+                    let colon = find_unescaped(rest, ':').ok_or(anyhow!(
+                        "Ninja parsing error: Build statement {:?} without ':'.",
+                        line
+                    ))?;
+                    let outputs: Vec<String> = split_ninja_paths(&rest[..colon])
+                        .into_iter()
+                        .filter(|output| output != "|")
+                        .map(|output| expand_ninja(&output, &lookup))
+                        .collect::<Result<_>>()?;
+                    let mut words = split_ninja_paths(&rest[colon + 1..]).into_iter();
+                    let rule = words.next().ok_or(anyhow!(
+                        "Ninja parsing error: Build statement {:?} without rule.",
+                        line
+                    ))?;
+                    let (mut explicit_inputs, mut inputs, mut explicit) = (vec![], vec![], true);
+                    for word in words {
+                        match word.as_str() {
+                            "|" | "||" | "|@" => explicit = false,
+                            _ => {
+                                let input = expand_ninja(&word, &lookup)?;
+                                if explicit {
+                                    explicit_inputs.push(input.clone());
+                                }
+                                inputs.push(input);
+                            }
+                        }
+                    }
+                    if outputs.is_empty() {
+                        return Err(anyhow!(
+                            "Ninja parsing error: Build statement {:?} without outputs.",
+                            line
+                        ));
+                    }
+                    builds.push(Build {
+                        outputs,
+                        explicit_inputs,
+                        inputs,
+                        rule,
+                        bindings: BTreeMap::new(),
+                    });
+                    block = Block::Build(builds.len() - 1);
+                }
+                "default" | "pool" => block = Block::Ignored,
+                "include" | "subninja" => {
+                    return Err(anyhow!(
+                        "Ninja parsing error: Unsupported statement {:?}.",
+                        line
+                    ))
+                }
+                _ => {
+                    // Top-level variables like `cflags = -O2`
+                    let (key, value) = line
+                        .split_once('=')
+                        .ok_or(anyhow!("Ninja parsing error: Unsupported line {:?}.", line))?;
+                    let value = expand_ninja(value.trim(), &|name| variables.get(name).cloned())?;
+                    variables.insert(key.trim().to_string(), value);
+                    block = Block::Ignored;
+                }
+            }
+        }
+
+        let producers: BTreeMap<&str, &str> = builds
+            .iter()
+            .flat_map(|build| {
+                build
+                    .outputs
+                    .iter()
+                    .map(|output| (output.as_str(), build.outputs[0].as_str()))
+            })
+            .collect();
+        let mut nodes = vec![];
+        let mut edges = vec![];
+        for build in &builds {
+            let node_id = build.outputs[0].clone();
+            let node = match build.rule.as_str() {
+                "phony" => Node::new(node_id.clone()),
+                rule => {
+                    let rule_bindings = rules.get(rule).ok_or(anyhow!(
+                        "Ninja parsing error: Unknown rule {} of build {}.",
+                        rule,
+                        node_id
+                    ))?;
+                    let (inputs, outputs) =
+                        (build.explicit_inputs.join(" "), build.outputs.join(" "));
+                    // Rule bindings are expanded in the scope of the build
+                    let build_lookup = |name: &str| -> Option<String> {
+                        match name {
+                            "in" => Some(inputs.clone()),
+                            "out" => Some(outputs.clone()),
+                            name => build.bindings.get(name).or(variables.get(name)).cloned(),
+                        }
+                    };
+                    let lookup = |name: &str| -> Option<String> {
+                        match (build_lookup(name), rule_bindings.get(name)) {
+                            (Some(value), _) => Some(value),
+                            (None, Some(value)) => expand_ninja(value, &build_lookup).ok(),
+                            (None, None) => None,
+                        }
+                    };
+                    let command = rule_bindings.get("command").ok_or(anyhow!(
+                        "Ninja parsing error: Rule {} has no command.",
+                        rule
+                    ))?;
+                    Node::new(node_id.clone()).with_command(expand_ninja(command, &lookup)?)?
+                }
+            };
+            nodes.push((node_id.clone(), node));
+
+            let parents: BTreeSet<&str> = build
+                .inputs
+                .iter()
+                .filter_map(|input| producers.get(input.as_str()).copied())
+                .filter(|parent| *parent != node_id)
+                .collect();
+            for parent in parents {
+                edges.push(Edge::new(parent.to_string(), node_id.clone()));
+            }
+        }
+
+        DirectedAcyclicGraph::new(nodes, edges)
+    }
+}
+
+/// Splits `text` into logical lines, joining lines ending with `continuation` with the following line.
+fn join_continuation_lines(text: &str, continuation: char) -> Vec<String> {
+    let mut lines = vec![];
+    let mut current: Option<String> = None;
+    for line in text.lines() {
+        let line = match current.take() {
+            Some(previous) => previous + " " + line.trim_start(),
+            None => line.to_string(),
+        };
+        // An even number of `$` at the end of a ninja line are escaped dollars, not a continuation
+        let trailing = line.len() - line.trim_end_matches(continuation).len();
+        match trailing % 2 == 1 {
+            true => current = Some(line[..line.len() - 1].trim_end().to_string()),
+            false => lines.push(line),
+        }
+    }
+    lines.extend(current);
+    lines
+}
+
+/// Expands the variable references in a Makefile `text`. `automatic` provides the target and prerequisites
+/// of the rule whose recipe is expanded.
+fn expand_make(
+    text: &str,
+    variables: &BTreeMap<String, MakeVariable>,
+    automatic: Option<(&str, &[String])>,
+    depth: usize,
+) -> Result<String> {
+    if depth > MAX_EXPANSION_DEPTH {
+        return Err(anyhow!(
+            "Makefile parsing error: Variable references nested too deeply in {:?}.",
+            text
+        ));
+    }
+    let mut expanded = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            expanded.push(c);
+            continue;
+        }
+        let name = match chars.next() {
+            Some('$') => {
+                expanded.push('$');
+                continue;
+            }
+            Some(open @ ('(' | '{')) => {
+                let close = if open == '(' { ')' } else { '}' };
+                let name: String = chars.by_ref().take_while(|c| *c != close).collect();
+                if name.contains([' ', ',', '$']) {
+                    return Err(anyhow!(
+                        "Makefile parsing error: Functions and computed variable names are not supported: $({}).",
+                        name
+                    ));
+                }
+                name
+            }
+            Some(name) => name.to_string(),
+            None => break,
+        };
+        let value = match (name.as_str(), automatic) {
+            ("@", Some((target, _))) => target.to_string(),
+            ("<", Some((_, prerequisites))) => prerequisites.first().cloned().unwrap_or_default(),
+            ("^" | "?", Some((_, prerequisites))) => {
+                let mut unique = vec![];
+                for prerequisite in prerequisites {
+                    if !unique.contains(prerequisite) {
+                        unique.push(prerequisite.clone());
+                    }
+                }
+                unique.join(" ")
+            }
+            _ => match variables.get(&name) {
+                Some(MakeVariable::Simple(value)) => value.clone(),
+                Some(MakeVariable::Recursive(value)) => {
+                    expand_make(value, variables, automatic, depth + 1)?
+                }
+                None => std::env::var(&name).unwrap_or_default(),
+            },
+        };
+        expanded.push_str(&value);
+    }
+    Ok(expanded)
+}
+
+/// Returns the byte position of the first `target` character in a ninja `text` which is not escaped with `$`.
+fn find_unescaped(text: &str, target: char) -> Option<usize> {
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '$' => {
+                chars.next();
+            }
+            c if c == target => return Some(i),
+            _ => (),
+        }
+    }
+    None
+}
+
+/// Splits a ninja path list at spaces which are not escaped with `$`, keeping the escapes.
+fn split_ninja_paths(text: &str) -> Vec<String> {
+    let mut paths = vec![];
+    let mut current = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '$' => {
+                current.push(c);
+                current.extend(chars.next());
+            }
+            ' ' | '\t' => {
+                if !current.is_empty() {
+                    paths.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        paths.push(current);
+    }
+    paths
+}
+
+/// Expands the variable references (`$name`, `${name}`) and escapes (`$$`, `$ `, `$:`) in a ninja `text`,
+/// looking variables up with `lookup`. Unknown variables expand to an empty string.
+fn expand_ninja(text: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<String> {
+    let mut expanded = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            expanded.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some(escaped @ ('$' | ' ' | ':')) => expanded.push(escaped),
+            Some('{') => {
+                let name: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                expanded.push_str(&lookup(&name).unwrap_or_default());
+            }
+            Some(first) if first.is_ascii_alphanumeric() || first == '_' || first == '-' => {
+                let mut name = String::from(first);
+                while let Some(next) =
+                    chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+                {
+                    name.push(next);
+                }
+                expanded.push_str(&lookup(&name).unwrap_or_default());
+            }
+            other => {
+                return Err(anyhow!(
+                    "Ninja parsing error: Invalid escape ${} in {:?}.",
+                    other.map(String::from).unwrap_or_default(),
+                    text
+                ))
+            }
+        }
+    }
+    Ok(expanded)
+}
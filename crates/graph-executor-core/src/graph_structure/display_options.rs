@@ -0,0 +1,12 @@
+/// Options of printing a [`super::graph::DirectedAcyclicGraph`] as DOT, see
+/// [`super::graph::DirectedAcyclicGraph::display`]. The default options print the graph such that it parses to the
+/// same graph, other options tailor exports to their audience.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DisplayOptions {
+    /// Whether edges are labelled with their [`super::edge::Dependency`], e.g. for rendering with Graphviz.
+    pub edge_labels: bool,
+    /// Whether the `args` of nodes are left out of their labels, e.g. because they contain secrets.
+    pub hide_args: bool,
+    /// Whether nodes are identified by their index instead of their (possibly long) string identifier.
+    pub compact_ids: bool,
+}
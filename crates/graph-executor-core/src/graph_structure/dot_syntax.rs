@@ -0,0 +1,152 @@
+use anyhow::{anyhow, Result};
+
+/// Lexical token of the subset of the DOT language understood by [`super::graph::DirectedAcyclicGraph`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Token {
+    /// Bare identifier, e.g. `a`, `node_1`, `0` or `über`.
+    Id(String),
+    /// Double-quoted identifier with its escape sequences already resolved, e.g. `"my node"`.
+    QuotedId(String),
+    /// Edge operator `->`.
+    Arrow,
+    /// Opening bracket of an attribute list `[`.
+    OpenBracket,
+    /// Closing bracket of an attribute list `]`.
+    CloseBracket,
+    /// Opening brace of a graph body `{`.
+    OpenBrace,
+    /// Closing brace of a graph body `}`.
+    CloseBrace,
+    /// Assignment inside an attribute list `=`.
+    Equals,
+    /// Separator inside an attribute list `,`.
+    Comma,
+    /// Statement separator: `;` or a line break outside of a quoted identifier.
+    Separator,
+}
+
+impl Token {
+    /// Returns the identifier's value if the token is either a bare or a quoted identifier.
+    pub(crate) fn as_id(&self) -> Option<&str> {
+        match self {
+            Token::Id(id) | Token::QuotedId(id) => Some(id),
+            _ => None,
+        }
+    }
+}
+
+/// Splits a DOT string into [`Token`]s.
+///
+/// Quoted identifiers may contain spaces, punctuation, unicode and the escape sequences
+/// `\"`, `\\`, `\n`, `\l` and `\r` (the latter three are read as a line break).
+pub(crate) fn tokenize(dot_string: &str) -> Result<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = dot_string.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' | ';' => tokens.push(Token::Separator),
+            c if c.is_whitespace() => (),
+            '[' => tokens.push(Token::OpenBracket),
+            ']' => tokens.push(Token::CloseBracket),
+            '{' => tokens.push(Token::OpenBrace),
+            '}' => tokens.push(Token::CloseBrace),
+            '=' => tokens.push(Token::Equals),
+            ',' => tokens.push(Token::Comma),
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                tokens.push(Token::Arrow);
+            }
+            '"' => {
+                let mut id = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('"') => id.push('"'),
+                            Some('\\') => id.push('\\'),
+                            Some('n') | Some('l') | Some('r') => id.push('\n'),
+                            Some('\n') => (), // Line continuation
+                            Some(other) => {
+                                id.push('\\');
+                                id.push(other);
+                            }
+                            None => {
+                                return Err(anyhow!(
+                                    "DOT parsing error: Unterminated escape sequence in quoted identifier."
+                                ))
+                            }
+                        },
+                        Some(other) => id.push(other),
+                        None => {
+                            return Err(anyhow!(
+                                "DOT parsing error: Unterminated quoted identifier \"{}.",
+                                id
+                            ))
+                        }
+                    }
+                }
+                tokens.push(Token::QuotedId(id));
+            }
+            c if is_id_char(c) || c == '-' || c == '.' => {
+                let mut id = String::from(c);
+                while let Some(&next) = chars.peek() {
+                    if is_id_char(next) || next == '.' {
+                        id.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Id(id));
+            }
+            other => {
+                return Err(anyhow!(
+                    "DOT parsing error: Unexpected character '{}'.",
+                    other
+                ))
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Escapes `"`, `\` and line breaks so that `string` can be written inside a quoted DOT identifier.
+pub(crate) fn escape(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+    for c in string.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Formats a node identifier for DOT output, quoting (and escaping) it only if it is not a valid bare identifier.
+pub(crate) fn format_id(id: &str) -> String {
+    if is_bare_id(id) {
+        id.to_string()
+    } else {
+        format!("\"{}\"", escape(id))
+    }
+}
+
+/// Characters allowed in bare identifiers (DOT allows any non-ASCII character).
+fn is_id_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || !c.is_ascii()
+}
+
+/// Checks whether `id` can be written without quotes, i.e. it is either a numeral or an
+/// alphanumeric string not starting with a digit and not being a DOT keyword.
+fn is_bare_id(id: &str) -> bool {
+    let is_numeral = !id.is_empty() && id.chars().all(|c| c.is_ascii_digit());
+    let is_alphanumeric = id.chars().next().is_some_and(|c| !c.is_ascii_digit())
+        && id.chars().all(is_id_char)
+        && !["node", "edge", "graph", "digraph", "subgraph", "strict"]
+            .contains(&id.to_ascii_lowercase().as_str());
+    is_numeral || is_alphanumeric
+}
@@ -0,0 +1,22 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+/// Error returned when a [`super::graph::DirectedAcyclicGraph`] is constructed from multiple
+/// [`super::node::Node`]s sharing the same string identifier.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DuplicateNodeId {
+    /// Every string identifier which is used by more than one node (each listed once).
+    pub node_ids: Vec<String>,
+}
+
+impl fmt::Display for DuplicateNodeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Multiple nodes declared with the same identifier: {:?}",
+            self.node_ids
+        )
+    }
+}
+
+impl core::error::Error for DuplicateNodeId {}
@@ -0,0 +1,117 @@
+#[cfg(feature = "std")]
+use super::dot_syntax::{tokenize, Token};
+use alloc::string::String;
+use anyhow::{anyhow, Error, Result};
+use core::{fmt, str::FromStr};
+#[cfg(feature = "std")]
+use std::string::ToString;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Edge {
+    /// Directed edge (connection) between two nodes.
+    /// First index indicates the parent and the second the child node.
+    pub(crate) parent: String,
+    pub(crate) child: String,
+    /// When the child may start relative to its parent.
+    pub(crate) dependency: Dependency,
+}
+
+impl Edge {
+    /// Creates new [`Edge`] from two node indeces returned by [`petgraph::prelude::StableDiGraph`] when adding [`super::node::Node`]s.
+    pub fn new(parent: String, child: String) -> Self {
+        Edge {
+            parent,
+            child,
+            dependency: Dependency::Completion,
+        }
+    }
+
+    /// Get the [`Dependency`] of the child on its parent.
+    pub fn dependency(&self) -> Dependency {
+        self.dependency
+    }
+
+    /// Sets the [`Dependency`] of the child on its parent.
+    pub fn with_dependency(mut self, dependency: Dependency) -> Self {
+        self.dependency = dependency;
+        self
+    }
+}
+
+/// Dependency of the child of an [`Edge`] on its parent, stored as the weight of the edges of a
+/// [`super::graph::DirectedAcyclicGraph`].
+#[derive(Clone, Debug, PartialEq, Eq, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Dependency {
+    /// The child starts once its parent has been executed.
+    #[default]
+    Completion,
+    /// The child starts once its parent has been executed or, while the parent is still executing, has emitted its
+    /// first progress mark (see [`super::node::Node::progress_marks`]), e.g. so that a consumer processes the first
+    /// chunks of a long-running producer's output while the producer writes the rest.
+    Streaming,
+}
+
+impl fmt::Display for Dependency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Dependency::Completion => write!(f, "completion"),
+            Dependency::Streaming => write!(f, "streaming"),
+        }
+    }
+}
+
+impl FromStr for Dependency {
+    type Err = Error;
+    /// Parses [`Dependency`] from a string like: "completion" or "streaming".
+    fn from_str(dependency: &str) -> Result<Self> {
+        match dependency {
+            "completion" => Ok(Dependency::Completion),
+            "streaming" => Ok(Dependency::Streaming),
+            _ => Err(anyhow!(
+                "Dependency::from_str parsing error: Invalid dependency {}, expected completion or streaming.",
+                dependency
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromStr for Edge {
+    type Err = Error;
+    /// Parses [`Edge`] from a string like: "0 -> 1 [ ]" or "\"my node\" -> b". Its dependency is always
+    /// [`Dependency::Completion`], see [`super::graph::DirectedAcyclicGraph`]'s `FromStr` for the `dependency`
+    /// attribute.
+    ///
+    /// The following two [`Edge`]s are identical:
+    /// ```
+    /// # use graph_executor_core::graph_structure::edge::Edge;
+    /// # use std::str::FromStr;
+    /// let edge_from_str = Edge::from_str("0 -> 1 [ ]").unwrap();
+    /// let edge_new = Edge::new(String::from("0"), String::from("1"));
+    /// assert_eq!(edge_from_str, edge_new);
+    /// ```
+    fn from_str(edge_string: &str) -> Result<Self> {
+        let tokens = tokenize(edge_string)?;
+
+        Ok(Edge {
+            parent: tokens
+                .first()
+                .and_then(|t| t.as_id())
+                .ok_or(anyhow!(
+                    "Edge::from_str parsing error: Could not find first node index."
+                ))?
+                .to_string(),
+            child: match (tokens.get(1), tokens.get(2).and_then(|t| t.as_id())) {
+                (Some(Token::Arrow), Some(child)) => child.to_string(),
+                _ => {
+                    return Err(anyhow!(
+                        "Edge::from_str parsing error: Could not find second node index."
+                    ))
+                }
+            },
+            dependency: Dependency::Completion,
+        })
+    }
+}
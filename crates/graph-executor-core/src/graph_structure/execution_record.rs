@@ -0,0 +1,14 @@
+use core::time::Duration;
+
+/// Record of how an executed [`super::node::Node`] was executed, shared with all workers of a run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ExecutionRecord {
+    /// Index of the worker in the run's worker table which executed the node.
+    pub worker_id: usize,
+    /// Number of bytes of output the node produced.
+    pub output_bytes: u64,
+    /// Time after the start of the run at which the node finished executing.
+    pub finished_after: Duration,
+}
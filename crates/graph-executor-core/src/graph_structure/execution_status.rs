@@ -0,0 +1,109 @@
+use anyhow::{anyhow, Error, Result};
+use core::{fmt, str::FromStr};
+
+#[derive(Clone, Debug, PartialEq, Eq, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[repr(u8)]
+pub enum ExecutionStatus {
+    /// The associated [`super::node::Node`]'s `execute()` method is finished.
+    Executed,
+    /// The associated [`super::node::Node`] currently runs its `execute()` method.
+    Executing,
+    /// The associated [`super::node::Node`]'s `execute()` method is ready to run;
+    /// all its parent [`super::node::Node`]s have run their respective `execute()` methods.
+    Executable,
+    /// The associated [`super::node::Node`]'s `execute()` method is not ready to run;
+    /// not all its parent [`super::node::Node`]s have run their respective `execute()` methods.
+    NonExecutable,
+    /// The associated [`super::node::Node`]'s `execute()` method returned an error.
+    Failed,
+    /// The associated [`super::node::Node`] is never executed, because one of its ancestors failed.
+    Skipped,
+    /// The associated [`super::node::Node`] was executing when the run was cancelled after another node failed.
+    Cancelled,
+}
+
+impl ExecutionStatus {
+    /// Checks whether the associated [`super::node::Node`] has finished: it was executed, has failed or was skipped
+    /// or cancelled.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            ExecutionStatus::Executed
+                | ExecutionStatus::Failed
+                | ExecutionStatus::Skipped
+                | ExecutionStatus::Cancelled
+        )
+    }
+}
+
+impl From<ExecutionStatus> for u8 {
+    /// Encodes [`ExecutionStatus`] as a single byte, e.g. to store it in an atomic status slot.
+    fn from(execution_status: ExecutionStatus) -> Self {
+        execution_status as u8
+    }
+}
+
+impl TryFrom<u8> for ExecutionStatus {
+    type Error = Error;
+    /// Decodes [`ExecutionStatus`] from a byte encoded by `u8::from`.
+    fn try_from(byte: u8) -> Result<Self> {
+        match byte {
+            0 => Ok(ExecutionStatus::Executed),
+            1 => Ok(ExecutionStatus::Executing),
+            2 => Ok(ExecutionStatus::Executable),
+            3 => Ok(ExecutionStatus::NonExecutable),
+            4 => Ok(ExecutionStatus::Failed),
+            5 => Ok(ExecutionStatus::Skipped),
+            6 => Ok(ExecutionStatus::Cancelled),
+            _ => Err(anyhow!("Invalid execution status byte {}.", byte)),
+        }
+    }
+}
+
+impl fmt::Display for ExecutionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ExecutionStatus::Executed => "Executed",
+                ExecutionStatus::Executing => "Executing",
+                ExecutionStatus::Executable => "Executable",
+                ExecutionStatus::NonExecutable => "NonExecutable",
+                ExecutionStatus::Failed => "Failed",
+                ExecutionStatus::Skipped => "Skipped",
+                ExecutionStatus::Cancelled => "Cancelled",
+            }
+        )
+    }
+}
+
+impl FromStr for ExecutionStatus {
+    type Err = Error;
+    /// Parses [`ExecutionStatus`] from a string like: "Executed".
+    ///
+    /// The following two [`ExecutionStatus`] are identical:
+    /// ```
+    /// # use graph_executor_core::graph_structure::execution_status::ExecutionStatus;
+    /// # use std::str::FromStr;
+    /// let execution_status_from_str = ExecutionStatus::from_str("Executed").unwrap();
+    /// let execution_status_direct = ExecutionStatus::Executed;
+    /// assert_eq!(execution_status_from_str, execution_status_direct);
+    /// ```
+    fn from_str(execution_status_string: &str) -> Result<Self> {
+        match execution_status_string {
+            "Executed" => Ok(ExecutionStatus::Executed),
+            "Executing" => Ok(ExecutionStatus::Executing),
+            "Executable" => Ok(ExecutionStatus::Executable),
+            "NonExecutable" => Ok(ExecutionStatus::NonExecutable),
+            "Failed" => Ok(ExecutionStatus::Failed),
+            "Skipped" => Ok(ExecutionStatus::Skipped),
+            "Cancelled" => Ok(ExecutionStatus::Cancelled),
+            _ => Err(anyhow!(
+                "ExecutionStatus::from_str parsing error: Invalid execution status."
+            )),
+        }
+    }
+}
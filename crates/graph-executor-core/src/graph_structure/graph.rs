@@ -0,0 +1,1426 @@
+use super::{
+    attempt::Hook,
+    duplicate_node_id::DuplicateNodeId,
+    edge::{Dependency, Edge},
+    execution_status::ExecutionStatus,
+    graph_limits::GraphLimits,
+    node::Node,
+    node_ids::NodeIds,
+    node_progress::NodeProgress,
+    node_role::NodeRole,
+    parse_mode::ParseMode,
+};
+#[cfg(feature = "std")]
+use super::{
+    attribute_spec::{EdgeSpec, NodeSpec},
+    display_options::DisplayOptions,
+    dot_syntax::{self, Token},
+    include::{Fragment, IncludeContext},
+};
+use crate::as_from_bytes::AsFromBytes;
+use alloc::{
+    collections::btree_map::Entry,
+    collections::BTreeMap,
+    collections::BTreeSet,
+    collections::VecDeque,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use anyhow::Error;
+use anyhow::{anyhow, Ok, Result};
+use core::{
+    ops::{Index, IndexMut},
+    time::Duration,
+};
+use petgraph::{
+    acyclic::Acyclic, algo::has_path_connecting, graph::NodeIndex, prelude::StableDiGraph,
+    Direction,
+};
+use sha2::{Digest, Sha256};
+#[cfg(feature = "std")]
+use std::{
+    fmt,
+    fs::{read_to_string, write},
+    str::FromStr,
+};
+
+/// This struct is a wrapper for [`petgraph::prelude::StableDiGraph`] implementation.
+///
+/// Nodes can carry a payload of type `P` next to their [`Node`], e.g. the Rust computation executing them, see
+/// [`DirectedAcyclicGraph::with_payloads`]. Payloads belong to the process which attached them: they are neither
+/// serialized nor compared, so a graph read from shared memory or a file has none.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(bound = ""))]
+pub struct DirectedAcyclicGraph<P = ()> {
+    /// [`petgraph::prelude::StableDiGraph`]
+    #[cfg_attr(feature = "schema", schemars(with = "SerializedGraph"))]
+    graph: StableDiGraph<Node, Dependency>,
+    /// String identifiers of all nodes, indexed by their [`NodeIndex`].
+    #[cfg_attr(feature = "schema", schemars(with = "Vec<String>"))]
+    node_ids: NodeIds,
+    /// Size limits checked on construction and mutation.
+    limits: GraphLimits,
+    /// Number of writes which changed nodes of the graph, see [`DirectedAcyclicGraph::stamp_changes`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    generation: u64,
+    /// Generation in which each node last changed by its string identifier, without nodes which never changed.
+    #[cfg_attr(feature = "serde", serde(default))]
+    changed_in: BTreeMap<String, u64>,
+    /// Payloads of the nodes by their [`NodeIndex`], without nodes which have none.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[cfg_attr(feature = "schema", schemars(skip))]
+    payloads: BTreeMap<NodeIndex, P>,
+}
+
+/// Serialized form of a [`petgraph::prelude::StableDiGraph`], describing the `graph` of a [`DirectedAcyclicGraph`]
+/// in its JSON Schema.
+#[cfg(feature = "schema")]
+#[allow(dead_code)]
+#[derive(schemars::JsonSchema)]
+struct SerializedGraph {
+    /// Nodes in the order of their indices, without removed nodes.
+    nodes: Vec<Node>,
+    /// Indices of removed nodes.
+    node_holes: Vec<u32>,
+    /// Always `"directed"`.
+    edge_property: String,
+    /// Parent index, child index and dependency of every edge, `null` for removed edges.
+    edges: Vec<Option<(u32, u32, Dependency)>>,
+}
+
+#[cfg(feature = "std")]
+impl<P> fmt::Display for DirectedAcyclicGraph<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.display(DisplayOptions::default()).fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<P> DirectedAcyclicGraph<P> {
+    /// Get a printer of the graph as DOT with the given [`DisplayOptions`]. With the default options, it prints the
+    /// graph like its [`fmt::Display`] implementation.
+    pub fn display(&self, options: DisplayOptions) -> impl fmt::Display + '_ {
+        Dot {
+            graph: self,
+            keep: &|_| true,
+            options,
+        }
+    }
+
+    /// Prints the graph like its [`fmt::Display`] implementation, but only with the nodes at the indices for which
+    /// `keep` returns `true` and the edges between them.
+    pub fn to_dot_filtered(&self, keep: impl Fn(NodeIndex) -> bool) -> String {
+        Dot {
+            graph: self,
+            keep: &keep,
+            options: DisplayOptions::default(),
+        }
+        .to_string()
+    }
+}
+
+/// Printer of the nodes of a [`DirectedAcyclicGraph`] for which `keep` returns `true` and the edges between them as
+/// DOT.
+#[cfg(feature = "std")]
+struct Dot<'a, P> {
+    graph: &'a DirectedAcyclicGraph<P>,
+    keep: &'a dyn Fn(NodeIndex) -> bool,
+    options: DisplayOptions,
+}
+
+#[cfg(feature = "std")]
+impl<P> fmt::Display for Dot<'_, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (graph, keep) = (self.graph, self.keep);
+        let id = |i: NodeIndex| match self.options.compact_ids {
+            true => i.index().to_string(),
+            false => dot_syntax::format_id(graph.node_id(i)),
+        };
+        writeln!(f, "digraph {{")?;
+        for i in graph.graph.node_indices().filter(|i| keep(*i)) {
+            let label = match self.options.hide_args {
+                true => graph[i].without_args().to_string(),
+                false => graph[i].to_string(),
+            };
+            writeln!(
+                f,
+                "    {} [ label = \"{}\" ]",
+                id(i),
+                dot_syntax::escape(&label)
+            )?;
+        }
+        for e in graph.graph.edge_indices() {
+            let (parent, child) = graph.graph.edge_endpoints(e).ok_or(fmt::Error)?;
+            if !keep(parent) || !keep(child) {
+                continue;
+            }
+            let mut attributes = match graph.graph[e] {
+                Dependency::Completion => String::new(),
+                dependency => format!("dependency = {} ", dependency),
+            };
+            if self.options.edge_labels {
+                attributes = format!("label = \"{}\" {}", graph.graph[e], attributes);
+            }
+            writeln!(f, "    {} -> {} [ {}]", id(parent), id(child), attributes)?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromStr for DirectedAcyclicGraph {
+    type Err = Error;
+    /// Parses [`DirectedAcyclicGraph`] from String in [`ParseMode::Strict`].
+    ///
+    /// Node identifiers may be quoted (`"my node" -> "über"`), in which case they can contain
+    /// spaces, punctuation, unicode and escape sequences like `\"`.
+    /// Repeated declarations of a node like `a; a [ retries = 2 ];` are merged, later attributes taking precedence.
+    ///
+    /// ```
+    /// # use graph_executor_core::graph_structure::graph::DirectedAcyclicGraph;
+    /// # use std::str::FromStr;
+    /// let graph = DirectedAcyclicGraph::from_str("digraph example {\n    a -> b -> c;\n    b -> d;\n}")?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    fn from_str(dag_string: &str) -> Result<Self> {
+        DirectedAcyclicGraph::from_str_with_mode(dag_string, ParseMode::Strict)
+    }
+}
+
+/// Adds the [`Edge`]s wiring the `nodes` with a [`NodeRole`] to all other nodes, see
+/// [`DirectedAcyclicGraph::new_with_limits`]. Edges which are already declared are not added again, so that printed
+/// graphs parse to the same graph.
+fn wire_setup_and_teardown(nodes: &BTreeMap<String, Node>, edges: &mut Vec<Edge>) {
+    let with_role = |role: Option<NodeRole>| -> Vec<&String> {
+        nodes
+            .iter()
+            .filter(|(_, node)| node.role() == role)
+            .map(|(id, _)| id)
+            .collect()
+    };
+    let (setup, teardown) = (
+        with_role(Some(NodeRole::Setup)),
+        with_role(Some(NodeRole::Teardown)),
+    );
+    if setup.is_empty() && teardown.is_empty() {
+        return;
+    }
+    let others = with_role(None);
+    let is_other = |id: &String| nodes.get(id).is_some_and(|node| node.role().is_none());
+    let (mut with_parent, mut with_child) = (BTreeSet::new(), BTreeSet::new());
+    for edge in edges.iter() {
+        if is_other(&edge.parent) && is_other(&edge.child) {
+            with_parent.insert(&edge.child);
+            with_child.insert(&edge.parent);
+        }
+    }
+    let roots = others.iter().filter(|id| !with_parent.contains(*id));
+    let leaves: Vec<&String> = match others.is_empty() {
+        true => setup.clone(),
+        false => others
+            .iter()
+            .filter(|id| !with_child.contains(*id))
+            .copied()
+            .collect(),
+    };
+    let mut wired = vec![];
+    for parent in &setup {
+        wired.extend(
+            roots
+                .clone()
+                .map(|root| Edge::new((*parent).clone(), (*root).clone())),
+        );
+    }
+    for leaf in leaves {
+        wired.extend(
+            teardown
+                .iter()
+                .map(|child| Edge::new(leaf.clone(), (*child).clone())),
+        );
+    }
+    let declared: BTreeSet<(String, String)> = edges
+        .iter()
+        .map(|edge| (edge.parent.clone(), edge.child.clone()))
+        .collect();
+    edges.extend(
+        wired
+            .into_iter()
+            .filter(|edge| !declared.contains(&(edge.parent.clone(), edge.child.clone()))),
+    );
+}
+
+/// Parses a single DOT statement and adds the [`Node`]s and [`Edge`]s it declares.
+/// Nodes which are only implicitly declared as part of an edge statement are kept separately,
+/// so that explicit declarations take precedence over them. Repeated explicit declarations of a node are merged,
+/// for which the attributes of each declared node are kept in `node_attributes`.
+#[cfg(feature = "std")]
+fn parse_statement(
+    statement: &[Token],
+    declared_nodes: &mut Vec<(String, Node)>,
+    node_attributes: &mut BTreeMap<String, Vec<(String, String)>>,
+    implicit_nodes: &mut BTreeMap<String, Node>,
+    edges: &mut Vec<Edge>,
+    context: &mut IncludeContext,
+) -> Result<()> {
+    let attribute_list_start = statement
+        .iter()
+        .position(|t| *t == Token::OpenBracket)
+        .unwrap_or(statement.len());
+    let (head, attribute_list) = statement.split_at(attribute_list_start);
+    let attributes = parse_attribute_list(attribute_list)?;
+
+    match head {
+        // Skip empty statements and attribute statements like `node [ shape = box ]` or `rankdir = LR`
+        [] | [_, Token::Equals, _] => (),
+        [Token::Id(keyword)] if is_attribute_statement_keyword(keyword) => (),
+        // Include the nodes and edges of another pipeline file if the statement looks like:
+        // include "common.dot"
+        // include "common.dot" [ namespace = lib ]
+        [Token::Id(keyword), Token::QuotedId(path) | Token::Id(path)] if keyword == "include" => {
+            let namespace = attributes
+                .iter()
+                .find(|(key, _)| key == "namespace")
+                .map(|(_, namespace)| namespace.as_str());
+            let mut included = Fragment::default();
+            context.include(path, namespace, &mut included)?;
+            declared_nodes.extend(included.nodes);
+            edges.extend(included.edges);
+        }
+        // Parse statement as `Node` if it looks like:
+        // 0 [ label = "Struct Node, Node.args: -- Node 0 was just executed --, Node.execution_status: Executable" ]
+        // "my node" [ label = "printed label" ]
+        // "my node"
+        [id] => {
+            let id = id.as_id().ok_or(anyhow!(
+                "DirectedAcyclicGraph::from_str parsing error: Invalid node identifier {:?}.",
+                id
+            ))?;
+            // Parse the node's attributes like `a [ command = "make -j4 all", retries = 2 ]`, merging them into
+            // those of its previous declarations like `a; a [ retries = 2 ];`, later attributes taking precedence
+            let is_repeated = node_attributes.contains_key(id);
+            let node_attributes = node_attributes.entry(id.to_string()).or_default();
+            node_attributes.extend(attributes);
+            let node = NodeSpec::parse(id, node_attributes, context.parse_mode())?.into_node(id)?;
+            match declared_nodes
+                .iter_mut()
+                .rev()
+                .find(|(node_id, _)| node_id == id)
+            {
+                Some((_, declared)) if is_repeated => *declared = node,
+                _ => declared_nodes.push((id.to_string(), node)),
+            }
+        }
+        // Parse statement as `Edge`s if it looks like:
+        // 0 -> 1 [ ]
+        // a -> "b c" -> c
+        // producer -> consumer [ dependency = streaming ]
+        _ => {
+            let mut node_ids: Vec<String> = vec![];
+            for (i, token) in head.iter().enumerate() {
+                match (i % 2, token.as_id()) {
+                    (0, Some(id)) => node_ids.push(id.to_string()),
+                    (1, None) if *token == Token::Arrow => (),
+                    _ => {
+                        return Err(anyhow!(
+                            "DirectedAcyclicGraph::from_str parsing error: Unexpected token {:?} in edge statement.",
+                            token
+                        ))
+                    }
+                }
+            }
+            if head.len() % 2 == 0 {
+                return Err(anyhow!(
+                    "DirectedAcyclicGraph::from_str parsing error: Edge statement ends with '->'."
+                ));
+            }
+
+            // Edge statements implicitly declare every node in the chain a -> b -> c, whatever their attributes
+            for node_id in node_ids.iter() {
+                implicit_nodes
+                    .entry(node_id.clone())
+                    .or_insert_with(|| Node::new(node_id.clone()));
+            }
+            let dependency =
+                EdgeSpec::parse(&node_ids.join(" -> "), &attributes, context.parse_mode())?
+                    .dependency;
+            for pair in node_ids.windows(2) {
+                edges.push(Edge::new(pair[0].clone(), pair[1].clone()).with_dependency(dependency));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks whether a statement starting with `keyword` sets default attributes (`graph`, `node`, `edge`).
+#[cfg(feature = "std")]
+fn is_attribute_statement_keyword(keyword: &str) -> bool {
+    ["graph", "node", "edge"].contains(&keyword.to_ascii_lowercase().as_str())
+}
+
+/// Parses attribute lists like `[ label = "a", shape = box ]` into key-value pairs.
+#[cfg(feature = "std")]
+fn parse_attribute_list(attribute_list: &[Token]) -> Result<Vec<(String, String)>> {
+    let mut attributes = vec![];
+    let mut tokens = attribute_list
+        .iter()
+        .filter(|t| !matches!(t, Token::Comma | Token::Separator));
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::OpenBracket | Token::CloseBracket => (),
+            key => match (
+                key.as_id(),
+                tokens.next(),
+                tokens.next().and_then(|t| t.as_id()),
+            ) {
+                (Some(key), Some(Token::Equals), Some(value)) => {
+                    attributes.push((key.to_string(), value.to_string()))
+                }
+                _ => {
+                    return Err(anyhow!(
+                    "DirectedAcyclicGraph::from_str parsing error: Invalid attribute list {:?}.",
+                    attribute_list
+                ))
+                }
+            },
+        }
+    }
+    Ok(attributes)
+}
+
+impl<P> Index<NodeIndex> for DirectedAcyclicGraph<P> {
+    type Output = Node;
+    fn index(&self, index: NodeIndex) -> &Self::Output {
+        &self.graph[index]
+    }
+}
+
+impl<P> IndexMut<NodeIndex> for DirectedAcyclicGraph<P> {
+    fn index_mut(&mut self, index: NodeIndex) -> &mut Self::Output {
+        &mut self.graph[index]
+    }
+}
+
+impl<P> PartialEq for DirectedAcyclicGraph<P> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.graph.node_indices().count() != other.graph.node_indices().count()
+            || self.graph.edge_indices().count() != other.graph.edge_indices().count()
+            || self.node_ids != other.node_ids
+        {
+            return false;
+        }
+        for n in self.graph.node_indices() {
+            if self[n] != other[n] {
+                return false;
+            }
+        }
+        for e in self.graph.edge_indices() {
+            if self.graph.edge_endpoints(e).unwrap() != other.graph.edge_endpoints(e).unwrap()
+                || self.graph[e] != other.graph[e]
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+impl AsFromBytes for DirectedAcyclicGraph {}
+
+impl DirectedAcyclicGraph {
+    /// Creates [`DirectedAcyclicGraph`] from `(string identifier, Node)` pairs (e.g. a [`BTreeMap<String, Node>`]
+    /// or a [`Vec<(String, Node)>`]) and [`Vec<Edge>`]. Nodes are indexed in the order of their string identifiers.
+    /// A [`DuplicateNodeId`] error listing all collisions is returned if multiple nodes share an identifier.
+    ///
+    /// You can create a [`DirectedAcyclicGraph`] like this:
+    /// ```
+    /// # use graph_executor_core::graph_structure::{edge::Edge, graph::DirectedAcyclicGraph, node::Node};
+    /// # use std::collections::BTreeMap;
+    /// let graph = DirectedAcyclicGraph::new(
+    ///     BTreeMap::from([
+    ///         (String::from("0"), Node::new(String::from("Node 0"))),
+    ///         (String::from("1"), Node::new(String::from("Node 1"))),
+    ///         (String::from("2"), Node::new(String::from("Node 2"))),
+    ///         (String::from("3"), Node::new(String::from("Node 3"))),
+    ///     ]),
+    ///     vec![
+    ///         Edge::new(String::from("0"), String::from("1")),
+    ///         Edge::new(String::from("2"), String::from("3")),
+    ///         Edge::new(String::from("1"), String::from("3")),
+    ///     ],
+    /// )?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn new(nodes: impl IntoIterator<Item = (String, Node)>, edges: Vec<Edge>) -> Result<Self> {
+        DirectedAcyclicGraph::new_with_mode(nodes, edges, ParseMode::Strict)
+    }
+
+    /// Creates [`DirectedAcyclicGraph`] like [`DirectedAcyclicGraph::new`], but lets `parse_mode` decide
+    /// whether edges referencing undeclared nodes are an error ([`ParseMode::Strict`]) or are dropped
+    /// with a warning ([`ParseMode::Lenient`]).
+    pub fn new_with_mode(
+        nodes: impl IntoIterator<Item = (String, Node)>,
+        edges: Vec<Edge>,
+        parse_mode: ParseMode,
+    ) -> Result<Self> {
+        DirectedAcyclicGraph::new_with_limits(nodes, edges, parse_mode, GraphLimits::default())
+    }
+
+    /// Creates [`DirectedAcyclicGraph`] like [`DirectedAcyclicGraph::new_with_mode`] and checks it against `limits`,
+    /// which stay in effect for later mutations via [`DirectedAcyclicGraph::add_node`] and [`DirectedAcyclicGraph::add_edge`].
+    ///
+    /// Nodes with a [`NodeRole`] are wired to all other nodes: every setup node becomes a parent of every root and
+    /// every teardown node a child of every leaf, roots and leaves being determined by the `edges` between nodes
+    /// without a role. Without other nodes, teardown nodes become children of the setup nodes.
+    pub fn new_with_limits(
+        nodes: impl IntoIterator<Item = (String, Node)>,
+        mut edges: Vec<Edge>,
+        parse_mode: ParseMode,
+        limits: GraphLimits,
+    ) -> Result<Self> {
+        // Check for multiple nodes with the same string identifier.
+        let mut nodes_by_id: BTreeMap<String, Node> = BTreeMap::new();
+        let mut duplicate_node_ids: BTreeSet<String> = BTreeSet::new();
+        for (string_id, node) in nodes {
+            match nodes_by_id.entry(string_id) {
+                Entry::Occupied(entry) => {
+                    duplicate_node_ids.insert(entry.key().clone());
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(node);
+                }
+            }
+        }
+        if !duplicate_node_ids.is_empty() {
+            return Err(DuplicateNodeId {
+                node_ids: duplicate_node_ids.into_iter().collect(),
+            }
+            .into());
+        }
+        let nodes = nodes_by_id;
+        wire_setup_and_teardown(&nodes, &mut edges);
+        limits.check_counts(nodes.len(), edges.len())?;
+
+        let mut graph = StableDiGraph::<Node, Dependency>::new();
+        let node_ids: NodeIds = nodes.keys().cloned().collect();
+
+        // Populate graph with all nodes.
+        let node_string_id_to_node_index_map: BTreeMap<String, NodeIndex> = nodes
+            .into_iter()
+            .map(|(string_id, node)| (string_id, graph.add_node(node)))
+            .collect();
+
+        // Check for edges referencing undeclared nodes.
+        let unknown_node_ids: BTreeSet<&String> = edges
+            .iter()
+            .flat_map(|edge| [&edge.parent, &edge.child])
+            .filter(|node_id| !node_string_id_to_node_index_map.contains_key(*node_id))
+            .collect();
+        if parse_mode == ParseMode::Strict && !unknown_node_ids.is_empty() {
+            return Err(anyhow!(
+                "Edges reference nodes which are not declared: {:?}",
+                unknown_node_ids
+            ));
+        }
+
+        // Populate graph with all edges between nodes.
+        edges.into_iter().for_each(|edge| {
+            if node_string_id_to_node_index_map.contains_key(&edge.parent)
+                && node_string_id_to_node_index_map.contains_key(&edge.child)
+            {
+                graph.add_edge(
+                    node_string_id_to_node_index_map[&edge.parent],
+                    node_string_id_to_node_index_map[&edge.child],
+                    edge.dependency,
+                );
+
+                // Set `ExecutionStatus` of child nodes to `NonExecutable`.
+                graph[node_string_id_to_node_index_map[&edge.child]].execution_status =
+                    ExecutionStatus::NonExecutable;
+            } else {
+                #[cfg(feature = "std")]
+                println!(
+                    "One or more of nodes of edge is not defined as a node: {:?}",
+                    edge
+                );
+            }
+        });
+
+        // Check that `StableDiGraph` is acyclic and return `DirectedAcyclicGraph` if successful.
+        Acyclic::try_from_graph(&graph)
+            .map_err(|e| anyhow!("Cyclic graph supplied on {:?}", e.node_id()))?;
+        let dag = DirectedAcyclicGraph {
+            graph,
+            node_ids,
+            limits,
+            generation: 0,
+            changed_in: BTreeMap::new(),
+            payloads: BTreeMap::new(),
+        };
+        dag.check_serialized_size()?;
+        Ok(dag)
+    }
+
+    /// Creates [`DirectedAcyclicGraph`] from a path to a file containing a description of a
+    /// directed graph in the DOT language.
+    #[cfg(feature = "std")]
+    pub fn from_file(file_path: &str) -> Result<Self> {
+        DirectedAcyclicGraph::from_file_with_mode(file_path, ParseMode::Strict)
+    }
+
+    /// Creates [`DirectedAcyclicGraph`] like [`DirectedAcyclicGraph::from_file`] in the supplied [`ParseMode`].
+    #[cfg(feature = "std")]
+    pub fn from_file_with_mode(file_path: &str, parse_mode: ParseMode) -> Result<Self> {
+        DirectedAcyclicGraph::from_file_with_limits(file_path, parse_mode, GraphLimits::default())
+    }
+
+    /// Creates [`DirectedAcyclicGraph`] like [`DirectedAcyclicGraph::from_file_with_mode`] with custom [`GraphLimits`].
+    #[cfg(feature = "std")]
+    pub fn from_file_with_limits(
+        file_path: &str,
+        parse_mode: ParseMode,
+        limits: GraphLimits,
+    ) -> Result<Self> {
+        DirectedAcyclicGraph::from_dot_str(
+            &read_to_string(file_path)
+                .map_err(|e| anyhow!("Failed reading file {}: {}", file_path, e))?,
+            parse_mode,
+            limits,
+            &mut IncludeContext::for_file(file_path, parse_mode, limits, None)?,
+        )
+    }
+
+    /// Parses [`DirectedAcyclicGraph`] from String like [`DirectedAcyclicGraph::from_str`] in the supplied [`ParseMode`].
+    #[cfg(feature = "std")]
+    pub fn from_str_with_mode(dag_string: &str, parse_mode: ParseMode) -> Result<Self> {
+        DirectedAcyclicGraph::from_str_with_limits(dag_string, parse_mode, GraphLimits::default())
+    }
+
+    /// Parses [`DirectedAcyclicGraph`] from String like [`DirectedAcyclicGraph::from_str_with_mode`] with custom [`GraphLimits`].
+    #[cfg(feature = "std")]
+    pub fn from_str_with_limits(
+        dag_string: &str,
+        parse_mode: ParseMode,
+        limits: GraphLimits,
+    ) -> Result<Self> {
+        DirectedAcyclicGraph::from_dot_str(
+            dag_string,
+            parse_mode,
+            limits,
+            &mut IncludeContext::new(parse_mode, limits, None),
+        )
+    }
+
+    /// Parses [`DirectedAcyclicGraph`] from String like [`DirectedAcyclicGraph::from_str_with_limits`], including
+    /// the pipeline files of its `include` statements with `context`, see
+    /// [`DirectedAcyclicGraph::from_pipeline_file`].
+    #[cfg(feature = "std")]
+    pub(crate) fn from_dot_str(
+        dag_string: &str,
+        parse_mode: ParseMode,
+        limits: GraphLimits,
+        context: &mut IncludeContext,
+    ) -> Result<Self> {
+        // Vectors for future `node`s and `edge`s of the new [`DirectedAcyclicGraph`]
+        let mut declared_nodes: Vec<(String, Node)> = vec![];
+        let mut node_attributes: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
+        let mut implicit_nodes: BTreeMap<String, Node> = BTreeMap::new();
+        let mut edges: Vec<Edge> = vec![];
+
+        if dag_string.trim().starts_with("digraph") {
+            let tokens = dot_syntax::tokenize(dag_string)?;
+
+            // Only parse the graph body: digraph name { ... }
+            let body_start = tokens
+                .iter()
+                .position(|t| *t == Token::OpenBrace)
+                .ok_or(anyhow!(
+                    "DirectedAcyclicGraph::from_str parsing error: No opening brace."
+                ))?
+                + 1;
+            let body_end = tokens
+                .iter()
+                .rposition(|t| *t == Token::CloseBrace)
+                .filter(|end| *end >= body_start)
+                .ok_or(anyhow!(
+                    "DirectedAcyclicGraph::from_str parsing error: No closing brace."
+                ))?;
+
+            // Split body into statements on separators outside of attribute lists
+            let mut statement_start = body_start;
+            let mut bracket_depth = 0;
+            for (i, token) in tokens.iter().enumerate().take(body_end).skip(body_start) {
+                match token {
+                    Token::OpenBracket => bracket_depth += 1,
+                    Token::CloseBracket => bracket_depth -= 1,
+                    Token::Separator if bracket_depth == 0 => {
+                        parse_statement(
+                            &tokens[statement_start..i],
+                            &mut declared_nodes,
+                            &mut node_attributes,
+                            &mut implicit_nodes,
+                            &mut edges,
+                            context,
+                        )?;
+                        statement_start = i + 1;
+                    }
+                    _ => (),
+                }
+            }
+            parse_statement(
+                &tokens[statement_start..body_end],
+                &mut declared_nodes,
+                &mut node_attributes,
+                &mut implicit_nodes,
+                &mut edges,
+                context,
+            )?;
+        }
+
+        // Explicit declarations take precedence over implicit ones.
+        for (node_id, _) in declared_nodes.iter() {
+            implicit_nodes.remove(node_id);
+        }
+        DirectedAcyclicGraph::new_with_limits(
+            declared_nodes.into_iter().chain(implicit_nodes),
+            edges,
+            parse_mode,
+            limits,
+        )
+    }
+}
+
+impl<P> DirectedAcyclicGraph<P> {
+    /// Write [`DirectedAcyclicGraph`] to `path`.
+    ///
+    /// ```
+    /// # use graph_executor_core::graph_structure::{edge::Edge, graph::DirectedAcyclicGraph, node::Node};
+    /// let graph = DirectedAcyclicGraph::new(
+    ///     ["0", "1"].map(|id| (String::from(id), Node::new(String::from(id)))),
+    ///     vec![Edge::new(String::from("0"), String::from("1"))],
+    /// )?;
+    /// graph.to_file(std::env::temp_dir().join("example.dot").to_str().unwrap())?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_file(&self, file_path: &str) -> Result<()> {
+        self.to_file_with_options(file_path, DisplayOptions::default())
+    }
+
+    /// Saves the graph printed with the given [`DisplayOptions`] to a file, see [`DirectedAcyclicGraph::display`].
+    #[cfg(feature = "std")]
+    pub fn to_file_with_options(&self, file_path: &str, options: DisplayOptions) -> Result<()> {
+        write(file_path, self.display(options).to_string())?;
+        Ok(())
+    }
+
+    /// Returns a copy of the graph which can be shared in bug reports without leaking proprietary commands: only its
+    /// topology (including the edges' dependencies) and the execution statuses and costs of its nodes are kept, while
+    /// nodes are identified by their index and lose their args, commands, payloads and all other attributes.
+    pub fn anonymized(&self) -> DirectedAcyclicGraph {
+        let mut anonymized = self.without_payloads();
+        for i in self.graph.node_indices() {
+            let mut node = match self.graph[i].cost() {
+                Some(cost) => Node::default().with_cost(cost),
+                None => Node::default(),
+            };
+            node.execution_status = self.graph[i].execution_status;
+            anonymized.graph[i] = node;
+        }
+        anonymized.node_ids = self
+            .graph
+            .node_indices()
+            .map(|i| i.index().to_string())
+            .collect();
+        anonymized.changed_in = self
+            .changed_in
+            .iter()
+            .filter_map(|(id, generation)| {
+                Some((self.node_index(id)?.index().to_string(), *generation))
+            })
+            .collect();
+        anonymized
+    }
+
+    /// Returns the graph with the payloads `payload` returns for its nodes by their [`NodeIndex`], [`None`] for nodes
+    /// without one, replacing its current payloads.
+    ///
+    /// ```
+    /// # use graph_executor_core::graph_structure::graph::DirectedAcyclicGraph;
+    /// # use std::str::FromStr;
+    /// let graph = DirectedAcyclicGraph::from_str("digraph {\n    a -> b\n}")?
+    ///     .with_payloads(|_, node| Some(node.args().len()));
+    /// assert_eq!(graph.payload(graph.node_index("a").unwrap()), Some(&1));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn with_payloads<Q>(
+        self,
+        mut payload: impl FnMut(NodeIndex, &Node) -> Option<Q>,
+    ) -> DirectedAcyclicGraph<Q> {
+        let payloads = self
+            .graph
+            .node_indices()
+            .filter_map(|i| Some((i, payload(i, &self.graph[i])?)))
+            .collect();
+        DirectedAcyclicGraph {
+            graph: self.graph,
+            node_ids: self.node_ids,
+            limits: self.limits,
+            generation: self.generation,
+            changed_in: self.changed_in,
+            payloads,
+        }
+    }
+
+    /// Returns a copy of the graph without its payloads, e.g. to share it with workers of other processes.
+    pub fn without_payloads(&self) -> DirectedAcyclicGraph {
+        DirectedAcyclicGraph {
+            graph: self.graph.clone(),
+            node_ids: self.node_ids.clone(),
+            limits: self.limits,
+            generation: self.generation,
+            changed_in: self.changed_in.clone(),
+            payloads: BTreeMap::new(),
+        }
+    }
+
+    /// Get the payload of the `Node` identified by [`NodeIndex`], [`None`] if it has none.
+    pub fn payload(&self, index: NodeIndex) -> Option<&P> {
+        self.payloads.get(&index)
+    }
+
+    /// Attaches `payload` to the `Node` identified by [`NodeIndex`], returning the payload it replaced.
+    pub fn set_payload(&mut self, index: NodeIndex, payload: P) -> Result<Option<P>> {
+        if !self.graph.contains_node(index) {
+            return Err(anyhow!("No node with index {}.", index.index()));
+        }
+        Ok(self.payloads.insert(index, payload))
+    }
+
+    /// Get the [`NodeIndex`]es of all nodes with a payload and their payloads.
+    pub fn payloads(&self) -> impl Iterator<Item = (NodeIndex, &P)> + '_ {
+        self.payloads.iter().map(|(i, payload)| (*i, payload))
+    }
+
+    /// Get the [`GraphLimits`] checked on mutation.
+    pub fn limits(&self) -> GraphLimits {
+        self.limits
+    }
+
+    /// Number of bytes of the graph serialized for shared memory.
+    #[cfg(all(feature = "std", feature = "serde"))]
+    pub fn serialized_size(&self) -> Result<usize> {
+        Ok(rmp_serde::to_vec(self)?.len())
+    }
+
+    /// Checks the graph's [`DirectedAcyclicGraph::serialized_size`] against its [`GraphLimits`]. Without the `std`
+    /// and `serde` features, graphs are not serialized for shared memory and the check always succeeds.
+    fn check_serialized_size(&self) -> Result<()> {
+        #[cfg(all(feature = "std", feature = "serde"))]
+        self.limits.check_serialized_size(self.serialized_size()?)?;
+        Ok(())
+    }
+
+    /// SHA-256 hash (hex) of the graph's definition, i.e. its node identifiers, node `args` and edges,
+    /// but not its execution statuses.
+    pub fn content_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        for i in self.graph.node_indices() {
+            self.hash_node(&mut hasher, i);
+        }
+        for e in self.graph.edge_indices() {
+            if let Some((parent, child)) = self.graph.edge_endpoints(e) {
+                hasher.update(self.node_id(parent).as_bytes());
+                hasher.update([0]);
+                hasher.update(self.node_id(child).as_bytes());
+                hasher.update([0]);
+                if self.graph[e] == Dependency::Streaming {
+                    hasher.update(self.graph[e].to_string().as_bytes());
+                }
+            }
+        }
+        to_hex(hasher)
+    }
+
+    /// SHA-256 hash (hex) of the definition of the node at `node_index` and of all its ancestors with the edges
+    /// between them, but not their execution statuses. It changes whenever anything in the graph the node's execution
+    /// may depend on changes, e.g. to key cached results of the node, while changes to other nodes keep it.
+    pub fn node_content_hash(&self, node_index: NodeIndex) -> String {
+        // Ancestors are hashed by identifier, so that the hash doesn't depend on the order nodes were declared in
+        let mut ancestors = BTreeMap::new();
+        let mut pending = vec![node_index];
+        while let Some(i) = pending.pop() {
+            if ancestors.insert(self.node_id(i), i).is_none() {
+                pending.extend(self.get_parent_node_indices(i));
+            }
+        }
+        let mut hasher = Sha256::new();
+        hasher.update(self.node_id(node_index).as_bytes());
+        hasher.update([0]);
+        for i in ancestors.into_values() {
+            self.hash_node(&mut hasher, i);
+            let mut parents: Vec<NodeIndex> = self.get_parent_node_indices(i).collect();
+            parents.sort_by_key(|parent| self.node_id(*parent));
+            for parent in parents {
+                hasher.update(b"<");
+                hasher.update(self.node_id(parent).as_bytes());
+                hasher.update([0]);
+                if self.is_streaming(parent, i) {
+                    hasher.update(Dependency::Streaming.to_string().as_bytes());
+                }
+            }
+        }
+        to_hex(hasher)
+    }
+
+    /// Feeds the definition of the node at `i` (its identifier and attributes) to `hasher`, see
+    /// [`DirectedAcyclicGraph::content_hash`].
+    fn hash_node(&self, hasher: &mut Sha256, i: NodeIndex) {
+        // Every attribute is fed with a tag of its own and its length, so that different nodes never feed the same
+        // bytes, e.g. a node with an input `x` and one with the command `x`
+        let node = &self[i];
+        hash_field(hasher, b'i', self.node_id(i).as_bytes());
+        hash_field(hasher, b'a', node.args().as_bytes());
+        if let Some(cost) = node.cost() {
+            hash_field(hasher, b'c', &cost.to_be_bytes());
+        }
+        if let Some(gang) = node.gang() {
+            hash_field(hasher, b'g', gang.as_bytes());
+        }
+        if let Some(mutex) = node.mutex() {
+            hash_field(hasher, b'm', mutex.as_bytes());
+        }
+        if node.priority() != 0 {
+            hash_field(hasher, b'p', &node.priority().to_be_bytes());
+        }
+        if let Some(nice) = node.nice() {
+            hash_field(hasher, b'n', &nice.to_be_bytes());
+        }
+        if let Some(ionice) = node.ionice() {
+            hash_field(hasher, b'o', ionice.to_string().as_bytes());
+        }
+        if node.retries() != 0 {
+            hash_field(hasher, b'r', &node.retries().to_be_bytes());
+        }
+        if let Some(timeout) = node.timeout() {
+            hash_field(hasher, b't', &timeout.as_nanos().to_be_bytes());
+        }
+        hash_list(hasher, b'I', node.inputs());
+        hash_list(hasher, b'O', node.outputs());
+        if node.requires_approval() {
+            hash_field(hasher, b'A', &[]);
+        }
+        if let Some(event) = node.awaited_event() {
+            hash_field(hasher, b'e', event.as_bytes());
+        }
+        if let Some(role) = node.role() {
+            hash_field(hasher, b'R', role.to_string().as_bytes());
+        }
+        hash_field(hasher, b'S', node.isolation().to_string().as_bytes());
+        if let Some(command) = node.command() {
+            hash_field(hasher, b'C', command.as_bytes());
+        }
+        if let Some(command) = node.hook(Hook::Pre) {
+            hash_field(hasher, b'<', command.as_bytes());
+        }
+        if let Some(command) = node.hook(Hook::Post) {
+            hash_field(hasher, b'>', command.as_bytes());
+        }
+        if let Some(cwd) = node.cwd() {
+            hash_field(hasher, b'd', cwd.as_bytes());
+        }
+        hasher.update([b'E']);
+        hasher.update((node.env().len() as u64).to_be_bytes());
+        for (name, value) in node.env() {
+            hash_field(hasher, b'k', name.as_bytes());
+            hash_field(hasher, b'v', value.as_bytes());
+        }
+    }
+
+    /// Adds a [`Node`] identified by `id` to the graph, checking it against the graph's [`GraphLimits`].
+    pub fn add_node(&mut self, id: String, node: Node) -> Result<NodeIndex> {
+        if self.node_index(&id).is_some() {
+            return Err(DuplicateNodeId { node_ids: vec![id] }.into());
+        }
+        self.limits
+            .check_counts(self.graph.node_count() + 1, self.graph.edge_count())?;
+
+        let index = self.graph.add_node(node);
+        self.node_ids.push(id);
+        if let Err(e) = self.check_serialized_size() {
+            self.graph.remove_node(index);
+            self.node_ids.pop();
+            return Err(e);
+        }
+        Ok(index)
+    }
+
+    /// Adds an [`Edge`] between two existing nodes to the graph, checking that it neither introduces a cycle
+    /// nor exceeds the graph's [`GraphLimits`]. The child node becomes [`ExecutionStatus::NonExecutable`]
+    /// unless its parent has already been executed.
+    pub fn add_edge(&mut self, edge: Edge) -> Result<()> {
+        let (parent, child) = match (self.node_index(&edge.parent), self.node_index(&edge.child)) {
+            (Some(parent), Some(child)) => (parent, child),
+            _ => {
+                return Err(anyhow!(
+                    "Edges reference nodes which are not declared: {:?}",
+                    edge
+                ))
+            }
+        };
+        if has_path_connecting(&self.graph, child, parent, None) {
+            return Err(anyhow!("Cyclic graph supplied on {:?}", parent));
+        }
+        self.limits
+            .check_counts(self.graph.node_count(), self.graph.edge_count() + 1)?;
+
+        let edge_index = self.graph.add_edge(parent, child, edge.dependency);
+        if let Err(e) = self.check_serialized_size() {
+            self.graph.remove_edge(edge_index);
+            return Err(e);
+        }
+        if self.graph[parent].execution_status != ExecutionStatus::Executed
+            && self.graph[child].execution_status == ExecutionStatus::Executable
+        {
+            self.graph[child].execution_status = ExecutionStatus::NonExecutable;
+        }
+        Ok(())
+    }
+
+    /// Get the string identifier of the node at `index`.
+    pub fn node_id(&self, index: NodeIndex) -> &str {
+        self.node_ids.id(index)
+    }
+
+    /// Get the [`NodeIndex`] of the node with string identifier `id`.
+    pub fn node_index(&self, id: &str) -> Option<NodeIndex> {
+        self.node_ids.index(id)
+    }
+
+    /// Sorts `indices` by the string identifiers of their nodes. All public methods iterating over nodes return
+    /// them in this order, so that it neither depends on the order in which nodes and edges were added nor on
+    /// whether the graph was deserialized.
+    fn sorted_by_node_id(&self, indices: impl Iterator<Item = NodeIndex>) -> Vec<NodeIndex> {
+        let mut indices: Vec<NodeIndex> = indices.collect();
+        indices.sort_by_key(|i| self.node_id(*i));
+        indices
+    }
+
+    /// Get all `Node` indices, ordered by string identifier.
+    pub fn get_node_indices(&self) -> impl Iterator<Item = NodeIndex> + '_ {
+        self.node_ids.sorted_indices()
+    }
+
+    /// Get all executable `Node` indeces, ordered by string identifier.
+    pub fn get_executable_node_indices(&self) -> VecDeque<NodeIndex> {
+        self.get_node_indices()
+            .filter(|i| self.graph[*i].execution_status == ExecutionStatus::Executable)
+            .collect()
+    }
+
+    /// Get the indices of all `Node`s belonging to the gang named `gang`, ordered by string identifier.
+    pub fn get_gang_node_indices(&self, gang: &str) -> Vec<NodeIndex> {
+        self.get_node_indices()
+            .filter(|i| self.graph[*i].gang() == Some(gang))
+            .collect()
+    }
+
+    /// Checks whether any `Node` belongs to a gang.
+    pub fn has_gangs(&self) -> bool {
+        self.graph.node_weights().any(|n| n.gang().is_some())
+    }
+
+    /// Checks whether a `Node` with the concurrency key `mutex` is executing, so that no other may be claimed.
+    pub fn is_mutex_held(&self, mutex: &str) -> bool {
+        self.graph
+            .node_weights()
+            .any(|n| n.mutex() == Some(mutex) && n.execution_status == ExecutionStatus::Executing)
+    }
+
+    /// Get the index of the executable `Node` with the smallest string identifier.
+    pub fn get_executable_node_index(&self) -> Option<NodeIndex> {
+        self.get_node_indices()
+            .find(|i| self.graph[*i].execution_status == ExecutionStatus::Executable)
+    }
+
+    /// Checks whether all nodes have been executed.
+    pub fn is_graph_executed(&self) -> bool {
+        self.graph
+            .node_weights()
+            .filter(|n| n.execution_status != ExecutionStatus::Executed)
+            .collect::<Vec<&Node>>()
+            .is_empty()
+    }
+
+    /// Checks whether no node is left to execute: every node has been executed, has failed or was skipped or
+    /// cancelled.
+    pub fn is_graph_finished(&self) -> bool {
+        self.graph
+            .node_weights()
+            .all(|n| n.execution_status.is_finished())
+    }
+
+    /// Checks whether any teardown `Node` (see [`NodeRole::Teardown`]) has not been started yet, so that workers
+    /// keep executing the run after a failure cancelled it.
+    pub fn is_teardown_pending(&self) -> bool {
+        self.graph.node_weights().any(|n| {
+            n.role() == Some(NodeRole::Teardown)
+                && matches!(
+                    n.execution_status,
+                    ExecutionStatus::Executable | ExecutionStatus::NonExecutable
+                )
+        })
+    }
+
+    /// Records `elapsed` (the time since the start of the run) as the time at which all executable `Node`s without
+    /// one were enqueued, see [`Node::enqueued_after`].
+    pub fn record_enqueue_times(&mut self, elapsed: Duration) {
+        for node in self.graph.node_weights_mut() {
+            if node.execution_status == ExecutionStatus::Executable && node.enqueued_after.is_none()
+            {
+                node.enqueued_after = Some(elapsed);
+            }
+        }
+    }
+
+    /// Get the indices of all failed `Node`s, ordered by string identifier.
+    pub fn get_failed_node_indices(&self) -> Vec<NodeIndex> {
+        self.get_node_indices()
+            .filter(|i| self.graph[*i].execution_status == ExecutionStatus::Failed)
+            .collect()
+    }
+
+    /// Get the number of `Node`s currently executing.
+    pub fn get_executing_node_count(&self) -> usize {
+        self.graph
+            .node_weights()
+            .filter(|n| n.execution_status == ExecutionStatus::Executing)
+            .count()
+    }
+
+    /// Marks the `Node` identified by [`NodeIndex`] as [`ExecutionStatus::Failed`] with `error` and all its
+    /// descendants except teardown `Node`s as [`ExecutionStatus::Skipped`], see [`NodeRole::Teardown`]. Descendants
+    /// which already started streaming from it (see [`Dependency::Streaming`]) become
+    /// [`ExecutionStatus::Cancelled`] instead, so that their workers stop them.
+    pub fn fail_node(&mut self, index: NodeIndex, error: String) {
+        self.graph[index].execution_status = ExecutionStatus::Failed;
+        self.graph[index].error = Some(error);
+        let mut descendants: Vec<NodeIndex> = self.get_child_node_indices(index).collect();
+        while let Some(descendant) = descendants.pop() {
+            if !matches!(
+                self.graph[descendant].execution_status,
+                ExecutionStatus::Skipped | ExecutionStatus::Cancelled
+            ) && self.graph[descendant].role() != Some(NodeRole::Teardown)
+            {
+                self.graph[descendant].execution_status =
+                    match self.graph[descendant].execution_status {
+                        ExecutionStatus::Executing => ExecutionStatus::Cancelled,
+                        _ => ExecutionStatus::Skipped,
+                    };
+                descendants.extend(self.get_child_node_indices(descendant));
+            }
+        }
+        self.release_teardown_nodes();
+    }
+
+    /// Cancels the `Node` identified by [`NodeIndex`] alone, e.g. because it is stuck: it becomes
+    /// [`ExecutionStatus::Cancelled`] (its worker stops it if it is executing) and all its descendants except
+    /// teardown `Node`s which have not finished yet [`ExecutionStatus::Skipped`].
+    pub fn cancel_node(&mut self, index: NodeIndex) {
+        self.graph[index].execution_status = ExecutionStatus::Cancelled;
+        let mut descendants: Vec<NodeIndex> = self.get_child_node_indices(index).collect();
+        while let Some(descendant) = descendants.pop() {
+            if matches!(
+                self.graph[descendant].execution_status,
+                ExecutionStatus::NonExecutable | ExecutionStatus::Executable
+            ) && self.graph[descendant].role() != Some(NodeRole::Teardown)
+            {
+                self.graph[descendant].execution_status = ExecutionStatus::Skipped;
+                descendants.extend(self.get_child_node_indices(descendant));
+            }
+        }
+        self.release_teardown_nodes();
+    }
+
+    /// Makes the teardown `Node`s (see [`NodeRole::Teardown`]) whose parents have all finished
+    /// [`ExecutionStatus::Executable`], as they are executed even if their parents failed or were skipped or
+    /// cancelled.
+    fn release_teardown_nodes(&mut self) {
+        let released: Vec<NodeIndex> = self
+            .graph
+            .node_indices()
+            .filter(|i| {
+                self.graph[*i].role() == Some(NodeRole::Teardown)
+                    && self.graph[*i].execution_status == ExecutionStatus::NonExecutable
+                    && self
+                        .graph
+                        .neighbors_directed(*i, Direction::Incoming)
+                        .all(|parent| self.graph[parent].execution_status.is_finished())
+            })
+            .collect();
+        for i in released {
+            self.graph[i].execution_status = ExecutionStatus::Executable;
+        }
+    }
+
+    /// Resets the `Node`s identified by `indices` and all their descendants, e.g. to re-execute them after the files
+    /// they read changed: they lose their execution record, error, attempts, approval and received event and become
+    /// [`ExecutionStatus::Executable`], or [`ExecutionStatus::NonExecutable`] if any parent is left unexecuted.
+    /// Returns the indices of all reset `Node`s, ordered by string identifier.
+    pub fn reset_nodes(&mut self, indices: impl IntoIterator<Item = NodeIndex>) -> Vec<NodeIndex> {
+        let mut reset = BTreeSet::new();
+        let mut unvisited: Vec<NodeIndex> = indices.into_iter().collect();
+        while let Some(index) = unvisited.pop() {
+            if reset.insert(index) {
+                unvisited.extend(self.get_child_node_indices(index));
+            }
+        }
+        for index in &reset {
+            let node = &mut self.graph[*index];
+            node.execution_record = None;
+            node.error = None;
+            node.attempts.clear();
+            node.approved = false;
+            node.event_received = false;
+            node.progress_marks = 0;
+            node.progress = None;
+            node.heartbeat_after = None;
+            node.enqueued_after = None;
+            node.execution_status = ExecutionStatus::NonExecutable;
+        }
+        for index in &reset {
+            if self
+                .get_parent_node_indices(*index)
+                .all(|parent| self.graph[parent].execution_status == ExecutionStatus::Executed)
+            {
+                self.graph[*index].execution_status = ExecutionStatus::Executable;
+            }
+        }
+        self.sorted_by_node_id(reset.into_iter())
+    }
+
+    /// Marks the external event `event` as received by all `Node`s awaiting it, so that they may be claimed once
+    /// executable. Returns the indices of the `Node`s which were waiting for it, ordered by string identifier.
+    pub fn receive_event(&mut self, event: &str) -> Vec<NodeIndex> {
+        let receiving: Vec<NodeIndex> = self
+            .graph
+            .node_indices()
+            .filter(|i| {
+                self.graph[*i].is_awaiting_event() && self.graph[*i].awaited_event() == Some(event)
+            })
+            .collect();
+        for index in &receiving {
+            self.graph[*index].event_received = true;
+        }
+        self.sorted_by_node_id(receiving.into_iter())
+    }
+
+    /// Resets the `Node`s of a run which finished with failures, so that the run can be resumed: the failed `Node`s
+    /// and those left unexecuted because of the failures ([`ExecutionStatus::Skipped`], or
+    /// [`ExecutionStatus::Cancelled`] when the failure cancelled the run) are reset like with
+    /// [`DirectedAcyclicGraph::reset_nodes`], while executed `Node`s keep their results. Returns the indices of all
+    /// reset `Node`s, ordered by string identifier.
+    pub fn reset_failed_nodes(&mut self) -> Vec<NodeIndex> {
+        let failed: Vec<NodeIndex> = self
+            .get_node_indices()
+            .filter(|i| {
+                matches!(
+                    self.graph[*i].execution_status,
+                    ExecutionStatus::Failed | ExecutionStatus::Skipped | ExecutionStatus::Cancelled
+                )
+            })
+            .collect();
+        self.reset_nodes(failed)
+    }
+
+    /// Cancels the rest of the run: executing `Node`s become [`ExecutionStatus::Cancelled`] (their workers stop
+    /// them) and `Node`s which have not been started yet [`ExecutionStatus::Skipped`]. Teardown `Node`s are not
+    /// cancelled but executed once their parents have finished, see [`NodeRole::Teardown`].
+    pub fn cancel(&mut self) {
+        for node in self.graph.node_weights_mut() {
+            if node.role() == Some(NodeRole::Teardown) {
+                continue;
+            }
+            node.execution_status = match node.execution_status {
+                ExecutionStatus::Executing => ExecutionStatus::Cancelled,
+                ExecutionStatus::Executable | ExecutionStatus::NonExecutable => {
+                    ExecutionStatus::Skipped
+                }
+                execution_status => execution_status,
+            };
+        }
+        self.release_teardown_nodes();
+    }
+
+    /// Get all parent node indices of some node identified by [`NodeIndex`], ordered by string identifier.
+    pub fn get_parent_node_indices(&self, index: NodeIndex) -> impl Iterator<Item = NodeIndex> {
+        self.sorted_by_node_id(self.graph.neighbors_directed(index, Direction::Incoming))
+            .into_iter()
+    }
+
+    /// Get all child node indices of some node identified by [`NodeIndex`], ordered by string identifier.
+    pub fn get_child_node_indices(&self, index: NodeIndex) -> impl Iterator<Item = NodeIndex> {
+        self.sorted_by_node_id(self.graph.neighbors_directed(index, Direction::Outgoing))
+            .into_iter()
+    }
+
+    /// Get the [`Dependency`] of `child` on `parent`, if they are connected by an edge.
+    pub fn dependency(&self, parent: NodeIndex, child: NodeIndex) -> Option<Dependency> {
+        self.graph
+            .find_edge(parent, child)
+            .map(|edge| self.graph[edge])
+    }
+
+    /// Checks whether `child` may start although its `parent` is still [`ExecutionStatus::Executing`], because it
+    /// depends on it with [`Dependency::Streaming`] and the parent has emitted a progress mark.
+    pub fn is_streaming(&self, parent: NodeIndex, child: NodeIndex) -> bool {
+        self.dependency(parent, child) == Some(Dependency::Streaming)
+            && self.graph[parent].execution_status == ExecutionStatus::Executing
+            && self.graph[parent].progress_marks > 0
+    }
+
+    /// Records the `progress` reported by the executing `Node` identified by [`NodeIndex`], replacing the progress it
+    /// reported before. Returns `false` and records nothing if the node is not executing (anymore).
+    pub fn report_progress(&mut self, index: NodeIndex, progress: NodeProgress) -> bool {
+        if self.graph[index].execution_status != ExecutionStatus::Executing {
+            return false;
+        }
+        self.graph[index].progress = Some(progress);
+        true
+    }
+
+    /// Get the generation of the graph, which [`DirectedAcyclicGraph::stamp_changes`] advances with every write
+    /// changing its nodes.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Advances the generation of the graph if any of its nodes changed compared to `before`, the graph prior to a
+    /// write, and records it as the generation in which those nodes last changed. Nodes which did not change keep
+    /// the generation recorded in `before`, even if the write replaced the whole graph.
+    pub fn stamp_changes(&mut self, before: &Self) {
+        let generation = before.generation + 1;
+        self.generation = before.generation;
+        self.changed_in = before.changed_in.clone();
+        for i in self.graph.node_indices() {
+            let is_changed = before
+                .node_index(self.node_id(i))
+                .is_none_or(|j| before[j] != self[i]);
+            if is_changed {
+                self.generation = generation;
+                self.changed_in
+                    .insert(self.node_id(i).to_string(), generation);
+            }
+        }
+    }
+
+    /// Get the generation in which the `Node` identified by [`NodeIndex`] last changed, 0 if it never did.
+    pub fn changed_in(&self, index: NodeIndex) -> u64 {
+        self.changed_in
+            .get(self.node_id(index))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Records that the execution status of the `Node` identified by [`NodeIndex`] changed to `execution_status` in
+    /// `generation` without a write of the whole graph, e.g. by a `compare_exchange` in shared memory. The graph's
+    /// generation becomes `generation` if that is newer.
+    pub fn record_status_change(
+        &mut self,
+        index: NodeIndex,
+        execution_status: ExecutionStatus,
+        generation: u64,
+    ) {
+        self.graph[index].execution_status = execution_status;
+        if generation > 0 {
+            self.changed_in
+                .insert(self.node_id(index).to_string(), generation);
+        }
+        self.generation = self.generation.max(generation);
+    }
+
+    /// Returns the string identifier and execution status of every `Node` which changed after `since_generation`
+    /// (see [`DirectedAcyclicGraph::stamp_changes`]), ordered by string identifier, so that monitors polling the graph
+    /// only process the nodes changed since their last poll at [`DirectedAcyclicGraph::generation`].
+    pub fn changes_since(&self, since_generation: u64) -> Vec<(String, ExecutionStatus)> {
+        self.changed_in
+            .iter()
+            .filter(|(_, generation)| **generation > since_generation)
+            .filter_map(|(id, _)| {
+                self.node_index(id)
+                    .map(|i| (id.clone(), self[i].execution_status))
+            })
+            .collect()
+    }
+
+    /// Records that the executing `Node` identified by [`NodeIndex`] was alive `after` the start of the run, so that
+    /// long-running nodes can be told apart from those of dead workers. Returns `false` and records nothing if the
+    /// node is not executing (anymore).
+    pub fn heartbeat(&mut self, index: NodeIndex, after: Duration) -> bool {
+        if self.graph[index].execution_status != ExecutionStatus::Executing {
+            return false;
+        }
+        self.graph[index].heartbeat_after = Some(after);
+        true
+    }
+
+    /// Records a progress mark emitted by the executing `Node` identified by [`NodeIndex`]. With its first mark, its
+    /// [`ExecutionStatus::NonExecutable`] children depending on it with [`Dependency::Streaming`] become
+    /// [`ExecutionStatus::Executable`] if all their other parents are executed or streaming too, see
+    /// [`DirectedAcyclicGraph::is_streaming`]. Returns the indices of those children, ordered by string identifier.
+    pub fn mark_progress(&mut self, index: NodeIndex) -> Vec<NodeIndex> {
+        if self.graph[index].execution_status != ExecutionStatus::Executing {
+            return vec![];
+        }
+        self.graph[index].progress_marks += 1;
+        if self.graph[index].progress_marks > 1 {
+            return vec![];
+        }
+        let released: Vec<NodeIndex> = self
+            .get_child_node_indices(index)
+            .filter(|child| {
+                self.is_streaming(index, *child)
+                    && self.graph[*child].execution_status == ExecutionStatus::NonExecutable
+                    && self.get_parent_node_indices(*child).all(|parent| {
+                        self.graph[parent].execution_status == ExecutionStatus::Executed
+                            || self.is_streaming(parent, *child)
+                    })
+            })
+            .collect();
+        for child in released.iter() {
+            self.graph[*child].execution_status = ExecutionStatus::Executable;
+        }
+        released
+    }
+}
+
+/// Feeds the attribute `bytes` to `hasher`, preceded by the attribute's `tag` and their length.
+fn hash_field(hasher: &mut Sha256, tag: u8, bytes: &[u8]) {
+    hasher.update([tag]);
+    hasher.update((bytes.len() as u64).to_be_bytes());
+    hasher.update(bytes);
+}
+
+/// Feeds the list attribute `items` to `hasher` like [`hash_field`], preceded by the attribute's `tag` and their
+/// number.
+fn hash_list(hasher: &mut Sha256, tag: u8, items: &[String]) {
+    hasher.update([tag]);
+    hasher.update((items.len() as u64).to_be_bytes());
+    for item in items {
+        hasher.update((item.len() as u64).to_be_bytes());
+        hasher.update(item.as_bytes());
+    }
+}
+
+/// Formats the hash computed by `hasher` as lowercase hex.
+fn to_hex(hasher: Sha256) -> String {
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
@@ -0,0 +1,288 @@
+use super::{
+    attribute_spec::{EdgeSpec, NodeSpec},
+    edge::Dependency,
+    graph::DirectedAcyclicGraph,
+};
+use std::{collections::BTreeMap, fmt};
+
+/// ANSI escape sequences coloring the lines of a [`GraphDiff`] printed by [`GraphDiff::render`].
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+/// Kind of change of a node or edge between two graphs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    /// Only in the second graph.
+    Added,
+    /// Only in the first graph.
+    Removed,
+    /// In both graphs, with different attributes.
+    Changed,
+}
+
+impl Change {
+    /// Get the prefix of lines printing the change, like the ones of a unified diff.
+    fn marker(&self) -> char {
+        match self {
+            Change::Added => '+',
+            Change::Removed => '-',
+            Change::Changed => '~',
+        }
+    }
+
+    /// Get the ANSI color of lines printing the change.
+    fn color(&self) -> &'static str {
+        match self {
+            Change::Added => GREEN,
+            Change::Removed => RED,
+            Change::Changed => YELLOW,
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Change::Added => write!(f, "added"),
+            Change::Removed => write!(f, "removed"),
+            Change::Changed => write!(f, "changed"),
+        }
+    }
+}
+
+/// Change of a single attribute of a node or edge, see [`NodeSpec::attributes`] and [`EdgeSpec::attributes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttributeChange {
+    /// Key of the attribute.
+    pub key: String,
+    /// Value in the first graph, [`None`] if it wasn't set.
+    pub before: Option<String>,
+    /// Value in the second graph, [`None`] if it isn't set.
+    pub after: Option<String>,
+}
+
+/// Change of a node or of an edge (identified by `parent -> child`) between two graphs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ElementChange {
+    /// String identifier of the node, or `parent -> child` of the edge.
+    pub id: String,
+    /// Kind of change.
+    pub change: Change,
+    /// Attributes which differ: all set attributes of added and removed elements.
+    pub attributes: Vec<AttributeChange>,
+}
+
+/// Differences between two graphs, e.g. two versions of a pipeline file, so that pipeline changes can be reviewed
+/// like code. Execution statuses and attempts are not compared, only what the graphs declare.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphDiff {
+    /// Added, removed and changed nodes, ordered by string identifier.
+    pub nodes: Vec<ElementChange>,
+    /// Added, removed and changed edges, ordered by parent and child.
+    pub edges: Vec<ElementChange>,
+}
+
+impl GraphDiff {
+    /// Compares the nodes and edges of `before` and `after`.
+    pub fn between(before: &DirectedAcyclicGraph, after: &DirectedAcyclicGraph) -> Self {
+        GraphDiff {
+            nodes: diff_elements(node_attributes(before), node_attributes(after)),
+            edges: diff_elements(edge_attributes(before), edge_attributes(after)),
+        }
+    }
+
+    /// Checks whether both graphs declare the same nodes and edges.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.edges.is_empty()
+    }
+
+    /// Prints the diff for humans: a line per added (`+`), removed (`-`) or changed (`~`) node or edge, followed by
+    /// its attribute changes, in green, red and yellow if `color` is set.
+    pub fn render(&self, color: bool) -> String {
+        let mut rendered = String::new();
+        for (kind, changes) in [("node", &self.nodes), ("edge", &self.edges)] {
+            for change in changes {
+                let (start, end) = match color {
+                    true => (change.change.color(), RESET),
+                    false => ("", ""),
+                };
+                rendered.push_str(&format!(
+                    "{}{} {} {}{}\n",
+                    start,
+                    change.change.marker(),
+                    kind,
+                    change.id,
+                    end
+                ));
+                for attribute in &change.attributes {
+                    let value = |value: &Option<String>| match value {
+                        Some(value) => format!("{:?}", value),
+                        None => String::from("(unset)"),
+                    };
+                    rendered.push_str(&format!(
+                        "{}    {}: {} -> {}{}\n",
+                        start,
+                        attribute.key,
+                        value(&attribute.before),
+                        value(&attribute.after),
+                        end
+                    ));
+                }
+            }
+        }
+        rendered
+    }
+
+    /// Prints the diff as a JSON object with the arrays `nodes` and `edges` of changes, each with its `id`, `change`
+    /// (`added`, `removed` or `changed`) and `attributes` with their `key`, `before` and `after` (`null` if unset).
+    pub fn to_json(&self) -> String {
+        let optional =
+            |value: &Option<String>| value.as_deref().map_or(String::from("null"), json_string);
+        let changes = |changes: &[ElementChange]| {
+            changes
+                .iter()
+                .map(|change| {
+                    let attributes: Vec<String> = change
+                        .attributes
+                        .iter()
+                        .map(|attribute| {
+                            format!(
+                                "{{\"key\":{},\"before\":{},\"after\":{}}}",
+                                json_string(&attribute.key),
+                                optional(&attribute.before),
+                                optional(&attribute.after)
+                            )
+                        })
+                        .collect();
+                    format!(
+                        "{{\"id\":{},\"change\":\"{}\",\"attributes\":[{}]}}",
+                        json_string(&change.id),
+                        change.change,
+                        attributes.join(",")
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+        format!(
+            "{{\"nodes\":[{}],\"edges\":[{}]}}\n",
+            changes(&self.nodes),
+            changes(&self.edges)
+        )
+    }
+}
+
+impl fmt::Display for GraphDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(false))
+    }
+}
+
+/// Quotes `value` as a JSON string.
+fn json_string(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Attributes of the nodes of `graph` by their string identifiers.
+fn node_attributes(graph: &DirectedAcyclicGraph) -> BTreeMap<String, Vec<(&'static str, String)>> {
+    graph
+        .get_node_indices()
+        .map(|i| {
+            (
+                graph.node_id(i).to_string(),
+                NodeSpec::from_node(&graph[i]).attributes(),
+            )
+        })
+        .collect()
+}
+
+/// Attributes of the edges of `graph` by `parent -> child`.
+fn edge_attributes(graph: &DirectedAcyclicGraph) -> BTreeMap<String, Vec<(&'static str, String)>> {
+    let mut edges = BTreeMap::new();
+    for parent in graph.get_node_indices() {
+        for child in graph.get_child_node_indices(parent) {
+            let spec = EdgeSpec {
+                label: None,
+                dependency: graph
+                    .dependency(parent, child)
+                    .unwrap_or(Dependency::Completion),
+            };
+            edges.insert(
+                format!("{} -> {}", graph.node_id(parent), graph.node_id(child)),
+                spec.attributes(),
+            );
+        }
+    }
+    edges
+}
+
+/// Compares the attributes of the elements of two graphs by their identifiers.
+fn diff_elements(
+    before: BTreeMap<String, Vec<(&'static str, String)>>,
+    mut after: BTreeMap<String, Vec<(&'static str, String)>>,
+) -> Vec<ElementChange> {
+    let mut changes = vec![];
+    for (id, before) in before {
+        let (change, after) = match after.remove(&id) {
+            Some(after) => (Change::Changed, after),
+            None => (Change::Removed, vec![]),
+        };
+        let attributes = diff_attributes(&before, &after);
+        if !attributes.is_empty() || change == Change::Removed {
+            changes.push(ElementChange {
+                id,
+                change,
+                attributes,
+            });
+        }
+    }
+    for (id, after) in after {
+        changes.push(ElementChange {
+            attributes: diff_attributes(&[], &after),
+            id,
+            change: Change::Added,
+        });
+    }
+    changes.sort_by(|a, b| a.id.cmp(&b.id));
+    changes
+}
+
+/// Compares two lists of attributes by their keys.
+fn diff_attributes(
+    before: &[(&'static str, String)],
+    after: &[(&'static str, String)],
+) -> Vec<AttributeChange> {
+    let value = |attributes: &[(&'static str, String)], key: &str| {
+        attributes
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| value.clone())
+    };
+    let mut keys: Vec<&'static str> = before.iter().map(|(key, _)| *key).collect();
+    for (key, _) in after {
+        if !keys.contains(key) {
+            keys.push(key);
+        }
+    }
+    keys.into_iter()
+        .map(|key| AttributeChange {
+            key: key.to_string(),
+            before: value(before, key),
+            after: value(after, key),
+        })
+        .filter(|attribute| attribute.before != attribute.after)
+        .collect()
+}
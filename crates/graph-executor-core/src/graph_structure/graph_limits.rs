@@ -0,0 +1,69 @@
+use anyhow::{anyhow, Result};
+
+/// Upper bounds on the size of a [`super::graph::DirectedAcyclicGraph`], which are checked when the graph
+/// is loaded and whenever it is mutated, so that oversized graphs are rejected with a clear error before
+/// they are written to shared memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GraphLimits {
+    /// Maximum number of [`super::node::Node`]s.
+    pub max_nodes: usize,
+    /// Maximum number of [`super::edge::Edge`]s.
+    pub max_edges: usize,
+    /// Maximum number of bytes of the serialized graph.
+    pub max_serialized_size: usize,
+}
+
+impl Default for GraphLimits {
+    /// Constructs limits suitable for graphs executed via shared memory.
+    fn default() -> Self {
+        GraphLimits {
+            max_nodes: 1_000,
+            max_edges: 10_000,
+            max_serialized_size: 64 * 1024,
+        }
+    }
+}
+
+impl GraphLimits {
+    /// Constructs limits which never reject a graph.
+    pub fn unlimited() -> Self {
+        GraphLimits {
+            max_nodes: usize::MAX,
+            max_edges: usize::MAX,
+            max_serialized_size: usize::MAX,
+        }
+    }
+
+    /// Checks the number of nodes and edges of a graph against `max_nodes` and `max_edges`.
+    pub fn check_counts(&self, node_count: usize, edge_count: usize) -> Result<()> {
+        if node_count > self.max_nodes {
+            return Err(anyhow!(
+                "Graph exceeds the limit of {} nodes with {} nodes.",
+                self.max_nodes,
+                node_count
+            ));
+        }
+        if edge_count > self.max_edges {
+            return Err(anyhow!(
+                "Graph exceeds the limit of {} edges with {} edges.",
+                self.max_edges,
+                edge_count
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks the number of bytes of a serialized graph against `max_serialized_size`.
+    pub fn check_serialized_size(&self, serialized_size: usize) -> Result<()> {
+        if serialized_size > self.max_serialized_size {
+            return Err(anyhow!(
+                "Graph exceeds the limit of {} bytes when serialized with {} bytes.",
+                self.max_serialized_size,
+                serialized_size
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+use super::{
+    edge::Edge, graph::DirectedAcyclicGraph, graph_limits::GraphLimits, node::Node,
+    parse_mode::ParseMode, template,
+};
+use anyhow::{anyhow, Result};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// Nodes and edges declared by a pipeline file and the fragments it includes, which are assembled into a
+/// [`DirectedAcyclicGraph`] once all of them were loaded.
+#[derive(Default)]
+pub(crate) struct Fragment {
+    pub(crate) nodes: Vec<(String, Node)>,
+    pub(crate) edges: Vec<Edge>,
+}
+
+/// State of loading a pipeline file and the files it includes, see [`DirectedAcyclicGraph::from_pipeline_file`].
+pub(crate) struct IncludeContext<'a> {
+    /// Directory relative to which the including file's includes are resolved.
+    directory: PathBuf,
+    /// Variables every loaded file is rendered with as a template, none if files are not rendered.
+    variables: Option<&'a BTreeMap<String, String>>,
+    /// Parse mode of included DOT files.
+    parse_mode: ParseMode,
+    /// Limits every loaded file is checked against.
+    limits: GraphLimits,
+    /// Canonical paths of the files currently being loaded, to detect include cycles.
+    including: Vec<PathBuf>,
+}
+
+impl<'a> IncludeContext<'a> {
+    /// Creates an [`IncludeContext`] resolving includes relative to the working directory.
+    pub(crate) fn new(
+        parse_mode: ParseMode,
+        limits: GraphLimits,
+        variables: Option<&'a BTreeMap<String, String>>,
+    ) -> Self {
+        IncludeContext {
+            directory: PathBuf::from("."),
+            variables,
+            parse_mode,
+            limits,
+            including: vec![],
+        }
+    }
+
+    /// Creates an [`IncludeContext`] for loading the file at `file_path` itself, resolving its includes relative to
+    /// its directory.
+    pub(crate) fn for_file(
+        file_path: &str,
+        parse_mode: ParseMode,
+        limits: GraphLimits,
+        variables: Option<&'a BTreeMap<String, String>>,
+    ) -> Result<Self> {
+        let canonical_path = std::fs::canonicalize(file_path)
+            .map_err(|e| anyhow!("Failed reading file {}: {}", file_path, e))?;
+        Ok(IncludeContext {
+            directory: canonical_path
+                .parent()
+                .map_or(PathBuf::from("/"), Path::to_path_buf),
+            variables,
+            parse_mode,
+            limits,
+            including: vec![canonical_path],
+        })
+    }
+
+    /// Get the parse mode of the loaded DOT files.
+    pub(crate) fn parse_mode(&self) -> ParseMode {
+        self.parse_mode
+    }
+
+    /// Get the limits of the loaded files.
+    pub(crate) fn limits(&self) -> GraphLimits {
+        self.limits
+    }
+
+    /// Loads the pipeline file at `path` (relative to the including file's directory) as a [`DirectedAcyclicGraph`]
+    /// with its own includes: a DOT file or, by its extension, an Argo Workflow or Airflow DAG in YAML or JSON.
+    pub(crate) fn load(&mut self, path: &str) -> Result<DirectedAcyclicGraph> {
+        let path = self.directory.join(path);
+        let canonical_path = std::fs::canonicalize(&path)
+            .map_err(|e| anyhow!("Failed reading file {}: {}", path.display(), e))?;
+        if self.including.contains(&canonical_path) {
+            return Err(anyhow!(
+                "Include cycle: {} includes itself.",
+                path.display()
+            ));
+        }
+        let mut text = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow!("Failed reading file {}: {}", path.display(), e))?;
+        if let Some(variables) = self.variables {
+            text = template::render(&text, variables)
+                .map_err(|e| anyhow!("{} in {}", e, path.display()))?;
+        }
+
+        let directory = canonical_path
+            .parent()
+            .map_or(PathBuf::from("/"), Path::to_path_buf);
+        let directory = std::mem::replace(&mut self.directory, directory);
+        self.including.push(canonical_path);
+        let is_workflow = [".yaml", ".yml", ".json"]
+            .iter()
+            .any(|extension| path.to_string_lossy().ends_with(extension));
+        let graph = match is_workflow {
+            true => DirectedAcyclicGraph::from_workflow_str_with_imports(&text, self),
+            false => DirectedAcyclicGraph::from_dot_str(&text, self.parse_mode, self.limits, self),
+        };
+        self.including.pop();
+        self.directory = directory;
+        graph.map_err(|e| anyhow!("{} (in {})", e, path.display()))
+    }
+
+    /// Loads the pipeline file at `path` like [`IncludeContext::load`] and adds its nodes and edges to `fragment`,
+    /// their node identifiers prefixed by `namespace` and a `.` (by default the file's name without extension, no
+    /// prefix if empty).
+    pub(crate) fn include(
+        &mut self,
+        path: &str,
+        namespace: Option<&str>,
+        fragment: &mut Fragment,
+    ) -> Result<()> {
+        let graph = self.load(path)?;
+        let namespace = match namespace {
+            Some(namespace) => namespace.to_string(),
+            None => Path::new(path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        };
+        let id = |i| match namespace.is_empty() {
+            true => graph.node_id(i).to_string(),
+            false => format!("{}.{}", namespace, graph.node_id(i)),
+        };
+        for i in graph.get_node_indices() {
+            fragment.nodes.push((id(i), graph[i].clone()));
+            for child in graph.get_child_node_indices(i) {
+                fragment.edges.push(
+                    Edge::new(id(i), id(child))
+                        .with_dependency(graph.dependency(i, child).unwrap_or_default()),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DirectedAcyclicGraph {
+    /// Loads a pipeline file assembled from shared fragments: a DOT file, whose `include "common.dot"` statements
+    /// include other pipeline files, or an Argo Workflow or Airflow DAG in YAML or JSON (detected by the file's
+    /// extension), whose top-level `imports` list them, like `imports: [common.yaml, {file: deploy.dot, namespace:
+    /// prod}]`. Paths are resolved relative to the including file.
+    ///
+    /// The nodes of an included file are added under identifiers prefixed by its namespace and a `.`, e.g.
+    /// `common.build`, so that the including file can declare edges to them and include the same fragment multiple
+    /// times. The namespace defaults to the file's name without extension and is set in DOT like
+    /// `include "common.dot" [ namespace = lib ]`; an empty namespace adds the nodes without a prefix.
+    ///
+    /// Every loaded file is rendered as a template with `variables` (see [`template::render`]) before parsing it.
+    pub fn from_pipeline_file(
+        file_path: &str,
+        parse_mode: ParseMode,
+        variables: &BTreeMap<String, String>,
+    ) -> Result<Self> {
+        DirectedAcyclicGraph::from_pipeline_file_with_limits(
+            file_path,
+            parse_mode,
+            variables,
+            GraphLimits::default(),
+        )
+    }
+
+    /// Loads a pipeline file like [`DirectedAcyclicGraph::from_pipeline_file`] with custom [`GraphLimits`], which
+    /// every loaded file is checked against as well.
+    pub fn from_pipeline_file_with_limits(
+        file_path: &str,
+        parse_mode: ParseMode,
+        variables: &BTreeMap<String, String>,
+        limits: GraphLimits,
+    ) -> Result<Self> {
+        IncludeContext::new(parse_mode, limits, Some(variables)).load(file_path)
+    }
+}
@@ -0,0 +1,75 @@
+use anyhow::{anyhow, Error, Result};
+use core::{fmt, str::FromStr};
+
+/// I/O scheduling class and level of a command node's process, like `ionice -c <class> -n <level>`.
+/// Levels range from 0 (highest) to 7 (lowest) priority.
+#[derive(Clone, Debug, PartialEq, Eq, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum IoPriority {
+    /// Served before all other I/O, regardless of other processes' I/O (requires privileges).
+    Realtime(u8),
+    /// Default class, served in order of `level`.
+    BestEffort(u8),
+    /// Only served when no other process has requested I/O for a while.
+    Idle,
+}
+
+impl IoPriority {
+    /// Value passed to the `ioprio_set` system call.
+    #[cfg(feature = "std")]
+    pub fn ioprio(&self) -> libc::c_int {
+        const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+        match self {
+            IoPriority::Realtime(level) => (1 << IOPRIO_CLASS_SHIFT) | *level as libc::c_int,
+            IoPriority::BestEffort(level) => (2 << IOPRIO_CLASS_SHIFT) | *level as libc::c_int,
+            IoPriority::Idle => 3 << IOPRIO_CLASS_SHIFT,
+        }
+    }
+
+    /// Applies the I/O priority to the calling process.
+    #[cfg(feature = "std")]
+    pub fn apply(&self) -> std::io::Result<()> {
+        const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+        // SAFETY: `ioprio_set` only reads its integer arguments, `0` refers to the calling process.
+        match unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, self.ioprio()) } {
+            -1 => Err(std::io::Error::last_os_error()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for IoPriority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IoPriority::Realtime(level) => write!(f, "realtime:{}", level),
+            IoPriority::BestEffort(level) => write!(f, "best-effort:{}", level),
+            IoPriority::Idle => write!(f, "idle"),
+        }
+    }
+}
+
+impl FromStr for IoPriority {
+    type Err = Error;
+    /// Parses [`IoPriority`] from a string like: "idle", "best-effort:7" or "realtime:0".
+    fn from_str(io_priority_string: &str) -> Result<Self> {
+        let parse_level = |level: &str| -> Result<u8> {
+            match level.parse::<u8>() {
+                Ok(level) if level <= 7 => Ok(level),
+                _ => Err(anyhow!(
+                    "IoPriority::from_str parsing error: Invalid level {}, expected 0 to 7.",
+                    level
+                )),
+            }
+        };
+        match io_priority_string.split_once(':') {
+            None if io_priority_string == "idle" => Ok(IoPriority::Idle),
+            Some(("realtime", level)) => Ok(IoPriority::Realtime(parse_level(level)?)),
+            Some(("best-effort", level)) => Ok(IoPriority::BestEffort(parse_level(level)?)),
+            _ => Err(anyhow!(
+                "IoPriority::from_str parsing error: Invalid I/O priority {}, expected idle, best-effort:<level> or realtime:<level>.",
+                io_priority_string
+            )),
+        }
+    }
+}
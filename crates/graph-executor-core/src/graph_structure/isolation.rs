@@ -0,0 +1,39 @@
+use anyhow::{anyhow, Error, Result};
+use core::{fmt, str::FromStr};
+
+/// Where a [`super::node::Node`] is executed by a worker. Both models can be mixed in one graph.
+#[derive(Clone, Debug, PartialEq, Eq, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Isolation {
+    /// Executed in the worker's own thread, for cheap pure-Rust work: the node's command names a function registered
+    /// with the executor, and nodes without a command print their args.
+    Inline,
+    /// Executed in a child process, for heavy or unsafe work which must not take the worker down with it: the node's
+    /// command is run with `sh -c`, and nodes without a command print their args from a child process.
+    Process,
+}
+
+impl fmt::Display for Isolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Isolation::Inline => write!(f, "inline"),
+            Isolation::Process => write!(f, "process"),
+        }
+    }
+}
+
+impl FromStr for Isolation {
+    type Err = Error;
+    /// Parses [`Isolation`] from a string like: "inline" or "process".
+    fn from_str(isolation: &str) -> Result<Self> {
+        match isolation {
+            "inline" => Ok(Isolation::Inline),
+            "process" => Ok(Isolation::Process),
+            _ => Err(anyhow!(
+                "Isolation::from_str parsing error: Invalid isolation {}, expected inline or process.",
+                isolation
+            )),
+        }
+    }
+}
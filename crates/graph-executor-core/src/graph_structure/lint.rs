@@ -0,0 +1,168 @@
+use super::graph::DirectedAcyclicGraph;
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt;
+use petgraph::graph::NodeIndex;
+
+/// Thresholds above which [`DirectedAcyclicGraph::lint_with`] warns about a graph's structure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LintThresholds {
+    /// Number of direct children above which a node is reported.
+    pub max_children: usize,
+    /// Number of consecutive nodes without a command from which a linear chain is reported.
+    pub min_trivial_chain_length: usize,
+}
+
+impl Default for LintThresholds {
+    fn default() -> Self {
+        LintThresholds {
+            max_children: 100,
+            min_trivial_chain_length: 10,
+        }
+    }
+}
+
+/// Anti-pattern found in the structure of a [`DirectedAcyclicGraph`] which does not prevent its execution but
+/// likely makes it slower or harder to maintain than necessary. Serializes with its kebab-case code (see
+/// [`LintWarning::code`]) in the `lint` field for machine-readable output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "lint", rename_all = "kebab-case"))]
+pub enum LintWarning {
+    /// A node with more direct children than [`LintThresholds::max_children`], which serializes their scheduling.
+    ManyChildren { node_id: String, child_count: usize },
+    /// A linear chain of nodes without a command, each of which only adds scheduling overhead.
+    TrivialChain { node_ids: Vec<String> },
+    /// A node without parents and children in a graph with other nodes, unconnected to the rest of the graph.
+    OrphanNode { node_id: String },
+    /// Multiple nodes with the same args, which likely duplicate each other's work.
+    DuplicateArgs { args: String, node_ids: Vec<String> },
+}
+
+impl LintWarning {
+    /// Stable identifier of the kind of warning, e.g. for filtering machine-readable output.
+    pub fn code(&self) -> &'static str {
+        match self {
+            LintWarning::ManyChildren { .. } => "many-children",
+            LintWarning::TrivialChain { .. } => "trivial-chain",
+            LintWarning::OrphanNode { .. } => "orphan-node",
+            LintWarning::DuplicateArgs { .. } => "duplicate-args",
+        }
+    }
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: ", self.code())?;
+        match self {
+            LintWarning::ManyChildren {
+                node_id,
+                child_count,
+            } => write!(f, "node {} with {} direct children", node_id, child_count),
+            LintWarning::TrivialChain { node_ids } => write!(
+                f,
+                "long linear chain of {} trivial nodes: {}",
+                node_ids.len(),
+                node_ids.join(" -> ")
+            ),
+            LintWarning::OrphanNode { node_id } => write!(
+                f,
+                "orphan node {} never reachable from any other node",
+                node_id
+            ),
+            LintWarning::DuplicateArgs { args, node_ids } => write!(
+                f,
+                "duplicate args {:?} across {} nodes: {}",
+                args,
+                node_ids.len(),
+                node_ids.join(", ")
+            ),
+        }
+    }
+}
+
+impl DirectedAcyclicGraph {
+    /// Checks the graph for structural anti-patterns with the default [`LintThresholds`].
+    pub fn lint(&self) -> Vec<LintWarning> {
+        self.lint_with(&LintThresholds::default())
+    }
+
+    /// Checks the graph for structural anti-patterns, returning the warnings grouped by kind.
+    pub fn lint_with(&self, thresholds: &LintThresholds) -> Vec<LintWarning> {
+        let mut warnings = vec![];
+        let child_count = |i: NodeIndex| self.get_child_node_indices(i).count();
+        let parent_count = |i: NodeIndex| self.get_parent_node_indices(i).count();
+
+        for i in self.get_node_indices() {
+            if child_count(i) > thresholds.max_children {
+                warnings.push(LintWarning::ManyChildren {
+                    node_id: self.node_id(i).to_string(),
+                    child_count: child_count(i),
+                });
+            }
+        }
+
+        // A chain continues from a trivial node to its only child if it is trivial and that node its only parent
+        let is_trivial = |i: NodeIndex| self[i].command().is_none();
+        let next_in_chain = |i: NodeIndex| {
+            let mut children = self.get_child_node_indices(i);
+            match (children.next(), children.next()) {
+                (Some(child), None) if is_trivial(child) && parent_count(child) == 1 => Some(child),
+                _ => None,
+            }
+        };
+        for start in self.get_node_indices().filter(|i| is_trivial(*i)) {
+            let mut parents = self.get_parent_node_indices(start);
+            if let (Some(parent), None) = (parents.next(), parents.next()) {
+                if is_trivial(parent) && next_in_chain(parent) == Some(start) {
+                    continue;
+                }
+            }
+            let mut chain = vec![start];
+            while let Some(next) = next_in_chain(*chain.last().unwrap_or(&start)) {
+                chain.push(next);
+            }
+            if chain.len() >= thresholds.min_trivial_chain_length {
+                warnings.push(LintWarning::TrivialChain {
+                    node_ids: chain.iter().map(|i| self.node_id(*i).to_string()).collect(),
+                });
+            }
+        }
+
+        if self.get_node_indices().nth(1).is_some() {
+            for i in self.get_node_indices() {
+                if parent_count(i) == 0 && child_count(i) == 0 {
+                    warnings.push(LintWarning::OrphanNode {
+                        node_id: self.node_id(i).to_string(),
+                    });
+                }
+            }
+        }
+
+        let mut nodes_by_args: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for i in self
+            .get_node_indices()
+            .filter(|i| !self[*i].args().is_empty())
+        {
+            nodes_by_args
+                .entry(self[i].args())
+                .or_default()
+                .push(self.node_id(i).to_string());
+        }
+        for (args, node_ids) in nodes_by_args {
+            if node_ids.len() > 1 {
+                warnings.push(LintWarning::DuplicateArgs {
+                    args: args.to_string(),
+                    node_ids,
+                });
+            }
+        }
+
+        warnings
+    }
+}
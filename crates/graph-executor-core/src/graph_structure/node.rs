@@ -0,0 +1,883 @@
+use super::{
+    attempt::{Attempt, Hook},
+    execution_record::ExecutionRecord,
+    execution_status::ExecutionStatus,
+    io_priority::IoPriority,
+    isolation::Isolation,
+    node_progress::NodeProgress,
+    node_role::NodeRole,
+};
+use alloc::{format, string::String, vec, vec::Vec};
+#[cfg(feature = "std")]
+use anyhow::Error;
+use anyhow::{anyhow, Result};
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::{fmt, os::unix::process::CommandExt, process::Command, str::FromStr, thread};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Node {
+    /// Execution placeholder prior to implementing arbitrary computation execution.
+    args: String,
+    /// The execution status indicates, whether a node is executable / is currently executing / has already been executed.
+    /// Changes during the [`Node`]'s lifetime in the following order:
+    ///
+    /// 1. [`ExecutionStatus::NonExecutable`] if the node has at least one parent node which hasn't been executed.
+    /// 2. [`ExecutionStatus::Executable`] if no parent node hasn't been executed.
+    /// 3. [`ExecutionStatus::Executing`] if some process started executing this node.
+    /// 4. [`ExecutionStatus::Executed`] if the process has finished executing.
+    ///
+    /// If executing fails, the node becomes [`ExecutionStatus::Failed`] and its descendants [`ExecutionStatus::Skipped`].
+    pub execution_status: ExecutionStatus,
+    /// Optional cost of executing the node (in arbitrary units, e.g. CPU seconds), see
+    /// [`crate::scheduler::CostAwareScheduler`].
+    cost: Option<u64>,
+    /// Optional name of the gang the node belongs to. All nodes of a gang are executed simultaneously, see
+    /// [`crate::scheduler::GraphView::executable_node_indices`].
+    gang: Option<String>,
+    /// Optional concurrency key of the node. No two nodes with the same key execute at the same time, in any
+    /// worker of the run, see [`crate::scheduler::GraphView::executable_node_indices`].
+    mutex: Option<String>,
+    /// Optional shell command run (with `sh -c`) when the node is executed, instead of printing `args`.
+    command: Option<String>,
+    /// Optional shell command run before the node's body by the executing worker, see [`Hook::Pre`].
+    pre: Option<String>,
+    /// Optional shell command run after the node's body by the executing worker, see [`Hook::Post`].
+    post: Option<String>,
+    /// Optional priority of the node, see
+    /// [`crate::scheduler::PriorityScheduler`] and
+    /// the preemption policy of `graph-executor-shm`. Nodes without a priority have priority 0.
+    priority: Option<i64>,
+    /// Optional niceness (-20 to 19) of the `command`'s process, so that heavy batch nodes don't starve
+    /// interactive processes.
+    nice: Option<i32>,
+    /// Optional I/O priority of the `command`'s process.
+    ionice: Option<IoPriority>,
+    /// Optional number of times the node is retried after failing before it is marked as
+    /// [`ExecutionStatus::Failed`].
+    retries: Option<u32>,
+    /// Optional longest time an attempt at executing the node may take before it is aborted and fails, see
+    /// [`Node::timeout`].
+    timeout: Option<Duration>,
+    /// Paths of the files the node reads, relative to the working directory of its `command`. Changes to these
+    /// files cause the node to be re-executed by `graph-executor watch-run`.
+    inputs: Vec<String>,
+    /// Paths of the files the node writes, relative to the working directory of its `command`. If all of them are
+    /// at least as new as all `inputs`, the node is up to date and its command is not run, see
+    /// [`Node::is_up_to_date`].
+    outputs: Vec<String>,
+    /// Whether the node must be approved (e.g. with `graph-executor shell`) before it is claimed, see
+    /// [`Node::is_awaiting_approval`].
+    approval: bool,
+    /// Set once the node requiring `approval` was approved.
+    pub approved: bool,
+    /// Optional name of an external event (the iceoryx2 event service notified by an external component) the node
+    /// waits for before it is claimed, see [`Node::is_awaiting_event`].
+    await_event: Option<String>,
+    /// Set once the event the node awaits was received.
+    pub event_received: bool,
+    /// Optional special role of the node, see [`NodeRole`].
+    role: Option<NodeRole>,
+    /// Optional isolation the node is executed with, see [`Node::isolation`].
+    isolation: Option<Isolation>,
+    /// Optional working directory of the `command`'s process, relative to the working directory of the worker
+    /// executing it (or of its isolated working directory).
+    cwd: Option<String>,
+    /// Environment variables set for the `command`'s process, overriding those of the worker.
+    env: Vec<(String, String)>,
+    /// Set once the node is [`ExecutionStatus::Executed`].
+    pub execution_record: Option<ExecutionRecord>,
+    /// Error message, set once the node is [`ExecutionStatus::Failed`].
+    pub error: Option<String>,
+    /// All attempts at executing the node in the order they ended.
+    pub attempts: Vec<Attempt>,
+    /// Number of progress marks the node's command emitted while executing, e.g. one per chunk of output written.
+    /// Children depending on the node with [`super::edge::Dependency::Streaming`] start with its first mark.
+    pub progress_marks: u32,
+    /// Latest progress the node reported while executing, see
+    /// [`super::graph::DirectedAcyclicGraph::report_progress`].
+    pub progress: Option<NodeProgress>,
+    /// Time after the start of the run at which the executing node last reported being alive (or its worker on its
+    /// behalf), see [`super::graph::DirectedAcyclicGraph::heartbeat`].
+    pub heartbeat_after: Option<Duration>,
+    /// Time after the start of the run at which the node was first seen executable by a worker of the run, from
+    /// which [`crate::scheduler::PriorityScheduler::aging`] boosts its priority while it waits to be claimed.
+    pub enqueued_after: Option<Duration>,
+}
+
+impl Node {
+    /// Creates a new [`Node`].
+    pub fn new(args: String) -> Self {
+        Node {
+            args,
+            execution_status: ExecutionStatus::Executable,
+            cost: None,
+            gang: None,
+            mutex: None,
+            command: None,
+            pre: None,
+            post: None,
+            priority: None,
+            nice: None,
+            ionice: None,
+            retries: None,
+            timeout: None,
+            inputs: vec![],
+            outputs: vec![],
+            approval: false,
+            approved: false,
+            await_event: None,
+            event_received: false,
+            role: None,
+            isolation: None,
+            cwd: None,
+            env: vec![],
+            execution_record: None,
+            error: None,
+            attempts: vec![],
+            progress_marks: 0,
+            progress: None,
+            heartbeat_after: None,
+            enqueued_after: None,
+        }
+    }
+}
+
+impl Default for Node {
+    /// Constructs a default Node instance with empty args.
+    fn default() -> Self {
+        Node::new(String::new())
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Struct Node, Node.args: {}, Node.execution_status: {}",
+            self.args, self.execution_status
+        )?;
+        if let Some(cost) = self.cost {
+            write!(f, ", Node.cost: {}", cost)?;
+        }
+        if let Some(gang) = &self.gang {
+            write!(f, ", Node.gang: {}", gang)?;
+        }
+        if let Some(mutex) = &self.mutex {
+            write!(f, ", Node.mutex: {}", mutex)?;
+        }
+        if let Some(priority) = self.priority {
+            write!(f, ", Node.priority: {}", priority)?;
+        }
+        if let Some(nice) = self.nice {
+            write!(f, ", Node.nice: {}", nice)?;
+        }
+        if let Some(ionice) = self.ionice {
+            write!(f, ", Node.ionice: {}", ionice)?;
+        }
+        if let Some(retries) = self.retries {
+            write!(f, ", Node.retries: {}", retries)?;
+        }
+        if let Some(timeout) = self.timeout {
+            write!(f, ", Node.timeout: {}", timeout.as_secs_f64())?;
+        }
+        if !self.inputs.is_empty() {
+            write!(f, ", Node.inputs: {}", self.inputs.join(","))?;
+        }
+        if !self.outputs.is_empty() {
+            write!(f, ", Node.outputs: {}", self.outputs.join(","))?;
+        }
+        if self.approval {
+            let approval = if self.approved { "approved" } else { "pending" };
+            write!(f, ", Node.approval: {}", approval)?;
+        }
+        if let Some(event) = &self.await_event {
+            let received = if self.event_received {
+                "received"
+            } else {
+                "pending"
+            };
+            write!(f, ", Node.await_event: {} {}", event, received)?;
+        }
+        if let Some(role) = self.role {
+            write!(f, ", Node.role: {}", role)?;
+        }
+        if let Some(isolation) = self.isolation {
+            write!(f, ", Node.isolation: {}", isolation)?;
+        }
+        if let Some(cwd) = &self.cwd {
+            write!(f, ", Node.cwd: {}", cwd)?;
+        }
+        if !self.env.is_empty() {
+            let env: Vec<String> = self
+                .env
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect();
+            write!(f, ", Node.env: {}", env.join(","))?;
+        }
+        if let Some(pre) = &self.pre {
+            write!(f, ", Node.pre: {}", pre)?;
+        }
+        if let Some(post) = &self.post {
+            write!(f, ", Node.post: {}", post)?;
+        }
+        if let Some(command) = &self.command {
+            write!(f, ", Node.command: {}", command)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromStr for Node {
+    type Err = Error;
+    /// Parses [`Node`] from a string like: "Struct Node, Node.args: , Node.execution_status: Executable"
+    ///
+    /// The following two [`Node`]s are identical:
+    /// ```
+    /// # use graph_executor_core::graph_structure::node::Node;
+    /// # use std::str::FromStr;
+    /// let node_from_str = Node::from_str("Struct Node, Node.args: , Node.execution_status: Executable").unwrap();
+    /// let node_new = Node::new(String::from(""));
+    /// assert_eq!(node_from_str, node_new);
+    /// ```
+    fn from_str(node_string: &str) -> Result<Self> {
+        let mut node = Node::default();
+
+        // `args` may itself contain commas, so it extends up to the last `execution_status` (which is printed
+        // directly after it) or, if there is none, up to the last field following it.
+        let node_string = node_string.trim();
+        let other_fields = match node_string.find(", Node.args: ") {
+            Some(args_start) => {
+                let args_and_other_fields = &node_string[args_start + ", Node.args: ".len()..];
+                let args_end = args_and_other_fields
+                    .rfind(", Node.execution_status: ")
+                    .or_else(|| args_and_other_fields.rfind(", Node."))
+                    .unwrap_or(args_and_other_fields.len());
+                node.args = String::from(&args_and_other_fields[..args_end]);
+                &args_and_other_fields[args_end..]
+            }
+            None => node_string,
+        };
+
+        // Other fields may contain commas as well (e.g. `command`), so they are split at the field prefix.
+        for part in other_fields.split(", Node.") {
+            let part = &format!(" Node.{}", part);
+            match part {
+                // Parsing `Node`'s `execution_status`.
+                part if part.starts_with(" Node.execution_status: ") => {
+                    node.execution_status =
+                        ExecutionStatus::from_str(part.strip_prefix(" Node.execution_status: ").ok_or(anyhow!(
+                            "Node::from_str parsing error: no ' execution_status: ' prefix despite successful check."
+                        ))?)?;
+                }
+                // Parsing `Node`'s optional `cost`.
+                part if part.starts_with(" Node.cost: ") => {
+                    node.cost = Some(part[" Node.cost: ".len()..].parse().map_err(|e| {
+                        anyhow!("Node::from_str parsing error: invalid cost {}: {}", part, e)
+                    })?);
+                }
+                // Parsing `Node`'s optional `gang`.
+                part if part.starts_with(" Node.gang: ") => {
+                    node.gang = Some(part[" Node.gang: ".len()..].to_string());
+                }
+                // Parsing `Node`'s optional `mutex`.
+                part if part.starts_with(" Node.mutex: ") => {
+                    node.mutex = Some(part[" Node.mutex: ".len()..].to_string());
+                }
+                // Parsing `Node`'s optional `priority`.
+                part if part.starts_with(" Node.priority: ") => {
+                    node.priority =
+                        Some(part[" Node.priority: ".len()..].parse().map_err(|e| {
+                            anyhow!(
+                                "Node::from_str parsing error: invalid priority {}: {}",
+                                part,
+                                e
+                            )
+                        })?);
+                }
+                // Parsing `Node`'s optional `nice`.
+                part if part.starts_with(" Node.nice: ") => {
+                    let nice = part[" Node.nice: ".len()..].parse().map_err(|e| {
+                        anyhow!("Node::from_str parsing error: invalid nice {}: {}", part, e)
+                    })?;
+                    node = node.with_nice(nice)?;
+                }
+                // Parsing `Node`'s optional `ionice`.
+                part if part.starts_with(" Node.ionice: ") => {
+                    node.ionice = Some(IoPriority::from_str(&part[" Node.ionice: ".len()..])?);
+                }
+                // Parsing `Node`'s optional `retries`.
+                part if part.starts_with(" Node.retries: ") => {
+                    node.retries = Some(part[" Node.retries: ".len()..].parse().map_err(|e| {
+                        anyhow!(
+                            "Node::from_str parsing error: invalid retries {}: {}",
+                            part,
+                            e
+                        )
+                    })?);
+                }
+                // Parsing `Node`'s optional `timeout` in seconds.
+                part if part.starts_with(" Node.timeout: ") => {
+                    node = node.with_timeout(parse_seconds(&part[" Node.timeout: ".len()..])?)?;
+                }
+                // Parsing `Node`'s optional `inputs`.
+                part if part.starts_with(" Node.inputs: ") => {
+                    node = node.with_inputs(
+                        part[" Node.inputs: ".len()..]
+                            .split(',')
+                            .map(str::to_string)
+                            .collect(),
+                    )?;
+                }
+                // Parsing `Node`'s optional `outputs`.
+                part if part.starts_with(" Node.outputs: ") => {
+                    node = node.with_outputs(
+                        part[" Node.outputs: ".len()..]
+                            .split(',')
+                            .map(str::to_string)
+                            .collect(),
+                    )?;
+                }
+                // Parsing `Node`'s optional `approval`.
+                part if part.starts_with(" Node.approval: ") => {
+                    node.approval = true;
+                    node.approved = match &part[" Node.approval: ".len()..] {
+                        "pending" => false,
+                        "approved" => true,
+                        approval => {
+                            return Err(anyhow!(
+                                "Node::from_str parsing error: invalid approval {}.",
+                                approval
+                            ))
+                        }
+                    };
+                }
+                // Parsing `Node`'s optional `await_event`.
+                part if part.starts_with(" Node.await_event: ") => {
+                    let (event, received) = part[" Node.await_event: ".len()..]
+                        .rsplit_once(' ')
+                        .ok_or(anyhow!(
+                            "Node::from_str parsing error: invalid await_event {}.",
+                            part
+                        ))?;
+                    node = node.with_awaited_event(event.to_string())?;
+                    node.event_received = match received {
+                        "pending" => false,
+                        "received" => true,
+                        received => {
+                            return Err(anyhow!(
+                                "Node::from_str parsing error: invalid event state {}.",
+                                received
+                            ))
+                        }
+                    };
+                }
+                // Parsing `Node`'s optional `role`.
+                part if part.starts_with(" Node.role: ") => {
+                    node.role = Some(NodeRole::from_str(&part[" Node.role: ".len()..])?);
+                }
+                // Parsing `Node`'s optional `isolation`.
+                part if part.starts_with(" Node.isolation: ") => {
+                    node.isolation = Some(Isolation::from_str(&part[" Node.isolation: ".len()..])?);
+                }
+                // Parsing `Node`'s optional `cwd`.
+                part if part.starts_with(" Node.cwd: ") => {
+                    node = node.with_cwd(part[" Node.cwd: ".len()..].to_string())?;
+                }
+                // Parsing `Node`'s optional `env`.
+                part if part.starts_with(" Node.env: ") => {
+                    node = node.with_env(parse_env(&part[" Node.env: ".len()..])?)?;
+                }
+                // Parsing `Node`'s optional `pre` and `post` hooks.
+                part if part.starts_with(" Node.pre: ") => {
+                    node.pre = Some(part[" Node.pre: ".len()..].to_string());
+                }
+                part if part.starts_with(" Node.post: ") => {
+                    node.post = Some(part[" Node.post: ".len()..].to_string());
+                }
+                // Parsing `Node`'s optional `command`.
+                part if part.starts_with(" Node.command: ") => {
+                    node.command = Some(part[" Node.command: ".len()..].to_string());
+                }
+                _ => (),
+            }
+        }
+
+        Ok(node)
+    }
+}
+
+impl Node {
+    /// Get the [`Node`]'s `args`.
+    pub fn args(&self) -> &str {
+        &self.args
+    }
+
+    /// Returns a copy of the [`Node`] without its `args`, e.g. to print it without them.
+    #[cfg(feature = "std")]
+    pub(crate) fn without_args(&self) -> Node {
+        Node {
+            args: String::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Get the [`Node`]'s optional `cost`.
+    pub fn cost(&self) -> Option<u64> {
+        self.cost
+    }
+
+    /// Returns the [`Node`] with its `cost` set.
+    pub fn with_cost(mut self, cost: u64) -> Self {
+        self.cost = Some(cost);
+        self
+    }
+
+    /// Get the name of the [`Node`]'s optional `gang`.
+    pub fn gang(&self) -> Option<&str> {
+        self.gang.as_deref()
+    }
+
+    /// Returns the [`Node`] as a member of the gang named `gang`, which must not contain commas.
+    pub fn with_gang(mut self, gang: String) -> Result<Self> {
+        if gang.contains(',') || gang.is_empty() {
+            return Err(anyhow!(
+                "Invalid gang name {:?}: must be non-empty and must not contain commas.",
+                gang
+            ));
+        }
+        self.gang = Some(gang);
+        Ok(self)
+    }
+
+    /// Get the [`Node`]'s optional concurrency key.
+    pub fn mutex(&self) -> Option<&str> {
+        self.mutex.as_deref()
+    }
+
+    /// Returns the [`Node`] with its concurrency key set to `mutex`, which must not contain commas.
+    pub fn with_mutex(mut self, mutex: String) -> Result<Self> {
+        if mutex.contains(',') || mutex.is_empty() {
+            return Err(anyhow!(
+                "Invalid mutex name {:?}: must be non-empty and must not contain commas.",
+                mutex
+            ));
+        }
+        self.mutex = Some(mutex);
+        Ok(self)
+    }
+
+    /// Get the [`Node`]'s optional shell `command`.
+    pub fn command(&self) -> Option<&str> {
+        self.command.as_deref()
+    }
+
+    /// Returns the [`Node`] with its shell `command` set, which must not contain `, Node.` or line breaks.
+    pub fn with_command(mut self, command: String) -> Result<Self> {
+        check_command(&command, "command")?;
+        self.command = Some(command);
+        Ok(self)
+    }
+
+    /// Get the shell command of the [`Node`]'s optional `hook`.
+    pub fn hook(&self, hook: Hook) -> Option<&str> {
+        match hook {
+            Hook::Pre => self.pre.as_deref(),
+            Hook::Post => self.post.as_deref(),
+        }
+    }
+
+    /// Returns the [`Node`] with the shell command of its `hook` set, which must not contain `, Node.` or line
+    /// breaks.
+    pub fn with_hook(mut self, hook: Hook, command: String) -> Result<Self> {
+        check_command(&command, &format!("{} hook", hook))?;
+        match hook {
+            Hook::Pre => self.pre = Some(command),
+            Hook::Post => self.post = Some(command),
+        }
+        Ok(self)
+    }
+
+    /// Get the [`Node`]'s `priority`, 0 if it has none.
+    pub fn priority(&self) -> i64 {
+        self.priority.unwrap_or(0)
+    }
+
+    /// Returns the [`Node`] with its `priority` set.
+    pub fn with_priority(mut self, priority: i64) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Get the [`Node`]'s optional niceness.
+    pub fn nice(&self) -> Option<i32> {
+        self.nice
+    }
+
+    /// Returns the [`Node`] with the niceness of its `command`'s process set, which must be between -20 and 19.
+    pub fn with_nice(mut self, nice: i32) -> Result<Self> {
+        if !(-20..=19).contains(&nice) {
+            return Err(anyhow!(
+                "Invalid nice {}: must be between -20 and 19.",
+                nice
+            ));
+        }
+        self.nice = Some(nice);
+        Ok(self)
+    }
+
+    /// Get the [`Node`]'s optional I/O priority.
+    pub fn ionice(&self) -> Option<IoPriority> {
+        self.ionice
+    }
+
+    /// Returns the [`Node`] with the I/O priority of its `command`'s process set.
+    pub fn with_ionice(mut self, ionice: IoPriority) -> Self {
+        self.ionice = Some(ionice);
+        self
+    }
+
+    /// Get the number of times the [`Node`] is retried after failing, 0 if it has none.
+    pub fn retries(&self) -> u32 {
+        self.retries.unwrap_or(0)
+    }
+
+    /// Returns the [`Node`] with the number of times it is retried after failing set.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = Some(retries);
+        self
+    }
+
+    /// Get the optional timeout of the [`Node`]: the longest time an attempt at executing it may take. An attempt
+    /// exceeding it is aborted (its command is terminated, inline functions are cancelled) and ends as
+    /// [`super::attempt::AttemptOutcome::TimedOut`], failing the node like an error once it has no retries left.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Returns the [`Node`] with its timeout set, which must not be zero.
+    pub fn with_timeout(mut self, timeout: Duration) -> Result<Self> {
+        if timeout.is_zero() {
+            return Err(anyhow!("Invalid timeout 0: must be positive."));
+        }
+        self.timeout = Some(timeout);
+        Ok(self)
+    }
+
+    /// Get the paths of the files the [`Node`] reads.
+    pub fn inputs(&self) -> &[String] {
+        &self.inputs
+    }
+
+    /// Returns the [`Node`] with the paths of the files it reads set, which must be non-empty and must not contain
+    /// commas or line breaks.
+    pub fn with_inputs(mut self, inputs: Vec<String>) -> Result<Self> {
+        check_paths(&inputs, "input")?;
+        self.inputs = inputs;
+        Ok(self)
+    }
+
+    /// Get the paths of the files the [`Node`] writes.
+    pub fn outputs(&self) -> &[String] {
+        &self.outputs
+    }
+
+    /// Returns the [`Node`] with the paths of the files it writes set, which must be non-empty and must not contain
+    /// commas or line breaks.
+    pub fn with_outputs(mut self, outputs: Vec<String>) -> Result<Self> {
+        check_paths(&outputs, "output")?;
+        self.outputs = outputs;
+        Ok(self)
+    }
+
+    /// Checks whether the [`Node`] must be approved before it is claimed.
+    pub fn requires_approval(&self) -> bool {
+        self.approval
+    }
+
+    /// Returns the [`Node`] requiring approval before it is claimed.
+    pub fn with_approval(mut self) -> Self {
+        self.approval = true;
+        self
+    }
+
+    /// Get the name of the optional external event the [`Node`] waits for before it is claimed.
+    pub fn awaited_event(&self) -> Option<&str> {
+        self.await_event.as_deref()
+    }
+
+    /// Returns the [`Node`] waiting for the external event `event` before it is claimed. The name must be non-empty
+    /// and must not contain commas or whitespace.
+    pub fn with_awaited_event(mut self, event: String) -> Result<Self> {
+        if event.is_empty() || event.contains(|c: char| c == ',' || c.is_whitespace()) {
+            return Err(anyhow!(
+                "Invalid event name {:?}: must be non-empty and must not contain commas or whitespace.",
+                event
+            ));
+        }
+        self.await_event = Some(event);
+        Ok(self)
+    }
+
+    /// Get the optional special role of the [`Node`].
+    pub fn role(&self) -> Option<NodeRole> {
+        self.role
+    }
+
+    /// Returns the [`Node`] with its special `role` set.
+    pub fn with_role(mut self, role: NodeRole) -> Self {
+        self.role = Some(role);
+        self
+    }
+
+    /// Get the [`Isolation`] the [`Node`] is executed with: the one it was declared with, otherwise
+    /// [`Isolation::Process`] if it has a `command` and [`Isolation::Inline`] if not.
+    pub fn isolation(&self) -> Isolation {
+        match (self.isolation, &self.command) {
+            (Some(isolation), _) => isolation,
+            (None, Some(_)) => Isolation::Process,
+            (None, None) => Isolation::Inline,
+        }
+    }
+
+    /// Returns the [`Node`] with its `isolation` set.
+    pub fn with_isolation(mut self, isolation: Isolation) -> Self {
+        self.isolation = Some(isolation);
+        self
+    }
+
+    /// Get the optional working directory of the [`Node`]'s `command`.
+    pub fn cwd(&self) -> Option<&str> {
+        self.cwd.as_deref()
+    }
+
+    /// Returns the [`Node`] with the working directory of its `command` set, which must be non-empty and must not
+    /// contain commas or line breaks.
+    pub fn with_cwd(mut self, cwd: String) -> Result<Self> {
+        check_paths(core::slice::from_ref(&cwd), "cwd")?;
+        self.cwd = Some(cwd);
+        Ok(self)
+    }
+
+    /// Get the environment variables set for the [`Node`]'s `command`.
+    pub fn env(&self) -> &[(String, String)] {
+        &self.env
+    }
+
+    /// Returns the [`Node`] with the environment variables of its `command` set. Names must be non-empty and must
+    /// not contain `=`, names and values must not contain commas or line breaks.
+    pub fn with_env(mut self, env: Vec<(String, String)>) -> Result<Self> {
+        if let Some((name, value)) = env.iter().find(|(name, value)| {
+            name.is_empty()
+                || name.contains('=')
+                || [name, value]
+                    .iter()
+                    .any(|part| part.contains(',') || part.contains('\n'))
+        }) {
+            return Err(anyhow!(
+                "Invalid env {}={:?}: names must be non-empty and must not contain '=', names and values must not \
+                 contain commas or line breaks.",
+                name,
+                value
+            ));
+        }
+        self.env = env;
+        Ok(self)
+    }
+
+    /// Checks whether the [`Node`] requires approval and hasn't been approved yet. Such nodes stay
+    /// [`ExecutionStatus::Executable`] without being claimed, see [`crate::scheduler::GraphView::executable_node_indices`].
+    pub fn is_awaiting_approval(&self) -> bool {
+        self.approval && !self.approved
+    }
+
+    /// Checks whether the [`Node`] awaits an external event which hasn't been received yet. Like nodes awaiting
+    /// approval, such nodes stay [`ExecutionStatus::Executable`] without being claimed.
+    pub fn is_awaiting_event(&self) -> bool {
+        self.await_event.is_some() && !self.event_received
+    }
+
+    /// Checks whether the [`Node`] doesn't need to be executed, like a target of `make`: it declares `outputs`, all
+    /// of which exist and were modified no earlier than the last modified of its `inputs`. Missing inputs make the
+    /// node out of date, so that its command reports them.
+    #[cfg(feature = "std")]
+    pub fn is_up_to_date(&self) -> bool {
+        let modified = |path: &String| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        let outputs: Option<Vec<_>> = self.outputs.iter().map(modified).collect();
+        let inputs: Option<Vec<_>> = self.inputs.iter().map(modified).collect();
+        match (outputs, inputs) {
+            (Some(outputs), Some(inputs)) if !outputs.is_empty() => {
+                inputs.iter().max() <= outputs.iter().min()
+            }
+            _ => false,
+        }
+    }
+
+    /// Get all attempts at executing the [`Node`] in the order they ended.
+    pub fn attempts(&self) -> &[Attempt] {
+        &self.attempts
+    }
+
+    /// Get the [`Node`]'s [`ExecutionRecord`], if it was executed.
+    pub fn execution_record(&self) -> Option<&ExecutionRecord> {
+        self.execution_record.as_ref()
+    }
+
+    /// Get the error the [`Node`] failed with, if it failed.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Builds the process running the [`Node`]'s shell `command` with its `cwd`, `env`, `nice` and `ionice`
+    /// settings, or printing `args` if it has no command, or [`None`] if it is executed with [`Isolation::Inline`].
+    #[cfg(feature = "std")]
+    pub fn command_process(&self) -> Option<Command> {
+        match (self.isolation(), &self.command) {
+            (Isolation::Inline, _) => None,
+            (Isolation::Process, Some(command)) => Some(self.process(command)),
+            (Isolation::Process, None) => {
+                let mut process = self.process("printf '%s\\n' \"$1\"");
+                process.arg("sh").arg(&self.args);
+                Some(process)
+            }
+        }
+    }
+
+    /// Builds the process running the shell command of the [`Node`]'s `hook` like its `command`, or [`None`] if it
+    /// has no such hook.
+    #[cfg(feature = "std")]
+    pub fn hook_process(&self, hook: Hook) -> Option<Command> {
+        Some(self.process(self.hook(hook)?))
+    }
+
+    /// Builds the process running the shell `command` with the [`Node`]'s `cwd`, `env`, `nice` and `ionice`
+    /// settings.
+    #[cfg(feature = "std")]
+    fn process(&self, command: &str) -> Command {
+        let mut process = Command::new("sh");
+        process.arg("-c").arg(command);
+        if let Some(cwd) = &self.cwd {
+            process.current_dir(cwd);
+        }
+        process.envs(self.env.iter().map(|(name, value)| (name, value)));
+        let (nice, ionice) = (self.nice, self.ionice);
+        if nice.is_some() || ionice.is_some() {
+            // SAFETY: only async-signal-safe system calls are made between `fork` and `exec`.
+            unsafe {
+                process.pre_exec(move || {
+                    if let Some(nice) = nice {
+                        if libc::setpriority(libc::PRIO_PROCESS, 0, nice) == -1 {
+                            return Err(std::io::Error::last_os_error());
+                        }
+                    }
+                    if let Some(ionice) = ionice {
+                        ionice.apply()?;
+                    }
+                    Ok(())
+                });
+            }
+        }
+        process
+    }
+
+    /// Executes a [`Node`]'s associated computation: running its [`Node::command_process`] to completion if it has
+    /// one, otherwise printing `Node.args`. Inline nodes with a `command` fail, as their function is only registered
+    /// with the executor.
+    #[cfg(feature = "std")]
+    pub fn execute(&self) -> Result<()> {
+        match self.execution_status {
+            ExecutionStatus::Executed => Err(anyhow!(
+                "Trying to execute node which has already been executed."
+            )),
+            ExecutionStatus::Executable => Err(anyhow!(
+                "Trying to execute node which is not yet set for execution."
+            )),
+            ExecutionStatus::NonExecutable => {
+                Err(anyhow!("Trying to execute node which is not executable."))
+            }
+            ExecutionStatus::Failed | ExecutionStatus::Skipped | ExecutionStatus::Cancelled => Err(
+                anyhow!("Trying to execute node which has failed or was skipped or cancelled."),
+            ),
+            ExecutionStatus::Executing => match (self.command_process(), &self.command) {
+                (Some(mut process), command) => {
+                    let status = process.status()?;
+                    if !status.success() {
+                        return Err(anyhow!(
+                            "Command {:?} failed: {}.",
+                            command.as_deref().unwrap_or_default(),
+                            status
+                        ));
+                    }
+                    Ok(())
+                }
+                (None, Some(command)) => Err(anyhow!(
+                    "Inline function {:?} can only be executed by a worker it is registered with.",
+                    command
+                )),
+                (None, None) => {
+                    thread::sleep(Duration::from_secs(1)); // Sleep if no executable `Node` is available
+                    println!("{}", self.args); // TODO: implement node execution.
+                    Ok(())
+                }
+            },
+        }
+    }
+}
+
+/// Returns an error if the shell `command` of the [`Node`] (`kind` being e.g. `command` or `pre hook`) is empty or
+/// contains `, Node.` or line breaks, which would break printing the [`Node`].
+fn check_command(command: &str, kind: &str) -> Result<()> {
+    if command.contains(", Node.") || command.contains('\n') || command.trim().is_empty() {
+        return Err(anyhow!(
+            "Invalid {} {:?}: must be non-empty and must not contain ', Node.' or line breaks.",
+            kind,
+            command
+        ));
+    }
+    Ok(())
+}
+
+/// Parses environment variables printed like `NAME=value,OTHER=value`, e.g. the `env` of a [`Node`].
+pub fn parse_env(env: &str) -> Result<Vec<(String, String)>> {
+    env.split(',')
+        .map(|variable| match variable.split_once('=') {
+            Some((name, value)) => Ok((String::from(name.trim()), String::from(value))),
+            None => Err(anyhow!(
+                "Invalid env variable {:?}: expected NAME=value.",
+                variable
+            )),
+        })
+        .collect()
+}
+
+/// Parses a duration given in seconds like `30` or `1.5`, e.g. the `timeout` of a [`Node`].
+pub fn parse_seconds(seconds: &str) -> Result<Duration> {
+    seconds
+        .trim()
+        .parse()
+        .map_err(|e| anyhow!("Invalid seconds {:?}: {}", seconds, e))
+        .and_then(|seconds| {
+            Duration::try_from_secs_f64(seconds)
+                .map_err(|e| anyhow!("Invalid seconds {}: {}", seconds, e))
+        })
+}
+
+/// Returns an error if any of the [`Node`]'s input or output `paths` is empty or contains commas or line breaks.
+fn check_paths(paths: &[String], kind: &str) -> Result<()> {
+    match paths
+        .iter()
+        .find(|path| path.is_empty() || path.contains(',') || path.contains('\n'))
+    {
+        Some(path) => Err(anyhow!(
+            "Invalid {} {:?}: must be non-empty and must not contain commas or line breaks.",
+            kind,
+            path
+        )),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,70 @@
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use petgraph::graph::NodeIndex;
+
+/// String identifiers of the nodes of a [`super::graph::DirectedAcyclicGraph`], indexed by their [`NodeIndex`] and
+/// mapped back to it, so that nodes are looked up and iterated in the order of their identifiers without a scan of
+/// all identifiers. Only the identifiers are serialized, the map is rebuilt from them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct NodeIds {
+    /// Identifiers by [`NodeIndex`].
+    ids: Vec<String>,
+    /// [`NodeIndex`] of every identifier.
+    indices: BTreeMap<String, NodeIndex>,
+}
+
+impl NodeIds {
+    /// Get the identifier of the node at `index`.
+    pub(crate) fn id(&self, index: NodeIndex) -> &str {
+        &self.ids[index.index()]
+    }
+
+    /// Get the [`NodeIndex`] of the node identified by `id`.
+    pub(crate) fn index(&self, id: &str) -> Option<NodeIndex> {
+        self.indices.get(id).copied()
+    }
+
+    /// Get the indices of all nodes, ordered by identifier.
+    pub(crate) fn sorted_indices(&self) -> impl Iterator<Item = NodeIndex> + '_ {
+        self.indices.values().copied()
+    }
+
+    /// Adds the identifier `id` of the node added next to the graph.
+    pub(crate) fn push(&mut self, id: String) {
+        self.indices
+            .insert(id.clone(), NodeIndex::new(self.ids.len()));
+        self.ids.push(id);
+    }
+
+    /// Removes the identifier of the node added last to the graph.
+    pub(crate) fn pop(&mut self) {
+        if let Some(id) = self.ids.pop() {
+            self.indices.remove(&id);
+        }
+    }
+}
+
+impl FromIterator<String> for NodeIds {
+    fn from_iter<I: IntoIterator<Item = String>>(ids: I) -> Self {
+        let mut node_ids = NodeIds::default();
+        for id in ids {
+            node_ids.push(id);
+        }
+        node_ids
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for NodeIds {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.ids.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for NodeIds {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Vec::<String>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
@@ -0,0 +1,39 @@
+use alloc::string::String;
+use core::fmt;
+
+/// Progress reported by a [`super::node::Node`] while it executes, so that nodes taking minutes can be told apart
+/// from hung ones, see [`super::graph::DirectedAcyclicGraph::report_progress`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct NodeProgress {
+    /// Completed fraction of the node's work in per mille, from 0 to 1000.
+    pub permille: u16,
+    /// Optional message describing what the node is doing, e.g. the current step.
+    pub message: Option<String>,
+}
+
+impl NodeProgress {
+    /// Creates the progress of a node which completed `fraction` (clamped to 0.0 to 1.0) of its work.
+    pub fn new(fraction: f64, message: Option<String>) -> Self {
+        NodeProgress {
+            permille: (fraction.clamp(0.0, 1.0) * 1000.0) as u16,
+            message,
+        }
+    }
+
+    /// Get the completed fraction of the node's work, from 0.0 to 1.0.
+    pub fn fraction(&self) -> f64 {
+        f64::from(self.permille) / 1000.0
+    }
+}
+
+impl fmt::Display for NodeProgress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}%", self.permille / 10, self.permille % 10)?;
+        if let Some(message) = &self.message {
+            write!(f, " ({})", message)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use anyhow::{anyhow, Error, Result};
+use core::{fmt, str::FromStr};
+
+/// Special role of a [`super::node::Node`] in its graph, which wires it to all other nodes: setup nodes become
+/// parents of all roots and teardown nodes children of all leaves, see
+/// [`super::graph::DirectedAcyclicGraph::new_with_limits`].
+#[derive(Clone, Debug, PartialEq, Eq, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum NodeRole {
+    /// Executes before all other nodes, e.g. to provision a test environment.
+    Setup,
+    /// Executes after all other nodes have finished, even if they failed or the run was cancelled, e.g. to clean up
+    /// the test environment.
+    Teardown,
+}
+
+impl fmt::Display for NodeRole {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NodeRole::Setup => write!(f, "setup"),
+            NodeRole::Teardown => write!(f, "teardown"),
+        }
+    }
+}
+
+impl FromStr for NodeRole {
+    type Err = Error;
+    /// Parses [`NodeRole`] from a string like: "setup" or "teardown".
+    fn from_str(role: &str) -> Result<Self> {
+        match role {
+            "setup" => Ok(NodeRole::Setup),
+            "teardown" => Ok(NodeRole::Teardown),
+            _ => Err(anyhow!(
+                "NodeRole::from_str parsing error: Invalid role {}, expected setup or teardown.",
+                role
+            )),
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use super::{attempt::Attempt, graph::DirectedAcyclicGraph};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use anyhow::{anyhow, Error, Result};
+use core::{fmt, str::FromStr};
+
+/// Assertion about the order in which two nodes were executed, checked against the attempts recorded by a run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OrderAssertion {
+    /// Written `a before b`: every attempt at `b` started after the last attempt at `a` finished, and `a` was
+    /// attempted if `b` was.
+    Before { first: String, second: String },
+    /// Written `a and b never concurrent`: no attempt at `a` overlapped any attempt at `b`.
+    NeverConcurrent { first: String, second: String },
+}
+
+impl OrderAssertion {
+    /// Get the string identifiers of the two nodes the assertion is about.
+    pub fn node_ids(&self) -> [&str; 2] {
+        match self {
+            OrderAssertion::Before { first, second }
+            | OrderAssertion::NeverConcurrent { first, second } => [first, second],
+        }
+    }
+}
+
+impl fmt::Display for OrderAssertion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderAssertion::Before { first, second } => write!(f, "{} before {}", first, second),
+            OrderAssertion::NeverConcurrent { first, second } => {
+                write!(f, "{} and {} never concurrent", first, second)
+            }
+        }
+    }
+}
+
+impl FromStr for OrderAssertion {
+    type Err = Error;
+    /// Parses [`OrderAssertion`] from a string like: "a before b" or "c and d never concurrent".
+    fn from_str(assertion: &str) -> Result<Self> {
+        let words: Vec<&str> = assertion.split_whitespace().collect();
+        match words[..] {
+            [first, "before", second] => Ok(OrderAssertion::Before {
+                first: first.to_string(),
+                second: second.to_string(),
+            }),
+            [first, "and", second, "never", "concurrent"] => Ok(OrderAssertion::NeverConcurrent {
+                first: first.to_string(),
+                second: second.to_string(),
+            }),
+            _ => Err(anyhow!(
+                "OrderAssertion::from_str parsing error: Invalid assertion {:?}, expected \"a before b\" or \"a and b \
+                 never concurrent\".",
+                assertion
+            )),
+        }
+    }
+}
+
+/// Expected execution order of a graph: a list of [`OrderAssertion`]s about its nodes, e.g. domain constraints the
+/// graph's edges and `mutex` attributes are meant to enforce.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OrderSpec {
+    /// Assertions in the order they were written.
+    pub assertions: Vec<OrderAssertion>,
+}
+
+impl FromStr for OrderSpec {
+    type Err = Error;
+    /// Parses [`OrderSpec`] from one [`OrderAssertion`] per line. Empty lines and lines starting with `#` are
+    /// ignored.
+    fn from_str(spec: &str) -> Result<Self> {
+        Ok(OrderSpec {
+            assertions: spec
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(OrderAssertion::from_str)
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+impl OrderSpec {
+    /// Returns an error if any assertion is about a node which isn't in `graph`, e.g. to reject a misspelled
+    /// specification before the run starts.
+    pub fn validate(&self, graph: &DirectedAcyclicGraph) -> Result<()> {
+        for assertion in &self.assertions {
+            if let Some(id) = assertion
+                .node_ids()
+                .into_iter()
+                .find(|id| graph.node_index(id).is_none())
+            {
+                return Err(anyhow!(
+                    "Order assertion {} is about unknown node {}.",
+                    assertion,
+                    id
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// [`OrderAssertion`] violated by the attempts recorded by a run, see [`DirectedAcyclicGraph::check_order`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrderViolation {
+    /// The violated assertion.
+    pub assertion: OrderAssertion,
+    /// How the recorded attempts violate it.
+    pub detail: String,
+}
+
+impl fmt::Display for OrderViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} violated: {}", self.assertion, self.detail)
+    }
+}
+
+impl DirectedAcyclicGraph {
+    /// Checks the attempts recorded for the graph's nodes against the assertions of `spec`, returning the violated
+    /// ones in the order of the specification.
+    pub fn check_order(&self, spec: &OrderSpec) -> Result<Vec<OrderViolation>> {
+        spec.validate(self)?;
+        let attempts = |id: &str| match self.node_index(id) {
+            Some(i) => self[i].attempts(),
+            None => &[],
+        };
+        Ok(spec
+            .assertions
+            .iter()
+            .filter_map(|assertion| {
+                let detail = match assertion {
+                    OrderAssertion::Before { first, second } => {
+                        check_before(first, attempts(first), second, attempts(second))
+                    }
+                    OrderAssertion::NeverConcurrent { first, second } => {
+                        check_never_concurrent(first, attempts(first), second, attempts(second))
+                    }
+                }?;
+                Some(OrderViolation {
+                    assertion: assertion.clone(),
+                    detail,
+                })
+            })
+            .collect())
+    }
+}
+
+/// Describes how the attempts at `second` violate `first before second`, [`None`] if they don't.
+fn check_before(
+    first: &str,
+    first_attempts: &[Attempt],
+    second: &str,
+    second_attempts: &[Attempt],
+) -> Option<String> {
+    let second_start = second_attempts.iter().map(|a| a.started_after).min()?;
+    match first_attempts.iter().map(|a| a.finished_after).max() {
+        None => Some(format!("{} was executed but {} never was", second, first)),
+        Some(first_finish) if second_start < first_finish => Some(format!(
+            "{} started at {:?}, before {} finished at {:?}",
+            second, second_start, first, first_finish
+        )),
+        Some(_) => None,
+    }
+}
+
+/// Describes the first overlap of an attempt at `first` with one at `second`, [`None`] if there is none.
+fn check_never_concurrent(
+    first: &str,
+    first_attempts: &[Attempt],
+    second: &str,
+    second_attempts: &[Attempt],
+) -> Option<String> {
+    first_attempts.iter().find_map(|a| {
+        let b = second_attempts
+            .iter()
+            .find(|b| a.started_after < b.finished_after && b.started_after < a.finished_after)?;
+        Some(format!(
+            "{} executed from {:?} to {:?} while {} executed from {:?} to {:?}",
+            first, a.started_after, a.finished_after, second, b.started_after, b.finished_after
+        ))
+    })
+}
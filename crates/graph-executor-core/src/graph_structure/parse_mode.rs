@@ -0,0 +1,10 @@
+/// Determines how [`super::graph::DirectedAcyclicGraph`] handles [`super::edge::Edge`]s whose parent
+/// or child is not declared as a [`super::node::Node`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Return an error listing all unknown edge endpoints.
+    #[default]
+    Strict,
+    /// Print a warning and drop edges with unknown endpoints; intended for interactive use.
+    Lenient,
+}
@@ -0,0 +1,221 @@
+use super::{execution_status::ExecutionStatus, graph::DirectedAcyclicGraph};
+use anyhow::{anyhow, Result};
+
+/// Fixed-capacity directed acyclic graph of at most `N` (≤ 32) nodes, e.g. for control loops on embedded targets.
+/// Nodes are identified by their index in the order they were added. Parents and children of every node are
+/// stored as bitmasks in arrays, as are the execution statuses of all nodes, so that neither constructing the graph
+/// nor claiming and completing nodes allocates.
+///
+/// Unlike [`DirectedAcyclicGraph`], nodes carry no args or command: the caller keeps its own array of computations
+/// indexed like the nodes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SmallDag<const N: usize> {
+    /// Number of nodes.
+    len: usize,
+    /// Bitmask of the parents of every node.
+    parents: [u32; N],
+    /// Bitmask of the children of every node.
+    children: [u32; N],
+    /// Bitmask of the [`ExecutionStatus::Executing`] nodes.
+    executing: u32,
+    /// Bitmask of the [`ExecutionStatus::Executed`] nodes.
+    executed: u32,
+    /// Bitmask of the [`ExecutionStatus::Failed`] nodes.
+    failed: u32,
+    /// Bitmask of the [`ExecutionStatus::Skipped`] nodes.
+    skipped: u32,
+}
+
+impl<const N: usize> Default for SmallDag<N> {
+    fn default() -> Self {
+        SmallDag::new()
+    }
+}
+
+impl<const N: usize> SmallDag<N> {
+    /// Fails compilation for capacities which don't fit a bitmask.
+    const CAPACITY_FITS_BITMASK: () = assert!(N <= 32, "SmallDag holds at most 32 nodes.");
+
+    /// Creates an empty [`SmallDag`].
+    pub const fn new() -> Self {
+        let () = Self::CAPACITY_FITS_BITMASK;
+        SmallDag {
+            len: 0,
+            parents: [0; N],
+            children: [0; N],
+            executing: 0,
+            executed: 0,
+            failed: 0,
+            skipped: 0,
+        }
+    }
+
+    /// Get the number of nodes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks whether the graph has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bitmask of all nodes.
+    fn nodes(&self) -> u32 {
+        match self.len {
+            32 => u32::MAX,
+            len => (1 << len) - 1,
+        }
+    }
+
+    /// Returns an error if there is no node at `index`.
+    fn check_index(&self, index: usize) -> Result<()> {
+        match index < self.len {
+            true => Ok(()),
+            false => Err(anyhow!(
+                "SmallDag has no node {}, it has {} nodes.",
+                index,
+                self.len
+            )),
+        }
+    }
+
+    /// Adds a node and returns its index, or an error if the graph is full.
+    pub fn add_node(&mut self) -> Result<usize> {
+        if self.len == N {
+            return Err(anyhow!("SmallDag exceeds its capacity of {} nodes.", N));
+        }
+        self.len += 1;
+        Ok(self.len - 1)
+    }
+
+    /// Adds an edge from the node at `parent` to the node at `child`, checking that it doesn't introduce a cycle.
+    pub fn add_edge(&mut self, parent: usize, child: usize) -> Result<()> {
+        self.check_index(parent)?;
+        self.check_index(child)?;
+        if parent == child || self.descendants(child) & (1 << parent) != 0 {
+            return Err(anyhow!("Cyclic graph supplied on {}", parent));
+        }
+        self.parents[child] |= 1 << parent;
+        self.children[parent] |= 1 << child;
+        Ok(())
+    }
+
+    /// Bitmask of all descendants of the node at `index`.
+    fn descendants(&self, index: usize) -> u32 {
+        let mut descendants = self.children[index];
+        let mut unvisited = descendants;
+        while unvisited != 0 {
+            let node = unvisited.trailing_zeros() as usize;
+            unvisited &= unvisited - 1;
+            unvisited |= self.children[node] & !descendants;
+            descendants |= self.children[node];
+        }
+        descendants
+    }
+
+    /// Bitmask of the [`ExecutionStatus::Executable`] nodes: all nodes whose parents have been executed and which
+    /// are neither executing nor finished.
+    pub fn executable(&self) -> u32 {
+        let unfinished = !(self.executing | self.executed | self.failed | self.skipped);
+        let mut executable = 0;
+        for (index, parents) in self.parents.iter().enumerate().take(self.len) {
+            executable |= ((parents & !self.executed == 0) as u32) << index;
+        }
+        executable & unfinished & self.nodes()
+    }
+
+    /// Get the [`ExecutionStatus`] of the node at `index`.
+    pub fn execution_status(&self, index: usize) -> Result<ExecutionStatus> {
+        self.check_index(index)?;
+        let node = 1 << index;
+        Ok(if self.executed & node != 0 {
+            ExecutionStatus::Executed
+        } else if self.executing & node != 0 {
+            ExecutionStatus::Executing
+        } else if self.failed & node != 0 {
+            ExecutionStatus::Failed
+        } else if self.skipped & node != 0 {
+            ExecutionStatus::Skipped
+        } else if self.executable() & node != 0 {
+            ExecutionStatus::Executable
+        } else {
+            ExecutionStatus::NonExecutable
+        })
+    }
+
+    /// Marks the executable node with the smallest index as [`ExecutionStatus::Executing`] and returns its index,
+    /// or [`None`] if no node is executable.
+    pub fn claim(&mut self) -> Option<usize> {
+        let executable = self.executable();
+        if executable == 0 {
+            return None;
+        }
+        self.executing |= executable & executable.wrapping_neg();
+        Some(executable.trailing_zeros() as usize)
+    }
+
+    /// Marks the executing node at `index` as [`ExecutionStatus::Executed`].
+    pub fn complete(&mut self, index: usize) -> Result<()> {
+        self.check_executing(index)?;
+        self.executing &= !(1 << index);
+        self.executed |= 1 << index;
+        Ok(())
+    }
+
+    /// Marks the executing node at `index` as [`ExecutionStatus::Failed`] and all its descendants as
+    /// [`ExecutionStatus::Skipped`].
+    pub fn fail(&mut self, index: usize) -> Result<()> {
+        self.check_executing(index)?;
+        self.executing &= !(1 << index);
+        self.failed |= 1 << index;
+        self.skipped |= self.descendants(index);
+        Ok(())
+    }
+
+    /// Returns an error if the node at `index` is not [`ExecutionStatus::Executing`].
+    fn check_executing(&self, index: usize) -> Result<()> {
+        self.check_index(index)?;
+        match self.executing & (1 << index) != 0 {
+            true => Ok(()),
+            false => Err(anyhow!(
+                "Trying to finish node {} which is not executing.",
+                index
+            )),
+        }
+    }
+
+    /// Checks whether no node is left to execute: every node has been executed, has failed or was skipped.
+    pub fn is_finished(&self) -> bool {
+        (self.executed | self.failed | self.skipped) == self.nodes()
+    }
+
+    /// Resets the execution statuses of all nodes, e.g. before the next iteration of a control loop.
+    pub fn reset(&mut self) {
+        self.executing = 0;
+        self.executed = 0;
+        self.failed = 0;
+        self.skipped = 0;
+    }
+}
+
+impl<const N: usize> TryFrom<&DirectedAcyclicGraph> for SmallDag<N> {
+    type Error = anyhow::Error;
+
+    /// Converts the structure of a [`DirectedAcyclicGraph`] into a [`SmallDag`] whose node indices follow the
+    /// order of the graph's string identifiers (see [`DirectedAcyclicGraph::get_node_indices`]). Execution
+    /// statuses are not converted.
+    fn try_from(graph: &DirectedAcyclicGraph) -> Result<Self> {
+        let mut small_dag = SmallDag::new();
+        let indices: alloc::vec::Vec<_> = graph.get_node_indices().collect();
+        for _ in &indices {
+            small_dag.add_node()?;
+        }
+        for (child, index) in indices.iter().enumerate() {
+            for parent in graph.get_parent_node_indices(*index) {
+                small_dag.add_edge(indices.iter().position(|i| *i == parent).unwrap(), child)?;
+            }
+        }
+        Ok(small_dag)
+    }
+}
@@ -0,0 +1,87 @@
+use anyhow::{anyhow, Result};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Renders a graph file (DOT or workflow YAML) used as a template before parsing it, so that one file can serve
+/// multiple environments: every `${NAME}` is replaced by the value of the variable `NAME` from `variables` or, if it
+/// isn't given there, from the environment of this process. `$${` escapes a literal `${`, other `$` (e.g. shell
+/// variables like `$HOME`) are kept as they are. Names consist of ASCII letters, digits and `_`.
+///
+/// Returns an error listing all unresolved variables instead of rendering a partially substituted graph.
+///
+/// ```
+/// # use graph_executor_core::graph_structure::template::render;
+/// # use std::collections::BTreeMap;
+/// let variables = BTreeMap::from([(String::from("STAGE"), String::from("prod"))]);
+/// let rendered = render("deploy [ command = \"./deploy.sh ${STAGE}\" ]", &variables)?;
+/// assert_eq!(rendered, "deploy [ command = \"./deploy.sh prod\" ]");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn render(template: &str, variables: &BTreeMap<String, String>) -> Result<String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut unresolved = BTreeSet::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('$') {
+        rendered.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(escaped) = rest.strip_prefix("$${") {
+            rendered.push_str("${");
+            rest = escaped;
+            continue;
+        }
+        let Some(reference) = rest.strip_prefix("${") else {
+            rendered.push('$');
+            rest = &rest[1..];
+            continue;
+        };
+        let name = reference
+            .find('}')
+            .map(|end| &reference[..end])
+            .filter(|name| is_variable_name(name))
+            .ok_or(anyhow!(
+                "Template error: Invalid variable reference {:?}, expected ${{NAME}}.",
+                rest.lines().next().unwrap_or_default()
+            ))?;
+        match variables
+            .get(name)
+            .cloned()
+            .or_else(|| std::env::var(name).ok())
+        {
+            Some(value) => rendered.push_str(&value),
+            None => {
+                unresolved.insert(name);
+            }
+        }
+        rest = &reference[name.len() + 1..];
+    }
+    rendered.push_str(rest);
+    if !unresolved.is_empty() {
+        return Err(anyhow!(
+            "Template error: Unresolved variables: {}.",
+            unresolved.into_iter().collect::<Vec<_>>().join(", ")
+        ));
+    }
+    Ok(rendered)
+}
+
+/// Parses `--set`-style variable assignments like `STAGE=prod` into variables for [`render`].
+pub fn parse_variables<'a>(
+    assignments: impl IntoIterator<Item = &'a str>,
+) -> Result<BTreeMap<String, String>> {
+    assignments
+        .into_iter()
+        .map(|assignment| match assignment.split_once('=') {
+            Some((name, value)) if is_variable_name(name) => {
+                Ok((name.to_string(), value.to_string()))
+            }
+            _ => Err(anyhow!(
+                "Invalid variable assignment {:?}: must look like <NAME>=<value>.",
+                assignment
+            )),
+        })
+        .collect()
+}
+
+/// Checks whether `name` is a valid variable name: non-empty ASCII letters, digits and `_`.
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -0,0 +1,327 @@
+use super::{
+    edge::Edge,
+    graph::DirectedAcyclicGraph,
+    graph_limits::GraphLimits,
+    include::{Fragment, IncludeContext},
+    node::Node,
+    parse_mode::ParseMode,
+};
+use anyhow::{anyhow, Result};
+use serde_yaml::{Mapping, Value};
+use std::collections::BTreeMap;
+
+impl DirectedAcyclicGraph {
+    /// Creates [`DirectedAcyclicGraph`] from a path to an Argo Workflow or an Airflow DAG definition in YAML or JSON,
+    /// detecting the format from its content (see [`DirectedAcyclicGraph::from_argo_str`] and
+    /// [`DirectedAcyclicGraph::from_airflow_str`]). Files listed by its top-level `imports` are included, see
+    /// [`DirectedAcyclicGraph::from_pipeline_file`].
+    pub fn from_workflow_file(file_path: &str) -> Result<Self> {
+        DirectedAcyclicGraph::from_workflow_str_with_imports(
+            &std::fs::read_to_string(file_path)?,
+            &mut IncludeContext::for_file(
+                file_path,
+                ParseMode::Strict,
+                GraphLimits::default(),
+                None,
+            )?,
+        )
+    }
+
+    /// Creates [`DirectedAcyclicGraph`] from an Argo Workflow or an Airflow DAG definition in YAML or JSON like
+    /// [`DirectedAcyclicGraph::from_workflow_file`], resolving its `imports` relative to the working directory.
+    pub fn from_workflow_str(workflow: &str) -> Result<Self> {
+        DirectedAcyclicGraph::from_workflow_str_with_imports(
+            workflow,
+            &mut IncludeContext::new(ParseMode::Strict, GraphLimits::default(), None),
+        )
+    }
+
+    /// Creates [`DirectedAcyclicGraph`] like [`DirectedAcyclicGraph::from_workflow_str`], including the files listed
+    /// by its top-level `imports` (paths or mappings with a `file` and an optional `namespace`) with `context`.
+    pub(crate) fn from_workflow_str_with_imports(
+        workflow: &str,
+        context: &mut IncludeContext,
+    ) -> Result<Self> {
+        let value: Value = serde_yaml::from_str(workflow)?;
+        let mut imported = Fragment::default();
+        for import in sequence(value.get("imports")) {
+            let (file, namespace) = match import {
+                Value::String(file) => (file.as_str(), None),
+                import => (
+                    string(import.get("file")).ok_or(anyhow!(
+                        "Workflow import error: Import {:?} has no file.",
+                        import
+                    ))?,
+                    string(import.get("namespace")),
+                ),
+            };
+            context.include(file, namespace, &mut imported)?;
+        }
+        match value.get("apiVersion").and_then(Value::as_str) {
+            Some(api_version) if api_version.starts_with("argoproj.io/") => {
+                DirectedAcyclicGraph::argo_graph(value, imported, context.limits())
+            }
+            _ => DirectedAcyclicGraph::airflow_graph(value, imported, context.limits()),
+        }
+    }
+
+    /// Creates [`DirectedAcyclicGraph`] from the entrypoint DAG template of an Argo `Workflow` (or `WorkflowTemplate`)
+    /// in YAML or JSON. Every task becomes a node running its template's `container` command and args or `script`
+    /// locally (the container image is ignored), with `{{inputs.parameters.*}}` and `{{workflow.parameters.*}}`
+    /// substituted. Task `dependencies` and the task names referenced by `depends` expressions become parents.
+    pub fn from_argo_str(workflow: &str) -> Result<Self> {
+        DirectedAcyclicGraph::argo_graph(
+            serde_yaml::from_str(workflow)?,
+            Fragment::default(),
+            GraphLimits::default(),
+        )
+    }
+
+    /// Creates [`DirectedAcyclicGraph`] from an Argo `workflow` like [`DirectedAcyclicGraph::from_argo_str`], with the
+    /// nodes and edges of the `imported` files, checked against `limits`.
+    fn argo_graph(workflow: Value, imported: Fragment, limits: GraphLimits) -> Result<Self> {
+        let spec = workflow
+            .get("spec")
+            .ok_or(anyhow!("Argo import error: Workflow has no spec."))?;
+        let templates: BTreeMap<&str, &Value> = sequence(spec.get("templates"))
+            .iter()
+            .filter_map(|template| Some((string(template.get("name"))?, template)))
+            .collect();
+        let workflow_parameters = parameters(spec.get("arguments"), "value");
+        let entrypoint = string(spec.get("entrypoint"))
+            .ok_or(anyhow!("Argo import error: Workflow has no entrypoint."))?;
+        let tasks = templates
+            .get(entrypoint)
+            .and_then(|template| template.get("dag"))
+            .map(|dag| sequence(dag.get("tasks")))
+            .ok_or(anyhow!(
+                "Argo import error: Entrypoint {} is not a DAG template.",
+                entrypoint
+            ))?;
+
+        let Fragment {
+            mut nodes,
+            mut edges,
+        } = imported;
+        for task in tasks {
+            let name =
+                string(task.get("name")).ok_or(anyhow!("Argo import error: Task without name."))?;
+            let template_name = string(task.get("template"))
+                .ok_or(anyhow!("Argo import error: Task {} has no template.", name))?;
+            let template = templates.get(template_name).ok_or(anyhow!(
+                "Argo import error: Unknown template {} of task {}.",
+                template_name,
+                name
+            ))?;
+
+            // Task arguments take precedence over the template's default input parameters
+            let mut input_parameters = parameters(template.get("inputs"), "default");
+            input_parameters.extend(parameters(task.get("arguments"), "value"));
+            let substitute = |text: &str| {
+                let mut text = text.to_string();
+                for (parameter, value) in &input_parameters {
+                    text = text.replace(&format!("{{{{inputs.parameters.{}}}}}", parameter), value);
+                }
+                for (parameter, value) in &workflow_parameters {
+                    text =
+                        text.replace(&format!("{{{{workflow.parameters.{}}}}}", parameter), value);
+                }
+                text
+            };
+
+            let command = if let Some(container) = template.get("container") {
+                let words: Vec<String> = sequence(container.get("command"))
+                    .iter()
+                    .chain(sequence(container.get("args")))
+                    .filter_map(scalar)
+                    .map(|word| shell_quote(&substitute(&word)))
+                    .collect();
+                Some(words.join(" "))
+            } else if let Some(script) = template.get("script") {
+                let interpreter: Vec<String> = sequence(script.get("command"))
+                    .iter()
+                    .filter_map(scalar)
+                    .map(|word| shell_quote(&substitute(&word)))
+                    .collect();
+                let interpreter = match interpreter.is_empty() {
+                    true => String::from("sh"),
+                    false => interpreter.join(" "),
+                };
+                string(script.get("source"))
+                    .map(|source| piped_script(&substitute(source), &interpreter))
+            } else {
+                None
+            };
+            let node = match command.filter(|command| !command.is_empty()) {
+                Some(command) => Node::new(name.to_string()).with_command(command)?,
+                None => Node::new(name.to_string()),
+            };
+            nodes.push((name.to_string(), node));
+
+            let mut parents: Vec<String> = sequence(task.get("dependencies"))
+                .iter()
+                .filter_map(scalar)
+                .collect();
+            if let Some(depends) = string(task.get("depends")) {
+                parents.extend(depends_task_names(depends));
+            }
+            parents.sort();
+            parents.dedup();
+            for parent in parents {
+                edges.push(Edge::new(parent, name.to_string()));
+            }
+        }
+
+        DirectedAcyclicGraph::new_with_limits(nodes, edges, ParseMode::Strict, limits)
+    }
+
+    /// Creates [`DirectedAcyclicGraph`] from an Airflow DAG, either in the YAML format of `dag-factory`
+    /// (a single DAG whose `tasks` map task ids to their `operator`, `bash_command` and `dependencies`) or a
+    /// serialized DAG in JSON (whose `dag.tasks` list their `task_id`, `bash_command` and `downstream_task_ids`).
+    /// Tasks with a `bash_command` become command nodes, all other operators become nodes without a command.
+    pub fn from_airflow_str(dag: &str) -> Result<Self> {
+        DirectedAcyclicGraph::airflow_graph(
+            serde_yaml::from_str(dag)?,
+            Fragment::default(),
+            GraphLimits::default(),
+        )
+    }
+
+    /// Creates [`DirectedAcyclicGraph`] from an Airflow `dag` like [`DirectedAcyclicGraph::from_airflow_str`], with
+    /// the nodes and edges of the `imported` files, checked against `limits`.
+    fn airflow_graph(dag: Value, imported: Fragment, limits: GraphLimits) -> Result<Self> {
+        let Fragment {
+            mut nodes,
+            mut edges,
+        } = imported;
+        let task_node = |task_id: &str, task: &Value| -> Result<(String, Node)> {
+            let node = match string(task.get("bash_command")) {
+                Some(bash_command) => Node::new(task_id.to_string())
+                    .with_command(piped_script(bash_command, "bash"))?,
+                None => Node::new(task_id.to_string()),
+            };
+            Ok((task_id.to_string(), node))
+        };
+
+        // Serialized DAG like `{"dag": {"tasks": [{"task_id": "a", "downstream_task_ids": ["b"]}]}}`
+        if let Some(tasks) = dag.get("dag").and_then(|dag| dag.get("tasks")) {
+            for task in sequence(Some(tasks)) {
+                // Newer Airflow versions wrap tasks like `{"__type": "operator", "__var": {...}}`
+                let task = task.get("__var").unwrap_or(task);
+                let task_id = string(task.get("task_id"))
+                    .ok_or(anyhow!("Airflow import error: Task without task_id."))?;
+                nodes.push(task_node(task_id, task)?);
+                for downstream in sequence(task.get("downstream_task_ids")) {
+                    if let Some(downstream) = scalar(downstream) {
+                        edges.push(Edge::new(task_id.to_string(), downstream));
+                    }
+                }
+            }
+            return DirectedAcyclicGraph::new_with_limits(nodes, edges, ParseMode::Strict, limits);
+        }
+
+        // dag-factory YAML like `my_dag: {tasks: {a: {bash_command: ...}, b: {dependencies: [a]}}}`
+        let dags: Vec<(&Value, &Value)> = dag
+            .as_mapping()
+            .map(Mapping::iter)
+            .into_iter()
+            .flatten()
+            .filter(|(_, dag)| dag.get("tasks").is_some())
+            .collect();
+        let [(_, dag)] = dags.as_slice() else {
+            return Err(anyhow!(
+                "Airflow import error: Expected exactly one DAG with tasks, found {}.",
+                dags.len()
+            ));
+        };
+        let tasks = dag.get("tasks").and_then(Value::as_mapping).ok_or(anyhow!(
+            "Airflow import error: DAG tasks are not a mapping."
+        ))?;
+        for (task_id, task) in tasks {
+            let task_id = scalar(task_id).ok_or(anyhow!(
+                "Airflow import error: Invalid task id {:?}.",
+                task_id
+            ))?;
+            nodes.push(task_node(&task_id, task)?);
+            for dependency in sequence(task.get("dependencies")) {
+                if let Some(dependency) = scalar(dependency) {
+                    edges.push(Edge::new(dependency, task_id.clone()));
+                }
+            }
+        }
+        DirectedAcyclicGraph::new_with_limits(nodes, edges, ParseMode::Strict, limits)
+    }
+}
+
+/// Returns the elements of `value` if it is a sequence, otherwise none.
+fn sequence(value: Option<&Value>) -> &[Value] {
+    value
+        .and_then(Value::as_sequence)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// Returns `value` if it is a string.
+fn string(value: Option<&Value>) -> Option<&str> {
+    value.and_then(Value::as_str)
+}
+
+/// Returns `value` as a string if it is a string, number or boolean.
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(string) => Some(string.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(boolean) => Some(boolean.to_string()),
+        _ => None,
+    }
+}
+
+/// Collects the `parameters` of Argo `arguments` or `inputs` as their names mapped to their `value_key`.
+fn parameters(value: Option<&Value>, value_key: &str) -> BTreeMap<String, String> {
+    sequence(value.and_then(|value| value.get("parameters")))
+        .iter()
+        .filter_map(|parameter| {
+            Some((
+                string(parameter.get("name"))?.to_string(),
+                scalar(parameter.get(value_key)?)?,
+            ))
+        })
+        .collect()
+}
+
+/// Extracts the task names from an Argo `depends` expression like `(A.Succeeded || B) && !C.Failed`.
+fn depends_task_names(depends: &str) -> Vec<String> {
+    depends
+        .split(['&', '|', '!', '(', ')', ' '])
+        .filter(|term| !term.is_empty())
+        .map(|term| term.split('.').next().unwrap_or(term).to_string())
+        .collect()
+}
+
+/// Quotes `word` for a POSIX shell unless it only consists of safe characters.
+fn shell_quote(word: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c);
+    match !word.is_empty() && word.chars().all(is_safe) {
+        true => word.to_string(),
+        false => format!("'{}'", word.replace('\'', "'\\''")),
+    }
+}
+
+/// Builds a single-line command feeding the (possibly multi-line) `script` to `interpreter` via its standard input,
+/// since node commands must not contain line breaks.
+fn piped_script(script: &str, interpreter: &str) -> String {
+    let script = script.trim_end();
+    match script.contains('\n') {
+        true => format!(
+            "printf '%s\\n' {} | {}",
+            script
+                .lines()
+                .map(shell_quote)
+                .collect::<Vec<_>>()
+                .join(" "),
+            interpreter
+        ),
+        false if interpreter == "sh" => script.to_string(),
+        false => format!("printf '%s\\n' {} | {}", shell_quote(script), interpreter),
+    }
+}
@@ -0,0 +1,21 @@
+//! Core of the graph executor: directed acyclic graphs of nodes, read from and written to files, and the
+//! scheduling policies deciding which node a worker executes next. Independent of how graphs are shared between
+//! workers, see `graph-executor-shm`.
+//!
+//! Without the default `std` feature, the crate is `no_std` and only requires `alloc`: it provides the graph
+//! model and the execution status machine for targets with their own executor, but neither reads nor writes DOT
+//! files, executes nodes nor schedules them.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod as_from_bytes;
+#[cfg(feature = "std")]
+pub mod clock;
+pub mod graph_structure;
+#[cfg(feature = "std")]
+pub mod monotonic_timestamp;
+#[cfg(feature = "std")]
+pub mod scheduler;
+#[cfg(feature = "std")]
+pub mod worker_info;
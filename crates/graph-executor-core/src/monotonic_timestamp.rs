@@ -0,0 +1,106 @@
+use crate::clock::{Clock, SystemClock};
+use anyhow::{anyhow, Result};
+use std::{sync::OnceLock, time::Duration};
+
+/// Point in time which can be shared between processes via shared memory.
+///
+/// The timestamp is based on `CLOCK_MONOTONIC`, which is unaffected by changes of the wall clock and shared by
+/// all processes (and containers) running on the same kernel. The kernel's boot id is stored alongside, so that
+/// timestamps taken on a different boot (e.g. left over in `/dev/shm`) are detected instead of being compared.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MonotonicTimestamp {
+    /// Identifier of the kernel boot the timestamp was taken on.
+    boot_id: String,
+    /// Nanoseconds of `CLOCK_MONOTONIC`.
+    nanoseconds: u64,
+}
+
+impl MonotonicTimestamp {
+    /// Takes a timestamp of the current point in time.
+    pub fn now() -> Result<Self> {
+        MonotonicTimestamp::now_on(&SystemClock)
+    }
+
+    /// Takes a timestamp of the current point in time of `clock`.
+    pub fn now_on(clock: &dyn Clock) -> Result<Self> {
+        Ok(MonotonicTimestamp {
+            boot_id: boot_id()?.to_string(),
+            nanoseconds: clock.now().as_nanos() as u64,
+        })
+    }
+
+    /// Checks whether the timestamp was taken on the current boot.
+    pub fn is_from_current_boot(&self) -> Result<bool> {
+        Ok(self.boot_id == boot_id()?)
+    }
+
+    /// Returns the duration elapsed from `earlier` to `self` (zero if `earlier` is later than `self`),
+    /// or an error if both timestamps were taken on different boots and are therefore not comparable.
+    pub fn duration_since(&self, earlier: &MonotonicTimestamp) -> Result<Duration> {
+        if self.boot_id != earlier.boot_id {
+            return Err(anyhow!(
+                "Timestamps taken on different boots are not comparable: {} != {}",
+                self.boot_id,
+                earlier.boot_id
+            ));
+        }
+        Ok(Duration::from_nanos(
+            self.nanoseconds.saturating_sub(earlier.nanoseconds),
+        ))
+    }
+
+    /// Returns the duration elapsed since the timestamp was taken.
+    pub fn elapsed(&self) -> Result<Duration> {
+        self.elapsed_on(&SystemClock)
+    }
+
+    /// Returns the duration elapsed on `clock` since the timestamp was taken.
+    pub fn elapsed_on(&self, clock: &dyn Clock) -> Result<Duration> {
+        MonotonicTimestamp::now_on(clock)?.duration_since(self)
+    }
+}
+
+/// Retrieves the identifier of the current kernel boot (read once per process).
+fn boot_id() -> Result<&'static str> {
+    static BOOT_ID: OnceLock<String> = OnceLock::new();
+    match BOOT_ID.get() {
+        Some(boot_id) => Ok(boot_id),
+        None => {
+            let boot_id = read_boot_id()?;
+            Ok(BOOT_ID.get_or_init(|| boot_id))
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_boot_id() -> Result<String> {
+    Ok(std::fs::read_to_string("/proc/sys/kernel/random/boot_id")?
+        .trim()
+        .to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn read_boot_id() -> Result<String> {
+    let mut buffer = [0u8; 64];
+    let mut length = buffer.len();
+    let name = std::ffi::CString::new("kern.bootsessionuuid")?;
+    if unsafe {
+        libc::sysctlbyname(
+            name.as_ptr(),
+            buffer.as_mut_ptr() as *mut libc::c_void,
+            &mut length,
+            std::ptr::null_mut(),
+            0,
+        )
+    } == -1
+    {
+        return Err(anyhow!(
+            "Failed reading kern.bootsessionuuid: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&buffer[..length])
+        .trim_end_matches('\0')
+        .to_string())
+}
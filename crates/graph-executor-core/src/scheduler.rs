@@ -0,0 +1,435 @@
+use crate::graph_structure::{execution_status::ExecutionStatus, graph::DirectedAcyclicGraph};
+use crate::worker_info::WorkerInfo;
+use petgraph::graph::NodeIndex;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt,
+    ops::Deref,
+    sync::Mutex,
+    time::Duration,
+};
+
+/// Read-only view of the current state of the graph in shared memory, handed to a [`Scheduler`]. Readers outside
+/// of a worker's claim loop take an owned `GraphSnapshot` (see `graph-executor-shm`) instead.
+#[derive(Clone, Copy)]
+pub struct GraphView<'a> {
+    graph: &'a DirectedAcyclicGraph,
+    elapsed: Duration,
+    idle_worker_count: usize,
+    /// Optional filter restricting the nodes returned by [`GraphView::executable_node_indices`].
+    node_filter: Option<&'a dyn Fn(NodeIndex) -> bool>,
+}
+
+impl fmt::Debug for GraphView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GraphView")
+            .field("graph", self.graph)
+            .field("elapsed", &self.elapsed)
+            .field("idle_worker_count", &self.idle_worker_count)
+            .field("node_filter", &self.node_filter.is_some())
+            .finish()
+    }
+}
+
+impl<'a> GraphView<'a> {
+    /// Creates a view of `graph`, `elapsed` after the run was started, while `idle_worker_count` workers
+    /// (including the picking one) are not executing a node.
+    pub fn new(
+        graph: &'a DirectedAcyclicGraph,
+        elapsed: Duration,
+        idle_worker_count: usize,
+    ) -> Self {
+        GraphView {
+            graph,
+            elapsed,
+            idle_worker_count,
+            node_filter: None,
+        }
+    }
+
+    /// Returns a view whose executable nodes are additionally restricted to those for which `node_filter` is true,
+    /// allowing schedulers to delegate to other schedulers on a subset of the nodes.
+    pub fn filtered<'b>(&'b self, node_filter: &'b dyn Fn(NodeIndex) -> bool) -> GraphView<'b> {
+        GraphView {
+            node_filter: Some(node_filter),
+            ..*self
+        }
+    }
+
+    /// Time since the run was started.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Number of workers (including the picking one) which are not executing a node.
+    pub fn idle_worker_count(&self) -> usize {
+        self.idle_worker_count
+    }
+
+    /// Indices of all nodes which are currently executable and may be claimed, ordered by string identifier. Nodes
+    /// awaiting approval (see [`crate::graph_structure::node::Node::is_awaiting_approval`]) or an external event (see
+    /// [`crate::graph_structure::node::Node::is_awaiting_event`]) may not be claimed.
+    ///
+    /// Members of a gang may only be claimed if another member has already been claimed, or if all members
+    /// are executable and at least as many workers as members are idle, so that the gang can start together.
+    /// Nodes with a concurrency key (`mutex`) may not be claimed while another node with the same key is executing.
+    pub fn executable_node_indices(&self) -> VecDeque<NodeIndex> {
+        self.graph
+            .get_executable_node_indices()
+            .into_iter()
+            .filter(|i| {
+                !self.graph[*i].is_awaiting_approval() && !self.graph[*i].is_awaiting_event()
+            })
+            .filter(|i| {
+                self.graph[*i]
+                    .mutex()
+                    .is_none_or(|mutex| !self.graph.is_mutex_held(mutex))
+            })
+            .filter(|i| match self.graph[*i].gang() {
+                Some(gang) => self.is_gang_claimable(gang),
+                None => true,
+            })
+            .filter(|i| self.node_filter.is_none_or(|node_filter| node_filter(*i)))
+            .collect()
+    }
+
+    /// Checks whether the members of the gang named `gang` may be claimed, see [`GraphView::executable_node_indices`].
+    pub fn is_gang_claimable(&self, gang: &str) -> bool {
+        let members = self.graph.get_gang_node_indices(gang);
+        let status = |i: &NodeIndex| self.graph[*i].execution_status;
+        members
+            .iter()
+            .any(|i| status(i) == ExecutionStatus::Executing)
+            || (members
+                .iter()
+                .all(|i| status(i) == ExecutionStatus::Executable)
+                && self.idle_worker_count >= members.len())
+    }
+}
+
+impl Deref for GraphView<'_> {
+    type Target = DirectedAcyclicGraph;
+
+    fn deref(&self) -> &Self::Target {
+        self.graph
+    }
+}
+
+/// Policy choosing which executable node a worker claims next.
+///
+/// A scheduler only picks a node; claiming it (and retrying if another worker was faster) is done by the
+/// synchronization code of `graph-executor-shm`'s `ExecuteGraph::execute_with_options`.
+pub trait Scheduler: fmt::Debug + Send + Sync {
+    /// Picks one of `view`'s executable nodes for `worker`, or [`None`] if no node should be claimed.
+    fn pick(&self, view: &GraphView, worker: &WorkerInfo) -> Option<NodeIndex>;
+}
+
+/// Picks executable nodes in the order of their string identifiers. This is the default policy.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FifoScheduler;
+
+impl Scheduler for FifoScheduler {
+    fn pick(&self, view: &GraphView, _worker: &WorkerInfo) -> Option<NodeIndex> {
+        view.executable_node_indices().pop_front()
+    }
+}
+
+/// Picks the executable node with the highest priority. Priorities configured in the scheduler take precedence
+/// over the nodes' `priority` attributes, nodes with neither have priority 0. Ties are broken by string identifier.
+///
+/// With `aging`, the priority of an executable node grows the longer it waits to be claimed, so that low-priority
+/// nodes don't starve while higher-priority nodes keep becoming executable on a busy fleet.
+#[derive(Clone, Debug, Default)]
+pub struct PriorityScheduler {
+    /// Priority per node, keyed by the node's string identifier.
+    pub priorities: BTreeMap<String, i64>,
+    /// Optional time an executable node waits for each boost of its priority by 1, measured from its
+    /// [`crate::graph_structure::node::Node::enqueued_after`].
+    pub aging: Option<Duration>,
+}
+
+impl PriorityScheduler {
+    /// Creates a [`PriorityScheduler`] from the priorities per node identifier.
+    pub fn new(priorities: BTreeMap<String, i64>) -> Self {
+        PriorityScheduler {
+            priorities,
+            aging: None,
+        }
+    }
+
+    /// Boosts the priority of executable nodes by 1 for every `interval` they wait to be claimed.
+    pub fn with_aging(mut self, interval: Duration) -> Self {
+        self.aging = Some(interval).filter(|interval| !interval.is_zero());
+        self
+    }
+
+    /// Priority of `view`'s node at `node_index`.
+    pub fn priority(&self, view: &GraphView, node_index: NodeIndex) -> i64 {
+        self.priorities
+            .get(view.node_id(node_index))
+            .copied()
+            .unwrap_or(view[node_index].priority())
+    }
+
+    /// Priority of `view`'s node at `node_index` including the boost for the time it has waited since it was
+    /// enqueued, see [`PriorityScheduler::aging`]. Nodes which were not enqueued yet are not boosted.
+    pub fn effective_priority(&self, view: &GraphView, node_index: NodeIndex) -> i64 {
+        let boost = match (self.aging, view[node_index].enqueued_after) {
+            (Some(interval), Some(enqueued_after)) => {
+                let waited = view.elapsed().saturating_sub(enqueued_after);
+                (waited.as_nanos() / interval.as_nanos()).min(i64::MAX as u128) as i64
+            }
+            _ => 0,
+        };
+        self.priority(view, node_index).saturating_add(boost)
+    }
+}
+
+impl Scheduler for PriorityScheduler {
+    fn pick(&self, view: &GraphView, _worker: &WorkerInfo) -> Option<NodeIndex> {
+        view.executable_node_indices()
+            .into_iter()
+            .rev() // `max_by_key` returns the last maximum
+            .max_by_key(|i| self.effective_priority(view, *i))
+    }
+}
+
+/// Picks the executable node on the longest remaining path to a sink, so that the critical path of the graph
+/// is started as early as possible. Ties are broken by string identifier.
+#[derive(Clone, Debug, Default)]
+pub struct CriticalPathScheduler {
+    /// Length of the longest path from each node (inclusive) to a sink, keyed by the node's string identifier.
+    pub remaining_path_lengths: BTreeMap<String, Duration>,
+}
+
+impl CriticalPathScheduler {
+    /// Creates a [`CriticalPathScheduler`] for `graph` in which every node takes the same time.
+    pub fn new(graph: &DirectedAcyclicGraph) -> Self {
+        CriticalPathScheduler::with_durations(graph, &BTreeMap::new())
+    }
+
+    /// Creates a [`CriticalPathScheduler`] for `graph` from estimated durations per node identifier.
+    /// Nodes without an estimate take one second.
+    pub fn with_durations(
+        graph: &DirectedAcyclicGraph,
+        durations: &BTreeMap<String, Duration>,
+    ) -> Self {
+        let mut remaining_path_lengths = BTreeMap::new();
+        for node_index in graph.get_node_indices() {
+            remaining_path_length(graph, durations, node_index, &mut remaining_path_lengths);
+        }
+        CriticalPathScheduler {
+            remaining_path_lengths,
+        }
+    }
+}
+
+/// Computes (memoized in `lengths`) the length of the longest path from `node_index` to a sink.
+fn remaining_path_length(
+    graph: &DirectedAcyclicGraph,
+    durations: &BTreeMap<String, Duration>,
+    node_index: NodeIndex,
+    lengths: &mut BTreeMap<String, Duration>,
+) -> Duration {
+    let node_id = graph.node_id(node_index);
+    if let Some(length) = lengths.get(node_id) {
+        return *length;
+    }
+    let longest_child_path = graph
+        .get_child_node_indices(node_index)
+        .map(|child_index| remaining_path_length(graph, durations, child_index, lengths))
+        .max()
+        .unwrap_or_default();
+    let length = durations
+        .get(node_id)
+        .copied()
+        .unwrap_or(Duration::from_secs(1))
+        + longest_child_path;
+    lengths.insert(node_id.to_string(), length);
+    length
+}
+
+impl Scheduler for CriticalPathScheduler {
+    fn pick(&self, view: &GraphView, _worker: &WorkerInfo) -> Option<NodeIndex> {
+        view.executable_node_indices()
+            .into_iter()
+            .rev() // `max_by_key` returns the last maximum
+            .max_by_key(|i| self.remaining_path_lengths.get(view.node_id(*i)).copied())
+    }
+}
+
+/// Picks a random executable node.
+#[derive(Debug)]
+pub struct RandomScheduler {
+    rng: Mutex<StdRng>,
+}
+
+impl Default for RandomScheduler {
+    fn default() -> Self {
+        RandomScheduler {
+            rng: Mutex::new(StdRng::from_entropy()),
+        }
+    }
+}
+
+impl RandomScheduler {
+    /// Creates a [`RandomScheduler`] with a fixed `seed` for reproducible experiments.
+    pub fn seeded(seed: u64) -> Self {
+        RandomScheduler {
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+}
+
+impl Scheduler for RandomScheduler {
+    fn pick(&self, view: &GraphView, _worker: &WorkerInfo) -> Option<NodeIndex> {
+        let executable_node_indices = Vec::from(view.executable_node_indices());
+        let mut rng = self.rng.lock().unwrap_or_else(|e| e.into_inner());
+        executable_node_indices.choose(&mut *rng).copied()
+    }
+}
+
+/// Minimizes the cost of a run (node cost times the cost rate of the executing worker) while trying to meet
+/// a deadline, using a greedy heuristic:
+///
+/// - Workers with the cheapest cost rate always pick the executable node on the longest remaining path.
+/// - More expensive workers only pick a node if the cheapest workers alone are estimated to miss the deadline,
+///   i.e. if the elapsed time plus the maximum of the remaining critical path and the remaining work divided
+///   by the number of cheapest workers exceeds the deadline. Among the executable nodes on the longest remaining
+///   path they prefer the one with the lowest cost.
+#[derive(Clone, Debug)]
+pub struct CostAwareScheduler {
+    /// Time after the start of the run by which all nodes should be executed.
+    pub deadline: Duration,
+    /// Cost rates of all workers of the run.
+    pub worker_cost_rates: Vec<u64>,
+    /// Estimated duration per node identifier.
+    durations: BTreeMap<String, Duration>,
+    /// Critical path lengths used to prioritize nodes.
+    critical_path: CriticalPathScheduler,
+}
+
+impl CostAwareScheduler {
+    /// Creates a [`CostAwareScheduler`] for `graph` from estimated durations per node identifier (nodes without
+    /// an estimate take one second), the `deadline` and the cost rates of all workers of the run.
+    pub fn new(
+        graph: &DirectedAcyclicGraph,
+        durations: &BTreeMap<String, Duration>,
+        deadline: Duration,
+        worker_cost_rates: Vec<u64>,
+    ) -> Self {
+        CostAwareScheduler {
+            deadline,
+            worker_cost_rates,
+            durations: durations.clone(),
+            critical_path: CriticalPathScheduler::with_durations(graph, durations),
+        }
+    }
+
+    /// Estimated point in time (after the start of the run) at which the cheapest workers alone finish the run.
+    pub fn estimated_finish_of_cheapest_workers(&self, view: &GraphView) -> Duration {
+        let cheapest_cost_rate = self.worker_cost_rates.iter().min().copied().unwrap_or(0);
+        let cheapest_worker_count = self
+            .worker_cost_rates
+            .iter()
+            .filter(|rate| **rate == cheapest_cost_rate)
+            .count()
+            .max(1) as u32;
+
+        let (mut remaining_work, mut remaining_critical_path) = (Duration::ZERO, Duration::ZERO);
+        for i in view.get_node_indices() {
+            if view[i].execution_status == ExecutionStatus::Executed {
+                continue;
+            }
+            let node_id = view.node_id(i);
+            remaining_work += self
+                .durations
+                .get(node_id)
+                .copied()
+                .unwrap_or(Duration::from_secs(1));
+            if let Some(length) = self.critical_path.remaining_path_lengths.get(node_id) {
+                remaining_critical_path = remaining_critical_path.max(*length);
+            }
+        }
+
+        view.elapsed() + remaining_critical_path.max(remaining_work / cheapest_worker_count)
+    }
+}
+
+impl Scheduler for CostAwareScheduler {
+    fn pick(&self, view: &GraphView, worker: &WorkerInfo) -> Option<NodeIndex> {
+        let cheapest_cost_rate = self.worker_cost_rates.iter().min().copied().unwrap_or(0);
+        if worker.cost_rate <= cheapest_cost_rate {
+            return self.critical_path.pick(view, worker);
+        }
+        if self.estimated_finish_of_cheapest_workers(view) <= self.deadline {
+            return None;
+        }
+        view.executable_node_indices()
+            .into_iter()
+            .rev() // `max_by_key` returns the last maximum
+            .max_by_key(|i| {
+                (
+                    self.critical_path
+                        .remaining_path_lengths
+                        .get(view.node_id(*i))
+                        .copied(),
+                    std::cmp::Reverse(view[*i].cost().unwrap_or(0)),
+                )
+            })
+    }
+}
+
+/// Scheduling hint for data locality: a child is preferably claimed by the worker which executed the parent with
+/// the largest output. Other workers only claim the child once it has been executable for longer than `max_wait`.
+/// Among the nodes a worker may claim, the `inner` scheduler picks, preferring the worker's own children.
+#[derive(Clone, Debug)]
+pub struct AffinityScheduler<S: Scheduler> {
+    /// Scheduler picking among the nodes allowed by the affinity.
+    pub inner: S,
+    /// Time after which a child may be claimed by any worker.
+    pub max_wait: Duration,
+}
+
+impl<S: Scheduler> AffinityScheduler<S> {
+    /// Creates an [`AffinityScheduler`] delegating to `inner`, falling back to any worker after `max_wait`.
+    pub fn new(inner: S, max_wait: Duration) -> Self {
+        AffinityScheduler { inner, max_wait }
+    }
+
+    /// The worker which executed the parent of `node_index` with the largest output and the time at which the
+    /// last parent finished, or [`None`] if `node_index` has no executed parents.
+    pub fn preferred_worker(view: &GraphView, node_index: NodeIndex) -> Option<(usize, Duration)> {
+        let records: Vec<_> = view
+            .get_parent_node_indices(node_index)
+            .filter_map(|parent_index| view[parent_index].execution_record().copied())
+            .collect();
+        let executable_since = records.iter().map(|r| r.finished_after).max()?;
+        let largest_output = records
+            .iter()
+            .rev() // `max_by_key` returns the last maximum
+            .max_by_key(|r| r.output_bytes)?;
+        Some((largest_output.worker_id, executable_since))
+    }
+}
+
+impl<S: Scheduler> Scheduler for AffinityScheduler<S> {
+    fn pick(&self, view: &GraphView, worker: &WorkerInfo) -> Option<NodeIndex> {
+        let is_own_child = |i: NodeIndex| {
+            AffinityScheduler::<S>::preferred_worker(view, i)
+                .is_some_and(|(worker_id, _)| worker_id == worker.id)
+        };
+        if let Some(i) = self.inner.pick(&view.filtered(&is_own_child), worker) {
+            return Some(i);
+        }
+
+        let is_claimable = |i: NodeIndex| match AffinityScheduler::<S>::preferred_worker(view, i) {
+            Some((_, executable_since)) => {
+                view.elapsed().saturating_sub(executable_since) >= self.max_wait
+            }
+            None => true,
+        };
+        self.inner.pick(&view.filtered(&is_claimable), worker)
+    }
+}
@@ -0,0 +1,66 @@
+use crate::monotonic_timestamp::MonotonicTimestamp;
+use std::fmt;
+
+/// Entry of a worker (process or thread executing the graph) in a run's worker table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WorkerInfo {
+    /// Index of the worker in the run's worker table.
+    pub id: usize,
+    /// Process ID of the worker.
+    pub pid: u32,
+    /// Hostname of the machine the worker runs on.
+    pub hostname: String,
+    /// Point in time the worker attached to the run.
+    pub started_at: MonotonicTimestamp,
+    /// Bitmask of shared memory protocol features supported by the worker.
+    pub capabilities: u64,
+    /// Cost per unit of node cost when the worker executes a node.
+    pub cost_rate: u64,
+    /// Identifier of the node the worker is currently executing (or waiting to execute), if any.
+    pub current_node: Option<String>,
+    /// Number of nodes the worker has executed.
+    pub nodes_completed: u64,
+    /// Number of nodes whose results the worker read from the remote cache instead of executing them.
+    pub cache_hits: u64,
+    /// Number of nodes the worker executed after finding no results for them in the remote cache.
+    pub cache_misses: u64,
+    /// Point in time the worker last reported being alive.
+    pub last_heartbeat: MonotonicTimestamp,
+    /// Whether the worker has left the run.
+    pub detached: bool,
+}
+
+impl fmt::Display for WorkerInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let seconds = |timestamp: &MonotonicTimestamp| match timestamp.elapsed() {
+            Ok(elapsed) => format!("{:.1}s", elapsed.as_secs_f64()),
+            Err(_) => String::from("unknown"),
+        };
+        let current_node = match &self.current_node {
+            Some(node_id) => format!("executing {}", node_id),
+            None => String::from("idle"),
+        };
+        write!(
+            f,
+            "worker {}: pid {} on {}: {}, up {}, {} nodes completed, last heartbeat {} ago, capabilities {:#x}, cost rate {}",
+            self.id,
+            self.pid,
+            self.hostname,
+            current_node,
+            seconds(&self.started_at),
+            self.nodes_completed,
+            seconds(&self.last_heartbeat),
+            self.capabilities,
+            self.cost_rate
+        )?;
+        if self.cache_hits + self.cache_misses > 0 {
+            write!(
+                f,
+                ", remote cache {} hits and {} misses",
+                self.cache_hits, self.cache_misses
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+//! Execution of the graphs of `graph-executor-core` by multiple processes, which share a graph and the state of
+//! its run via POSIX shared memory synchronized by a multiple-reader single-writer lock. The layout of the shared
+//! memory is versioned by [`shared_memory_graph_execution::protocol`] and not yet stable.
+//!
+//! Crates embedding the graph executor instead of running the `graph-executor` binary only need the items
+//! re-exported at the root of this crate: a [`DirectedAcyclicGraph`] of [`Node`]s and [`Edge`]s, executed by an
+//! [`Executor`] in any [`ExecutionMode`].
+
+pub mod shared_memory;
+pub mod shared_memory_graph_execution;
+
+pub use graph_executor_core::graph_structure::{
+    edge::Edge, execution_status::ExecutionStatus, graph::DirectedAcyclicGraph, node::Node,
+};
+pub use shared_memory::posix_shared_memory::PosixSharedMemory;
+pub use shared_memory_graph_execution::{
+    execution_options::ExecutionOptions,
+    execution_report::ExecutionReport,
+    executor::{ExecutionMode, Executor},
+};
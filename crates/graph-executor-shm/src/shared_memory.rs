@@ -0,0 +1,512 @@
+pub mod attach_counter;
+pub mod event;
+#[cfg(target_os = "linux")]
+pub mod futex;
+pub mod lock_audit;
+pub mod posix_shared_memory;
+pub mod rwlock;
+pub mod semaphore;
+pub mod shared_counter;
+pub mod shared_segment;
+pub mod status_slots;
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        attach_counter::AttachCounter,
+        lock_audit::{self, LockHold},
+        posix_shared_memory::PosixSharedMemory,
+        rwlock,
+        semaphore::{Semaphore, SyncBackend},
+        shared_counter::{SharedCounter, MAX_READER_COUNT},
+    };
+    use anyhow::{anyhow, Result};
+    use graph_executor_core::{
+        graph_structure::{edge::Edge, graph::DirectedAcyclicGraph, node::Node},
+        monotonic_timestamp::MonotonicTimestamp,
+    };
+    use std::{
+        collections::BTreeMap,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    // `DirectedAcyclicGraph` shared memory tests
+
+    #[test]
+    fn dag_serialize_deserialize() -> Result<()> {
+        let graph_new = DirectedAcyclicGraph::new(
+            BTreeMap::from([
+                (
+                    String::from("0"),
+                    Node::new(String::from("Node 0 was just executed")),
+                ),
+                (
+                    String::from("1"),
+                    Node::new(String::from("Node 1 was just executed")),
+                ),
+            ]),
+            vec![Edge::new(String::from("0"), String::from("1"))],
+        )?;
+
+        let bytes = rmp_serde::to_vec(&graph_new)?;
+        let graph_from_bytes = rmp_serde::from_slice::<DirectedAcyclicGraph>(&bytes)?;
+
+        if graph_new != graph_from_bytes {
+            return Err(anyhow!(
+                "Original DAG and its serialized and then deserialized version are not equal:\n{} != {}",
+                graph_new,
+                graph_from_bytes
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn shrinking_data_stays_readable_by_other_handles() -> Result<()> {
+        let filename_suffix = "cargo_test_shrinking_data";
+        let mut writer = PosixSharedMemory::new(filename_suffix, vec![1u8; 64])?;
+        let (mut reader, _) = PosixSharedMemory::open::<Vec<u8>>(filename_suffix)?;
+
+        // Another handle reads the current data after it shrank and grew again, not what it mapped before
+        writer.write(&vec![2u8; 8])?;
+        assert_eq!(reader.read::<Vec<u8>>()?, vec![2u8; 8]);
+        writer.write(&vec![3u8; 64])?;
+        assert_eq!(reader.read::<Vec<u8>>()?, vec![3u8; 64]);
+
+        // The handle taking over ownership removes all of the mapping, including what the larger data occupied
+        writer.write(&vec![4u8; 8])?;
+        writer.set_ownership(false)?;
+        drop(writer);
+        reader.set_ownership(true)?;
+        drop(reader);
+        let leftovers = std::fs::read_dir("/dev/shm")?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .contains(filename_suffix)
+            })
+            .count();
+        assert_eq!(leftovers, 0, "Shared memory of the mapping is left behind.");
+        Ok(())
+    }
+
+    #[test]
+    fn data_segment_grows_across_handles() -> Result<()> {
+        let filename_suffix = "cargo_test_data_segment";
+        let mut writer = PosixSharedMemory::new(filename_suffix, vec![1u8; 4])?;
+        let (mut reader, data) = PosixSharedMemory::open::<Vec<u8>>(filename_suffix)?;
+        assert_eq!(data, vec![1u8; 4]);
+
+        // The reader remaps the segment grown by the writer, whose data is a single object in `/dev/shm`
+        let large = vec![2u8; 1 << 17];
+        writer.write(&large)?;
+        assert_eq!(reader.read::<Vec<u8>>()?, large);
+        assert_eq!(reader.allocated_bytes(), writer.allocated_bytes());
+        assert!(
+            writer.allocated_bytes() >= large.len()
+                && writer.allocated_bytes() % PosixSharedMemory::allocated_bytes_for(1) == 0,
+            "Segment of {} bytes doesn't hold the data in whole pages.",
+            writer.allocated_bytes()
+        );
+        let data_objects = std::fs::read_dir("/dev/shm")?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                // Reader count and attach counter are objects of their own besides the data
+                name.starts_with(filename_suffix)
+                    && !name.ends_with("_read_count")
+                    && !name.ends_with("_attach")
+            })
+            .count();
+        assert_eq!(data_objects, 1, "Data is not held by a single segment.");
+
+        // Shrinking data keeps the segment mapped by the reader, but releases the pages it no longer occupies
+        let peak_allocated_bytes = writer.allocated_bytes();
+        writer.write(&vec![3u8; 2])?;
+        assert_eq!(reader.read::<Vec<u8>>()?, vec![3u8; 2]);
+        assert_eq!(
+            (writer.allocated_bytes(), reader.allocated_bytes()),
+            (
+                PosixSharedMemory::allocated_bytes_for(1),
+                PosixSharedMemory::allocated_bytes_for(1)
+            ),
+            "Shrunk data still counts the pages of the larger data."
+        );
+        assert_eq!(writer.peak_allocated_bytes(), peak_allocated_bytes);
+        writer.write(&large)?;
+        assert_eq!(reader.read::<Vec<u8>>()?, large);
+        Ok(())
+    }
+
+    // `Semaphore` and `rwlock` tests
+
+    #[test]
+    fn rwlock() -> Result<()> {
+        // Create RwLock
+        let filename_suffix = "cargo_test";
+        let write_lock = Semaphore::create(&format!("/{}_write_lock_write", filename_suffix), 1)
+            .map_err(|e| anyhow!("Failed to create write_lock: {}", e))?;
+        let read_count = SharedCounter::create(
+            &format!("/{}_read_count_write", filename_suffix),
+            MAX_READER_COUNT,
+        )
+        .map_err(|e| anyhow!("Failed to create read_count: {}", e))?;
+        assert_eq!(
+            write_lock
+                .get_value()
+                .map_err(|e| anyhow!("Failed getting write_lock semaphore value: {}", e))?,
+            1,
+            "write_lock semaphore not equal to 1 after initialization."
+        );
+        assert_eq!(
+            read_count.get_value(),
+            0,
+            "read_count counter not equal to 0 after initialization."
+        );
+
+        rwlock::read_lock(&write_lock, &read_count)?;
+        assert_eq!(
+            write_lock
+                .get_value()
+                .map_err(|e| anyhow!("Failed getting write_lock semaphore value: {}", e))?,
+            1,
+            "write_lock semaphore changed (not equal to 1) after registering new reader."
+        );
+        assert_eq!(
+            read_count.get_value(),
+            1,
+            "read_count counter not equal to 1 after registering new reader."
+        );
+
+        rwlock::read_lock(&write_lock, &read_count)?;
+        assert_eq!(
+            write_lock
+                .get_value()
+                .map_err(|e| anyhow!("Failed getting write_lock semaphore value: {}", e))?,
+            1,
+            "write_lock semaphore changed (not equal to 1) after registering new reader."
+        );
+        assert_eq!(
+            read_count.get_value(),
+            2,
+            "read_count counter not equal to 2 after registering new reader."
+        );
+
+        rwlock::read_unlock(&read_count)?;
+        assert_eq!(
+            read_count.get_value(),
+            1,
+            "read_count counter not equal to 1 after unregistering active reader."
+        );
+
+        rwlock::read_unlock(&read_count)?;
+        assert_eq!(
+            read_count.get_value(),
+            0,
+            "read_count counter not equal to 0 after unregistering active reader."
+        );
+
+        rwlock::write_lock(&write_lock, &read_count)?;
+        assert_eq!(
+            write_lock
+                .get_value()
+                .map_err(|e| anyhow!("Failed getting write_lock semaphore value: {}", e))?,
+            0,
+            "write_lock semaphore not equal to 0 after registering writer."
+        );
+        assert_eq!(
+            read_count.get_value(),
+            0,
+            "read_count counter not equal to 0 after registering writer."
+        );
+
+        rwlock::write_unlock(&write_lock, &read_count)?;
+        assert_eq!(
+            write_lock
+                .get_value()
+                .map_err(|e| anyhow!("Failed getting write_lock semaphore value: {}", e))?,
+            1,
+            "write_lock semaphore not equal to 1 after unregistering writer."
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_count_overflow() -> Result<()> {
+        let write_lock = Semaphore::create("/cargo_test_overflow_write_lock", 1)
+            .map_err(|e| anyhow!("Failed to create write_lock: {}", e))?;
+        let read_count = SharedCounter::create("/cargo_test_overflow_read_count", 2)
+            .map_err(|e| anyhow!("Failed to create read_count: {}", e))?;
+
+        rwlock::read_lock(&write_lock, &read_count)?;
+        rwlock::read_lock(&write_lock, &read_count)?;
+        assert!(
+            rwlock::read_lock(&write_lock, &read_count)
+                .is_err_and(|e| e.to_string().contains("(errno: 75)")),
+            "Reader registered beyond the maximum read_count without EOVERFLOW."
+        );
+        assert_eq!(
+            (
+                write_lock.get_value().map_err(|e| anyhow!(e))?,
+                read_count.get_value()
+            ),
+            (1, 2),
+            "Failed read lock left write_lock locked or changed read_count."
+        );
+
+        rwlock::read_unlock(&read_count)?;
+        rwlock::read_unlock(&read_count)?;
+        assert!(
+            rwlock::read_unlock(&read_count).is_err(),
+            "Reader unregistered without any active readers."
+        );
+        assert_eq!(read_count.get_value(), 0, "read_count dropped below 0.");
+        Ok(())
+    }
+
+    #[test]
+    fn lock_audit_records_writers() -> Result<()> {
+        // Correct uses of the lock pass the audit, so that enabling it doesn't affect concurrent tests
+        lock_audit::set_enabled(true);
+        let mut shm_mapping = PosixSharedMemory::new("cargo_test_lock_audit", vec![0u8; 4])?;
+        shm_mapping.write(&vec![1u8; 4])?;
+        shm_mapping.read::<Vec<u8>>()?;
+        lock_audit::set_enabled(false);
+        let holds: Vec<LockHold> = lock_audit::records()
+            .into_iter()
+            .filter(|hold| hold.name == "/cargo_test_lock_audit_write_lock")
+            .collect();
+        assert_eq!(holds.len(), 2, "Write locks are not recorded: {:?}", holds);
+        assert!(
+            holds.iter().all(|hold| hold.pid == std::process::id()),
+            "Writer's pid is not recorded."
+        );
+
+        let write_lock =
+            Semaphore::open("/cargo_test_lock_audit_write_lock").map_err(|e| anyhow!(e))?;
+        let read_count = SharedCounter::open("/cargo_test_lock_audit_read_count", MAX_READER_COUNT)
+            .map_err(|e| anyhow!(e))?;
+        let dump = lock_audit::diagnostic("test violation", &write_lock, &read_count);
+        assert!(
+            dump.contains("test violation")
+                && dump.contains("/cargo_test_lock_audit_write_lock: value 1")
+                && dump.contains("/cargo_test_lock_audit_read_count: value 0"),
+            "Diagnostic dump lacks the lock's state: {}",
+            dump
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn lock_in_memory() -> Result<()> {
+        let locked_kilobytes = || -> Result<usize> {
+            std::fs::read_to_string("/proc/self/status")?
+                .lines()
+                .find_map(|line| line.strip_prefix("VmLck:"))
+                .and_then(|value| value.trim().trim_end_matches("kB").trim().parse().ok())
+                .ok_or(anyhow!("Missing VmLck in /proc/self/status."))
+        };
+        let mut shm_mapping = PosixSharedMemory::new("cargo_test_lock_in_memory", vec![0u8; 4])?;
+        match shm_mapping.lock_in_memory() {
+            // Processes which may not lock memory keep their mapping unlocked
+            Err(_) => assert!(
+                !shm_mapping.is_locked_in_memory(),
+                "Mapping is locked although locking failed."
+            ),
+            Ok(()) => {
+                let before = locked_kilobytes()?;
+                shm_mapping.write(&vec![0u8; 1 << 17])?;
+                assert!(
+                    locked_kilobytes()? > before,
+                    "Segment grown after locking the mapping is not locked."
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn futex_semaphore() -> Result<()> {
+        let name = "/cargo_test_futex_write_lock";
+        let write_lock = Semaphore::create_with_backend(name, 1, SyncBackend::Futex)
+            .map_err(|e| anyhow!("Failed to create futex write_lock: {}", e))?;
+        let read_count = SharedCounter::create("/cargo_test_futex_read_count", MAX_READER_COUNT)
+            .map_err(|e| anyhow!("Failed to create read_count: {}", e))?;
+        assert!(
+            Semaphore::create_with_backend(name, 1, SyncBackend::PosixSemaphore)
+                .is_err_and(|e| e.ends_with("File exists (errno: 17)")),
+            "Named semaphore created despite a futex semaphore with the same name."
+        );
+
+        // Other handles open the semaphore with the backend it was created with
+        let opened = Semaphore::open(name).map_err(|e| anyhow!(e))?;
+        assert_eq!(
+            opened.backend(),
+            SyncBackend::Futex,
+            "Futex semaphore opened as named semaphore."
+        );
+
+        // A writer blocks another writer until it unlocks
+        rwlock::write_lock(&write_lock, &read_count)?;
+        let waiter = thread::spawn(move || -> Result<()> {
+            let opened = Semaphore::open(name).map_err(|e| anyhow!(e))?;
+            opened.wait().map_err(|e| anyhow!(e))?;
+            opened.post().map_err(|e| anyhow!(e))
+        });
+        thread::sleep(Duration::from_millis(50));
+        assert!(!waiter.is_finished(), "Waiter did not block on futex.");
+        rwlock::write_unlock(&write_lock, &read_count)?;
+        waiter.join().unwrap()?;
+        assert_eq!(
+            write_lock.get_value().map_err(|e| anyhow!(e))?,
+            1,
+            "write_lock futex not equal to 1 after the waiter unlocked it."
+        );
+
+        rwlock::read_lock(&write_lock, &read_count)?;
+        assert_eq!(
+            read_count.get_value(),
+            1,
+            "read_count counter not equal to 1 after registering new reader."
+        );
+        rwlock::read_unlock(&read_count)?;
+
+        drop(write_lock);
+        assert!(
+            Semaphore::open(name).is_err(),
+            "Futex semaphore not removed by its creator."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn attach_counter_hands_over_to_semaphores() -> Result<()> {
+        let name = "/cargo_test_attach";
+        let mut counter = AttachCounter::create(name).map_err(|e| anyhow!(e))?;
+        counter.attach(true);
+        let mut other = AttachCounter::open(name).map_err(|e| anyhow!(e))?;
+        other.attach(true);
+        assert!(
+            counter.is_exclusive() && counter.processes() == 1,
+            "Handles of a single process don't lock the mapping exclusively."
+        );
+
+        // Handles of the same process exclude each other with the lock word
+        assert!(counter.try_write_lock(), "Writer took the semaphores.");
+        let reader = thread::spawn(move || (other.try_read_lock(), other));
+        thread::sleep(Duration::from_millis(50));
+        assert!(!reader.is_finished(), "Reader did not wait for the writer.");
+        counter.write_unlock();
+        let (locked, other) = reader.join().unwrap();
+        assert!(locked, "Reader took the semaphores.");
+
+        // Switching to shared mode waits for the reader, after which handles take the semaphores
+        let sharer = thread::spawn(move || {
+            counter.share();
+            counter
+        });
+        thread::sleep(Duration::from_millis(50));
+        assert!(
+            !sharer.is_finished(),
+            "Mode switched while a reader held the lock word."
+        );
+        other.read_unlock();
+        let counter = sharer.join().unwrap();
+        assert!(
+            !counter.is_exclusive() && !other.try_read_lock() && !other.try_write_lock(),
+            "Handles keep locking the mapping exclusively after switching to shared mode."
+        );
+        assert_eq!(counter.lock_state(), 0, "Lock word held after the switch.");
+        Ok(())
+    }
+
+    #[test]
+    fn change_event_wakes_waiters() -> Result<()> {
+        let filename_suffix = "cargo_test_change_event";
+        let (mut shm_mapping, _) = PosixSharedMemory::create_or_open_with_status_slots(
+            filename_suffix,
+            &vec![0u8; 4],
+            None,
+            Some(&[0u8; 2]),
+        )?;
+
+        // A waiter in another handle blocks until the data is written, not until its timeout
+        let seen = shm_mapping.change_sequence();
+        let waiter = thread::spawn(move || -> Result<Duration> {
+            let (opened, _) = PosixSharedMemory::open::<Vec<u8>>(filename_suffix)?;
+            let started = Instant::now();
+            opened.wait_for_change(seen, Duration::from_secs(10))?;
+            Ok(started.elapsed())
+        });
+        thread::sleep(Duration::from_millis(50));
+        assert!(
+            !waiter.is_finished(),
+            "Waiter did not block on change event."
+        );
+        shm_mapping.write(&vec![1u8; 4])?;
+        let waited = waiter.join().unwrap()?;
+        assert!(
+            waited < Duration::from_secs(5),
+            "Waiter was not woken by the write, but waited {:?}.",
+            waited
+        );
+
+        // Changes of status slots notify as well, and waiters return right away on changes they haven't seen
+        let seen = shm_mapping.change_sequence();
+        assert_eq!(
+            shm_mapping.compare_exchange_status_slot(1, 0, 2)?,
+            Some(true)
+        );
+        assert_ne!(
+            shm_mapping.change_sequence(),
+            seen,
+            "Status slot change did not notify the change event."
+        );
+        let started = Instant::now();
+        shm_mapping.wait_for_change(seen, Duration::from_secs(10))?;
+        assert!(
+            started.elapsed() < Duration::from_secs(5),
+            "Waiter blocked despite a change it hadn't seen."
+        );
+        Ok(())
+    }
+
+    // `MonotonicTimestamp` tests
+
+    #[test]
+    fn monotonic_timestamp_duration_since() -> Result<()> {
+        let earlier = MonotonicTimestamp::now()?;
+        thread::sleep(Duration::from_millis(10));
+        let later = MonotonicTimestamp::now()?;
+
+        assert!(
+            later.duration_since(&earlier)? >= Duration::from_millis(10),
+            "Duration between timestamps shorter than slept duration."
+        );
+        assert_eq!(
+            earlier.duration_since(&later)?,
+            Duration::ZERO,
+            "Duration since a later timestamp is not zero."
+        );
+        assert!(
+            earlier.is_from_current_boot()?,
+            "Timestamp not from current boot."
+        );
+
+        let other_boot = rmp_serde::from_slice::<MonotonicTimestamp>(&rmp_serde::to_vec(&(
+            "other-boot-id",
+            0u64,
+        ))?)?;
+        assert!(
+            !other_boot.is_from_current_boot()? && later.duration_since(&other_boot).is_err(),
+            "Timestamps from different boots are compared."
+        );
+
+        Ok(())
+    }
+}
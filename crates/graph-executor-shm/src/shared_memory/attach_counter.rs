@@ -0,0 +1,251 @@
+use super::shared_segment::SharedSegment;
+use std::{
+    collections::BTreeMap,
+    mem::size_of,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+/// Number of 4-byte words of the object: the number of attached processes, the mode and the lock word.
+const ATTACH_WORDS: usize = 3;
+
+/// Mode in which the attached processes lock the mapping with its semaphores.
+const SHARED: u32 = 0;
+/// Mode in which the only attached process locks the mapping with atomics on the lock word.
+const EXCLUSIVE: u32 = 1;
+
+/// Bit of the lock word set by a writer, the other bits count the readers.
+const WRITER: u32 = 1 << 31;
+
+/// Number of times a waiter yields before it sleeps between attempts to take the lock word.
+const SPINS_BEFORE_SLEEP: u32 = 64;
+
+/// Number of handles of each attach counter in this process, by name, so that a process attaches once however
+/// many of its threads open the same mapping.
+static LOCAL_HANDLES: Mutex<BTreeMap<String, usize>> = Mutex::new(BTreeMap::new());
+
+/// Counter of the processes attached to a shared memory mapping in a named shared memory object, with which the
+/// only attached process skips the mapping's semaphores.
+///
+/// While a single process is attached, the mapping is in exclusive mode: its threads lock it with atomics on a
+/// lock word next to the counter instead of the semaphores, so that locking needs no system calls. The first
+/// handle of a second process switches the mapping to shared mode for good, by taking the lock word like a writer
+/// (waiting for the current holders of the first process to release it) and then flipping the mode, after which
+/// all processes lock the mapping with its semaphores.
+#[derive(Debug)]
+pub struct AttachCounter {
+    segment: SharedSegment,
+    attached: bool,
+}
+
+impl AttachCounter {
+    /// Creates a new named counter with no attached process, in shared mode. If an object with the name is left by
+    /// a process which died before removing it, it is taken over and reset.
+    pub fn create(name: &str) -> Result<Self, String> {
+        let object_len = size_of::<AtomicU32>() * ATTACH_WORDS;
+        let mut segment = SharedSegment::create(name, object_len).or_else(|_| {
+            SharedSegment::open(name).map(|mut segment| {
+                segment.set_remove_on_drop(true);
+                segment
+            })
+        })?;
+        segment.grow(object_len)?;
+        let counter = AttachCounter {
+            segment,
+            attached: false,
+        };
+        for index in 0..ATTACH_WORDS {
+            counter.word(index).store(0, Ordering::SeqCst);
+        }
+        Ok(counter)
+    }
+
+    /// Opens an existing named counter without attaching to it.
+    pub fn open(name: &str) -> Result<Self, String> {
+        let segment = SharedSegment::open(name)?;
+        if segment.len() < size_of::<AtomicU32>() * ATTACH_WORDS {
+            return Err(format!("Attach counter {} is not sized yet", name));
+        }
+        Ok(AttachCounter {
+            segment,
+            attached: false,
+        })
+    }
+
+    /// Get the 4-byte word at `index` of the object.
+    fn word(&self, index: usize) -> &AtomicU32 {
+        // SAFETY: the mapping is page aligned and holds `ATTACH_WORDS` words, and shared memory is only ever
+        // accessed atomically.
+        unsafe { &*(self.segment.as_slice().as_ptr() as *const AtomicU32).add(index) }
+    }
+
+    fn process_count(&self) -> &AtomicU32 {
+        self.word(0)
+    }
+
+    fn mode(&self) -> &AtomicU32 {
+        self.word(1)
+    }
+
+    fn lock_word(&self) -> &AtomicU32 {
+        self.word(2)
+    }
+
+    /// Attaches the process, unless another handle of it already did. The first process to attach switches the
+    /// mapping to exclusive mode if `exclusive` is allowed, any further one switches it to shared mode.
+    pub fn attach(&mut self, exclusive: bool) {
+        if self.attached {
+            return;
+        }
+        self.attached = true;
+        {
+            let mut local_handles = LOCAL_HANDLES.lock().unwrap();
+            let handles = local_handles.entry(self.name().to_string()).or_insert(0);
+            *handles += 1;
+            if *handles > 1 {
+                return;
+            }
+        }
+        let processes = self.process_count().fetch_add(1, Ordering::SeqCst) + 1;
+        if processes == 1 && exclusive {
+            self.mode().store(EXCLUSIVE, Ordering::SeqCst);
+            // A process attaching meanwhile may have missed the switch, so it is undone if there is one
+            if self.process_count().load(Ordering::SeqCst) > 1 {
+                self.share();
+            }
+        } else if self.mode().load(Ordering::SeqCst) == EXCLUSIVE {
+            self.share();
+        }
+    }
+
+    /// Switches the mapping to shared mode for good once the holders of the lock word released it, as attaching a
+    /// second process does.
+    pub fn share(&self) {
+        self.lock_exclusively();
+        self.mode().store(SHARED, Ordering::SeqCst);
+        self.lock_word().store(0, Ordering::SeqCst);
+    }
+
+    /// Checks whether the mapping is in exclusive mode.
+    pub fn is_exclusive(&self) -> bool {
+        self.mode().load(Ordering::SeqCst) == EXCLUSIVE
+    }
+
+    /// Number of attached processes, including processes which died without detaching.
+    pub fn processes(&self) -> u32 {
+        self.process_count().load(Ordering::SeqCst)
+    }
+
+    /// Current value of the lock word: the number of readers, with the highest bit set while a writer holds or waits
+    /// for it.
+    pub fn lock_state(&self) -> u32 {
+        self.lock_word().load(Ordering::SeqCst)
+    }
+
+    /// Acquires the lock word for reading if the mapping is in exclusive mode, and returns whether it did. Otherwise
+    /// the caller has to take the semaphores.
+    pub fn try_read_lock(&self) -> bool {
+        let mut attempt = 0;
+        while self.is_exclusive() {
+            let registered = self
+                .lock_word()
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |word| {
+                    (word & WRITER == 0).then_some(word + 1)
+                })
+                .is_ok();
+            if registered {
+                // The mode is only switched while holding the lock word, so it is final once it is held
+                if self.is_exclusive() {
+                    return true;
+                }
+                self.read_unlock();
+                return false;
+            }
+            backoff(&mut attempt);
+        }
+        false
+    }
+
+    /// Releases the lock word acquired by [`AttachCounter::try_read_lock`].
+    pub fn read_unlock(&self) {
+        self.lock_word().fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Acquires the lock word for writing if the mapping is in exclusive mode, and returns whether it did. Otherwise
+    /// the caller has to take the semaphores.
+    pub fn try_write_lock(&self) -> bool {
+        if !self.is_exclusive() {
+            return false;
+        }
+        self.lock_exclusively();
+        if self.is_exclusive() {
+            return true;
+        }
+        self.write_unlock();
+        false
+    }
+
+    /// Releases the lock word acquired by [`AttachCounter::try_write_lock`].
+    pub fn write_unlock(&self) {
+        self.lock_word().store(0, Ordering::SeqCst);
+    }
+
+    /// Sets the writer bit of the lock word once no other writer holds it, which keeps new readers out, and waits
+    /// for the readers to release it.
+    fn lock_exclusively(&self) {
+        let mut attempt = 0;
+        while self
+            .lock_word()
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |word| {
+                (word & WRITER == 0).then_some(word | WRITER)
+            })
+            .is_err()
+        {
+            backoff(&mut attempt);
+        }
+        let mut attempt = 0;
+        while self.lock_word().load(Ordering::SeqCst) != WRITER {
+            backoff(&mut attempt);
+        }
+    }
+
+    /// Sets whether the counter is removed when dropped, e.g. to hand over its removal to another process.
+    pub fn set_remove_on_drop(&mut self, remove_on_drop: bool) {
+        self.segment.set_remove_on_drop(remove_on_drop);
+    }
+
+    /// Retrieves the name of the counter's shared memory object.
+    pub fn name(&self) -> &str {
+        self.segment.name()
+    }
+}
+
+impl Drop for AttachCounter {
+    /// Detaches the process when its last handle is dropped.
+    fn drop(&mut self) {
+        if !self.attached {
+            return;
+        }
+        let mut local_handles = LOCAL_HANDLES.lock().unwrap();
+        if let Some(handles) = local_handles.get_mut(self.name()) {
+            *handles -= 1;
+            if *handles == 0 {
+                local_handles.remove(self.name());
+                self.process_count().fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+    }
+}
+
+/// Waits before the next attempt to take a contended lock word, yielding at first and then sleeping briefly.
+fn backoff(attempt: &mut u32) {
+    *attempt += 1;
+    match *attempt < SPINS_BEFORE_SLEEP {
+        true => thread::yield_now(),
+        false => thread::sleep(Duration::from_micros(100)),
+    }
+}
@@ -0,0 +1,140 @@
+use super::shared_segment::SharedSegment;
+#[cfg(target_os = "linux")]
+use std::io;
+use std::{
+    mem::size_of,
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+/// Number of 4-byte words of the object: the sequence number and the number of waiters.
+const EVENT_WORDS: usize = 2;
+
+/// Longest a waiter sleeps at once where waiters can't be woken (without `futex(2)`), so that it still notices
+/// changes soon.
+#[cfg(not(target_os = "linux"))]
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Cross-process event in a named shared memory object, on which processes block until another process changed
+/// the data it belongs to instead of polling the data. Every [`SharedEvent::notify`] increments a sequence number
+/// and wakes all processes waiting for it to change from the value they last saw (using Linux's `futex(2)`), so that
+/// a notification between reading the sequence number and waiting is never missed.
+#[derive(Debug)]
+pub struct SharedEvent {
+    segment: SharedSegment,
+}
+
+impl SharedEvent {
+    /// Creates a new named event with sequence number 0. If an object with the name is left by a process which died
+    /// before removing it, it is taken over.
+    pub fn create(name: &str) -> Result<Self, String> {
+        let object_len = size_of::<AtomicU32>() * EVENT_WORDS;
+        let mut segment = SharedSegment::create(name, object_len).or_else(|_| {
+            SharedSegment::open(name).map(|mut segment| {
+                segment.set_remove_on_drop(true);
+                segment
+            })
+        })?;
+        segment.grow(object_len)?;
+        Ok(SharedEvent { segment })
+    }
+
+    /// Opens an existing named event.
+    pub fn open(name: &str) -> Result<Self, String> {
+        let segment = SharedSegment::open(name)?;
+        if segment.len() < size_of::<AtomicU32>() * EVENT_WORDS {
+            return Err(format!("Event {} is not sized yet", name));
+        }
+        Ok(SharedEvent { segment })
+    }
+
+    /// Get the 4-byte word at `index` of the object.
+    fn word(&self, index: usize) -> &AtomicU32 {
+        // SAFETY: the mapping is page aligned and holds `EVENT_WORDS` words, and shared memory is only ever accessed
+        // atomically.
+        unsafe { &*(self.segment.as_slice().as_ptr() as *const AtomicU32).add(index) }
+    }
+
+    fn sequence_word(&self) -> &AtomicU32 {
+        self.word(0)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn waiter_count(&self) -> &AtomicU32 {
+        self.word(1)
+    }
+
+    /// Current sequence number, to be read before the data it belongs to and passed to [`SharedEvent::wait`].
+    pub fn sequence(&self) -> u32 {
+        self.sequence_word().load(Ordering::Acquire)
+    }
+
+    /// Increments the sequence number and wakes all waiters. The wake-up system call is skipped if no process waits.
+    pub fn notify(&self) {
+        self.sequence_word().fetch_add(1, Ordering::AcqRel);
+        #[cfg(target_os = "linux")]
+        if self.waiter_count().load(Ordering::Acquire) > 0 {
+            unsafe {
+                libc::syscall(
+                    libc::SYS_futex,
+                    self.sequence_word().as_ptr(),
+                    libc::FUTEX_WAKE,
+                    i32::MAX,
+                )
+            };
+        }
+    }
+
+    /// Blocks until the sequence number differs from `seen` or `timeout` passed, and returns whether it changed.
+    /// Waking spuriously or on signals returns early, callers recheck what they wait for anyway.
+    #[cfg(target_os = "linux")]
+    pub fn wait(&self, seen: u32, timeout: Duration) -> io::Result<bool> {
+        self.waiter_count().fetch_add(1, Ordering::AcqRel);
+        let timeout = libc::timespec {
+            tv_sec: timeout.as_secs() as libc::time_t,
+            tv_nsec: timeout.subsec_nanos() as libc::c_long,
+        };
+        // Sleeps only if the sequence number is still `seen`
+        let result = unsafe {
+            libc::syscall(
+                libc::SYS_futex,
+                self.sequence_word().as_ptr(),
+                libc::FUTEX_WAIT,
+                seen,
+                &timeout as *const libc::timespec,
+            )
+        };
+        let error = io::Error::last_os_error();
+        self.waiter_count().fetch_sub(1, Ordering::AcqRel);
+        if result == -1
+            && !matches!(
+                error.raw_os_error(),
+                Some(libc::EAGAIN | libc::EINTR | libc::ETIMEDOUT)
+            )
+        {
+            return Err(error);
+        }
+        Ok(self.sequence() != seen)
+    }
+
+    /// Blocks until the sequence number differs from `seen` or `timeout` passed, and returns whether it changed.
+    /// Without `futex(2)` the sequence number is polled.
+    #[cfg(not(target_os = "linux"))]
+    pub fn wait(&self, seen: u32, timeout: Duration) -> std::io::Result<bool> {
+        let started = std::time::Instant::now();
+        while self.sequence() == seen && started.elapsed() < timeout {
+            std::thread::sleep(POLL_INTERVAL.min(timeout.saturating_sub(started.elapsed())));
+        }
+        Ok(self.sequence() != seen)
+    }
+
+    /// Sets whether the event is removed when dropped, e.g. to hand over its removal to another process.
+    pub fn set_remove_on_drop(&mut self, remove_on_drop: bool) {
+        self.segment.set_remove_on_drop(remove_on_drop);
+    }
+
+    /// Retrieves the name of the event's shared memory object.
+    pub fn name(&self) -> &str {
+        self.segment.name()
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    fs::{File, OpenOptions},
+    io,
+    os::fd::AsRawFd,
+    path::PathBuf,
+    ptr,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+/// Size of the shared memory file holding the semaphore's value.
+const FUTEX_FILE_LEN: u64 = std::mem::size_of::<AtomicU32>() as u64;
+
+/// Semaphore whose value is an atomic in a shared memory file (`/dev/shm/<name>.futex`) on which waiters sleep
+/// using Linux's `futex(2)`. Unlike named POSIX semaphores it neither counts against `sem_open` limits nor needs
+/// a libc implementation of process-shared semaphores.
+#[derive(Debug)]
+pub(crate) struct FutexSemaphore {
+    value: *const AtomicU32,
+    path: PathBuf,
+}
+
+impl FutexSemaphore {
+    /// Path of the shared memory file of the semaphore called `name`.
+    pub(crate) fn path(name: &str) -> PathBuf {
+        PathBuf::from(format!("/dev/shm/{}.futex", name.trim_start_matches('/')))
+    }
+
+    /// Creates the semaphore called `name` with `initial_value`, failing with `EEXIST` if it already exists.
+    pub(crate) fn create(name: &str, initial_value: u32) -> io::Result<Self> {
+        // The file is initialized under a temporary name and then linked to its name, which fails if it exists,
+        // so that other processes never open an uninitialized semaphore
+        let path = FutexSemaphore::path(name);
+        let temporary_path = path.with_extension(format!("futex.{}", std::process::id()));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&temporary_path)?;
+        let result = file
+            .set_len(FUTEX_FILE_LEN)
+            .and_then(|()| FutexSemaphore::map(&file, path.clone()))
+            .and_then(|semaphore| {
+                semaphore.atomic().store(initial_value, Ordering::SeqCst);
+                std::fs::hard_link(&temporary_path, &path)?;
+                Ok(semaphore)
+            });
+        std::fs::remove_file(&temporary_path)?;
+        result
+    }
+
+    /// Opens the existing semaphore called `name`.
+    pub(crate) fn open(name: &str) -> io::Result<Self> {
+        let path = FutexSemaphore::path(name);
+        let file = OpenOptions::new().read(true).write(true).open(&path)?;
+        if file.metadata()?.len() < FUTEX_FILE_LEN {
+            return Err(io::Error::from_raw_os_error(libc::EINVAL));
+        }
+        FutexSemaphore::map(&file, path)
+    }
+
+    /// Maps the semaphore's value from `file` into memory.
+    fn map(file: &File, path: PathBuf) -> io::Result<Self> {
+        let value = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                FUTEX_FILE_LEN as usize,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if value == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(FutexSemaphore {
+            value: value as *const AtomicU32,
+            path,
+        })
+    }
+
+    fn atomic(&self) -> &AtomicU32 {
+        unsafe { &*self.value }
+    }
+
+    /// Decrements the value if it is greater than 0 and returns whether it did.
+    pub(crate) fn try_wait(&self) -> bool {
+        self.atomic()
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |value| {
+                value.checked_sub(1)
+            })
+            .is_ok()
+    }
+
+    /// Decrements the value, sleeping while it is 0.
+    pub(crate) fn wait(&self) -> io::Result<()> {
+        while !self.try_wait() {
+            // Sleeps only if the value is still 0, waking spuriously or on signals is handled by retrying
+            let result = unsafe {
+                libc::syscall(
+                    libc::SYS_futex,
+                    self.value,
+                    libc::FUTEX_WAIT,
+                    0u32,
+                    ptr::null::<libc::timespec>(),
+                )
+            };
+            if result == -1 {
+                let error = io::Error::last_os_error();
+                if !matches!(error.raw_os_error(), Some(libc::EAGAIN | libc::EINTR)) {
+                    return Err(error);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Increments the value and wakes a waiter, failing with `EOVERFLOW` like `sem_post(3)` if the value would
+    /// exceed `SEM_VALUE_MAX`.
+    pub(crate) fn post(&self) -> io::Result<()> {
+        self.atomic()
+            .fetch_update(Ordering::Release, Ordering::Relaxed, |value| {
+                value
+                    .checked_add(1)
+                    .filter(|value| *value <= i32::MAX as u32)
+            })
+            .map_err(|_| io::Error::from_raw_os_error(libc::EOVERFLOW))?;
+        if unsafe { libc::syscall(libc::SYS_futex, self.value, libc::FUTEX_WAKE, 1) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Current value.
+    pub(crate) fn value(&self) -> u32 {
+        self.atomic().load(Ordering::Relaxed)
+    }
+
+    /// Removes the semaphore's shared memory file, processes which opened it can still use it.
+    pub(crate) fn remove(&self) -> io::Result<()> {
+        std::fs::remove_file(&self.path)
+    }
+}
+
+impl Drop for FutexSemaphore {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.value as *mut libc::c_void, FUTEX_FILE_LEN as usize) };
+    }
+}
@@ -0,0 +1,214 @@
+use super::{semaphore::Semaphore, shared_counter::SharedCounter};
+use std::{
+    collections::VecDeque,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    thread::{self, ThreadId},
+    time::{Duration, Instant},
+};
+
+/// Number of the most recent [`LockHold`]s kept for [`records`] and the diagnostic dump of a violation.
+const RECORD_CAPACITY: usize = 64;
+
+/// Whether this process audits its use of the locks of shared memory mappings, see [`set_enabled`].
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// Write locks held by the threads of this process and the most recent holds.
+static STATE: Mutex<AuditState> = Mutex::new(AuditState {
+    held: Vec::new(),
+    records: VecDeque::new(),
+});
+
+/// State of the lock audit of this process.
+struct AuditState {
+    /// Name of the `write_lock` semaphore, thread and acquisition time of every write lock held by this process.
+    /// Tracked even while the audit is disabled, so that enabling it doesn't flag locks acquired before.
+    held: Vec<(String, ThreadId, Instant)>,
+    /// Most recent write lock holds, at most [`RECORD_CAPACITY`].
+    records: VecDeque<LockHold>,
+}
+
+/// Write lock of a shared memory mapping held by a process, recorded by the lock audit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LockHold {
+    /// Name of the mapping's `write_lock` semaphore.
+    pub name: String,
+    /// Process which held the lock.
+    pub pid: u32,
+    /// Time for which the lock was held.
+    pub held_for: Duration,
+}
+
+/// Enables or disables the lock audit of this process, a debug mode validating the reader-writer lock of shared
+/// memory mappings (see [`super::rwlock`]) under real workloads. While enabled, every write lock is recorded with
+/// the writer's pid and how long it was held (see [`records`]), and the lock's invariants are asserted:
+///
+/// - no thread waits for a lock it already holds for writing,
+/// - a writer holds the `write_lock` semaphore alone (its value is 0) and no reader is registered while it writes,
+/// - every `post` of the semaphore releasing a write lock is balanced by the `wait` acquiring it.
+///
+/// On a violation, a diagnostic dump of the lock's state and the recent holds is printed and the process aborts,
+/// so that a broken lock is noticed before it corrupts a run.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether the lock audit of this process is enabled, see [`set_enabled`].
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Returns the most recent write lock holds of this process recorded while the lock audit was enabled, oldest
+/// first.
+pub fn records() -> Vec<LockHold> {
+    STATE.lock().unwrap().records.iter().cloned().collect()
+}
+
+/// Checks that the calling thread does not hold `write_lock` for writing before waiting for it, which would never
+/// return.
+pub(crate) fn before_wait(write_lock: &Semaphore, read_count: &SharedCounter) {
+    let is_held =
+        STATE.lock().unwrap().held.iter().any(|(name, thread, _)| {
+            name == write_lock.name() && *thread == thread::current().id()
+        });
+    if is_held {
+        violation(
+            "thread waits for a lock it already holds for writing",
+            write_lock,
+            read_count,
+        );
+    }
+}
+
+/// Checks that a writer which just acquired `write_lock` holds it alone and no reader is registered, and tracks
+/// the hold.
+pub(crate) fn after_write_lock(write_lock: &Semaphore, read_count: &SharedCounter) {
+    check_exclusive(write_lock, read_count);
+    STATE.lock().unwrap().held.push((
+        write_lock.name().to_string(),
+        thread::current().id(),
+        Instant::now(),
+    ));
+}
+
+/// Checks that a reader which just waited for `write_lock` to register holds it alone.
+pub(crate) fn after_read_wait(write_lock: &Semaphore, read_count: &SharedCounter) {
+    if is_enabled() && write_lock.get_value().is_ok_and(|value| value != 0) {
+        violation(
+            "write_lock is not held exclusively by a registering reader",
+            write_lock,
+            read_count,
+        );
+    }
+}
+
+/// Checks that the calling thread holds `write_lock` for writing (so that releasing it is balanced) alone and no
+/// reader registered meanwhile, and records the hold.
+pub(crate) fn before_write_unlock(write_lock: &Semaphore, read_count: &SharedCounter) {
+    let mut state = STATE.lock().unwrap();
+    let position = state.held.iter().rposition(|(name, thread, _)| {
+        name == write_lock.name() && *thread == thread::current().id()
+    });
+    let Some(position) = position else {
+        drop(state);
+        if is_enabled() {
+            violation(
+                "write_lock is posted without being held for writing",
+                write_lock,
+                read_count,
+            );
+        }
+        return;
+    };
+    let (name, _, acquired) = state.held.remove(position);
+    if is_enabled() {
+        if state.records.len() == RECORD_CAPACITY {
+            state.records.pop_front();
+        }
+        state.records.push_back(LockHold {
+            name,
+            pid: std::process::id(),
+            held_for: acquired.elapsed(),
+        });
+    }
+    drop(state);
+    check_exclusive(write_lock, read_count);
+}
+
+/// Checks that the writer holding `write_lock` holds it alone and no reader is registered.
+fn check_exclusive(write_lock: &Semaphore, read_count: &SharedCounter) {
+    if !is_enabled() {
+        return;
+    }
+    if write_lock.get_value().is_ok_and(|value| value != 0) {
+        violation(
+            "write_lock is not held exclusively by its writer",
+            write_lock,
+            read_count,
+        );
+    }
+    if read_count.get_value() > 0 {
+        violation(
+            "reader is registered while a writer holds the lock",
+            write_lock,
+            read_count,
+        );
+    }
+}
+
+/// Prints the diagnostic dump of the violated invariant and aborts the process.
+fn violation(invariant: &str, write_lock: &Semaphore, read_count: &SharedCounter) -> ! {
+    eprintln!("{}", diagnostic(invariant, write_lock, read_count));
+    std::process::abort()
+}
+
+/// Diagnostic dump of a violated lock `invariant`: the state of the lock, the write locks held by this process and
+/// the most recent holds.
+pub(crate) fn diagnostic(
+    invariant: &str,
+    write_lock: &Semaphore,
+    read_count: &SharedCounter,
+) -> String {
+    let mut dump = format!(
+        "Lock audit violation in pid {} ({:?}): {}.\n",
+        std::process::id(),
+        thread::current().id(),
+        invariant
+    );
+    let _ = writeln!(
+        dump,
+        "  {}: {}",
+        write_lock.name(),
+        match write_lock.get_value() {
+            Ok(value) => format!("value {}", value),
+            Err(e) => e,
+        }
+    );
+    let _ = writeln!(
+        dump,
+        "  {}: value {}",
+        read_count.name(),
+        read_count.get_value()
+    );
+    // The audit state may be poisoned by the thread which found the violation
+    let state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    for (name, thread, acquired) in &state.held {
+        let _ = writeln!(
+            dump,
+            "  held: {} by {:?} for {:?}",
+            name,
+            thread,
+            acquired.elapsed()
+        );
+    }
+    for hold in &state.records {
+        let _ = writeln!(
+            dump,
+            "  recent: {} by pid {} for {:?}",
+            hold.name, hold.pid, hold.held_for
+        );
+    }
+    dump
+}
@@ -0,0 +1,732 @@
+use super::{
+    attach_counter::AttachCounter,
+    event::SharedEvent,
+    lock_audit, rwlock,
+    semaphore::Semaphore,
+    shared_counter::{SharedCounter, MAX_READER_COUNT},
+    shared_segment::SharedSegment,
+    status_slots::SharedStatusSlots,
+};
+use anyhow::{anyhow, Result};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Time [`PosixSharedMemory::create_or_open`] keeps retrying to open a mapping which another process is still
+/// creating, i.e. whose semaphores or initial data are not complete yet.
+const OPEN_WHILE_CREATING_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Number of pages by which the data segment grows at least, so that growing data doesn't remap it on every write.
+const SEGMENT_GROWTH_PAGES: usize = 16;
+
+/// Number of bytes in front of the data in the data segment, holding the total length of both in big endian.
+const LENGTH_PREFIX_LEN: usize = usize::BITS as usize / 8;
+
+/// Longest sleep of [`PosixSharedMemory::wait_for_change`] on mappings without a change event.
+const CHANGE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Longest file name allowed in `/dev/shm`.
+const NAME_MAX: usize = 255;
+
+pub struct PosixSharedMemory {
+    /// Suffix of all shared memory objects in `/dev/shm`
+    filename_suffix: String,
+    /// Write lock, 1: no current writer, 0: currently active writer
+    write_lock: Semaphore,
+    /// Number of current readers
+    read_count: SharedCounter,
+    /// Segment holding the length prefix followed by the serialized data. It never shrinks, since other handles
+    /// may still have it mapped, but the memory beyond shrunk data is released, see [`SharedSegment::release`].
+    data_segment: SharedSegment,
+    /// Optional cap on the shared memory bytes allocated for `data_segment`; exceeding writes fail before the segment is touched
+    max_allocated_bytes: Option<usize>,
+    /// Highest number of bytes of `data_segment` seen allocated, see [`PosixSharedMemory::allocated_bytes`]
+    peak_allocated_bytes: usize,
+    /// Optional observer of all writes of this mapping, see [`WriteObserver`]
+    write_observer: Option<WriteObserver>,
+    /// Whether `data_segment` is locked into RAM, see [`PosixSharedMemory::lock_in_memory`]
+    locked_in_memory: bool,
+    /// Number of bytes of the current mapping of `data_segment` locked into RAM
+    locked_len: usize,
+    /// Optional statuses changed atomically next to the data, see [`PosixSharedMemory::compare_exchange_status_slot`]
+    status_slots: Option<SharedStatusSlots>,
+    /// Optional event notified on every change of the data or the status slots, see [`PosixSharedMemory::wait_for_change`]
+    change_event: Option<SharedEvent>,
+    /// Counter of the processes attached to the mapping, with which a single process skips the semaphores, see
+    /// [`AttachCounter`]
+    attach_counter: AttachCounter,
+    /// Whether this handle holds the lock word of `attach_counter` instead of the semaphores
+    holds_attach_lock: bool,
+}
+
+/// Called with the serialized data in shared memory before and after every write of a [`PosixSharedMemory`] (while
+/// still holding its write lock), e.g. to audit changes. An error fails the write after the data was written.
+pub type WriteObserver = Box<dyn Fn(&[u8], &[u8]) -> Result<()> + Send>;
+
+/// Error of a write which would allocate more shared memory than the cap of its [`PosixSharedMemory`], see
+/// [`PosixSharedMemory::set_max_allocated_bytes`]. It is returned before the data segment is touched, so that callers can
+/// find it with [`anyhow::Error::downcast_ref`] and degrade gracefully instead of allocations failing mid-write.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SharedMemoryFull {
+    /// Number of bytes of the data which was to be written.
+    pub data_len: usize,
+    /// Number of shared memory bytes the data requires.
+    pub required_bytes: usize,
+    /// Cap on the shared memory bytes of the mapping.
+    pub max_allocated_bytes: usize,
+}
+
+impl std::fmt::Display for SharedMemoryFull {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Writing {} bytes of data requires {} bytes of shared memory, exceeding the cap of {} bytes.",
+            self.data_len, self.required_bytes, self.max_allocated_bytes
+        )
+    }
+}
+
+impl std::error::Error for SharedMemoryFull {}
+
+impl std::fmt::Debug for PosixSharedMemory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "PosixSharedMemory: {{filename_suffix: {:?}, write_lock: {:?}, read_count: {:?}, data_segment: {:?}}}",
+            self.filename_suffix, self.write_lock, self.read_count, self.data_segment
+        )
+    }
+}
+
+/// Checks that the names of all shared memory objects of the mapping are valid file names, the longest being that
+/// of a futex write lock while it is created (see [`super::futex`]).
+fn check_filename_suffix(filename_suffix: &str) -> Result<()> {
+    let longest_name = format!("{}_write_lock.futex.{}", filename_suffix, u32::MAX);
+    if longest_name.len() > NAME_MAX || filename_suffix.contains('\0') {
+        return Err(anyhow!(
+            "Filename suffix {} is too long or contains invalid characters.",
+            filename_suffix
+        ));
+    }
+    Ok(())
+}
+
+/// Size of a page, the unit in which shared memory objects in `/dev/shm` are allocated.
+fn page_size() -> usize {
+    match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+        page_size if page_size > 0 => page_size as usize,
+        _ => 4096,
+    }
+}
+
+impl PosixSharedMemory {
+    /// Create new mapping of `data` with filename_suffix.
+    pub fn new(filename_suffix: &str, data: impl serde::Serialize) -> Result<Self> {
+        PosixSharedMemory::new_with_max_allocated_bytes(filename_suffix, data, None)
+    }
+
+    /// Create new mapping like [`PosixSharedMemory::new`], failing every write which would allocate more
+    /// than `max_allocated_bytes` of shared memory.
+    pub fn new_with_max_allocated_bytes(
+        filename_suffix: &str,
+        data: impl serde::Serialize,
+        max_allocated_bytes: Option<usize>,
+    ) -> Result<Self> {
+        PosixSharedMemory::create(filename_suffix, data, max_allocated_bytes, None)
+    }
+
+    /// Create new mapping like [`PosixSharedMemory::new_with_max_allocated_bytes`], with `status_slots` holding
+    /// the initial statuses and a change event if [`Some`]. Both are created before the initial write of `data`, so
+    /// that processes reading the data also find them.
+    fn create(
+        filename_suffix: &str,
+        data: impl serde::Serialize,
+        max_allocated_bytes: Option<usize>,
+        status_slots: Option<&[u8]>,
+    ) -> Result<Self> {
+        let filename_suffix = filename_suffix.replace("/", "_"); // Handle slash in filename
+        check_filename_suffix(&filename_suffix)?;
+
+        // Create RwLock, construct shared memory mapping
+        let write_lock = Semaphore::create(&format!("/{}_write_lock", filename_suffix), 1)
+            .map_err(|e| anyhow!("Failed to create write_lock: {}", e))?;
+        let read_count = SharedCounter::create(
+            &format!("/{}_read_count", filename_suffix),
+            MAX_READER_COUNT,
+        )
+        .map_err(|e| anyhow!("Failed to create read_count: {}", e))?;
+        // A segment left by a process which died before removing it is taken over and overwritten
+        let data_segment_name = format!("/{}_data", filename_suffix);
+        let data_segment = SharedSegment::create(&data_segment_name, 0)
+            .or_else(|_| {
+                SharedSegment::open(&data_segment_name).map(|mut data_segment| {
+                    data_segment.set_remove_on_drop(true);
+                    data_segment
+                })
+            })
+            .map_err(|e| anyhow!("Failed to create data segment: {}", e))?;
+        let status_slots = status_slots
+            .map(|statuses| {
+                SharedStatusSlots::create(&format!("/{}_statuses", filename_suffix), statuses)
+            })
+            .transpose()
+            .map_err(|e| anyhow!("Failed to create status slots: {}", e))?;
+        let change_event = status_slots
+            .as_ref()
+            .map(|_| SharedEvent::create(&format!("/{}_events", filename_suffix)))
+            .transpose()
+            .map_err(|e| anyhow!("Failed to create change event: {}", e))?;
+        let mut attach_counter = AttachCounter::create(&format!("/{}_attach", filename_suffix))
+            .map_err(|e| anyhow!("Failed to create attach counter: {}", e))?;
+        attach_counter.attach(!lock_audit::is_enabled());
+
+        let mut shm_mapping = PosixSharedMemory {
+            filename_suffix,
+            write_lock,
+            read_count,
+            data_segment,
+            max_allocated_bytes,
+            peak_allocated_bytes: 0,
+            write_observer: None,
+            locked_in_memory: false,
+            locked_len: 0,
+            status_slots,
+            change_event,
+            attach_counter,
+            holds_attach_lock: false,
+        };
+
+        // Initial write of data to shared memory
+        shm_mapping.write(&data)?;
+
+        Ok(shm_mapping)
+    }
+
+    /// Create new mapping holding `data` like [`PosixSharedMemory::new_with_max_allocated_bytes`] or,
+    /// if another process already created it, open it like [`PosixSharedMemory::open`] and return the data in shared memory.
+    pub fn create_or_open<T: serde::Serialize + serde::de::DeserializeOwned + Clone>(
+        filename_suffix: &str,
+        data: &T,
+        max_allocated_bytes: Option<usize>,
+    ) -> Result<(Self, T)> {
+        PosixSharedMemory::create_or_open_with_status_slots(
+            filename_suffix,
+            data,
+            max_allocated_bytes,
+            None,
+        )
+    }
+
+    /// Like [`PosixSharedMemory::create_or_open`], creating the mapping with `status_slots` holding the initial
+    /// statuses if [`Some`]. An opened mapping keeps the slots its creator created, if any.
+    pub fn create_or_open_with_status_slots<
+        T: serde::Serialize + serde::de::DeserializeOwned + Clone,
+    >(
+        filename_suffix: &str,
+        data: &T,
+        max_allocated_bytes: Option<usize>,
+        status_slots: Option<&[u8]>,
+    ) -> Result<(Self, T)> {
+        match PosixSharedMemory::create(filename_suffix, data, max_allocated_bytes, status_slots) {
+            Ok(shm_mapping) => Ok((shm_mapping, data.clone())),
+            Err(e) if e.to_string() == format!(
+                "Failed to create write_lock: Failed to create semaphore /{}_write_lock: File exists (errno: 17)",
+                filename_suffix.replace("/", "_")
+            ) => {
+                let started = Instant::now();
+                let (mut shm_mapping, data) = loop {
+                    match PosixSharedMemory::open::<T>(filename_suffix) {
+                        Ok(opened) => break opened,
+                        Err(_) if started.elapsed() < OPEN_WHILE_CREATING_TIMEOUT => {
+                            thread::sleep(Duration::from_millis(1))
+                        }
+                        Err(e) => return Err(e),
+                    }
+                };
+                shm_mapping.set_max_allocated_bytes(max_allocated_bytes);
+                Ok((shm_mapping, data))
+            }
+            // Callers handle a full mapping by its type
+            Err(e) if e.is::<SharedMemoryFull>() => Err(e),
+            Err(e) => Err(anyhow!("Failed to create shared memory {}: {}", filename_suffix, e)),
+        }
+    }
+
+    /// Create mapping from the shared memory objects with filename_suffix that already exist in shared memory.
+    pub fn open<T: serde::de::DeserializeOwned>(filename_suffix: &str) -> Result<(Self, T)> {
+        let filename_suffix = filename_suffix.replace("/", "_"); // Handle slash in filename
+        check_filename_suffix(&filename_suffix)?;
+
+        // Read semaphores from shared memory, construct shared memory mapping
+        let write_lock = Semaphore::open(&format!("/{}_write_lock", filename_suffix))
+            .map_err(|e| anyhow!("Failed to open write_lock: {}", e))?;
+        let read_count = SharedCounter::open(
+            &format!("/{}_read_count", filename_suffix),
+            MAX_READER_COUNT,
+        )
+        .map_err(|e| anyhow!("Failed to open read_count: {}", e))?;
+        let data_segment = SharedSegment::open(&format!("/{}_data", filename_suffix))
+            .map_err(|e| anyhow!("Failed to open data segment: {}", e))?;
+        // Attaching a second process switches the mapping's creator from the attach counter to the semaphores
+        let mut attach_counter = AttachCounter::open(&format!("/{}_attach", filename_suffix))
+            .map_err(|e| anyhow!("Failed to open attach counter: {}", e))?;
+        attach_counter.attach(!lock_audit::is_enabled());
+
+        let mut shm_mapping = PosixSharedMemory {
+            filename_suffix,
+            write_lock,
+            read_count,
+            data_segment,
+            max_allocated_bytes: None,
+            peak_allocated_bytes: 0,
+            write_observer: None,
+            locked_in_memory: false,
+            locked_len: 0,
+            status_slots: None,
+            change_event: None,
+            attach_counter,
+            holds_attach_lock: false,
+        };
+
+        // Acquire read lock
+        shm_mapping.read_lock()?;
+
+        // Read data bytes from shared memory, releasing the lock on errors as well
+        let data_bytes = shm_mapping.read_from_shm();
+
+        // Release read lock
+        shm_mapping.read_unlock()?;
+        let data_bytes = data_bytes?;
+
+        // Status slots are created before the data is first written, so they exist now if the mapping has any
+        shm_mapping.status_slots =
+            SharedStatusSlots::open(&format!("/{}_statuses", shm_mapping.filename_suffix)).ok();
+        shm_mapping.change_event =
+            SharedEvent::open(&format!("/{}_events", shm_mapping.filename_suffix)).ok();
+
+        // Deserialize and return data
+        let data = rmp_serde::from_slice::<T>(&data_bytes)?;
+        Ok((shm_mapping, data))
+    }
+
+    /// Acquire read lock, read the serialized data from the data segment and deserialize it.
+    pub fn read<T: serde::de::DeserializeOwned>(&mut self) -> Result<T> {
+        // Acquire read lock
+        self.read_lock()?;
+
+        // Read data from shared memory, releasing the lock on errors as well (e.g. if the data is not written yet)
+        let data_bytes = self.read_from_shm();
+
+        // Release read lock
+        self.read_unlock()?;
+        let data_bytes = data_bytes?;
+
+        // Return deserialized data
+        let data = rmp_serde::from_slice::<T>(data_bytes.as_slice())?;
+        Ok(data)
+    }
+
+    /// Acquire write lock and write `data` to shared memory.
+    /// The data segment grows if the data requires more space than currently allocated, but never shrinks.
+    pub fn write<T: serde::Serialize>(&mut self, data: &T) -> Result<()> {
+        // Acquire write lock
+        self.write_lock()?;
+
+        // Initialize data for write, releasing the lock on errors as well
+        let result = self.write_to_shm(data);
+
+        // Release write lock
+        self.write_unlock()?;
+
+        result
+    }
+
+    /// Acquire write lock, write `data_write` to shared memory if `data_condition` is equal to current data in shared memory.
+    /// If `data_condition` is not equal to the data in shared memory, then return the data in shared memory.
+    pub fn shm_compare_data_and_swap<
+        T: serde::Serialize + serde::de::DeserializeOwned + PartialEq,
+    >(
+        &mut self,
+        data_equal_to_shm: &T,
+        data_write: &T,
+    ) -> Result<Option<T>> {
+        // Acquire exclusive (write) lock
+        self.write_lock()?;
+
+        // Write data to shared memory if `data_condition` is equal to current state of data in shared memory
+        let data_bytes = self.read_from_shm()?;
+        let data_in_shm = rmp_serde::from_slice::<T>(data_bytes.as_slice())?;
+        match data_in_shm == *data_equal_to_shm {
+            true => {
+                // Release write lock (on errors as well) and return None on successful write
+                let result = self.write_to_shm(data_write);
+                self.write_unlock()?;
+                result.map(|()| None)
+            }
+            false => {
+                // Release write lock and if `data_condition` no longer matches return `data_in_shm`
+                self.write_unlock()?;
+                Ok(Some(data_in_shm))
+            }
+        }
+    }
+
+    /// Acquire write lock, apply `update` to the data in shared memory, write it back and return the updated data.
+    pub fn shm_update<T: serde::Serialize + serde::de::DeserializeOwned>(
+        &mut self,
+        update: impl FnOnce(&mut T),
+    ) -> Result<T> {
+        // Acquire exclusive (write) lock
+        self.write_lock()?;
+
+        // Read, update and write data while holding the lock, releasing it on errors as well
+        let result = self.read_from_shm().and_then(|data_bytes| {
+            let mut data = rmp_serde::from_slice::<T>(data_bytes.as_slice())?;
+            update(&mut data);
+            self.write_to_shm(&data)?;
+            Ok(data)
+        });
+
+        // Release write lock
+        self.write_unlock()?;
+
+        result
+    }
+
+    /// Sets whether the data segment and semaphores of this shared memory mapping are removed when `self` is
+    /// dropped, so that a process can take over the removal of a mapping created by another process.
+    pub fn set_ownership(&mut self, owner: bool) -> Result<()> {
+        self.data_segment.set_remove_on_drop(owner);
+        self.write_lock.set_remove_on_drop(owner);
+        self.read_count.set_remove_on_drop(owner);
+        if let Some(status_slots) = &mut self.status_slots {
+            status_slots.set_remove_on_drop(owner);
+        }
+        if let Some(change_event) = &mut self.change_event {
+            change_event.set_remove_on_drop(owner);
+        }
+        self.attach_counter.set_remove_on_drop(owner);
+        Ok(())
+    }
+
+    /// Get the status slots of this mapping, if it was created with any.
+    pub fn status_slots(&self) -> Option<&SharedStatusSlots> {
+        self.status_slots.as_ref()
+    }
+
+    /// Acquire read lock and change the status in `slot` from `current` to `new` with a single `compare_exchange`
+    /// (see [`SharedStatusSlots::compare_exchange`]), so that writers of the whole data, which hold the write lock,
+    /// never race with it. Returns whether the status was changed, or [`None`] if the change must be made by writing
+    /// the data instead: if the mapping has no status slots or a write observer, which only sees written data.
+    pub fn compare_exchange_status_slot(
+        &mut self,
+        slot: usize,
+        current: u8,
+        new: u8,
+    ) -> Result<Option<bool>> {
+        if self.status_slots.is_none() || self.write_observer.is_some() {
+            return Ok(None);
+        }
+        self.read_lock()?;
+        let exchanged = self
+            .status_slots
+            .as_ref()
+            .map(|status_slots| status_slots.compare_exchange(slot, current, new).is_ok());
+        self.read_unlock()?;
+        if let (Some(true), Some(change_event)) = (exchanged, &self.change_event) {
+            change_event.notify();
+        }
+        Ok(exchanged)
+    }
+
+    /// Like [`PosixSharedMemory::compare_exchange_status_slot`], but only keeps the change if `is_allowed` holds for
+    /// the slots changed to `new` before it, see [`SharedStatusSlots::compare_exchange_if`].
+    pub fn compare_exchange_status_slot_if(
+        &mut self,
+        slot: usize,
+        current: u8,
+        new: u8,
+        is_allowed: impl FnOnce(&[usize]) -> bool,
+    ) -> Result<Option<bool>> {
+        if self.status_slots.is_none() || self.write_observer.is_some() {
+            return Ok(None);
+        }
+        self.read_lock()?;
+        let exchanged = self
+            .status_slots
+            .as_ref()
+            .map(|status_slots| status_slots.compare_exchange_if(slot, current, new, is_allowed));
+        self.read_unlock()?;
+        // A change which was not kept is stamped as well, so that waiters see it
+        if let (Some(Ok(_)), Some(change_event)) = (exchanged, &self.change_event) {
+            change_event.notify();
+        }
+        Ok(exchanged.map(|exchanged| exchanged.unwrap_or(false)))
+    }
+
+    /// Acquire read lock and set the enqueue time of every one of `slots` without one to `after` (see
+    /// [`SharedStatusSlots::enqueue`]), like [`PosixSharedMemory::compare_exchange_status_slot`]. Returns whether the
+    /// times were set, `false` if they must be set by writing the data instead.
+    pub fn enqueue_status_slots(
+        &mut self,
+        slots: impl Iterator<Item = usize>,
+        after: Duration,
+    ) -> Result<bool> {
+        if self.status_slots.is_none() || self.write_observer.is_some() {
+            return Ok(false);
+        }
+        self.read_lock()?;
+        if let Some(status_slots) = &self.status_slots {
+            for slot in slots {
+                status_slots.enqueue(slot, after);
+            }
+        }
+        self.read_unlock()?;
+        Ok(true)
+    }
+
+    /// Get the sequence number of the change event of this mapping, to be read before the data and passed to
+    /// [`PosixSharedMemory::wait_for_change`]. It is 0 if the mapping has no change event.
+    pub fn change_sequence(&self) -> u32 {
+        self.change_event
+            .as_ref()
+            .map_or(0, |change_event| change_event.sequence())
+    }
+
+    /// Blocks until the data or status slots changed since the change sequence number was `seen` (see
+    /// [`PosixSharedMemory::change_sequence`]), or `timeout` passed. Mappings without a change event, e.g. created by
+    /// a process of an older version, sleep for at most [`CHANGE_POLL_INTERVAL`] instead, so that callers keep polling.
+    pub fn wait_for_change(&self, seen: u32, timeout: Duration) -> Result<()> {
+        match &self.change_event {
+            Some(change_event) => {
+                change_event
+                    .wait(seen, timeout)
+                    .map_err(|e| anyhow!("Failed to wait for change event: {}", e))?;
+            }
+            None => thread::sleep(timeout.min(CHANGE_POLL_INTERVAL)),
+        }
+        Ok(())
+    }
+
+    /// Set the observer called after every write of this mapping, see [`WriteObserver`].
+    pub fn set_write_observer(&mut self, write_observer: Option<WriteObserver>) {
+        self.write_observer = write_observer;
+    }
+
+    /// Set the cap on allocated shared memory bytes checked before every write, exceeding writes fail with
+    /// [`SharedMemoryFull`].
+    pub fn set_max_allocated_bytes(&mut self, max_allocated_bytes: Option<usize>) {
+        self.max_allocated_bytes = max_allocated_bytes;
+    }
+
+    /// Locks the pages of the data segment into RAM with `mlock(2)`, including those it grows by later, so that
+    /// page faults never delay reads and writes, e.g. of real-time runs. Fails (leaving the pages unlocked) if the
+    /// pages exceed `RLIMIT_MEMLOCK` or the process may not lock memory. Should the segment grow beyond it later, a
+    /// warning is printed and it is left unlocked.
+    pub fn lock_in_memory(&mut self) -> Result<()> {
+        self.locked_in_memory = true;
+        if let Err(e) = self.lock_remapped_segment() {
+            self.locked_in_memory = false;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Whether the pages of the data segment are locked into RAM, see [`PosixSharedMemory::lock_in_memory`].
+    pub fn is_locked_in_memory(&self) -> bool {
+        self.locked_in_memory
+    }
+
+    /// Locks the pages of the data segment into RAM if requested and it was remapped since the last call, since
+    /// the locks of a mapping end with it. The segment only grows, so it was remapped if its length changed.
+    fn lock_remapped_segment(&mut self) -> Result<()> {
+        if !self.locked_in_memory || self.locked_len == self.data_segment.len() {
+            return Ok(());
+        }
+        let segment = self.data_segment.as_slice();
+        // SAFETY: the slice lies within the mapping owned by `data_segment`.
+        if unsafe { libc::mlock(segment.as_ptr() as *const libc::c_void, segment.len()) } == -1 {
+            return Err(anyhow!(
+                "Failed locking shared memory {} into RAM (see RLIMIT_MEMLOCK): {}",
+                self.filename_suffix,
+                std::io::Error::last_os_error()
+            ));
+        }
+        self.locked_len = segment.len();
+        Ok(())
+    }
+
+    /// Locks the data segment remapped by a read or write into RAM if requested, falling back to an unlocked
+    /// segment once the locked pages would exceed the limit.
+    fn lock_remapped_segment_or_warn(&mut self) {
+        if let Err(e) = self.lock_remapped_segment() {
+            eprintln!("Warning: {}; leaving the grown segment unlocked.", e);
+            self.locked_in_memory = false;
+        }
+    }
+
+    /// Number of shared memory bytes currently allocated for the data segment: the whole pages holding the data
+    /// written last. Pages beyond them are released when the data shrinks.
+    pub fn allocated_bytes(&self) -> usize {
+        let total_buf_len = match self.data_segment.as_slice().get(..LENGTH_PREFIX_LEN) {
+            Some(prefix) => usize::from_be_bytes(prefix.try_into().unwrap_or_default()),
+            None => 0,
+        };
+        PosixSharedMemory::allocated_bytes_for(total_buf_len.min(self.data_segment.len()))
+    }
+
+    /// Number of shared memory bytes allocated for `data_len` bytes of (serialized) data: the data segment takes
+    /// up whole pages.
+    pub fn allocated_bytes_for(data_len: usize) -> usize {
+        data_len.div_ceil(page_size()) * page_size()
+    }
+
+    /// Highest number of shared memory bytes seen allocated for the data segment.
+    pub fn peak_allocated_bytes(&self) -> usize {
+        self.peak_allocated_bytes
+    }
+
+    /// Acquire read lock on the shared memory mapping, with the lock word of the attach counter while this is the
+    /// only process attached to the mapping.
+    pub(crate) fn read_lock(&mut self) -> Result<()> {
+        if self.attach_counter.try_read_lock() {
+            self.holds_attach_lock = true;
+            return Ok(());
+        }
+        rwlock::read_lock(&self.write_lock, &self.read_count)
+    }
+
+    /// Release read lock on the shared memory mapping.
+    pub(crate) fn read_unlock(&mut self) -> Result<()> {
+        if std::mem::take(&mut self.holds_attach_lock) {
+            self.attach_counter.read_unlock();
+            return Ok(());
+        }
+        rwlock::read_unlock(&self.read_count)
+    }
+
+    /// Acquire write lock on the shared memory mapping, with the lock word of the attach counter while this is the
+    /// only process attached to the mapping.
+    pub(crate) fn write_lock(&mut self) -> Result<()> {
+        if self.attach_counter.try_write_lock() {
+            self.holds_attach_lock = true;
+            return Ok(());
+        }
+        rwlock::write_lock(&self.write_lock, &self.read_count)
+    }
+
+    /// Release write lock on the shared memory mapping.
+    pub(crate) fn write_unlock(&mut self) -> Result<()> {
+        if std::mem::take(&mut self.holds_attach_lock) {
+            self.attach_counter.write_unlock();
+            return Ok(());
+        }
+        rwlock::write_unlock(&self.write_lock, &self.read_count)
+    }
+
+    /// Checks whether this is the only process attached to the mapping, which then locks it without its
+    /// semaphores, see [`AttachCounter`].
+    pub fn is_exclusive(&self) -> bool {
+        self.attach_counter.is_exclusive()
+    }
+
+    /// Returns the data bytes from the data segment, remapping it first if another handle grew it.
+    pub(crate) fn read_from_shm(&mut self) -> Result<Vec<u8>> {
+        self.refresh_segment()?;
+
+        // The length prefix is zero until the data is written first
+        let segment = self.data_segment.as_slice();
+        let total_buf_len = match segment.get(..LENGTH_PREFIX_LEN) {
+            Some(prefix) => usize::from_be_bytes(prefix.try_into()?),
+            None => 0,
+        };
+        if !(LENGTH_PREFIX_LEN..=segment.len()).contains(&total_buf_len) {
+            return Err(anyhow!(
+                "Data segment {} holds no data of {} bytes in its {} bytes.",
+                self.data_segment.name(),
+                total_buf_len,
+                segment.len()
+            ));
+        }
+        Ok(segment[LENGTH_PREFIX_LEN..total_buf_len].to_vec())
+    }
+
+    /// Serializes `data` and copies it (preceded by its length prefix) into the data segment, growing the segment
+    /// in chunks of [`SEGMENT_GROWTH_PAGES`] pages if it is too small.
+    pub(crate) fn write_to_shm<T: serde::Serialize>(&mut self, data: &T) -> Result<()> {
+        let data_bytes = rmp_serde::to_vec(&data)?; // Serialized data bytes to be written in `data_segment`
+        let bytes = {
+            let total_buf_len = LENGTH_PREFIX_LEN + data_bytes.len(); // Length of the prefix and the data
+            let mut total_buf_len_bytes = total_buf_len.to_be_bytes().to_vec();
+
+            // Bytes that will be written (total_buf_len and data) are simply concatenated
+            total_buf_len_bytes.extend(&data_bytes);
+            total_buf_len_bytes
+        };
+        // The data before the write is only read for an observer
+        let previous_data_bytes = match self.write_observer {
+            Some(_) => Some(self.read_from_shm()?),
+            None => None,
+        };
+
+        // Fail before touching the segment if the data would exceed the allocation cap
+        let required_bytes = PosixSharedMemory::allocated_bytes_for(bytes.len());
+        if let Some(max_allocated_bytes) = self.max_allocated_bytes {
+            if required_bytes > max_allocated_bytes {
+                return Err(SharedMemoryFull {
+                    data_len: bytes.len(),
+                    required_bytes,
+                    max_allocated_bytes,
+                }
+                .into());
+            }
+        }
+
+        // Grow the segment by whole chunks, but not beyond the cap
+        self.refresh_segment()?;
+        if self.data_segment.len() < bytes.len() {
+            let chunk = SEGMENT_GROWTH_PAGES * page_size();
+            let grown_len = (self.data_segment.len() + chunk).max(required_bytes).min(
+                self.max_allocated_bytes
+                    .unwrap_or(usize::MAX)
+                    .max(required_bytes),
+            );
+            self.data_segment
+                .grow(grown_len)
+                .map_err(|e| anyhow!("Failed to grow data segment: {}", e))?;
+            self.lock_remapped_segment_or_warn();
+        }
+
+        // Write to shared memory, release the pages only the previous data occupied and wake processes waiting for
+        // a change
+        let previous_bytes = self.allocated_bytes();
+        self.data_segment.as_mut_slice()[..bytes.len()].copy_from_slice(&bytes);
+        if previous_bytes > required_bytes {
+            self.data_segment
+                .release(required_bytes)
+                .map_err(|e| anyhow!("Failed to release shrunk data: {}", e))?;
+        }
+        self.peak_allocated_bytes = self.peak_allocated_bytes.max(required_bytes);
+        if let Some(change_event) = &self.change_event {
+            change_event.notify();
+        }
+
+        if let (Some(write_observer), Some(previous_data_bytes)) =
+            (&self.write_observer, previous_data_bytes)
+        {
+            write_observer(&previous_data_bytes, &data_bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Remaps the data segment if another handle grew it.
+    fn refresh_segment(&mut self) -> Result<()> {
+        self.data_segment
+            .refresh()
+            .map_err(|e| anyhow!("Failed to map data segment: {}", e))?;
+        self.peak_allocated_bytes = self.peak_allocated_bytes.max(self.allocated_bytes());
+        self.lock_remapped_segment_or_warn();
+        Ok(())
+    }
+}
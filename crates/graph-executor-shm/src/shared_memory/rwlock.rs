@@ -0,0 +1,73 @@
+use super::{lock_audit, semaphore::Semaphore, shared_counter::SharedCounter};
+use anyhow::{anyhow, Result};
+use std::{thread, time::Duration};
+
+/// Acquire read lock by:
+/// - Decrement write_lock semaphore, thereby write locking and checking that there is no active writer
+/// - Register new reader by incrementing the read_count counter
+/// - Incrementing write_lock semaphore to unlock write_lock
+pub(crate) fn read_lock(write_lock: &Semaphore, read_count: &SharedCounter) -> Result<()> {
+    // Check if there are active writers
+    lock_audit::before_wait(write_lock, read_count);
+    write_lock
+        .wait()
+        .map_err(|e| anyhow!("Failed locking write_lock semaphore: {}", e))?;
+    lock_audit::after_read_wait(write_lock, read_count);
+
+    // Indicate presence of new reader, unlocking write_lock as well if there are too many readers
+    let registered = read_count.increment().map_err(|e| {
+        anyhow!(
+            "Failed incrementing read_count counter to indicate new active reader: {}",
+            e
+        )
+    });
+
+    // Allow new writers (which have to check read_count) and readers
+    write_lock
+        .post()
+        .map_err(|e| anyhow!("Failed unlocking write_lock semaphore: {}", e))?;
+
+    registered.map(|_| ())
+}
+
+/// Release read lock by:
+/// - Decrement read_count to unregister active reader.
+pub(crate) fn read_unlock(read_count: &SharedCounter) -> Result<()> {
+    read_count.decrement().map_err(|e| {
+        anyhow!(
+            "Decrementing read_count counter (unregistering a reader), which is equal to 0 and therefore indicating no active readers: {}",
+            e
+        )
+    })?;
+    Ok(())
+}
+
+/// Acquire write lock by:
+/// - Decrement write_lock semaphore's value if it is greater than 0 (indicating there are current writers);
+///   else block main thread until it is greater than 0 and decrement then.
+/// - Wait until read_count counter's value is equal to 0, indicating there are no active readers anymore.
+pub(crate) fn write_lock(write_lock: &Semaphore, read_count: &SharedCounter) -> Result<()> {
+    // Get writing permission, new readers and writers are blocked, but readers can be still active
+    lock_audit::before_wait(write_lock, read_count);
+    write_lock
+        .wait()
+        .map_err(|e| anyhow!("Failed acquiring lock: {}", e))?;
+
+    // Wait until there are no active readers
+    while read_count.get_value() > 0 {
+        thread::sleep(Duration::from_millis(30)); // wait until next try
+    }
+    lock_audit::after_write_lock(write_lock, read_count);
+
+    Ok(())
+}
+
+/// Release write lock by:
+/// - Increment write_lock semaphore value; a greater than 0 value indicates a writable state to other processes.
+pub(crate) fn write_unlock(write_lock: &Semaphore, read_count: &SharedCounter) -> Result<()> {
+    lock_audit::before_write_unlock(write_lock, read_count);
+    write_lock
+        .post()
+        .map_err(|e| anyhow!("Failed posting write_lock Semaphore: {}", e))?;
+    Ok(())
+}
@@ -0,0 +1,374 @@
+#[cfg(target_os = "linux")]
+use super::futex::FutexSemaphore;
+use libc::{
+    c_int, c_uint, sem_close, sem_open, sem_post, sem_trywait, sem_unlink, sem_wait, strerror,
+    O_CREAT, O_EXCL, SEM_FAILED, S_IRUSR, S_IWUSR,
+};
+use std::{
+    ffi::CStr,
+    ffi::CString,
+    str::FromStr,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+#[cfg(target_os = "macos")]
+unsafe fn get_errno() -> i32 {
+    *libc::__error()
+}
+
+#[cfg(target_os = "linux")]
+unsafe fn get_errno() -> i32 {
+    *libc::__errno_location()
+}
+
+/// Retrieves and formats an error message from `errno`.
+fn get_last_error(context: &str) -> String {
+    format_error(context, unsafe { get_errno() })
+}
+
+/// Formats an error message from the error number `err`.
+fn format_error(context: &str, err: i32) -> String {
+    unsafe {
+        let err_str = strerror(err);
+        format!(
+            "{}: {} (errno: {})",
+            context,
+            CStr::from_ptr(err_str).to_string_lossy(),
+            err
+        )
+    }
+}
+
+/// Mechanism implementing [`Semaphore`]s. Semaphores are opened with the mechanism they were created with, but
+/// every process creating the semaphores of a run should use the same one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SyncBackend {
+    /// Named POSIX semaphores (see `sem_overview(7)`), limited by the system and prone to name collisions with
+    /// other programs.
+    #[default]
+    PosixSemaphore,
+    /// Atomics in shared memory files on which waiting processes sleep using `futex(2)`, Linux only.
+    Futex,
+}
+
+/// [`SyncBackend`] of semaphores created by this process, as its index in [`SyncBackend::ALL`].
+static SYNC_BACKEND: AtomicU8 = AtomicU8::new(0);
+
+impl SyncBackend {
+    const ALL: [SyncBackend; 2] = [SyncBackend::PosixSemaphore, SyncBackend::Futex];
+
+    /// Sets the mechanism of all semaphores subsequently created by this process.
+    pub fn set_process_default(self) -> Result<(), String> {
+        if cfg!(not(target_os = "linux")) && self == SyncBackend::Futex {
+            return Err("Futex semaphores are only supported on Linux.".to_string());
+        }
+        let index = SyncBackend::ALL
+            .iter()
+            .position(|b| *b == self)
+            .unwrap_or(0);
+        SYNC_BACKEND.store(index as u8, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Mechanism of semaphores created by this process.
+    pub fn process_default() -> Self {
+        SyncBackend::ALL[SYNC_BACKEND.load(Ordering::Relaxed) as usize]
+    }
+}
+
+impl FromStr for SyncBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "semaphore" => Ok(SyncBackend::PosixSemaphore),
+            "futex" => Ok(SyncBackend::Futex),
+            _ => Err(format!("Unknown sync backend {}.", s)),
+        }
+    }
+}
+
+/// Handle of a [`Semaphore`] of either [`SyncBackend`].
+#[derive(Debug)]
+enum Handle {
+    PosixSemaphore(*mut libc::sem_t),
+    #[cfg(target_os = "linux")]
+    Futex(FutexSemaphore),
+}
+
+/// A semaphore implementation for inter-process synchronization.
+#[derive(Debug)]
+pub struct Semaphore {
+    handle: Handle,
+    name: String,
+    creator: bool,
+}
+
+impl Semaphore {
+    /// Creates a new named semaphore with the given initial value, using the process's [`SyncBackend`].
+    ///
+    /// # Arguments
+    /// * `name` - The name of the semaphore.
+    /// * `initial_value` - The initial count of the semaphore.
+    ///
+    /// # Returns
+    /// * `Ok(Self)` if the semaphore is created successfully.
+    /// * `Err(String)` if the creation fails.
+    pub fn create(name: &str, initial_value: u32) -> Result<Self, String> {
+        Semaphore::create_with_backend(name, initial_value, SyncBackend::process_default())
+    }
+
+    /// Creates a new named semaphore with the given initial value like [`Semaphore::create`], using `backend`.
+    /// Fails if a semaphore with the same name exists with either backend.
+    pub fn create_with_backend(
+        name: &str,
+        initial_value: u32,
+        backend: SyncBackend,
+    ) -> Result<Self, String> {
+        let context = format!("Failed to create semaphore {}", name);
+        let name_cstr = CString::new(name).map_err(|_| "Invalid semaphore name".to_string())?;
+        match backend {
+            SyncBackend::PosixSemaphore =>
+            {
+                #[cfg(target_os = "linux")]
+                if FutexSemaphore::path(name).exists() {
+                    return Err(format_error(&context, libc::EEXIST));
+                }
+            }
+            #[cfg(target_os = "linux")]
+            SyncBackend::Futex => {
+                // Named semaphores are files in `/dev/shm` as well, prefixed by `sem.`
+                let posix_path = format!("/dev/shm/sem.{}", name.trim_start_matches('/'));
+                if std::path::Path::new(&posix_path).exists() {
+                    return Err(format_error(&context, libc::EEXIST));
+                }
+                let semaphore = FutexSemaphore::create(name, initial_value).map_err(|e| {
+                    format_error(&context, e.raw_os_error().unwrap_or(libc::EINVAL))
+                })?;
+                return Ok(Self {
+                    handle: Handle::Futex(semaphore),
+                    name: name.to_string(),
+                    creator: true,
+                });
+            }
+            #[cfg(not(target_os = "linux"))]
+            SyncBackend::Futex => {
+                return Err("Futex semaphores are only supported on Linux.".to_string())
+            }
+        }
+        let id = unsafe {
+            sem_open(
+                name_cstr.as_ptr(),
+                O_CREAT | O_EXCL,
+                (S_IRUSR | S_IWUSR) as c_int,
+                initial_value as c_uint,
+            )
+        };
+
+        if id == SEM_FAILED {
+            return Err(get_last_error(&context));
+        }
+
+        Ok(Self {
+            handle: Handle::PosixSemaphore(id),
+            name: name.to_string(),
+            creator: true,
+        })
+    }
+
+    /// Opens an existing named semaphore of either [`SyncBackend`].
+    ///
+    /// # Arguments
+    /// * `name` - The name of the semaphore to open.
+    ///
+    /// # Returns
+    /// * `Ok(Self)` if the semaphore is opened successfully.
+    /// * `Err(String)` if the operation fails.
+    pub fn open(name: &str) -> Result<Self, String> {
+        let name_cstr = CString::new(name).map_err(|_| "Invalid semaphore name".to_string())?;
+        #[cfg(target_os = "linux")]
+        if FutexSemaphore::path(name).exists() {
+            let semaphore = FutexSemaphore::open(name).map_err(|e| {
+                format_error(
+                    &format!("Failed to open semaphore {}", name),
+                    e.raw_os_error().unwrap_or(libc::EINVAL),
+                )
+            })?;
+            return Ok(Self {
+                handle: Handle::Futex(semaphore),
+                name: name.to_string(),
+                creator: false,
+            });
+        }
+        let id = unsafe { sem_open(name_cstr.as_ptr(), 0) };
+
+        if id == SEM_FAILED {
+            return Err(get_last_error(&format!(
+                "Failed to open semaphore {}",
+                name
+            )));
+        }
+
+        Ok(Self {
+            handle: Handle::PosixSemaphore(id),
+            name: name.to_string(),
+            creator: false,
+        })
+    }
+
+    /// Performs a blocking wait (decrement) operation on the semaphore.
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful.
+    /// * `Err(String)` if the operation fails.
+    pub fn wait(&self) -> Result<(), String> {
+        let id = match &self.handle {
+            Handle::PosixSemaphore(id) => *id,
+            #[cfg(target_os = "linux")]
+            Handle::Futex(semaphore) => {
+                return semaphore.wait().map_err(|e| {
+                    format_error(
+                        &format!("Failed to lock semaphore {}", self.name),
+                        e.raw_os_error().unwrap_or(libc::EINVAL),
+                    )
+                })
+            }
+        };
+        if unsafe { sem_wait(id) } == -1 {
+            return Err(get_last_error(&format!(
+                "Failed to lock semaphore {}",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Attempts to perform a non-blocking wait (decrement) operation on the semaphore.
+    ///
+    /// # Returns
+    /// * `Ok(true)` if the operation succeeds.
+    /// * `Ok(false)` if the semaphore is unavailable.
+    /// * `Err(String)` if an error occurs.
+    pub fn try_wait(&self) -> Result<bool, String> {
+        let id = match &self.handle {
+            Handle::PosixSemaphore(id) => *id,
+            #[cfg(target_os = "linux")]
+            Handle::Futex(semaphore) => return Ok(semaphore.try_wait()),
+        };
+        if unsafe { sem_trywait(id) } == -1 {
+            let err = unsafe { get_errno() };
+            if err == libc::EAGAIN {
+                // The  operation  could  not  be  performed without blocking (i.e., the semaphore currently has the value zero).
+                return Ok(false);
+            }
+            return Err(get_last_error(&format!(
+                "Failed to try-lock semaphore {}",
+                self.name
+            )));
+        }
+        Ok(true)
+    }
+
+    /// Performs a post (increment) operation on the semaphore.
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful.
+    /// * `Err(String)` if the operation fails.
+    pub fn post(&self) -> Result<(), String> {
+        let id = match &self.handle {
+            Handle::PosixSemaphore(id) => *id,
+            #[cfg(target_os = "linux")]
+            Handle::Futex(semaphore) => {
+                return semaphore.post().map_err(|e| {
+                    format_error(
+                        &format!("Failed to unlock semaphore {}", self.name),
+                        e.raw_os_error().unwrap_or(libc::EINVAL),
+                    )
+                })
+            }
+        };
+        if unsafe { sem_post(id) } == -1 {
+            return Err(get_last_error(&format!(
+                "Failed to unlock semaphore {}",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Sets whether the semaphore is removed when dropped, e.g. to hand over its removal to another process.
+    pub fn set_remove_on_drop(&mut self, remove_on_drop: bool) {
+        self.creator = remove_on_drop;
+    }
+
+    /// Retrieves the name of the semaphore
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Retrieves the mechanism implementing the semaphore.
+    pub fn backend(&self) -> SyncBackend {
+        match self.handle {
+            Handle::PosixSemaphore(_) => SyncBackend::PosixSemaphore,
+            #[cfg(target_os = "linux")]
+            Handle::Futex(_) => SyncBackend::Futex,
+        }
+    }
+
+    /// Retrieves the current value of the semaphore (Linux only).
+    ///
+    /// # Returns
+    /// * `Ok(u32)` representing the semaphore value.
+    /// * `Err(String)` if the operation fails.
+    #[cfg(target_os = "linux")]
+    pub fn get_value(&self) -> Result<u32, String> {
+        let id = match &self.handle {
+            Handle::PosixSemaphore(id) => *id,
+            Handle::Futex(semaphore) => return Ok(semaphore.value()),
+        };
+        let mut value: c_int = 0;
+        if unsafe { libc::sem_getvalue(id, &mut value) } == -1 {
+            return Err(get_last_error(&format!(
+                "Failed to get semaphore value {}",
+                self.name
+            )));
+        }
+        Ok(value as u32)
+    }
+    #[cfg(target_os = "macos")]
+    pub fn get_value(&self) -> Result<u32, String> {
+        Ok(0)
+    }
+}
+
+impl Drop for Semaphore {
+    /// Closes and optionally removes the semaphore when dropped.
+    fn drop(&mut self) {
+        let id = match &self.handle {
+            Handle::PosixSemaphore(id) => *id,
+            #[cfg(target_os = "linux")]
+            Handle::Futex(semaphore) => {
+                if self.creator {
+                    if let Err(e) = semaphore.remove() {
+                        eprintln!("Warning: removing futex failed {}: {}", self.name, e);
+                    }
+                }
+                return;
+            }
+        };
+        unsafe {
+            if sem_close(id) == -1 {
+                let err = get_errno();
+                eprintln!("Warning: sem_close failed {}: {}", self.name, err);
+            }
+
+            if self.creator {
+                let name_cstr = CString::new(self.name.clone()).expect("Failed to create CString");
+                if sem_unlink(name_cstr.as_ptr()) == -1 {
+                    let err = get_errno();
+                    eprintln!("Warning: sem_unlink failed {}: {}", self.name, err);
+                }
+            }
+        }
+    }
+}
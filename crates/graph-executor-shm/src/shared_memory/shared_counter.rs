@@ -0,0 +1,198 @@
+use libc::{
+    c_int, fstat, ftruncate, mmap, munmap, shm_open, shm_unlink, strerror, MAP_FAILED, MAP_SHARED,
+    O_CREAT, O_EXCL, O_RDWR, PROT_READ, PROT_WRITE, S_IRUSR, S_IWUSR,
+};
+use std::{
+    ffi::{CStr, CString},
+    io, ptr,
+    sync::atomic::{AtomicU32, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Highest value of a [`SharedCounter`] counting readers, equal to Linux's `SEM_VALUE_MAX` which capped the
+/// semaphore previously counting them.
+pub const MAX_READER_COUNT: u32 = i32::MAX as u32;
+
+/// Size of the shared memory object holding the counter's value.
+const COUNTER_LEN: usize = std::mem::size_of::<AtomicU32>();
+
+/// Time an opening process waits for the creator to size a counter's shared memory object.
+const INITIALIZATION_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Formats an error message from the error number `err`.
+fn format_error(context: &str, err: i32) -> String {
+    let err_str = unsafe { CStr::from_ptr(strerror(err)) };
+    format!(
+        "{}: {} (errno: {})",
+        context,
+        err_str.to_string_lossy(),
+        err
+    )
+}
+
+/// A counter in a named shared memory object (see `shm_open(3)`) which processes change atomically, failing with
+/// `EOVERFLOW` instead of exceeding its maximum and with `ERANGE` instead of dropping below 0.
+#[derive(Debug)]
+pub struct SharedCounter {
+    value: *const AtomicU32,
+    max: u32,
+    name: String,
+    creator: bool,
+}
+
+impl SharedCounter {
+    /// Creates a new named counter with value 0 which cannot exceed `max`.
+    pub fn create(name: &str, max: u32) -> Result<Self, String> {
+        let context = format!("Failed to create counter {}", name);
+        let name_cstr = CString::new(name).map_err(|_| "Invalid counter name".to_string())?;
+        let fd = unsafe {
+            shm_open(
+                name_cstr.as_ptr(),
+                O_CREAT | O_EXCL | O_RDWR,
+                S_IRUSR | S_IWUSR,
+            )
+        };
+        if fd == -1 {
+            return Err(format_error(&context, last_errno()));
+        }
+        // Objects are zero-filled when sized, so that the counter starts at 0
+        let result = match unsafe { ftruncate(fd, COUNTER_LEN as libc::off_t) } {
+            -1 => Err(format_error(&context, last_errno())),
+            _ => SharedCounter::map(fd, name, max, &context),
+        };
+        unsafe { libc::close(fd) };
+        if result.is_err() {
+            unsafe { shm_unlink(name_cstr.as_ptr()) };
+        }
+        result.map(|mut counter| {
+            counter.creator = true;
+            counter
+        })
+    }
+
+    /// Opens an existing named counter which cannot exceed `max`.
+    pub fn open(name: &str, max: u32) -> Result<Self, String> {
+        let context = format!("Failed to open counter {}", name);
+        let name_cstr = CString::new(name).map_err(|_| "Invalid counter name".to_string())?;
+        let fd = unsafe { shm_open(name_cstr.as_ptr(), O_RDWR, 0) };
+        if fd == -1 {
+            return Err(format_error(&context, last_errno()));
+        }
+        // Accessing the object before its creator sized it would raise SIGBUS
+        let started = Instant::now();
+        let result = loop {
+            let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+            if unsafe { fstat(fd, &mut stat) } == -1 {
+                break Err(format_error(&context, last_errno()));
+            }
+            if stat.st_size as usize >= COUNTER_LEN {
+                break SharedCounter::map(fd, name, max, &context);
+            }
+            if started.elapsed() > INITIALIZATION_TIMEOUT {
+                break Err(format_error(&context, libc::EINVAL));
+            }
+            thread::sleep(Duration::from_millis(1));
+        };
+        unsafe { libc::close(fd) };
+        result
+    }
+
+    /// Maps the counter's value from the shared memory object `fd` into memory.
+    fn map(fd: c_int, name: &str, max: u32, context: &str) -> Result<Self, String> {
+        let value = unsafe {
+            mmap(
+                ptr::null_mut(),
+                COUNTER_LEN,
+                PROT_READ | PROT_WRITE,
+                MAP_SHARED,
+                fd,
+                0,
+            )
+        };
+        if value == MAP_FAILED {
+            return Err(format_error(context, last_errno()));
+        }
+        Ok(SharedCounter {
+            value: value as *const AtomicU32,
+            max,
+            name: name.to_string(),
+            creator: false,
+        })
+    }
+
+    fn atomic(&self) -> &AtomicU32 {
+        unsafe { &*self.value }
+    }
+
+    /// Increments the counter and returns its new value.
+    pub fn increment(&self) -> Result<u32, String> {
+        self.atomic()
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |value| {
+                value.checked_add(1).filter(|value| *value <= self.max)
+            })
+            .map(|value| value + 1)
+            .map_err(|_| {
+                format_error(
+                    &format!(
+                        "Failed to increment counter {} beyond {}",
+                        self.name, self.max
+                    ),
+                    libc::EOVERFLOW,
+                )
+            })
+    }
+
+    /// Decrements the counter and returns its new value.
+    pub fn decrement(&self) -> Result<u32, String> {
+        self.atomic()
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |value| {
+                value.checked_sub(1)
+            })
+            .map(|value| value - 1)
+            .map_err(|_| {
+                format_error(
+                    &format!("Failed to decrement counter {} below 0", self.name),
+                    libc::ERANGE,
+                )
+            })
+    }
+
+    /// Retrieves the current value of the counter.
+    pub fn get_value(&self) -> u32 {
+        self.atomic().load(Ordering::Acquire)
+    }
+
+    /// Sets whether the counter is removed when dropped, e.g. to hand over its removal to another process.
+    pub fn set_remove_on_drop(&mut self, remove_on_drop: bool) {
+        self.creator = remove_on_drop;
+    }
+
+    /// Retrieves the name of the counter.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Retrieves `errno`.
+fn last_errno() -> i32 {
+    io::Error::last_os_error().raw_os_error().unwrap_or(0)
+}
+
+impl Drop for SharedCounter {
+    /// Unmaps and optionally removes the counter when dropped.
+    fn drop(&mut self) {
+        unsafe {
+            if munmap(self.value as *mut libc::c_void, COUNTER_LEN) == -1 {
+                eprintln!("Warning: munmap failed {}: {}", self.name, last_errno());
+            }
+
+            if self.creator {
+                let name_cstr = CString::new(self.name.clone()).expect("Failed to create CString");
+                if shm_unlink(name_cstr.as_ptr()) == -1 {
+                    eprintln!("Warning: shm_unlink failed {}: {}", self.name, last_errno());
+                }
+            }
+        }
+    }
+}
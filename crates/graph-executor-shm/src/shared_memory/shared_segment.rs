@@ -0,0 +1,229 @@
+use libc::{
+    c_int, fstat, ftruncate, mmap, munmap, shm_open, shm_unlink, strerror, MAP_FAILED, MAP_SHARED,
+    O_CREAT, O_EXCL, O_RDWR, PROT_READ, PROT_WRITE, S_IRUSR, S_IWUSR,
+};
+use std::{
+    ffi::{CStr, CString},
+    io, ptr,
+};
+
+/// Formats an error message from the error number `err`.
+fn format_error(context: &str, err: i32) -> String {
+    let err_str = unsafe { CStr::from_ptr(strerror(err)) };
+    format!(
+        "{}: {} (errno: {})",
+        context,
+        err_str.to_string_lossy(),
+        err
+    )
+}
+
+/// A contiguous region of shared memory in a named shared memory object (see `shm_open(3)`), mapped in one piece.
+/// The region only grows (see [`SharedSegment::grow`]), so that mappings of other processes never extend beyond
+/// the object; they pick up its growth with [`SharedSegment::refresh`].
+#[derive(Debug)]
+pub struct SharedSegment {
+    /// Descriptor of the shared memory object, kept open to check its size and to remap it.
+    fd: c_int,
+    /// Start of the mapping, null while nothing is mapped.
+    data: *mut u8,
+    /// Number of bytes mapped.
+    len: usize,
+    name: String,
+    creator: bool,
+}
+
+// SAFETY: the mapping is owned by the segment and only accessed through it.
+unsafe impl Send for SharedSegment {}
+
+impl SharedSegment {
+    /// Creates a new named segment of `len` zeroed bytes.
+    pub fn create(name: &str, len: usize) -> Result<Self, String> {
+        let context = format!("Failed to create segment {}", name);
+        let name_cstr = CString::new(name).map_err(|_| "Invalid segment name".to_string())?;
+        let fd = unsafe {
+            shm_open(
+                name_cstr.as_ptr(),
+                O_CREAT | O_EXCL | O_RDWR,
+                S_IRUSR | S_IWUSR,
+            )
+        };
+        if fd == -1 {
+            return Err(format_error(&context, last_errno()));
+        }
+        let mut segment = SharedSegment {
+            fd,
+            data: ptr::null_mut(),
+            len: 0,
+            name: name.to_string(),
+            creator: true,
+        };
+        // The segment removes the object when dropped on errors
+        segment.grow(len)?;
+        Ok(segment)
+    }
+
+    /// Opens an existing named segment, mapping as many bytes as its object currently has.
+    pub fn open(name: &str) -> Result<Self, String> {
+        let context = format!("Failed to open segment {}", name);
+        let name_cstr = CString::new(name).map_err(|_| "Invalid segment name".to_string())?;
+        let fd = unsafe { shm_open(name_cstr.as_ptr(), O_RDWR, 0) };
+        if fd == -1 {
+            return Err(format_error(&context, last_errno()));
+        }
+        let mut segment = SharedSegment {
+            fd,
+            data: ptr::null_mut(),
+            len: 0,
+            name: name.to_string(),
+            creator: false,
+        };
+        segment.refresh()?;
+        Ok(segment)
+    }
+
+    /// Remaps the segment if its object grew since it was mapped, e.g. by another process, and returns whether it
+    /// was remapped.
+    pub fn refresh(&mut self) -> Result<bool, String> {
+        let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+        if unsafe { fstat(self.fd, &mut stat) } == -1 {
+            return Err(format_error(
+                &format!("Failed to check size of segment {}", self.name),
+                last_errno(),
+            ));
+        }
+        match stat.st_size as usize {
+            len if len > self.len => self.map(len).map(|()| true),
+            _ => Ok(false),
+        }
+    }
+
+    /// Grows the segment's object to at least `len` bytes (new bytes are zeroed) and remaps it.
+    pub fn grow(&mut self, len: usize) -> Result<(), String> {
+        self.refresh()?;
+        if len <= self.len {
+            return Ok(());
+        }
+        if unsafe { ftruncate(self.fd, len as libc::off_t) } == -1 {
+            return Err(format_error(
+                &format!("Failed to grow segment {} to {} bytes", self.name, len),
+                last_errno(),
+            ));
+        }
+        self.map(len)
+    }
+
+    /// Frees the memory of the segment's object from `offset`, a multiple of the page size, to its end, which reads
+    /// as zeroes afterwards. The object keeps its size, so that mappings of other processes stay valid.
+    pub fn release(&mut self, offset: usize) -> Result<(), String> {
+        if offset >= self.len {
+            return Ok(());
+        }
+        let mode = libc::FALLOC_FL_PUNCH_HOLE | libc::FALLOC_FL_KEEP_SIZE;
+        let released = (self.len - offset) as libc::off_t;
+        if unsafe { libc::fallocate(self.fd, mode, offset as libc::off_t, released) } == -1 {
+            return Err(format_error(
+                &format!(
+                    "Failed to release segment {} from byte {}",
+                    self.name, offset
+                ),
+                last_errno(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Replaces the current mapping by one of the first `len` bytes of the segment's object.
+    fn map(&mut self, len: usize) -> Result<(), String> {
+        let data = unsafe {
+            mmap(
+                ptr::null_mut(),
+                len,
+                PROT_READ | PROT_WRITE,
+                MAP_SHARED,
+                self.fd,
+                0,
+            )
+        };
+        if data == MAP_FAILED {
+            return Err(format_error(
+                &format!("Failed to map segment {}", self.name),
+                last_errno(),
+            ));
+        }
+        self.unmap();
+        (self.data, self.len) = (data as *mut u8, len);
+        Ok(())
+    }
+
+    /// Unmaps the segment, if mapped.
+    fn unmap(&mut self) {
+        if self.data.is_null() {
+            return;
+        }
+        if unsafe { munmap(self.data as *mut libc::c_void, self.len) } == -1 {
+            eprintln!("Warning: munmap failed {}: {}", self.name, last_errno());
+        }
+        (self.data, self.len) = (ptr::null_mut(), 0);
+    }
+
+    /// Number of bytes mapped.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether nothing is mapped, i.e. the segment's object was not sized yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the mapped bytes. Callers synchronize with writers of other processes, e.g. by a lock.
+    pub fn as_slice(&self) -> &[u8] {
+        match self.data.is_null() {
+            true => &[],
+            // SAFETY: `data` points to a mapping of `len` bytes owned by the segment.
+            false => unsafe { std::slice::from_raw_parts(self.data, self.len) },
+        }
+    }
+
+    /// Get the mapped bytes for writing. Callers synchronize with readers and writers of other processes, e.g. by
+    /// a lock.
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        match self.data.is_null() {
+            true => &mut [],
+            // SAFETY: `data` points to a mapping of `len` bytes owned by the segment.
+            false => unsafe { std::slice::from_raw_parts_mut(self.data, self.len) },
+        }
+    }
+
+    /// Sets whether the segment is removed when dropped, e.g. to hand over its removal to another process.
+    pub fn set_remove_on_drop(&mut self, remove_on_drop: bool) {
+        self.creator = remove_on_drop;
+    }
+
+    /// Retrieves the name of the segment.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Retrieves `errno`.
+fn last_errno() -> i32 {
+    io::Error::last_os_error().raw_os_error().unwrap_or(0)
+}
+
+impl Drop for SharedSegment {
+    /// Unmaps and optionally removes the segment when dropped.
+    fn drop(&mut self) {
+        self.unmap();
+        unsafe {
+            libc::close(self.fd);
+            if self.creator {
+                let name_cstr = CString::new(self.name.clone()).expect("Failed to create CString");
+                if shm_unlink(name_cstr.as_ptr()) == -1 {
+                    eprintln!("Warning: shm_unlink failed {}: {}", self.name, last_errno());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use super::shared_segment::SharedSegment;
+use std::{
+    mem::size_of,
+    sync::atomic::{AtomicU64, AtomicU8, Ordering},
+    time::Duration,
+};
+
+/// Number of 8-byte words in front of the stamps: the generation counter and the number of slots.
+const HEADER_WORDS: usize = 2;
+
+/// Fixed array of one-byte statuses in a named shared memory object, which processes change with a single
+/// `compare_exchange` instead of rewriting the serialized data they belong to. Every successful change is stamped
+/// with the next value of a generation counter shared by all slots, so that readers can tell which slots changed
+/// since they last looked. Every slot additionally holds the time at which it was enqueued, which is set once.
+///
+/// The object holds the generation counter and the number of slots, followed by the generation stamp of every slot,
+/// the enqueue time of every slot and the statuses themselves. The number of slots is fixed when the array is
+/// created.
+#[derive(Debug)]
+pub struct SharedStatusSlots {
+    segment: SharedSegment,
+    len: usize,
+}
+
+impl SharedStatusSlots {
+    /// Creates a new named array holding `statuses`, with generation 0 and all stamps 0. If an object with the
+    /// name is left by a process which died before removing it, it is taken over and overwritten.
+    pub fn create(name: &str, statuses: &[u8]) -> Result<Self, String> {
+        let object_len = SharedStatusSlots::object_len(statuses.len());
+        let mut segment = SharedSegment::create(name, object_len).or_else(|_| {
+            SharedSegment::open(name).map(|mut segment| {
+                segment.set_remove_on_drop(true);
+                segment
+            })
+        })?;
+        segment.grow(object_len)?;
+        let slots = SharedStatusSlots {
+            segment,
+            len: statuses.len(),
+        };
+        slots
+            .word(1)
+            .store(statuses.len() as u64, Ordering::Release);
+        slots.store_all(0, statuses.iter().map(|status| (*status, 0, None)));
+        Ok(slots)
+    }
+
+    /// Opens an existing named array. Its creator must have finished creating it, e.g. by writing the data the
+    /// slots belong to afterwards.
+    pub fn open(name: &str) -> Result<Self, String> {
+        let segment = SharedSegment::open(name)?;
+        if segment.len() < size_of::<AtomicU64>() * HEADER_WORDS {
+            return Err(format!("Status slots {} are not sized yet", name));
+        }
+        let mut slots = SharedStatusSlots { segment, len: 0 };
+        let len = slots.word(1).load(Ordering::Acquire) as usize;
+        if slots.segment.len() < SharedStatusSlots::object_len(len) {
+            return Err(format!("Status slots {} are not sized yet", name));
+        }
+        slots.len = len;
+        Ok(slots)
+    }
+
+    /// Number of bytes of the object holding `len` slots.
+    fn object_len(len: usize) -> usize {
+        size_of::<AtomicU64>() * (HEADER_WORDS + 2 * len) + len
+    }
+
+    /// Number of slots.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks whether the array has no slots.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the 8-byte word at `index` of the object: the header words followed by the stamps and enqueue times.
+    fn word(&self, index: usize) -> &AtomicU64 {
+        // SAFETY: the mapping is page aligned and holds the header words, `len` stamps and `len` statuses, and
+        // shared memory is only ever accessed atomically.
+        unsafe { &*(self.segment.as_slice().as_ptr() as *const AtomicU64).add(index) }
+    }
+
+    fn generation_counter(&self) -> &AtomicU64 {
+        self.word(0)
+    }
+
+    fn stamp(&self, slot: usize) -> &AtomicU64 {
+        assert!(slot < self.len, "Status slot {} out of {}", slot, self.len);
+        self.word(HEADER_WORDS + slot)
+    }
+
+    /// Enqueue time of `slot` in nanoseconds plus one, 0 if it was not enqueued.
+    fn enqueued(&self, slot: usize) -> &AtomicU64 {
+        assert!(slot < self.len, "Status slot {} out of {}", slot, self.len);
+        self.word(HEADER_WORDS + self.len + slot)
+    }
+
+    fn status(&self, slot: usize) -> &AtomicU8 {
+        assert!(slot < self.len, "Status slot {} out of {}", slot, self.len);
+        let offset = size_of::<AtomicU64>() * (HEADER_WORDS + 2 * self.len) + slot;
+        // SAFETY: see `word`.
+        unsafe { &*(self.segment.as_slice().as_ptr().add(offset) as *const AtomicU8) }
+    }
+
+    /// Current value of the generation counter, the highest stamp of any slot.
+    pub fn generation(&self) -> u64 {
+        self.generation_counter().load(Ordering::Acquire)
+    }
+
+    /// Loads the status of `slot` with the generation in which it last changed. The stamp is loaded first, so that a
+    /// reader never sees a stamp newer than the status: at worst it sees a new status with its previous stamp, and
+    /// the change's stamp on its next load.
+    pub fn load(&self, slot: usize) -> (u8, u64) {
+        let stamp = self.stamp(slot).load(Ordering::Acquire);
+        (self.status(slot).load(Ordering::Acquire), stamp)
+    }
+
+    /// Changes the status of `slot` from `current` to `new` with a single `compare_exchange`, stamping it with the
+    /// next generation. Returns the status found in the slot if it was not `current`.
+    pub fn compare_exchange(&self, slot: usize, current: u8, new: u8) -> Result<(), u8> {
+        self.status(slot)
+            .compare_exchange(current, new, Ordering::AcqRel, Ordering::Acquire)?;
+        let generation = self.generation_counter().fetch_add(1, Ordering::AcqRel) + 1;
+        // Changes of the same slot by other processes may stamp it in a different order than they happened
+        self.stamp(slot).fetch_max(generation, Ordering::Release);
+        Ok(())
+    }
+
+    /// Like [`SharedStatusSlots::compare_exchange`], but changes `slot` back to `current` (keeping the new stamp)
+    /// unless `is_allowed` holds for the other slots holding `new` which were stamped before it, loaded after the
+    /// change. Of several processes changing slots at the same time, each sees the changes stamped before its own, so
+    /// that e.g. only a bounded number of slots hold `new` at the same time and the earliest change wins. Returns
+    /// whether the change was kept, or the status found in the slot if it was not `current`.
+    pub fn compare_exchange_if(
+        &self,
+        slot: usize,
+        current: u8,
+        new: u8,
+        is_allowed: impl FnOnce(&[usize]) -> bool,
+    ) -> Result<bool, u8> {
+        self.status(slot)
+            .compare_exchange(current, new, Ordering::SeqCst, Ordering::Acquire)?;
+        let generation = self.generation_counter().fetch_add(1, Ordering::SeqCst) + 1;
+        self.stamp(slot).fetch_max(generation, Ordering::SeqCst);
+        // Slots changed after this one are not stamped yet or stamped later, their changes see this one instead
+        let earlier: Vec<usize> = (0..self.len)
+            .filter(|other| {
+                *other != slot
+                    && self.status(*other).load(Ordering::SeqCst) == new
+                    && self.stamp(*other).load(Ordering::SeqCst) < generation
+            })
+            .collect();
+        if is_allowed(&earlier) {
+            return Ok(true);
+        }
+        self.status(slot).store(current, Ordering::SeqCst);
+        Ok(false)
+    }
+
+    /// Loads the time at which `slot` was enqueued, or [`None`] if it was not, see [`SharedStatusSlots::enqueue`].
+    pub fn load_enqueued(&self, slot: usize) -> Option<Duration> {
+        match self.enqueued(slot).load(Ordering::Acquire) {
+            0 => None,
+            nanos => Some(Duration::from_nanos(nanos - 1)),
+        }
+    }
+
+    /// Sets the time at which `slot` was enqueued to `after` with a single `compare_exchange`, unless it already has
+    /// one. Returns whether it was set.
+    pub fn enqueue(&self, slot: usize, after: Duration) -> bool {
+        let nanos = u64::try_from(after.as_nanos()).unwrap_or(u64::MAX - 1) + 1;
+        self.enqueued(slot)
+            .compare_exchange(0, nanos, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+    }
+
+    /// Stores the status, generation stamp and enqueue time of every slot, in order, and sets the generation counter
+    /// to `generation`. Must not race with [`SharedStatusSlots::compare_exchange`] or [`SharedStatusSlots::enqueue`],
+    /// e.g. by holding the write lock of the mapping the slots belong to.
+    pub fn store_all(
+        &self,
+        generation: u64,
+        slots: impl Iterator<Item = (u8, u64, Option<Duration>)>,
+    ) {
+        for (slot, (status, stamp, enqueued)) in slots.take(self.len).enumerate() {
+            self.status(slot).store(status, Ordering::Release);
+            self.stamp(slot).store(stamp, Ordering::Release);
+            self.enqueued(slot).store(0, Ordering::Release);
+            if let Some(after) = enqueued {
+                self.enqueue(slot, after);
+            }
+        }
+        self.generation_counter()
+            .store(generation, Ordering::Release);
+    }
+
+    /// Sets whether the array is removed when dropped, e.g. to hand over its removal to another process.
+    pub fn set_remove_on_drop(&mut self, remove_on_drop: bool) {
+        self.segment.set_remove_on_drop(remove_on_drop);
+    }
+
+    /// Retrieves the name of the array's shared memory object.
+    pub fn name(&self) -> &str {
+        self.segment.name()
+    }
+}
@@ -0,0 +1,488 @@
+use anyhow::{anyhow, Result};
+use std::{
+    fmt, fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+#[cfg(feature = "s3")]
+use {
+    hmac::{Hmac, Mac},
+    sha2::{Digest, Sha256},
+    std::{io::Read, time::SystemTime},
+};
+
+/// Number of temporary files created by this process, naming the next one, see [`temporary_path`].
+static TEMPORARY_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// Storage of artifacts (e.g. result blobs or cached outputs of nodes) under string keys, which may be shared by the
+/// workers of different runs and machines executing the same pipelines.
+///
+/// Keys are relative paths like `3f2a…/build`: non-empty segments of ASCII letters, digits, `-`, `_` and `.`
+/// separated by `/`, see [`check_key`]. Artifacts are written whole, so that readers never see a partial artifact.
+pub trait ArtifactStore: fmt::Debug + Send + Sync {
+    /// Stores `data` under `key`, replacing the artifact stored under it.
+    fn put(&self, key: &str, data: &[u8]) -> Result<()>;
+
+    /// Reads the artifact stored under `key`, [`None`] if there is none.
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Removes the artifact stored under `key`, if there is one.
+    fn remove(&self, key: &str) -> Result<()>;
+
+    /// Checks whether an artifact is stored under `key`.
+    fn contains(&self, key: &str) -> Result<bool> {
+        Ok(self.get(key)?.is_some())
+    }
+
+    /// Checks whether artifacts are stored on this machine's disk, so that writing them counts towards a run's disk
+    /// usage, see [`super::resource_usage::ResourceUsage::disk_bytes`].
+    fn is_on_disk(&self) -> bool {
+        false
+    }
+}
+
+/// Opens the artifact store at `location`: an `s3://<bucket>[/<prefix>]` URL for an [`S3Store`] (which requires the
+/// `s3` feature), otherwise the path of a [`LocalDirectoryStore`].
+pub fn open_artifact_store(location: &str) -> Result<Arc<dyn ArtifactStore>> {
+    match location.strip_prefix("s3://") {
+        #[cfg(feature = "s3")]
+        Some(bucket_and_prefix) => {
+            let (bucket, prefix) = bucket_and_prefix
+                .split_once('/')
+                .unwrap_or((bucket_and_prefix, ""));
+            Ok(Arc::new(S3Store::from_env(bucket, prefix)?))
+        }
+        #[cfg(not(feature = "s3"))]
+        Some(_) => Err(anyhow!(
+            "Artifact store {} requires building with the s3 feature.",
+            location
+        )),
+        None => Ok(Arc::new(LocalDirectoryStore::create(location)?)),
+    }
+}
+
+/// Returns an error if `key` is not a valid key of an [`ArtifactStore`], e.g. because it is absolute or contains
+/// `..`, which would escape the store.
+pub fn check_key(key: &str) -> Result<()> {
+    let is_valid = |segment: &str| {
+        !segment.is_empty()
+            && segment != "."
+            && segment != ".."
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+    };
+    if !key.split('/').all(is_valid) {
+        return Err(anyhow!(
+            "Invalid artifact key {:?}: must be a relative path of letters, digits, '-', '_' and '.'.",
+            key
+        ));
+    }
+    Ok(())
+}
+
+/// [`ArtifactStore`] in a local directory, e.g. on a disk shared by the workers of a machine or on a network file
+/// system shared by several machines. Each artifact is a file at the path of its key below the directory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalDirectoryStore {
+    root: PathBuf,
+}
+
+impl LocalDirectoryStore {
+    /// Creates the (possibly existing) directory `root` of the store.
+    pub fn create(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root)
+            .map_err(|e| anyhow!("Failed creating artifact store {}: {}", root.display(), e))?;
+        Ok(LocalDirectoryStore { root })
+    }
+
+    /// Get the directory of the store.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Get the path of the artifact stored under `key`.
+    pub fn path(&self, key: &str) -> Result<PathBuf> {
+        check_key(key)?;
+        Ok(self.root.join(key))
+    }
+}
+
+impl ArtifactStore for LocalDirectoryStore {
+    /// Writes the artifact to a temporary file next to its path and renames it, so that concurrent readers (of this
+    /// or other processes) read either the old or the new artifact.
+    fn put(&self, key: &str, data: &[u8]) -> Result<()> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temporary = temporary_path(&path);
+        fs::write(&temporary, data)
+            .and_then(|()| fs::rename(&temporary, &path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&temporary);
+                anyhow!("Failed storing artifact {}: {}", path.display(), e)
+            })
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.path(key)?;
+        match fs::read(&path) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(anyhow!("Failed reading artifact {}: {}", path.display(), e)),
+        }
+    }
+
+    fn remove(&self, key: &str) -> Result<()> {
+        let path = self.path(key)?;
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(anyhow!(
+                "Failed removing artifact {}: {}",
+                path.display(),
+                e
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn contains(&self, key: &str) -> Result<bool> {
+        Ok(self.path(key)?.is_file())
+    }
+
+    fn is_on_disk(&self) -> bool {
+        true
+    }
+}
+
+/// [`ArtifactStore`] in a bucket of an S3-compatible object storage (e.g. AWS S3 or MinIO), sharing artifacts
+/// between machines. Each artifact is an object named by the store's prefix followed by its key.
+///
+/// Requests are signed with AWS Signature Version 4 and sent over HTTP(S) by the store itself. Objects are addressed
+/// path-style (`<endpoint>/<bucket>/<object>`), which all S3-compatible storages support.
+#[cfg(feature = "s3")]
+#[derive(Clone)]
+pub struct S3Store {
+    /// URL of the storage, e.g. `https://s3.eu-central-1.amazonaws.com` or `http://localhost:9000`.
+    endpoint: String,
+    /// Host (and port) of the endpoint, which is signed as the request's `Host` header.
+    host: String,
+    /// Path of the endpoint, empty unless the storage is served below a path.
+    base_path: String,
+    bucket: String,
+    /// Prefix of the names of the store's objects, empty or ending with `/`.
+    prefix: String,
+    region: String,
+    access_key_id: String,
+    secret_access_key: String,
+    /// Token of temporary credentials, e.g. of an assumed role.
+    session_token: Option<String>,
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "s3")]
+impl fmt::Debug for S3Store {
+    /// Formats the store without its secret access key and session token.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("S3Store")
+            .field("endpoint", &self.endpoint)
+            .field("bucket", &self.bucket)
+            .field("prefix", &self.prefix)
+            .field("region", &self.region)
+            .field("access_key_id", &self.access_key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "s3")]
+impl S3Store {
+    /// Creates the store of the objects below `prefix` in `bucket` at the `http` or `https` URL `endpoint`, signing
+    /// requests for `region` with the given credentials.
+    pub fn new(
+        endpoint: &str,
+        bucket: &str,
+        prefix: &str,
+        region: &str,
+        access_key_id: &str,
+        secret_access_key: &str,
+    ) -> Result<Self> {
+        let endpoint = endpoint.trim_end_matches('/');
+        let (host, base_path) = match endpoint.split_once("://") {
+            Some(("http" | "https", authority_and_path)) => authority_and_path.split_at(
+                authority_and_path
+                    .find('/')
+                    .unwrap_or(authority_and_path.len()),
+            ),
+            _ => {
+                return Err(anyhow!(
+                    "Invalid S3 endpoint {:?}: must be an http or https URL.",
+                    endpoint
+                ))
+            }
+        };
+        if host.is_empty() {
+            return Err(anyhow!("Invalid S3 endpoint {:?}: missing host.", endpoint));
+        }
+        let is_valid_bucket =
+            |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.';
+        if bucket.is_empty() || !bucket.chars().all(is_valid_bucket) {
+            return Err(anyhow!("Invalid S3 bucket {:?}.", bucket));
+        }
+        let prefix = prefix.trim_matches('/');
+        if !prefix.is_empty() {
+            check_key(prefix)?;
+        }
+        Ok(S3Store {
+            endpoint: endpoint.to_string(),
+            host: host.to_string(),
+            base_path: base_path.to_string(),
+            bucket: bucket.to_string(),
+            prefix: match prefix.is_empty() {
+                true => String::new(),
+                false => format!("{}/", prefix),
+            },
+            region: region.to_string(),
+            access_key_id: access_key_id.to_string(),
+            secret_access_key: secret_access_key.to_string(),
+            session_token: None,
+            agent: ureq::AgentBuilder::new().build(),
+        })
+    }
+
+    /// Signs requests with the session token of temporary credentials, which is sent as `x-amz-security-token`.
+    pub fn with_session_token(mut self, session_token: &str) -> Self {
+        self.session_token = Some(session_token.to_string());
+        self
+    }
+
+    /// Creates the store of the objects below `prefix` in `bucket` configured like the AWS command line interface:
+    /// the credentials are read from `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and (for temporary credentials)
+    /// `AWS_SESSION_TOKEN`, the region from `AWS_REGION` (`us-east-1` by default) and the endpoint from
+    /// `AWS_ENDPOINT_URL` (AWS S3 of the region by default).
+    pub fn from_env(bucket: &str, prefix: &str) -> Result<Self> {
+        let variable = |name: &str| {
+            std::env::var(name)
+                .map_err(|e| anyhow!("Missing {} for S3 artifact store: {}", name, e))
+        };
+        let region = std::env::var("AWS_REGION").unwrap_or_else(|_| String::from("us-east-1"));
+        let endpoint = std::env::var("AWS_ENDPOINT_URL")
+            .unwrap_or_else(|_| format!("https://s3.{}.amazonaws.com", region));
+        let store = S3Store::new(
+            &endpoint,
+            bucket,
+            prefix,
+            &region,
+            &variable("AWS_ACCESS_KEY_ID")?,
+            &variable("AWS_SECRET_ACCESS_KEY")?,
+        )?;
+        Ok(match std::env::var("AWS_SESSION_TOKEN") {
+            Ok(session_token) if !session_token.is_empty() => {
+                store.with_session_token(&session_token)
+            }
+            _ => store,
+        })
+    }
+
+    /// Sends a signed request with `method` and the body `data` for the object of the artifact stored under `key`,
+    /// returning the HTTP status and body of the response.
+    fn request(&self, method: &str, key: &str, data: &[u8]) -> Result<(u16, Vec<u8>)> {
+        check_key(key)?;
+        // Bucket, prefix and key consist of characters which need no escaping in the path
+        let object = format!("/{}/{}{}", self.bucket, self.prefix, key);
+        let url = format!("{}{}", self.endpoint, object);
+        let payload_hash = format!("{:x}", Sha256::digest(data));
+        let date = amz_date(SystemTime::now());
+        let mut headers = vec![
+            ("host", self.host.as_str()),
+            ("x-amz-content-sha256", payload_hash.as_str()),
+            ("x-amz-date", date.as_str()),
+        ];
+        if let Some(session_token) = &self.session_token {
+            headers.push(("x-amz-security-token", session_token));
+        }
+        let authorization = sign_v4(
+            &self.access_key_id,
+            &self.secret_access_key,
+            &self.region,
+            method,
+            &format!("{}{}", self.base_path, object),
+            &headers,
+            &payload_hash,
+        );
+        let request = headers.iter().fold(
+            self.agent
+                .request(method, &url)
+                .set("authorization", &authorization),
+            |request, (name, value)| request.set(name, value),
+        );
+        let response = match request.send_bytes(data) {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(e) => return Err(anyhow!("Request to {} failed: {}", url, e)),
+        };
+        let status = response.status();
+        let mut body = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut body)
+            .map_err(|e| anyhow!("Failed reading response from {}: {}", url, e))?;
+        Ok((status, body))
+    }
+}
+
+#[cfg(feature = "s3")]
+impl ArtifactStore for S3Store {
+    fn put(&self, key: &str, data: &[u8]) -> Result<()> {
+        match self.request("PUT", key, data)? {
+            (200..=299, _) => Ok(()),
+            (status, body) => Err(anyhow!(
+                "Storing {} failed with HTTP status {}: {}",
+                key,
+                status,
+                String::from_utf8_lossy(&body).trim()
+            )),
+        }
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match self.request("GET", key, &[])? {
+            (200, data) => Ok(Some(data)),
+            (404, _) => Ok(None),
+            (status, body) => Err(anyhow!(
+                "Reading {} failed with HTTP status {}: {}",
+                key,
+                status,
+                String::from_utf8_lossy(&body).trim()
+            )),
+        }
+    }
+
+    fn remove(&self, key: &str) -> Result<()> {
+        match self.request("DELETE", key, &[])? {
+            (200..=299 | 404, _) => Ok(()),
+            (status, body) => Err(anyhow!(
+                "Removing {} failed with HTTP status {}: {}",
+                key,
+                status,
+                String::from_utf8_lossy(&body).trim()
+            )),
+        }
+    }
+
+    fn contains(&self, key: &str) -> Result<bool> {
+        match self.request("HEAD", key, &[])? {
+            (200, _) => Ok(true),
+            (404, _) => Ok(false),
+            (status, _) => Err(anyhow!(
+                "Checking {} failed with HTTP status {}.",
+                key,
+                status
+            )),
+        }
+    }
+}
+
+/// Signs a request to S3 with AWS Signature Version 4 and returns its `Authorization` header. `path` is the
+/// request's escaped path (without query), `headers` are the signed headers with lowercase names sorted by name,
+/// including `x-amz-date` with the time of the request, and `payload_hash` is the hex SHA-256 hash of its body.
+#[cfg(feature = "s3")]
+pub(crate) fn sign_v4(
+    access_key_id: &str,
+    secret_access_key: &str,
+    region: &str,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    payload_hash: &str,
+) -> String {
+    let hmac = |key: &[u8], data: &str| {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(key).expect("HMAC-SHA256 takes keys of any length.");
+        mac.update(data.as_bytes());
+        mac.finalize().into_bytes()
+    };
+    let timestamp = headers
+        .iter()
+        .find(|(name, _)| *name == "x-amz-date")
+        .map_or("", |(_, value)| value);
+    let date = timestamp.get(..8).unwrap_or(timestamp);
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+        .collect();
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_request = format!(
+        "{}\n{}\n\n{}\n{}\n{}",
+        method, path, canonical_headers, signed_headers, payload_hash
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{:x}",
+        timestamp,
+        scope,
+        Sha256::digest(canonical_request.as_bytes())
+    );
+    let signing_key = [date, region, "s3", "aws4_request"].iter().fold(
+        format!("AWS4{}", secret_access_key).into_bytes(),
+        |key, part| hmac(&key, part).to_vec(),
+    );
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={:x}",
+        access_key_id,
+        scope,
+        signed_headers,
+        hmac(&signing_key, &string_to_sign)
+    )
+}
+
+/// Formats `time` as the `x-amz-date` of a request in UTC, e.g. `20130524T000000Z`.
+#[cfg(feature = "s3")]
+pub(crate) fn amz_date(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    // Civil date of the days since 1970-01-01, see https://howardhinnant.github.io/date_algorithms.html
+    let days = (seconds / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        seconds % 86400 / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// Returns a path for a temporary file next to `path`, unique among the processes and threads of the machine.
+fn temporary_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(
+        ".tmp-{}-{}",
+        std::process::id(),
+        TEMPORARY_FILES.fetch_add(1, Ordering::SeqCst)
+    ));
+    path.with_file_name(name)
+}
@@ -0,0 +1,150 @@
+use crate::shared_memory::posix_shared_memory::WriteObserver;
+use anyhow::{anyhow, Result};
+use graph_executor_core::graph_structure::{
+    execution_status::ExecutionStatus, graph::DirectedAcyclicGraph,
+};
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    os::unix::fs::OpenOptionsExt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Append-only log of all changes to the graph of a run, shared by all of its workers and its watchdog, e.g. for
+/// runs in regulated environments. Every change is appended as a line like
+///
+/// ```text
+/// 1760612345.678 pid=4242 uid=1000 run=filename_suffix action=claim node="build" Executable -> Executing
+/// ```
+///
+/// with the time (seconds since the Unix epoch), the process and user making the change and the run it belongs to.
+/// Status transitions are named after the step of the node's life cycle (`claim`, `complete`, `ready`, `requeue`,
+/// `fail`, `skip`, `cancel` or `reset`), recorded attempts are logged as `attempt`, the times nodes were first seen
+/// executable as `enqueue`, progress marks as `progress` and reported progress as `report`, and administrative
+/// actions like the watchdog's `reclaim` are recorded explicitly. Heartbeats of executing nodes are not logged, since
+/// they would flood the log.
+pub struct AuditLog {
+    /// Audit file, only ever opened for appending.
+    file: File,
+    /// Filename suffix of the audited run.
+    filename_suffix: String,
+}
+
+impl AuditLog {
+    /// Opens the audit file at `path` for appending entries of the run with `filename_suffix`, creating it
+    /// (readable by its owner and group only) if it doesn't exist yet.
+    pub fn open(path: &str, filename_suffix: &str) -> Result<Self> {
+        let file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .mode(0o640)
+            .open(path)
+            .map_err(|e| anyhow!("Failed opening audit log {}: {}", path, e))?;
+        Ok(AuditLog {
+            file,
+            filename_suffix: filename_suffix.to_string(),
+        })
+    }
+
+    /// Appends an entry for `action` on the node identified by `node_id` (if any) with further `detail`.
+    pub fn record(&self, action: &str, node_id: Option<&str>, detail: &str) -> Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        let mut entry = format!(
+            "{}.{:03} pid={} uid={} run={} action={}",
+            now.as_secs(),
+            now.subsec_millis(),
+            std::process::id(),
+            // SAFETY: `getuid` takes no arguments and always succeeds.
+            unsafe { libc::getuid() },
+            self.filename_suffix,
+            action
+        );
+        if let Some(node_id) = node_id {
+            entry.push_str(&format!(" node={:?}", node_id));
+        }
+        if !detail.is_empty() {
+            entry.push(' ');
+            entry.push_str(detail);
+        }
+        entry.push('\n');
+        // A single write per entry, so that entries of concurrent processes appending to the file don't interleave
+        (&self.file)
+            .write_all(entry.as_bytes())
+            .map_err(|e| anyhow!("Failed appending to audit log: {}", e))
+    }
+
+    /// Appends an entry for every status transition, attempt, enqueue time and progress mark recorded by a write
+    /// changing the run's graph from `before` to `after`, or a single `write` entry if the write changed none of them
+    /// (nor a node's heartbeat).
+    pub fn record_write(
+        &self,
+        before: &DirectedAcyclicGraph,
+        after: &DirectedAcyclicGraph,
+    ) -> Result<()> {
+        let mut recorded = false;
+        for i in after.get_node_indices() {
+            let node_id = after.node_id(i);
+            let Some(before_index) = before.node_index(node_id) else {
+                continue;
+            };
+            let (node_before, node) = (&before[before_index], &after[i]);
+            if node_before.execution_status != node.execution_status {
+                let (from, to) = (node_before.execution_status, node.execution_status);
+                let detail = format!("{} -> {}", from, to);
+                self.record(transition_action(from, to), Some(node_id), &detail)?;
+                recorded = true;
+            }
+            for attempt in node.attempts().iter().skip(node_before.attempts().len()) {
+                let detail = format!("outcome={} worker={}", attempt.outcome, attempt.worker_id);
+                self.record("attempt", Some(node_id), &detail)?;
+                recorded = true;
+            }
+            if let (None, Some(enqueued_after)) = (node_before.enqueued_after, node.enqueued_after)
+            {
+                let detail = format!("after={:.3}s", enqueued_after.as_secs_f64());
+                self.record("enqueue", Some(node_id), &detail)?;
+                recorded = true;
+            }
+            if node_before.progress_marks != node.progress_marks {
+                let detail = format!("marks={}", node.progress_marks);
+                self.record("progress", Some(node_id), &detail)?;
+                recorded = true;
+            }
+            if let (true, Some(progress)) = (node_before.progress != node.progress, &node.progress)
+            {
+                self.record("report", Some(node_id), &progress.to_string())?;
+                recorded = true;
+            }
+            recorded |= node_before.heartbeat_after != node.heartbeat_after;
+        }
+        match recorded {
+            true => Ok(()),
+            false => self.record("write", None, ""),
+        }
+    }
+
+    /// Turns the [`AuditLog`] into an observer of the writes of a run's graph mapping, see
+    /// [`crate::shared_memory::posix_shared_memory::PosixSharedMemory::set_write_observer`].
+    pub fn write_observer(self) -> WriteObserver {
+        Box::new(move |before: &[u8], after: &[u8]| {
+            self.record_write(
+                &rmp_serde::from_slice(before)?,
+                &rmp_serde::from_slice(after)?,
+            )
+        })
+    }
+}
+
+/// Name of the step of a node's life cycle changing its execution status `from` one `to` another.
+fn transition_action(from: ExecutionStatus, to: ExecutionStatus) -> &'static str {
+    match (from, to) {
+        (ExecutionStatus::Executable, ExecutionStatus::Executing) => "claim",
+        (ExecutionStatus::NonExecutable, ExecutionStatus::Executable) => "ready",
+        (ExecutionStatus::Executing, ExecutionStatus::Executable) => "requeue",
+        (_, ExecutionStatus::Executed) => "complete",
+        (_, ExecutionStatus::Failed) => "fail",
+        (_, ExecutionStatus::Skipped) => "skip",
+        (_, ExecutionStatus::Cancelled) => "cancel",
+        _ => "reset",
+    }
+}
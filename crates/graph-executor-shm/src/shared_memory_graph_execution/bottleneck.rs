@@ -0,0 +1,195 @@
+use super::simulation::DEFAULT_NODE_DURATION;
+use graph_executor_core::graph_structure::{attempt::AttemptOutcome, graph::DirectedAcyclicGraph};
+use petgraph::graph::NodeIndex;
+use std::{cmp::Reverse, collections::BTreeMap, fmt, time::Duration};
+
+/// Change of the graph which would shorten its critical path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Restructuring {
+    /// Removing the edge from the parent to the child node (identified by their string identifiers).
+    RemoveEdge(String, String),
+    /// Splitting the node into two halves which execute in parallel.
+    SplitNode(String),
+}
+
+impl fmt::Display for Restructuring {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Restructuring::RemoveEdge(parent, child) => {
+                write!(f, "remove edge {} -> {}", parent, child)
+            }
+            Restructuring::SplitNode(node_id) => write!(f, "split node {}", node_id),
+        }
+    }
+}
+
+/// Report of the edges and nodes on the critical path of a graph whose removal or splitting would shorten it the
+/// most, guiding the restructuring of slow pipelines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BottleneckReport {
+    /// Length of the graph's critical path, i.e. its longest path weighted by node durations.
+    pub critical_path_length: Duration,
+    /// Identifiers of the nodes on a critical path from a root to a sink.
+    pub critical_path: Vec<String>,
+    /// Restructurings which shorten the critical path, with the length they shorten it by, most effective first.
+    pub bottlenecks: Vec<(Restructuring, Duration)>,
+}
+
+impl BottleneckReport {
+    /// Analyses `graph` with the durations per node identifier (e.g. [`recorded_durations`]), nodes without a
+    /// duration take [`DEFAULT_NODE_DURATION`].
+    pub fn new(graph: &DirectedAcyclicGraph, durations: &BTreeMap<String, Duration>) -> Self {
+        let duration = |i: NodeIndex| {
+            durations
+                .get(graph.node_id(i))
+                .copied()
+                .unwrap_or(DEFAULT_NODE_DURATION)
+        };
+        let all_edges = |_: NodeIndex, _: NodeIndex| true;
+
+        // Longest paths ending at and starting from each node (including the node itself)
+        let mut ending_at = BTreeMap::new();
+        let mut starting_from = BTreeMap::new();
+        for i in graph.get_node_indices() {
+            path_length(graph, &duration, &all_edges, i, true, &mut ending_at);
+            path_length(graph, &duration, &all_edges, i, false, &mut starting_from);
+        }
+        let critical_path_length = starting_from.values().max().copied().unwrap_or_default();
+        let is_critical_node =
+            |i: NodeIndex| ending_at[&i] + starting_from[&i] - duration(i) == critical_path_length;
+
+        // Follow a critical path from a root to a sink
+        let mut critical_path = vec![];
+        let mut next = graph
+            .get_node_indices()
+            .find(|i| ending_at[i] == duration(*i) && starting_from[i] == critical_path_length);
+        while let Some(i) = next {
+            critical_path.push(graph.node_id(i).to_string());
+            next = graph
+                .get_child_node_indices(i)
+                .find(|child| starting_from[&i] == duration(i) + starting_from[child]);
+        }
+
+        // Recompute the critical path without each edge between and with each split node on a critical path
+        let mut bottlenecks = vec![];
+        for parent in graph.get_node_indices().filter(|i| is_critical_node(*i)) {
+            let split_duration = |i: NodeIndex| match i == parent {
+                true => duration(i) / 2,
+                false => duration(i),
+            };
+            let length = critical_path_length_with(graph, &split_duration, &all_edges);
+            bottlenecks.push((
+                Restructuring::SplitNode(graph.node_id(parent).to_string()),
+                critical_path_length - length,
+            ));
+
+            for child in graph.get_child_node_indices(parent) {
+                if ending_at[&parent] + starting_from[&child] != critical_path_length {
+                    continue;
+                }
+                let without_edge = |p: NodeIndex, c: NodeIndex| (p, c) != (parent, child);
+                let length = critical_path_length_with(graph, &duration, &without_edge);
+                bottlenecks.push((
+                    Restructuring::RemoveEdge(
+                        graph.node_id(parent).to_string(),
+                        graph.node_id(child).to_string(),
+                    ),
+                    critical_path_length - length,
+                ));
+            }
+        }
+        bottlenecks.retain(|(_, reduction)| !reduction.is_zero());
+        bottlenecks.sort_by_key(|(_, reduction)| Reverse(*reduction));
+
+        BottleneckReport {
+            critical_path_length,
+            critical_path,
+            bottlenecks,
+        }
+    }
+}
+
+impl fmt::Display for BottleneckReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Critical path: {:.3}s ({})",
+            self.critical_path_length.as_secs_f64(),
+            self.critical_path.join(" -> ")
+        )?;
+        for (restructuring, reduction) in &self.bottlenecks {
+            writeln!(
+                f,
+                "    {}: {:.3}s shorter",
+                restructuring,
+                reduction.as_secs_f64()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Durations of the last successful attempt of each executed node of `graph` by node identifier.
+pub fn recorded_durations(graph: &DirectedAcyclicGraph) -> BTreeMap<String, Duration> {
+    graph
+        .get_node_indices()
+        .filter_map(|i| {
+            let attempt = graph[i]
+                .attempts()
+                .iter()
+                .rev()
+                .find(|attempt| attempt.outcome == AttemptOutcome::Succeeded)?;
+            Some((
+                graph.node_id(i).to_string(),
+                attempt.finished_after.saturating_sub(attempt.started_after),
+            ))
+        })
+        .collect()
+}
+
+/// Length of the critical path of `graph` with the node durations `duration` and only the edges accepted by
+/// `has_edge`.
+fn critical_path_length_with(
+    graph: &DirectedAcyclicGraph,
+    duration: &dyn Fn(NodeIndex) -> Duration,
+    has_edge: &dyn Fn(NodeIndex, NodeIndex) -> bool,
+) -> Duration {
+    let mut lengths = BTreeMap::new();
+    graph
+        .get_node_indices()
+        .map(|i| path_length(graph, duration, has_edge, i, false, &mut lengths))
+        .max()
+        .unwrap_or_default()
+}
+
+/// Computes (memoized in `lengths`) the length of the longest path ending at (if `towards_roots`) or starting
+/// from `node_index`, including its own duration.
+fn path_length(
+    graph: &DirectedAcyclicGraph,
+    duration: &dyn Fn(NodeIndex) -> Duration,
+    has_edge: &dyn Fn(NodeIndex, NodeIndex) -> bool,
+    node_index: NodeIndex,
+    towards_roots: bool,
+    lengths: &mut BTreeMap<NodeIndex, Duration>,
+) -> Duration {
+    if let Some(length) = lengths.get(&node_index) {
+        return *length;
+    }
+    let longest_neighbor_path = match towards_roots {
+        true => graph
+            .get_parent_node_indices(node_index)
+            .filter(|parent| has_edge(*parent, node_index))
+            .collect::<Vec<NodeIndex>>(),
+        false => graph
+            .get_child_node_indices(node_index)
+            .filter(|child| has_edge(node_index, *child))
+            .collect::<Vec<NodeIndex>>(),
+    }
+    .into_iter()
+    .map(|neighbor| path_length(graph, duration, has_edge, neighbor, towards_roots, lengths))
+    .max()
+    .unwrap_or_default();
+    let length = duration(node_index) + longest_neighbor_path;
+    lengths.insert(node_index, length);
+    length
+}
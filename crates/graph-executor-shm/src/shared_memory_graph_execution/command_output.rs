@@ -0,0 +1,98 @@
+use anyhow::{anyhow, Result};
+use petgraph::graph::NodeIndex;
+use std::{
+    fs::{self, File, OpenOptions},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Captured standard output and error of one attempt at executing a command node and its hooks, written to files
+/// below the configured directory which are replaced by the node's next attempt.
+pub struct CommandOutput {
+    /// Path of the file capturing the standard output.
+    stdout: PathBuf,
+    /// Path of the file capturing the standard error.
+    stderr: PathBuf,
+}
+
+impl CommandOutput {
+    /// Creates the empty output files of the node at `node_index` in the run identified by `filename_suffix`, below
+    /// `directory`.
+    pub(crate) fn create(
+        directory: &Path,
+        filename_suffix: &str,
+        node_index: NodeIndex,
+    ) -> Result<Self> {
+        fs::create_dir_all(directory).map_err(|e| {
+            anyhow!(
+                "Failed creating output directory {}: {}",
+                directory.display(),
+                e
+            )
+        })?;
+        let output = CommandOutput {
+            stdout: output_path(directory, filename_suffix, node_index, "stdout"),
+            stderr: output_path(directory, filename_suffix, node_index, "stderr"),
+        };
+        File::create(&output.stdout)?;
+        File::create(&output.stderr)?;
+        Ok(output)
+    }
+
+    /// Get the path of the file capturing the standard output.
+    pub fn stdout_path(&self) -> &Path {
+        &self.stdout
+    }
+
+    /// Get the path of the file capturing the standard error.
+    pub fn stderr_path(&self) -> &Path {
+        &self.stderr
+    }
+
+    /// Appends the standard output and error of `process` to the captured ones.
+    pub(crate) fn apply(&self, process: &mut Command) -> Result<()> {
+        let append = |path: &Path| OpenOptions::new().append(true).open(path);
+        process
+            .stdout(append(&self.stdout)?)
+            .stderr(append(&self.stderr)?);
+        Ok(())
+    }
+
+    /// Get the last non-empty line of the captured standard error, e.g. to explain why a command failed.
+    pub fn last_error_line(&self) -> Option<String> {
+        let stderr = fs::read(&self.stderr).ok()?;
+        String::from_utf8_lossy(&stderr)
+            .lines()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .map(|line| line.trim().to_string())
+    }
+}
+
+/// Returns the number of bytes captured of the output of the node at `node_index` in the run identified by
+/// `filename_suffix` below `directory`, zero if it has no captured output.
+pub fn captured_bytes(directory: &Path, filename_suffix: &str, node_index: NodeIndex) -> usize {
+    ["stdout", "stderr"]
+        .iter()
+        .filter_map(|stream| {
+            fs::metadata(output_path(directory, filename_suffix, node_index, stream)).ok()
+        })
+        .map(|metadata| metadata.len() as usize)
+        .sum()
+}
+
+/// Returns the path of the file capturing the `stream` (`stdout` or `stderr`) of the node at `node_index` in the
+/// run identified by `filename_suffix`, below `directory`.
+pub fn output_path(
+    directory: &Path,
+    filename_suffix: &str,
+    node_index: NodeIndex,
+    stream: &str,
+) -> PathBuf {
+    directory.join(format!(
+        "graph-executor-{}-{}.{}",
+        filename_suffix,
+        node_index.index(),
+        stream
+    ))
+}
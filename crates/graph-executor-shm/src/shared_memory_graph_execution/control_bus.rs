@@ -0,0 +1,129 @@
+use super::{
+    control_socket::{ControlState, RunState, INVALID_PARAMS},
+    execution_options::ExecutionOptions,
+};
+use anyhow::{anyhow, Result};
+use std::{os::unix::net::UnixStream, sync::Arc};
+use zbus::{
+    blocking::{connection::Builder, Connection},
+    fdo, Guid,
+};
+
+/// Well-known name of the control service of [`ControlBus::register`] on the session bus.
+pub const CONTROL_BUS_NAME: &str = "io.github.MintyNumbers.GraphExecutor";
+/// Path of the object implementing [`CONTROL_INTERFACE`].
+pub const CONTROL_OBJECT_PATH: &str = "/io/github/MintyNumbers/GraphExecutor";
+/// Name of the D-Bus interface of a [`ControlBus`].
+pub const CONTROL_INTERFACE: &str = "io.github.MintyNumbers.GraphExecutor.Control1";
+
+/// Local control API of graph executor runs on D-Bus, offering the methods of a
+/// [`ControlServer`](super::control_socket::ControlServer) to desktop agents which speak D-Bus rather than JSON-RPC.
+///
+/// The object at [`CONTROL_OBJECT_PATH`] implements [`CONTROL_INTERFACE`] with the methods `Submit(s graph,
+/// s filename_suffix, u workers)`, `Status(s filename_suffix) -> (s state, s error, a(ss) nodes)`,
+/// `Cancel(s filename_suffix, s node) -> s message` and `Approve(s filename_suffix, s node) -> s message`, where an
+/// empty `node` cancels or approves the whole run and an empty `error` means the run didn't fail. Invalid arguments
+/// fail with `org.freedesktop.DBus.Error.InvalidArgs`, all other errors with `org.freedesktop.DBus.Error.Failed`.
+/// The methods are served by a thread of the connection until the [`ControlBus`] is dropped.
+pub struct ControlBus {
+    _connection: Connection,
+}
+
+impl ControlBus {
+    /// Registers the control service as [`CONTROL_BUS_NAME`] on the session bus, executing submitted runs with
+    /// `options`.
+    pub fn register(options: ExecutionOptions) -> Result<Self> {
+        let builder = Builder::session()
+            .and_then(|builder| builder.name(CONTROL_BUS_NAME))
+            .map_err(|e| anyhow!("Failed connecting to the session bus: {}", e))?;
+        Self::serve(builder, options)
+    }
+
+    /// Serves the control interface to the peer connected with `stream` without a bus, e.g. on a private socket,
+    /// executing submitted runs with `options`. Returns once the peer completed the D-Bus handshake.
+    pub fn serve_peer(stream: UnixStream, options: ExecutionOptions) -> Result<Self> {
+        Self::serve(
+            Builder::async_io_unix_stream(stream)
+                .server(Guid::generate())?
+                .p2p(),
+            options,
+        )
+    }
+
+    /// Serves the control interface on the connection of `builder`.
+    fn serve(builder: Builder, options: ExecutionOptions) -> Result<Self> {
+        let interface = ControlInterface {
+            state: ControlState::new(options),
+        };
+        let connection = builder
+            .serve_at(CONTROL_OBJECT_PATH, interface)
+            .and_then(Builder::build)
+            .map_err(|e| anyhow!("Failed serving {}: {}", CONTROL_INTERFACE, e))?;
+        Ok(ControlBus {
+            _connection: connection,
+        })
+    }
+}
+
+/// State, error and node identifiers and statuses of a run, as returned by `Status`.
+type StatusReply = (String, String, Vec<(String, String)>);
+
+/// Object implementing [`CONTROL_INTERFACE`].
+struct ControlInterface {
+    state: Arc<ControlState>,
+}
+
+#[zbus::interface(name = "io.github.MintyNumbers.GraphExecutor.Control1")]
+impl ControlInterface {
+    /// Starts a run of the graph file `graph` executed by `workers` worker threads of the service.
+    fn submit(&self, graph: String, filename_suffix: String, workers: u32) -> fdo::Result<()> {
+        self.state
+            .submit(&graph, &filename_suffix, workers as usize)
+            .map(|_| ())
+            .map_err(bus_error)
+    }
+
+    /// Gets the state of a run, its error (empty unless it failed) and the identifier and status of its nodes.
+    #[zbus(out_args("state", "error", "nodes"))]
+    fn status(&self, filename_suffix: String) -> fdo::Result<StatusReply> {
+        let RunState {
+            state,
+            error,
+            nodes,
+            ..
+        } = self.state.status(&filename_suffix).map_err(bus_error)?;
+        let nodes = nodes
+            .into_iter()
+            .map(|node| (node.id, node.status))
+            .collect();
+        Ok((state.to_string(), error.unwrap_or_default(), nodes))
+    }
+
+    /// Cancels a node (and skips its descendants) or, if `node` is empty, the whole run.
+    fn cancel(&self, filename_suffix: String, node: String) -> fdo::Result<String> {
+        self.control(&filename_suffix, "cancel", &node)
+    }
+
+    /// Approves a node or, if `node` is empty, all nodes awaiting approval.
+    fn approve(&self, filename_suffix: String, node: String) -> fdo::Result<String> {
+        self.control(&filename_suffix, "approve", &node)
+    }
+}
+
+impl ControlInterface {
+    /// Applies `action` to the node `node` (the whole run if empty) of the run with `filename_suffix`.
+    fn control(&self, filename_suffix: &str, action: &str, node: &str) -> fdo::Result<String> {
+        let node = (!node.is_empty()).then_some(node);
+        self.state
+            .control(filename_suffix, action, node)
+            .map_err(bus_error)
+    }
+}
+
+/// Converts the JSON-RPC error code and message of a control method into a D-Bus error.
+fn bus_error((code, message): (i64, String)) -> fdo::Error {
+    match code {
+        INVALID_PARAMS => fdo::Error::InvalidArgs(message),
+        _ => fdo::Error::Failed(message),
+    }
+}
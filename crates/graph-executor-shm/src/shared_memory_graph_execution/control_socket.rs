@@ -0,0 +1,442 @@
+use super::{
+    execute_graph::ExecuteGraph, execution_options::ExecutionOptions, run_header::RunHeader,
+    shell::RunShell,
+};
+use crate::shared_memory::posix_shared_memory::PosixSharedMemory;
+use anyhow::{anyhow, Result};
+use graph_executor_core::graph_structure::graph::DirectedAcyclicGraph;
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, BufReader, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+/// JSON-RPC error code of a request which is no valid JSON.
+const PARSE_ERROR: i64 = -32700;
+/// JSON-RPC error code of valid JSON which is no JSON-RPC 2.0 request object.
+const INVALID_REQUEST: i64 = -32600;
+/// JSON-RPC error code of a request naming an unknown method.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// JSON-RPC error code of a request with missing or invalid parameters.
+pub(super) const INVALID_PARAMS: i64 = -32602;
+/// JSON-RPC error code of a request which failed, e.g. because its run doesn't exist.
+pub(super) const SERVER_ERROR: i64 = -32000;
+
+/// Methods understood by [`ControlServer`], with their parameters.
+pub const CONTROL_METHODS: &str = "\
+submit   {graph, filename_suffix, workers?}  Start a run of a graph file executed by workers of the server
+status   {filename_suffix}                   Get the state of a run and the status of its nodes
+cancel   {filename_suffix, node?}            Cancel a node (and skip its descendants) or the whole run
+approve  {filename_suffix, node?}            Approve a node (or all nodes) awaiting approval";
+
+/// Request read from a client of a [`ControlServer`]: a JSON-RPC 2.0 request object on a single line. Its `id` is
+/// read separately, since a request without one is a notification, but one with a `null` one is not.
+#[derive(Debug, serde::Deserialize)]
+struct Request {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Option<Value>,
+}
+
+/// Parameters of all methods, see [`CONTROL_METHODS`].
+#[derive(Debug, Default, serde::Deserialize)]
+struct Params {
+    graph: Option<String>,
+    filename_suffix: Option<String>,
+    node: Option<String>,
+    workers: Option<usize>,
+}
+
+impl Params {
+    /// Get the required `filename_suffix` parameter.
+    fn filename_suffix(&self) -> Result<&str, (i64, String)> {
+        self.filename_suffix.as_deref().ok_or((
+            INVALID_PARAMS,
+            String::from("Missing parameter filename_suffix."),
+        ))
+    }
+}
+
+/// Response to a request of a client of a [`ControlServer`], with either a `result` or an `error`.
+#[derive(Debug, serde::Serialize)]
+struct Response {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ResponseError>,
+}
+
+/// Error of a [`Response`].
+#[derive(Debug, serde::Serialize)]
+struct ResponseError {
+    code: i64,
+    message: String,
+}
+
+/// Result of submitting a run to a control server.
+#[derive(Debug, serde::Serialize)]
+pub(super) struct Submitted {
+    pub(super) filename_suffix: String,
+    pub(super) workers: usize,
+}
+
+/// Result of getting the status of a run from a control server.
+#[derive(Debug, serde::Serialize)]
+pub(super) struct RunState {
+    pub(super) filename_suffix: String,
+    /// `submitted`, `running`, `succeeded` or `failed`.
+    pub(super) state: &'static str,
+    pub(super) error: Option<String>,
+    pub(super) nodes: Vec<NodeState>,
+}
+
+/// Status of a node of a [`RunState`].
+#[derive(Debug, serde::Serialize)]
+pub(super) struct NodeState {
+    pub(super) id: String,
+    pub(super) status: String,
+}
+
+/// State of a run submitted to a [`ControlServer`] once its workers left it, which removes it from shared memory.
+#[derive(Debug)]
+struct FinishedRun {
+    /// The graph as its last worker left it.
+    graph: DirectedAcyclicGraph,
+    /// Error of the run, if it failed.
+    error: Option<String>,
+}
+
+/// Local control API of graph executor runs on a Unix domain socket, so that other local tools (e.g. desktop or CI
+/// agents) can submit, inspect, cancel and approve runs without linking this crate or reading shared memory.
+///
+/// Clients send one JSON-RPC 2.0 request per line and receive one response per line, see [`CONTROL_METHODS`]:
+/// `{"jsonrpc":"2.0","id":1,"method":"status","params":{"filename_suffix":"nightly"}}`. Submitted runs are executed
+/// by worker threads of the server, but every method also works on runs started by other processes.
+pub struct ControlServer {
+    listener: UnixListener,
+    path: PathBuf,
+    state: Arc<ControlState>,
+}
+
+/// State shared by the connections of a control server.
+pub(super) struct ControlState {
+    /// Options the workers of submitted runs execute them with.
+    options: ExecutionOptions,
+    /// Submitted runs by filename suffix, [`None`] while their workers execute them.
+    runs: Mutex<BTreeMap<String, Option<FinishedRun>>>,
+}
+
+impl ControlServer {
+    /// Binds the control socket at `path`, executing submitted runs with `options`. A socket left at `path` by a
+    /// server which is no longer running is replaced.
+    pub fn bind(path: &Path, options: ExecutionOptions) -> Result<Self> {
+        if path.exists() {
+            if UnixStream::connect(path).is_ok() {
+                return Err(anyhow!(
+                    "Another server is listening on {}.",
+                    path.display()
+                ));
+            }
+            std::fs::remove_file(path)?;
+        }
+        let listener = UnixListener::bind(path)
+            .map_err(|e| anyhow!("Failed binding {}: {}", path.display(), e))?;
+        Ok(ControlServer {
+            listener,
+            path: path.to_path_buf(),
+            state: ControlState::new(options),
+        })
+    }
+
+    /// Accepts clients until the socket fails, serving each of them in a thread of its own.
+    pub fn serve(&self) -> Result<()> {
+        for stream in self.listener.incoming() {
+            let (stream, state) = (stream?, self.state.clone());
+            thread::spawn(move || {
+                if let Err(e) = state.serve_client(stream) {
+                    eprintln!("Control client failed: {}", e);
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ControlServer {
+    /// Removes the socket, so that clients fail to connect instead of waiting.
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl ControlState {
+    /// Creates the state of a control server executing submitted runs with `options`.
+    pub(super) fn new(options: ExecutionOptions) -> Arc<Self> {
+        Arc::new(ControlState {
+            options,
+            runs: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    /// Answers the requests of the client connected with `stream` until it disconnects.
+    fn serve_client(self: Arc<Self>, stream: UnixStream) -> Result<()> {
+        let mut writer = stream.try_clone()?;
+        for line in BufReader::new(stream).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.respond(&line) {
+                writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Answers the request on `line`, unless it is a notification.
+    fn respond(self: &Arc<Self>, line: &str) -> Option<Response> {
+        let error = |id, code, message| Response {
+            jsonrpc: "2.0",
+            id,
+            result: None,
+            error: Some(ResponseError { code, message }),
+        };
+        let request: Value = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(e) => {
+                return Some(error(
+                    Value::Null,
+                    PARSE_ERROR,
+                    format!("Invalid JSON: {}", e),
+                ))
+            }
+        };
+        let id = request.get("id").cloned();
+        if id
+            .as_ref()
+            .is_some_and(|id| !(id.is_null() || id.is_number() || id.is_string()))
+        {
+            return Some(error(
+                Value::Null,
+                INVALID_REQUEST,
+                String::from("Invalid request: id must be a number, a string or null."),
+            ));
+        }
+        let request = match serde_json::from_value::<Request>(request) {
+            Ok(request) if request.jsonrpc == "2.0" => request,
+            Ok(request) => {
+                return Some(error(
+                    Value::Null,
+                    INVALID_REQUEST,
+                    format!(
+                        "Invalid request: unsupported JSON-RPC version {}, expected 2.0.",
+                        request.jsonrpc
+                    ),
+                ))
+            }
+            Err(e) => {
+                return Some(error(
+                    Value::Null,
+                    INVALID_REQUEST,
+                    format!("Invalid request: {}", e),
+                ))
+            }
+        };
+        let result = self.call(&request);
+        match (id?, result) {
+            (id, Ok(result)) => Some(Response {
+                jsonrpc: "2.0",
+                id,
+                result: Some(result),
+                error: None,
+            }),
+            (id, Err((code, message))) => Some(error(id, code, message)),
+        }
+    }
+
+    /// Calls the method of `request` and returns its result, or the code and message of its error.
+    fn call(self: &Arc<Self>, request: &Request) -> Result<Value, (i64, String)> {
+        let params: Params = match &request.params {
+            Some(params) => serde_json::from_value(params.clone())
+                .map_err(|e| (INVALID_PARAMS, format!("Invalid parameters: {}", e)))?,
+            None => Params::default(),
+        };
+        let result = match request.method.as_str() {
+            "submit" => {
+                let graph = params
+                    .graph
+                    .as_deref()
+                    .ok_or((INVALID_PARAMS, String::from("Missing parameter graph.")))?;
+                serde_json::to_value(self.submit(
+                    graph,
+                    params.filename_suffix()?,
+                    params.workers.unwrap_or(1),
+                )?)
+            }
+            "status" => serde_json::to_value(self.status(params.filename_suffix()?)?),
+            method @ ("cancel" | "approve") => {
+                let message =
+                    self.control(params.filename_suffix()?, method, params.node.as_deref())?;
+                Ok(serde_json::json!({ "message": message }))
+            }
+            method => {
+                return Err((
+                    METHOD_NOT_FOUND,
+                    format!(
+                        "Unknown method {}, expected submit, status, cancel or approve.",
+                        method
+                    ),
+                ))
+            }
+        };
+        result.map_err(|e| (SERVER_ERROR, e.to_string()))
+    }
+
+    /// Cancels or approves (depending on `action`) the node `node` or else the whole run with `filename_suffix`,
+    /// returning the message of the run's shell.
+    pub(super) fn control(
+        &self,
+        filename_suffix: &str,
+        action: &str,
+        node: Option<&str>,
+    ) -> Result<String, (i64, String)> {
+        let command = match node {
+            Some(node) => format!("{} {}", action, node),
+            None => action.to_string(),
+        };
+        RunShell::attach(filename_suffix)
+            .and_then(|mut shell| shell.execute(&command))
+            .map_err(|e| (SERVER_ERROR, e.to_string()))
+    }
+
+    /// Starts a run of the graph file `graph_file` with `workers` worker threads of the server, returning once the
+    /// graph was read.
+    pub(super) fn submit(
+        self: &Arc<Self>,
+        graph_file: &str,
+        filename_suffix: &str,
+        workers: usize,
+    ) -> Result<Submitted, (i64, String)> {
+        let filename_suffix = filename_suffix.to_string();
+        if workers == 0 {
+            return Err((
+                INVALID_PARAMS,
+                String::from("At least one worker is required."),
+            ));
+        }
+        let graph = DirectedAcyclicGraph::from_file(graph_file).map_err(|e| {
+            (
+                SERVER_ERROR,
+                format!("Failed reading {}: {}", graph_file, e),
+            )
+        })?;
+        {
+            let mut runs = self.runs.lock().unwrap();
+            if matches!(runs.get(&filename_suffix), Some(None))
+                || RunHeader::open(&filename_suffix).is_ok()
+            {
+                return Err((
+                    SERVER_ERROR,
+                    format!("Run {} is already executing.", filename_suffix),
+                ));
+            }
+            runs.insert(filename_suffix.clone(), None);
+        }
+        let mut options = self.options.clone();
+        options.graph_source = Some(graph_file.to_string());
+        let state = self.clone();
+        let submitted = filename_suffix.clone();
+        thread::spawn(move || {
+            let workers: Vec<_> = (0..workers)
+                .map(|_| {
+                    let (mut graph, options) = (graph.clone(), options.clone());
+                    let filename_suffix = filename_suffix.clone();
+                    thread::spawn(move || {
+                        let result = graph.execute_with_options(filename_suffix, &options);
+                        (graph, result)
+                    })
+                })
+                .collect();
+            let mut finished: Option<FinishedRun> = None;
+            for worker in workers {
+                let Ok((graph, result)) = worker.join() else {
+                    continue;
+                };
+                let error = result.err().map(|e| e.to_string());
+                if finished.as_ref().is_none_or(|run| run.error.is_none()) {
+                    finished = Some(FinishedRun { graph, error });
+                }
+            }
+            let finished = finished.unwrap_or(FinishedRun {
+                graph,
+                error: Some(String::from("All workers panicked.")),
+            });
+            state
+                .runs
+                .lock()
+                .unwrap()
+                .insert(filename_suffix, Some(finished));
+        });
+        Ok(Submitted {
+            filename_suffix: submitted,
+            workers,
+        })
+    }
+
+    /// Describes the run with `filename_suffix`: its `state` (`submitted`, `running`, `succeeded` or `failed`), its
+    /// `error` if it failed and the `status` of each of its `nodes`. Runs in shared memory are read live, submitted
+    /// runs which already finished as their last worker left them.
+    pub(super) fn status(&self, filename_suffix: &str) -> Result<RunState, (i64, String)> {
+        let (state, graph, error) = match PosixSharedMemory::open_graph(filename_suffix) {
+            Ok((_, graph)) => {
+                let failed = !graph.get_failed_node_indices().is_empty();
+                let state = match (graph.is_graph_finished(), failed) {
+                    (false, _) => "running",
+                    (true, false) => "succeeded",
+                    (true, true) => "failed",
+                };
+                (state, Some(graph), None)
+            }
+            Err(e) => match self.runs.lock().unwrap().get(filename_suffix) {
+                Some(Some(run)) => (
+                    if run.error.is_some() {
+                        "failed"
+                    } else {
+                        "succeeded"
+                    },
+                    Some(run.graph.clone()),
+                    run.error.clone(),
+                ),
+                Some(None) => ("submitted", None, None),
+                None => {
+                    return Err((
+                        SERVER_ERROR,
+                        format!("No run {} in shared memory: {}", filename_suffix, e),
+                    ))
+                }
+            },
+        };
+        let nodes = graph
+            .iter()
+            .flat_map(|graph| {
+                graph.get_node_indices().map(|i| NodeState {
+                    id: graph.node_id(i).to_string(),
+                    status: graph[i].execution_status.to_string(),
+                })
+            })
+            .collect();
+        Ok(RunState {
+            filename_suffix: filename_suffix.to_string(),
+            state,
+            error,
+            nodes,
+        })
+    }
+}
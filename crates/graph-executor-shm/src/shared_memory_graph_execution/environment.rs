@@ -0,0 +1,118 @@
+use anyhow::{anyhow, Result};
+use graph_executor_core::graph_structure::graph::DirectedAcyclicGraph;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    process::Command,
+};
+
+/// Environment a run was created in, recorded in its [`super::run_header::RunHeader`] so that a later run can be
+/// checked to execute the same graph in the same environment, see [`EnvironmentManifest::differences`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct EnvironmentManifest {
+    /// [`DirectedAcyclicGraph::content_hash`] of the executed graph.
+    pub graph_hash: String,
+    /// Values of the allowlisted environment variables, [`None`] if a variable was not set.
+    pub variables: BTreeMap<String, Option<String>>,
+    /// Output of every declared tool probe (e.g. `cc --version`) by the probe's name.
+    pub tools: BTreeMap<String, String>,
+}
+
+impl fmt::Display for EnvironmentManifest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Graph: {}", self.graph_hash)?;
+        for (name, value) in &self.variables {
+            write!(f, "\n${}: {}", name, value.as_deref().unwrap_or("(unset)"))?;
+        }
+        for (name, version) in &self.tools {
+            write!(f, "\n{}: {}", name, version)?;
+        }
+        Ok(())
+    }
+}
+
+impl EnvironmentManifest {
+    /// Captures the environment of a run of `graph` in this process: the values of the `variables` and the output
+    /// of the shell commands of the `probes` (pairs of name and command), which must succeed.
+    pub fn capture(
+        graph: &DirectedAcyclicGraph,
+        variables: &[String],
+        probes: &[(String, String)],
+    ) -> Result<Self> {
+        let mut tools = BTreeMap::new();
+        for (name, command) in probes {
+            let output = Command::new("sh")
+                .arg("-c")
+                .arg(command)
+                .output()
+                .map_err(|e| anyhow!("Failed running probe {} ({:?}): {}", name, command, e))?;
+            if !output.status.success() {
+                return Err(anyhow!(
+                    "Probe {} ({:?}) failed: {}.",
+                    name,
+                    command,
+                    output.status
+                ));
+            }
+            tools.insert(
+                name.clone(),
+                String::from_utf8_lossy(&output.stdout).trim().to_string(),
+            );
+        }
+        Ok(EnvironmentManifest {
+            graph_hash: graph.content_hash(),
+            variables: variables
+                .iter()
+                .map(|name| (name.clone(), std::env::var(name).ok()))
+                .collect(),
+            tools,
+        })
+    }
+
+    /// Describes every difference between this environment and the `previous` one, e.g. to refuse executing a run
+    /// whose environment differs from the last recorded run. Variables and tools are compared by name, those
+    /// recorded in only one of both manifests differ as well.
+    pub fn differences(&self, previous: &EnvironmentManifest) -> Vec<String> {
+        let mut differences = vec![];
+        if self.graph_hash != previous.graph_hash {
+            differences.push(format!(
+                "graph hash {} differs from {}",
+                self.graph_hash, previous.graph_hash
+            ));
+        }
+        let format_value = |value: Option<&Option<String>>| match value {
+            Some(Some(value)) => format!("{:?}", value),
+            Some(None) => String::from("unset"),
+            None => String::from("not recorded"),
+        };
+        let names: BTreeSet<&String> = previous
+            .variables
+            .keys()
+            .chain(self.variables.keys())
+            .collect();
+        for name in names {
+            let (value, previous_value) = (self.variables.get(name), previous.variables.get(name));
+            if value != previous_value {
+                differences.push(format!(
+                    "${} is {}, was {}",
+                    name,
+                    format_value(value),
+                    format_value(previous_value)
+                ));
+            }
+        }
+        let names: BTreeSet<&String> = previous.tools.keys().chain(self.tools.keys()).collect();
+        for name in names {
+            let (version, previous_version) = (self.tools.get(name), previous.tools.get(name));
+            if version != previous_version {
+                differences.push(format!(
+                    "{} is {:?}, was {:?}",
+                    name,
+                    version.map_or("not recorded", String::as_str),
+                    previous_version.map_or("not recorded", String::as_str)
+                ));
+            }
+        }
+        differences
+    }
+}
@@ -0,0 +1,46 @@
+use super::execution_context::ExecutionContext;
+use anyhow::Result;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Rust computation executing a node with [`graph_executor_core::graph_structure::isolation::Isolation::Inline`] in
+/// the worker's thread, either registered with [`super::inline_functions::InlineFunctions::with_node`] under the
+/// name used as the node's `command` or attached to the node as the payload of a
+/// [`graph_executor_core::graph_structure::graph::DirectedAcyclicGraph`], see
+/// [`super::execute_graph::ExecuteGraph`].
+///
+/// The graph of a run is shared with other processes, which can't call into this one, so payloads stay with the
+/// worker which attached them: every worker which may claim an inline node attaches or registers its computation.
+pub trait ExecutableNode: Send + Sync {
+    /// Executes the node of `context`, returning its output once it finished or the error failing it.
+    fn execute(&self, context: &ExecutionContext) -> Result<NodeOutput>;
+}
+
+impl<T: ExecutableNode + ?Sized> ExecutableNode for Arc<T> {
+    fn execute(&self, context: &ExecutionContext) -> Result<NodeOutput> {
+        (**self).execute(context)
+    }
+}
+
+/// Output of an [`ExecutableNode`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NodeOutput {
+    /// Result passed to the node's children as its result blob, like the one a command node writes to
+    /// `$GRAPH_EXECUTOR_RESULT`, see [`ExecutionContext::parent_results`].
+    pub result: Option<Vec<u8>>,
+}
+
+impl NodeOutput {
+    /// Creates the output of a node passing `result` to its children.
+    pub fn with_result(result: Vec<u8>) -> Self {
+        NodeOutput {
+            result: Some(result),
+        }
+    }
+
+    /// Creates the output of a node passing `value` to its children, serialized with MessagePack like the run's
+    /// graph, so that they read it with [`ExecutionContext::parent_output`].
+    pub fn serialized<T: Serialize>(value: &T) -> Result<Self> {
+        Ok(NodeOutput::with_result(rmp_serde::to_vec(value)?))
+    }
+}
@@ -0,0 +1,1805 @@
+use super::{
+    audit::AuditLog,
+    command_output::{self, CommandOutput},
+    executable_node::{ExecutableNode, NodeOutput},
+    execution_context::ExecutionContext,
+    execution_options::ExecutionOptions,
+    execution_report::{ExecutionReport, RunFailed},
+    external_events::ExternalEvents,
+    failure_bundle::FailureBundle,
+    iteration_report::IterationReport,
+    lifecycle::LifecycleEvent,
+    preemption::PreemptionPolicy,
+    progress::ProgressFile,
+    progress::PROGRESS_VARIABLE,
+    remote_cache::{self, CacheStatistics},
+    resource_usage::{DiskFull, ResourceUsage},
+    result_blob::{self, ResultBlob, PARENT_RESULTS_VARIABLE, RESULT_VARIABLE},
+    run_header::RunHeader,
+    run_manifest::RunManifest,
+    systemd,
+    utilization::UtilizationTimeline,
+    workdir::Workdir,
+    worker_table::{WorkerRegistration, HEARTBEAT_INTERVAL},
+};
+use crate::shared_memory::posix_shared_memory::PosixSharedMemory;
+use anyhow::{anyhow, Error, Result};
+use graph_executor_core::{
+    clock::{Clock, SystemClock},
+    graph_structure::{
+        attempt::{Attempt, AttemptOutcome, Hook},
+        execution_record::ExecutionRecord,
+        execution_status::ExecutionStatus,
+        graph::DirectedAcyclicGraph,
+        isolation::Isolation,
+        node::Node,
+        node_role::NodeRole,
+    },
+    monotonic_timestamp::MonotonicTimestamp,
+    scheduler::{FifoScheduler, GraphView, Scheduler},
+};
+use petgraph::graph::NodeIndex;
+use rand::Rng;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, VecDeque},
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    process::Command,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+/// Time a worker waits for the other members of its node's gang to be claimed before releasing its claim.
+const GANG_BARRIER_TIMEOUT: Duration = Duration::from_secs(5);
+/// Interval at which a worker running a command node checks whether it should be preempted or was cancelled.
+const COMMAND_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Error of an attempt at executing a node which exceeded the node's timeout and was aborted, see
+/// [`Node::timeout`]. The attempt is recorded as [`AttemptOutcome::TimedOut`] instead of failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct NodeTimedOut {
+    /// Timeout of the node.
+    timeout: Duration,
+}
+
+impl std::fmt::Display for NodeTimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Timed out after {:?}.", self.timeout)
+    }
+}
+
+impl std::error::Error for NodeTimedOut {}
+/// Longest a worker without an executable node blocks waiting for the graph to change, after which it rechecks
+/// everything not changing the graph, e.g. idle workers of other processes for gangs or the run's deadline.
+const IDLE_WAIT_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Execution of a graph by workers sharing it and the state of its run via POSIX shared memory.
+pub trait ExecuteGraph {
+    /// Execute graph stored in shared memory mapping.
+    fn execute(&mut self, filename_suffix: String) -> Result<ExecutionReport>;
+
+    /// Execute graph stored in shared memory mapping with the supplied [`ExecutionOptions`].
+    fn execute_with_options(
+        &mut self,
+        filename_suffix: String,
+        options: &ExecutionOptions,
+    ) -> Result<ExecutionReport>;
+
+    /// Executes the graph `iterations` times in a row (e.g. as a control loop) like
+    /// [`ExecuteGraph::execute_with_options`], resetting it to its original state between iterations while keeping
+    /// the run's shared memory mappings and this worker's registration. Before each iteration, `ctx_fn` is called
+    /// with the iteration's index and returns the environment variables set for its command nodes (see
+    /// [`ExecutionOptions::environment`]).
+    ///
+    /// Iterations are reset by this worker alone, so the run should not be shared with other workers. The
+    /// iterations stop at the first failure or once `options.timeout` (which limits all iterations together) passed.
+    /// With `options.realtime`, iterations are released once per period and those exceeding the deadline are
+    /// reported in [`IterationReport::deadline_misses`].
+    fn execute_n(
+        &mut self,
+        filename_suffix: String,
+        iterations: usize,
+        options: &ExecutionOptions,
+        ctx_fn: impl FnMut(usize) -> Vec<(String, String)>,
+    ) -> Result<IterationReport>;
+}
+
+impl ExecuteGraph for DirectedAcyclicGraph {
+    fn execute(&mut self, filename_suffix: String) -> Result<ExecutionReport> {
+        self.execute_with_options(filename_suffix, &ExecutionOptions::default())
+    }
+
+    fn execute_with_options(
+        &mut self,
+        filename_suffix: String,
+        options: &ExecutionOptions,
+    ) -> Result<ExecutionReport> {
+        let mut run = self.join_run(filename_suffix, options)?;
+        self.execute_iteration(&mut run, options)?;
+        self.leave_run(run)
+    }
+
+    fn execute_n(
+        &mut self,
+        filename_suffix: String,
+        iterations: usize,
+        options: &ExecutionOptions,
+        mut ctx_fn: impl FnMut(usize) -> Vec<(String, String)>,
+    ) -> Result<IterationReport> {
+        let original = self.clone();
+        let mut options = options.clone();
+        let mut latencies = Vec::with_capacity(iterations);
+        let mut deadline_misses = vec![];
+        if let Some(realtime) = options.realtime {
+            realtime.check()?;
+            realtime.apply_scheduling_policy()?;
+        }
+        let mut run = self.join_run(filename_suffix, &options)?;
+        let clock = run.clock.clone();
+
+        let mut release = clock.now();
+        for iteration in 0..iterations {
+            if iteration > 0 {
+                // The generation keeps advancing across iterations, so that monitors notice the reset
+                *self = run
+                    .shared_memory
+                    .shm_update_graph(|graph| graph.clone_from(&original))?;
+                result_blob::remove_results(&run.filename_suffix)?;
+                result_blob::create_results_directory(&run.filename_suffix)?;
+            }
+            options.environment = ctx_fn(iteration);
+            // Latencies of real-time iterations are measured from their release, not from their actual start
+            clock.sleep(release.saturating_sub(clock.now()));
+            let started = match options.realtime {
+                Some(_) => release,
+                None => clock.now(),
+            };
+            self.execute_iteration(&mut run, &options)?;
+            // Failed and timed out iterations end the loop and are reported when leaving the run
+            if !self.is_graph_executed() {
+                break;
+            }
+            let latency = clock.elapsed(started);
+            latencies.push(latency);
+            if let Some(realtime) = options.realtime {
+                if latency > realtime.deadline {
+                    deadline_misses.push(iteration);
+                }
+                release = realtime.next_release(release, clock.as_ref());
+            }
+        }
+        Ok(IterationReport {
+            latencies,
+            deadline_misses,
+            report: self.leave_run(run)?,
+        })
+    }
+}
+
+/// Graphs whose nodes carry [`ExecutableNode`]s as payloads are executed like graphs without payloads, except that
+/// this worker executes the nodes with a payload by it, see [`super::inline_functions::InlineFunctions::with_payload`].
+/// Workers of other processes execute these nodes with the payloads they attached to their graph themselves.
+impl<P: ExecutableNode + Clone + 'static> ExecuteGraph for DirectedAcyclicGraph<P> {
+    fn execute(&mut self, filename_suffix: String) -> Result<ExecutionReport> {
+        self.execute_with_options(filename_suffix, &ExecutionOptions::default())
+    }
+
+    fn execute_with_options(
+        &mut self,
+        filename_suffix: String,
+        options: &ExecutionOptions,
+    ) -> Result<ExecutionReport> {
+        let options = with_payloads(self, options)?;
+        let mut graph = self.without_payloads();
+        let report = graph.execute_with_options(filename_suffix, &options);
+        *self = graph.with_payloads(|i, _| self.payload(i).cloned());
+        report
+    }
+
+    fn execute_n(
+        &mut self,
+        filename_suffix: String,
+        iterations: usize,
+        options: &ExecutionOptions,
+        ctx_fn: impl FnMut(usize) -> Vec<(String, String)>,
+    ) -> Result<IterationReport> {
+        let options = with_payloads(self, options)?;
+        let mut graph = self.without_payloads();
+        let report = graph.execute_n(filename_suffix, iterations, &options, ctx_fn);
+        *self = graph.with_payloads(|i, _| self.payload(i).cloned());
+        report
+    }
+}
+
+/// Returns `options` with the payloads of `graph` registered as the functions executing their nodes. Payloads can
+/// only execute nodes with [`Isolation::Inline`], since other nodes are executed in a child process.
+fn with_payloads<P: ExecutableNode + Clone + 'static>(
+    graph: &DirectedAcyclicGraph<P>,
+    options: &ExecutionOptions,
+) -> Result<ExecutionOptions> {
+    let mut options = options.clone();
+    for (i, payload) in graph.payloads() {
+        if graph[i].isolation() != Isolation::Inline {
+            return Err(anyhow!(
+                "Node {} has a payload, but is executed in a child process.",
+                graph.node_id(i)
+            ));
+        }
+        options.inline_functions = options
+            .inline_functions
+            .with_payload(graph.node_id(i), payload.clone());
+    }
+    Ok(options)
+}
+
+/// Shared memory mappings of a run and the registration of the worker executing it.
+pub(crate) struct Run {
+    pub(crate) filename_suffix: String,
+    header_shared_memory: PosixSharedMemory,
+    header: RunHeader,
+    pub(crate) shared_memory: PosixSharedMemory,
+    worker: WorkerRegistration,
+    clock: Arc<dyn Clock>,
+    /// Listeners on the iceoryx2 event services of the events awaited by the graph's nodes.
+    external_events: ExternalEvents,
+}
+
+/// Steps of [`ExecuteGraph::execute_with_options`] executed by a worker.
+pub(crate) trait ExecutionSteps {
+    fn join_run(&self, filename_suffix: String, options: &ExecutionOptions) -> Result<Run>;
+    fn execute_iteration(&mut self, run: &mut Run, options: &ExecutionOptions) -> Result<()>;
+    fn leave_run(&self, run: Run) -> Result<ExecutionReport>;
+    fn run_node(
+        &mut self,
+        shared_memory: &mut PosixSharedMemory,
+        worker: &mut WorkerRegistration,
+        header: &RunHeader,
+        options: &ExecutionOptions,
+        node_index: NodeIndex,
+    ) -> Result<()>;
+    #[allow(clippy::too_many_arguments)]
+    fn execute_node(
+        &mut self,
+        shared_memory: &mut PosixSharedMemory,
+        worker: &mut WorkerRegistration,
+        header: &RunHeader,
+        options: &ExecutionOptions,
+        node_index: NodeIndex,
+        started_after: Duration,
+        workdir: Option<&Workdir>,
+        output: Option<&CommandOutput>,
+    ) -> Result<bool>;
+    fn preempting_node(
+        &self,
+        worker: &mut WorkerRegistration,
+        node_index: NodeIndex,
+    ) -> Result<Option<NodeIndex>>;
+    #[allow(clippy::too_many_arguments)]
+    fn complete_node(
+        &mut self,
+        shared_memory: &mut PosixSharedMemory,
+        worker: &mut WorkerRegistration,
+        header: &RunHeader,
+        node_index: NodeIndex,
+        started_after: Duration,
+        outcome: AttemptOutcome,
+        output: Option<&CommandOutput>,
+    ) -> Result<()>;
+    fn record_failure(
+        &mut self,
+        shared_memory: &mut PosixSharedMemory,
+        worker: &mut WorkerRegistration,
+        options: &ExecutionOptions,
+        node_index: NodeIndex,
+        attempt: Attempt,
+    ) -> Result<()>;
+    fn record_cancelled_attempt(
+        &mut self,
+        shared_memory: &mut PosixSharedMemory,
+        worker: &mut WorkerRegistration,
+        header: &RunHeader,
+        node_index: NodeIndex,
+        started_after: Duration,
+    ) -> Result<()>;
+    fn cancel_if_past_deadline(
+        &mut self,
+        shared_memory: &mut PosixSharedMemory,
+        header: &RunHeader,
+        clock: &dyn Clock,
+    ) -> Result<()>;
+    fn failure_error(&self, filename_suffix: &str, failed_node_indices: &[NodeIndex]) -> Error;
+    fn wait_for_gang(
+        &mut self,
+        shared_memory: &mut PosixSharedMemory,
+        worker: &mut WorkerRegistration,
+        node_index: NodeIndex,
+        gang: &str,
+    ) -> Result<bool>;
+    fn execution_report(
+        &self,
+        shared_memory: &PosixSharedMemory,
+        header: RunHeader,
+        resource_usage: ResourceUsage,
+        worker_count: usize,
+    ) -> Result<ExecutionReport>;
+}
+
+impl ExecutionSteps for DirectedAcyclicGraph {
+    /// Checks the graph against `options`, creates or attaches to the run with `filename_suffix` and registers this
+    /// worker in it.
+    fn join_run(&self, filename_suffix: String, options: &ExecutionOptions) -> Result<Run> {
+        // Gangs larger than the cap on executing nodes would never be started
+        if let Some(jobs) = options.jobs {
+            if jobs == 0 {
+                return Err(anyhow!(
+                    "At least one job is required to execute the graph."
+                ));
+            }
+            if let Some(gang) = self.get_node_indices().find_map(|i| {
+                self[i]
+                    .gang()
+                    .filter(|gang| self.get_gang_node_indices(gang).len() > jobs)
+            }) {
+                return Err(anyhow!(
+                    "Gang {} has more nodes than the {} jobs allowed to execute at the same time.",
+                    gang,
+                    jobs
+                ));
+            }
+        }
+
+        // Members of a gang sharing a concurrency key could never execute together
+        if let Some((gang, mutex)) = self.get_node_indices().find_map(|i| {
+            let (gang, mutex) = (self[i].gang()?, self[i].mutex()?);
+            let members = self.get_gang_node_indices(gang);
+            (members
+                .iter()
+                .filter(|j| self[**j].mutex() == Some(mutex))
+                .count()
+                > 1)
+            .then_some((gang, mutex))
+        }) {
+            return Err(anyhow!(
+                "Gang {} has multiple nodes with mutex {}, which can't execute at the same time.",
+                gang,
+                mutex
+            ));
+        }
+
+        let clock: Arc<dyn Clock> = match &options.clock {
+            Some(clock) => clock.clone(),
+            None => Arc::new(SystemClock),
+        };
+        // Create/attach to the run's header first, so that incompatible workers never read the graph's mapping.
+        let (header_shared_memory, header) = RunHeader::create_or_attach(
+            &filename_suffix,
+            &RunHeader {
+                created_at: MonotonicTimestamp::now_on(clock.as_ref())?,
+                deadline: options.timeout,
+                environment: options.environment_manifest.clone(),
+                audit_log: absolute_path(options.audit_log.as_deref())?,
+                run_manifest: absolute_path(options.run_manifest.as_deref())?,
+                failure_bundle: absolute_path(options.failure_bundle.as_deref())?,
+                ..RunHeader::new(self, options.graph_source.clone())?
+            },
+        )?;
+        // Never attach to a run of another graph, e.g. after a typo in the filename suffix: its nodes would be
+        // executed with this graph's definition and its shared memory overwritten.
+        if header.graph_hash != self.content_hash() {
+            return Err(anyhow!(
+                "Run {} executes graph {} (created by pid {}), not this worker's graph {}; refusing to attach.",
+                filename_suffix,
+                header.graph_hash,
+                header.creator_pid,
+                self.content_hash()
+            ));
+        }
+        // Create/open shared memory mapping for `graph`.
+        let (mut shared_memory, _) =
+            PosixSharedMemory::create_or_open_graph(&filename_suffix, self, options.max_shm_bytes)?;
+        if let Some(audit_log) = &header.audit_log {
+            shared_memory.set_write_observer(Some(
+                AuditLog::open(audit_log, &filename_suffix)?.write_observer(),
+            ));
+        }
+        if options.lock_memory {
+            if let Err(e) = shared_memory.lock_in_memory() {
+                eprintln!("Warning: {}; continuing without locking.", e);
+            }
+        }
+        if !header.created_at.is_from_current_boot()? {
+            return Err(anyhow!(
+                "Shared memory of run {} was created during a previous boot and is stale.",
+                filename_suffix
+            ));
+        }
+        let worker = WorkerRegistration::register(
+            &filename_suffix,
+            options.worker_cost_rate,
+            clock.clone(),
+        )?;
+        let external_events = ExternalEvents::listen(self)?;
+        result_blob::create_results_directory(&filename_suffix)?;
+        systemd::notify("READY=1")?;
+        Ok(Run {
+            filename_suffix,
+            header_shared_memory,
+            header,
+            shared_memory,
+            worker,
+            clock,
+            external_events,
+        })
+    }
+
+    /// Claims and executes nodes of `run` until no node is left to execute or, unless keeping going, any node failed.
+    fn execute_iteration(&mut self, run: &mut Run, options: &ExecutionOptions) -> Result<()> {
+        let scheduler: &dyn Scheduler = match &options.scheduler {
+            Some(scheduler) => scheduler.as_ref(),
+            None => &FifoScheduler,
+        };
+        let Run {
+            header_shared_memory,
+            header,
+            shared_memory,
+            worker,
+            clock,
+            external_events,
+            ..
+        } = run;
+        let mut faults = options
+            .faults
+            .as_ref()
+            .map(|plan| plan.injector(worker.info().id));
+
+        let mut last_reclaim_check = clock.now();
+        loop {
+            // Get an executable `Node`, set `execution_status` for `node_index` to `ExecutionStatus::Executing` and execute associated `Node`.
+            // If no executable `Node` is available or the chosen `Node` is already being executed by another process wait for the graph to change.
+            let mut seen_change = shared_memory.change_sequence();
+            *self = shared_memory.read_graph()?;
+            let node_index = 'x: loop {
+                self.cancel_if_past_deadline(shared_memory, header, clock.as_ref())?;
+                // End loop if no node is left to execute or, unless keeping going, as soon as any node failed and no
+                // teardown node is left to execute
+                if self.is_graph_finished()
+                    || (!options.keep_going
+                        && !self.get_failed_node_indices().is_empty()
+                        && !self.is_teardown_pending())
+                {
+                    return Ok(());
+                }
+                // Record when nodes became executable, from which the priority scheduler ages them
+                if self
+                    .get_executable_node_indices()
+                    .into_iter()
+                    .any(|i| self[i].enqueued_after.is_none())
+                {
+                    let elapsed = header.created_at.elapsed_on(clock.as_ref())?;
+                    *self = shared_memory.shm_record_enqueue_times(self, elapsed)?;
+                }
+                // Idle workers are only relevant for claiming gangs
+                let idle_worker_count = match self.has_gangs() {
+                    true => worker.idle_worker_count()?,
+                    false => 1,
+                };
+                // Try to execute an `Executable` `Node` picked by the scheduler, unless the maximum number of nodes
+                // is already executing
+                let picked_node_index = match options.jobs {
+                    Some(jobs) if self.get_executing_node_count() >= jobs => None,
+                    _ => scheduler.pick(
+                        &GraphView::new(
+                            self,
+                            header.created_at.elapsed_on(clock.as_ref())?,
+                            idle_worker_count,
+                        ),
+                        worker.info(),
+                    ),
+                };
+                if let Some(i) = picked_node_index {
+                    if let Some(faults) = &mut faults {
+                        faults.delay_lock(clock.as_ref());
+                        if faults.claim_conflict() {
+                            *self = shared_memory.read_graph()?;
+                            continue;
+                        }
+                    }
+                    match shared_memory.shm_claim_node(self, i, options.jobs)? {
+                        Some(new_dag_in_shm) => *self = new_dag_in_shm, // Update `dag_in_shm` representation if the graph in shared memory was changed in the meantime
+                        None => break 'x i, // Return current graph and `NodeIndex` if no process has already started executing associated `Node` in the meantime
+                    }
+                }
+                // Update `dag_in_shm`
+                else {
+                    worker.heartbeat()?;
+                    // Release the nodes awaiting events notified on their iceoryx2 services
+                    let notified = external_events.notified()?;
+                    if !notified.is_empty() {
+                        *self = shared_memory.shm_update_graph(
+                            |graph: &mut DirectedAcyclicGraph| {
+                                for event in &notified {
+                                    graph.receive_event(event);
+                                }
+                            },
+                        )?;
+                        continue;
+                    }
+                    // Requeue the nodes of workers which died while executing them, so that the run doesn't wait
+                    // for them forever
+                    if let Some(reclaim_after) = options.reclaim_after {
+                        if clock.elapsed(last_reclaim_check) >= HEARTBEAT_INTERVAL {
+                            last_reclaim_check = clock.now();
+                            let reclaimed_nodes = worker.reclaim_dead_workers(
+                                header,
+                                self,
+                                shared_memory,
+                                reclaim_after,
+                            )?;
+                            if !reclaimed_nodes.is_empty() {
+                                eprintln!(
+                                    "Warning: requeued nodes of dead workers: {}",
+                                    reclaimed_nodes.join(", ")
+                                );
+                            }
+                        }
+                    }
+                    // Block until another worker changes the graph if no executable `Node` is available, waking up
+                    // for heartbeats, reclaim checks and the deadline. Virtual time only passes when slept on.
+                    match clock.passes_in_real_time() {
+                        true => shared_memory.wait_for_change(seen_change, IDLE_WAIT_TIMEOUT)?,
+                        false => clock.sleep(Duration::from_millis(10)),
+                    }
+                    seen_change = shared_memory.change_sequence();
+                    *self = shared_memory.read_graph()?;
+                }
+            };
+            self[node_index].execution_status = ExecutionStatus::Executing;
+            worker.set_current_node(Some(self.node_id(node_index)))?;
+            // An injected crash leaves the node executing and the run's mappings in place, like a killed process
+            if faults.as_mut().is_some_and(|faults| faults.crash()) {
+                header_shared_memory.set_ownership(false)?;
+                shared_memory.set_ownership(false)?;
+                worker.set_ownership(false)?;
+                return Err(anyhow!(
+                    "Injected crash of worker {} while executing node {}.",
+                    worker.info().id,
+                    self.node_id(node_index)
+                ));
+            }
+
+            // Start members of a gang together
+            if let Some(gang) = self[node_index].gang().map(str::to_string) {
+                if !self.wait_for_gang(shared_memory, worker, node_index, &gang)? {
+                    continue;
+                }
+            }
+            self.run_node(shared_memory, worker, header, options, node_index)?;
+            let captured_bytes = options.output_directory.as_deref().map_or(0, |directory| {
+                command_output::captured_bytes(directory, worker.filename_suffix(), node_index)
+            });
+            // A run exceeding its disk cap is cancelled, so that its workers stop writing, and fails once they left it
+            if let Err(error) = worker.record_disk_write(
+                self.node_id(node_index),
+                captured_bytes,
+                options.max_disk_bytes,
+            ) {
+                if !error.is::<DiskFull>() {
+                    return Err(error);
+                }
+                *self = shared_memory
+                    .shm_update_graph(|graph: &mut DirectedAcyclicGraph| graph.cancel())?;
+            }
+            result_blob::release_consumed_results(worker.filename_suffix(), self)?;
+        }
+    }
+
+    /// Detaches this worker from `run`, whose shared memory mappings are removed by the last worker leaving it, and
+    /// reports the run or fails with [`RunFailed`], carrying its report, if any of its nodes failed or it exceeded its
+    /// disk cap.
+    fn leave_run(&self, run: Run) -> Result<ExecutionReport> {
+        let Run {
+            filename_suffix,
+            mut header_shared_memory,
+            header,
+            mut shared_memory,
+            mut worker,
+            clock,
+            ..
+        } = run;
+        let is_last_worker = worker.detach()?;
+        let (resource_usage, disk_full) = worker.disk_usage()?;
+        if let (true, Some(path)) = (is_last_worker, &header.run_manifest) {
+            RunManifest {
+                filename_suffix: filename_suffix.clone(),
+                graph_source: header.graph_source.clone(),
+                graph: shared_memory.read_graph()?,
+            }
+            .write(path)?;
+        }
+        let failed_node_indices = self.get_failed_node_indices();
+        if is_last_worker {
+            worker.publish(LifecycleEvent::RunCompleted {
+                failed: !failed_node_indices.is_empty() || disk_full.is_some(),
+            })?;
+        }
+        let failure_bundle = match (is_last_worker, &header.failure_bundle) {
+            (true, Some(path)) if !failed_node_indices.is_empty() => {
+                FailureBundle::collect(&filename_suffix, &header, &shared_memory.read_graph()?)?
+                    .write(path, &format!("graph-executor-{}", filename_suffix))?;
+                Some(path.clone())
+            }
+            _ => None,
+        };
+        shared_memory.set_ownership(is_last_worker)?;
+        header_shared_memory.set_ownership(is_last_worker)?;
+        worker.set_ownership(is_last_worker)?;
+        // The results of nodes without children outlive the run in the last worker's report
+        let mut results = BTreeMap::new();
+        if is_last_worker {
+            for i in self
+                .get_node_indices()
+                .filter(|i| self.get_child_node_indices(*i).next().is_none())
+            {
+                if let Some(blob) = ResultBlob::open(&filename_suffix, i)? {
+                    results.insert(self.node_id(i).to_string(), Arc::new(blob));
+                }
+            }
+            result_blob::remove_results(&filename_suffix)?;
+        }
+        let failure = match (disk_full, failed_node_indices.is_empty()) {
+            (Some(disk_full), _) => Some(Error::from(disk_full)),
+            (None, false) => {
+                let error = self.failure_error(&filename_suffix, &failed_node_indices);
+                Some(match failure_bundle {
+                    Some(path) => anyhow!("{}\nFailure bundle written to {}.", error, path),
+                    None => error,
+                })
+            }
+            (None, true) => None,
+        };
+        let worker_count = worker.worker_count()?;
+        let cache = worker.cache_statistics()?;
+        // Without failures, nodes are only left unexecuted if the run was cancelled, at its deadline or manually
+        let timed_out = failure.is_none()
+            && !self.is_graph_executed()
+            && header.is_past_deadline(clock.as_ref())?;
+        let mut report =
+            self.execution_report(&shared_memory, header, resource_usage, worker_count)?;
+        report.is_last_worker = is_last_worker;
+        report.results = results;
+        report.timed_out = timed_out;
+        report.cache = cache;
+        // Failed runs are reported as well, so that their attempts can still be exported
+        match failure {
+            Some(error) => Err(RunFailed {
+                report: Box::new(report),
+                error,
+            }
+            .into()),
+            None => Ok(report),
+        }
+    }
+
+    /// Executes the claimed `node_index` and records the attempt: the node is completed if it succeeded and retried
+    /// or marked as failed if it failed, see [`ExecutionSteps::execute_node`].
+    ///
+    /// The node's [`Hook::Pre`] runs before and its [`Hook::Post`] after its body (unless the body was preempted or
+    /// cancelled), both by this worker. A failing hook fails the attempt like its body, but is recorded as the
+    /// attempt's [`Attempt::hook`], so that reports attribute the failure to it. The post hook runs even if the body
+    /// failed, the failure is then attributed to the body.
+    fn run_node(
+        &mut self,
+        shared_memory: &mut PosixSharedMemory,
+        worker: &mut WorkerRegistration,
+        header: &RunHeader,
+        options: &ExecutionOptions,
+        node_index: NodeIndex,
+    ) -> Result<()> {
+        let started_after = header.created_at.elapsed_on(worker.clock())?;
+        // Like `make`, nodes whose outputs are newer than their inputs are completed without running them
+        if self[node_index].is_up_to_date() {
+            return self.complete_node(
+                shared_memory,
+                worker,
+                header,
+                node_index,
+                started_after,
+                AttemptOutcome::UpToDate,
+                None,
+            );
+        }
+        // Command nodes and their hooks run in an isolated working directory, which is kept if the attempt failed
+        let mut workdir = match (&options.workdir_root, self[node_index].command_process()) {
+            (Some(root), Some(_)) => Some(Workdir::create(
+                root,
+                worker.filename_suffix(),
+                node_index,
+                self[node_index].attempts().len() + 1,
+                options.keep_workdirs,
+            )?),
+            _ => None,
+        };
+        // Their output is captured per node if configured, replacing the output of the node's previous attempt
+        let output = match (
+            &options.output_directory,
+            self[node_index].command_process(),
+        ) {
+            (Some(directory), Some(_)) => Some(CommandOutput::create(
+                directory,
+                worker.filename_suffix(),
+                node_index,
+            )?),
+            _ => None,
+        };
+        // Nodes whose results are in the remote cache are completed with them, the others write them through. Their
+        // relative inputs and outputs are resolved against the directory their command runs in
+        let directory = command_directory(&self[node_index], workdir.as_ref());
+        let cache_key = match &options.remote_cache {
+            Some(cache) => {
+                let key = remote_cache::cache_key(
+                    self,
+                    worker.filename_suffix(),
+                    node_index,
+                    directory.as_deref(),
+                )?;
+                if remote_cache::read_through(
+                    cache.as_ref(),
+                    &key,
+                    self,
+                    worker.filename_suffix(),
+                    node_index,
+                    directory.as_deref(),
+                )? {
+                    worker.cache_lookup(true)?;
+                    if result_blob::result_path(worker.filename_suffix(), node_index).exists() {
+                        store_result(shared_memory, worker, node_index, options)?;
+                    }
+                    return self.complete_node(
+                        shared_memory,
+                        worker,
+                        header,
+                        node_index,
+                        started_after,
+                        AttemptOutcome::Cached,
+                        output.as_ref(),
+                    );
+                }
+                worker.cache_lookup(false)?;
+                Some(key)
+            }
+            None => None,
+        };
+        if let Err(error) = run_hook(
+            &self[node_index],
+            Hook::Pre,
+            options,
+            worker,
+            workdir.as_ref(),
+            output.as_ref(),
+        ) {
+            if let Some(workdir) = &mut workdir {
+                workdir.keep();
+            }
+            let attempt = Attempt {
+                hook: Some(Hook::Pre),
+                ..attempt(
+                    worker,
+                    header,
+                    started_after,
+                    AttemptOutcome::Failed,
+                    Some(error.to_string()),
+                )?
+            };
+            return self.record_failure(shared_memory, worker, options, node_index, attempt);
+        }
+        let (error, hook, outcome) = match self.execute_node(
+            shared_memory,
+            worker,
+            header,
+            options,
+            node_index,
+            started_after,
+            workdir.as_ref(),
+            output.as_ref(),
+        ) {
+            Ok(true) => match run_hook(
+                &self[node_index],
+                Hook::Post,
+                options,
+                worker,
+                workdir.as_ref(),
+                output.as_ref(),
+            ) {
+                Ok(()) => {
+                    // A cache that can't be written to slows down later runs, but doesn't fail this one
+                    if let (Some(cache), Some(key)) = (&options.remote_cache, &cache_key) {
+                        match remote_cache::write_through(
+                            cache.as_ref(),
+                            key,
+                            self,
+                            worker.filename_suffix(),
+                            node_index,
+                            directory.as_deref(),
+                        ) {
+                            Ok(bytes) if cache.is_on_disk() => worker.wrote_to_disk(bytes),
+                            Ok(_) => {}
+                            Err(error) => eprintln!(
+                                "Warning: Failed caching results of {}: {}",
+                                self.node_id(node_index),
+                                error
+                            ),
+                        }
+                    }
+                    return self.complete_node(
+                        shared_memory,
+                        worker,
+                        header,
+                        node_index,
+                        started_after,
+                        AttemptOutcome::Succeeded,
+                        output.as_ref(),
+                    );
+                }
+                Err(error) => (error, Some(Hook::Post), AttemptOutcome::Failed),
+            },
+            Ok(false) => return Ok(()),
+            Err(error) => {
+                let outcome = match error.is::<NodeTimedOut>() {
+                    true => AttemptOutcome::TimedOut,
+                    false => AttemptOutcome::Failed,
+                };
+                let error = match run_hook(
+                    &self[node_index],
+                    Hook::Post,
+                    options,
+                    worker,
+                    workdir.as_ref(),
+                    output.as_ref(),
+                ) {
+                    Ok(()) => error,
+                    Err(post_error) => anyhow!("{} Post hook also failed: {}", error, post_error),
+                };
+                (error, None, outcome)
+            }
+        };
+        if let Some(workdir) = &mut workdir {
+            workdir.keep();
+        }
+        let attempt = Attempt {
+            hook,
+            ..attempt(
+                worker,
+                header,
+                started_after,
+                outcome,
+                Some(error.to_string()),
+            )?
+        };
+        self.record_failure(shared_memory, worker, options, node_index, attempt)
+    }
+
+    /// Executes the claimed `node_index` (whose attempt started `started_after` the start of the run), returns
+    /// `false` if it was preempted and requeued or cancelled instead, and a [`NodeTimedOut`] error if it exceeded its
+    /// [`Node::timeout`].
+    ///
+    /// Command nodes are polled while running, so that they are terminated once the run is cancelled (see
+    /// [`ExecutionOptions::keep_going`]), killed once they exceed their timeout and preempted if `options.preemption`
+    /// is enabled (see [`PreemptionPolicy`]).
+    /// The progress marks they append to their [`PROGRESS_VARIABLE`] file are recorded with the same interval, see
+    /// [`DirectedAcyclicGraph::mark_progress`].
+    #[allow(clippy::too_many_arguments)]
+    fn execute_node(
+        &mut self,
+        shared_memory: &mut PosixSharedMemory,
+        worker: &mut WorkerRegistration,
+        header: &RunHeader,
+        options: &ExecutionOptions,
+        node_index: NodeIndex,
+        started_after: Duration,
+        workdir: Option<&Workdir>,
+        output: Option<&CommandOutput>,
+    ) -> Result<bool> {
+        let Some(mut process) = self[node_index].command_process() else {
+            // Inline nodes are executed in this worker's thread, by their payload or the function registered under
+            // their command. A panic fails the node like an error instead of unwinding through the worker.
+            let (finished, filename_suffix, clock) = (
+                AtomicBool::new(false),
+                worker.filename_suffix(),
+                worker.clock(),
+            );
+            result_blob::remove_result(filename_suffix, node_index)?;
+            let node = &self[node_index];
+            let context = ExecutionContext::new(
+                node.clone(),
+                parent_results(self, filename_suffix, node_index),
+            );
+            let started = clock.now();
+            let result = thread::scope(|scope| {
+                scope.spawn(|| {
+                    watch_inline_node(
+                        filename_suffix,
+                        header,
+                        clock,
+                        node_index,
+                        &context,
+                        &finished,
+                    )
+                });
+                let payload = options.inline_functions.payload(self.node_id(node_index));
+                let result = catch_panic(|| match (payload, node.command()) {
+                    (Some(payload), _) => payload.execute(&context),
+                    (None, Some(name)) => options.inline_functions.call(name, &context),
+                    (None, None) => node.execute().map(|()| NodeOutput::default()),
+                });
+                finished.store(true, Ordering::SeqCst);
+                result
+            });
+            let timed_out = context
+                .node()
+                .timeout()
+                .filter(|timeout| clock.elapsed(started) >= *timeout);
+            // A cancelled node is not failed by the error its function may have returned when it stopped
+            if context.cancellation().is_cancelled() {
+                *self = shared_memory.read_graph()?;
+                self.cancel_if_past_deadline(shared_memory, header, worker.clock())?;
+                if self[node_index].execution_status == ExecutionStatus::Cancelled {
+                    self.record_cancelled_attempt(
+                        shared_memory,
+                        worker,
+                        header,
+                        node_index,
+                        started_after,
+                    )?;
+                    return Ok(false);
+                }
+            }
+            // Functions ignoring the cancellation are waited for, but their results are discarded
+            if let Some(timeout) = timed_out {
+                return Err(NodeTimedOut { timeout }.into());
+            }
+            if let Some(result) = result?.result {
+                std::fs::write(
+                    result_blob::result_path(worker.filename_suffix(), node_index),
+                    result,
+                )?;
+                store_result(shared_memory, worker, node_index, options)?;
+            }
+            return Ok(true);
+        };
+        prepare_process(&mut process, &self[node_index], options, workdir, output)?;
+        let mut progress_file = ProgressFile::create(node_index.index())?;
+        process.env(PROGRESS_VARIABLE, progress_file.path());
+        // A new blob is written for every attempt, so that readers of the previous one keep their mapping intact
+        let filename_suffix = worker.filename_suffix();
+        result_blob::remove_result(filename_suffix, node_index)?;
+        let parent_results: Vec<String> = parent_results(self, filename_suffix, node_index)
+            .iter()
+            .map(|(_, path)| path.display().to_string())
+            .collect();
+        process.env(
+            RESULT_VARIABLE,
+            result_blob::result_path(filename_suffix, node_index),
+        );
+        process.env(PARENT_RESULTS_VARIABLE, parent_results.join(" "));
+        let mut child = process.spawn()?;
+        let pid = child.id() as libc::pid_t;
+        let (mut last_check, mut last_heartbeat) = (worker.clock().now(), worker.clock().now());
+        let mut started = worker.clock().now();
+
+        loop {
+            if let Some(status) = child.try_wait()? {
+                if !status.success() {
+                    return Err(anyhow!(
+                        "Command {:?} failed: {}.{}",
+                        self[node_index].command().unwrap_or_default(),
+                        status,
+                        last_error_line(output)
+                    ));
+                }
+                store_result(shared_memory, worker, node_index, options)?;
+                return Ok(true);
+            }
+            worker.heartbeat()?;
+            let clock = worker.clock();
+            clock.sleep(Duration::from_millis(10));
+            if clock.elapsed(last_check) < COMMAND_CHECK_INTERVAL {
+                continue;
+            }
+            last_check = clock.now();
+
+            let new_marks = progress_file.new_marks()?;
+            let is_heartbeat_due = clock.elapsed(last_heartbeat) >= HEARTBEAT_INTERVAL;
+            if new_marks > 0 || is_heartbeat_due {
+                let after = header.created_at.elapsed_on(clock)?;
+                shared_memory.shm_update_graph(|graph: &mut DirectedAcyclicGraph| {
+                    for _ in 0..new_marks {
+                        graph.mark_progress(node_index);
+                    }
+                    if is_heartbeat_due {
+                        graph.heartbeat(node_index, after);
+                    }
+                })?;
+            }
+            if is_heartbeat_due {
+                last_heartbeat = clock.now();
+            }
+            *self = shared_memory.read_graph()?;
+            self.cancel_if_past_deadline(shared_memory, header, clock)?;
+            if self[node_index].execution_status == ExecutionStatus::Cancelled {
+                // SAFETY: `pid` is the id of a child process which has not been reaped yet.
+                unsafe { libc::kill(pid, libc::SIGTERM) };
+                child.wait()?;
+                self.record_cancelled_attempt(
+                    shared_memory,
+                    worker,
+                    header,
+                    node_index,
+                    started_after,
+                )?;
+                return Ok(false);
+            }
+            // A hung command would keep its worker (and its gang, mutex or descendants) waiting forever
+            if let Some(timeout) = self[node_index]
+                .timeout()
+                .filter(|timeout| worker.clock().elapsed(started) >= *timeout)
+            {
+                // SAFETY: `pid` is the id of a child process which has not been reaped yet.
+                unsafe { libc::kill(pid, libc::SIGKILL) };
+                child.wait()?;
+                return Err(NodeTimedOut { timeout }.into());
+            }
+            if options.preemption == PreemptionPolicy::Disabled {
+                continue;
+            }
+            let Some(preempting_index) = self.preempting_node(worker, node_index)? else {
+                continue;
+            };
+            match options.preemption {
+                PreemptionPolicy::Disabled => (),
+                PreemptionPolicy::Terminate => {
+                    // SAFETY: `pid` is the id of a child process which has not been reaped yet.
+                    unsafe { libc::kill(pid, libc::SIGTERM) };
+                    child.wait()?;
+                    // Requeue the node, it is started from scratch by the next worker claiming it (unless the run
+                    // was cancelled in the meantime)
+                    let attempt = attempt(
+                        worker,
+                        header,
+                        started_after,
+                        AttemptOutcome::Preempted,
+                        None,
+                    )?;
+                    *self =
+                        shared_memory.shm_update_graph(|graph: &mut DirectedAcyclicGraph| {
+                            if graph[node_index].execution_status == ExecutionStatus::Executing {
+                                graph[node_index].execution_status = ExecutionStatus::Executable;
+                            }
+                            graph[node_index].attempts.push(attempt);
+                        })?;
+                    worker.set_current_node(None)?;
+                    return Ok(false);
+                }
+                PreemptionPolicy::Stop => {
+                    // Claim the preempting node, unless another worker was faster or its mutex was taken
+                    match shared_memory.shm_claim_node(self, preempting_index, None)? {
+                        Some(new_dag_in_shm) => *self = new_dag_in_shm,
+                        None => {
+                            // SAFETY: `pid` is the id of a child process which has not been reaped yet.
+                            unsafe { libc::kill(pid, libc::SIGSTOP) };
+                            self[preempting_index].execution_status = ExecutionStatus::Executing;
+                            worker.set_current_node(Some(self.node_id(preempting_index)))?;
+                            // The stopped node's heartbeats are sent on its behalf, and its timeout is paused
+                            let (resumed, clock, stopped_at) = (
+                                AtomicBool::new(false),
+                                worker.shared_clock(),
+                                worker.clock().now(),
+                            );
+                            let filename_suffix = worker.filename_suffix().to_string();
+                            let result = thread::scope(|scope| {
+                                scope.spawn(|| {
+                                    watch_stopped_node(
+                                        &filename_suffix,
+                                        header,
+                                        clock.as_ref(),
+                                        node_index,
+                                        &resumed,
+                                    )
+                                });
+                                let result = self.run_node(
+                                    shared_memory,
+                                    worker,
+                                    header,
+                                    options,
+                                    preempting_index,
+                                );
+                                resumed.store(true, Ordering::SeqCst);
+                                result
+                            });
+                            started += clock.elapsed(stopped_at);
+                            // SAFETY: `pid` is the id of a child process which has not been reaped yet.
+                            unsafe { libc::kill(pid, libc::SIGCONT) };
+                            if let Err(error) = result {
+                                // The command is not left running without its worker, the attempt fails instead
+                                // SAFETY: `pid` is the id of a child process which has not been reaped yet.
+                                unsafe { libc::kill(pid, libc::SIGKILL) };
+                                child.wait()?;
+                                return Err(anyhow!(
+                                    "Preempting node {} failed: {}",
+                                    self.node_id(preempting_index),
+                                    error
+                                ));
+                            }
+                            worker.set_current_node(Some(self.node_id(node_index)))?;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the executable node which should preempt the running `node_index`, if any: the executable node with
+    /// the highest priority, if its priority is higher than `node_index`'s, no worker is idle and `node_index` is the
+    /// running command node with the lowest priority (ties are broken by string identifier, the last one is preempted).
+    fn preempting_node(
+        &self,
+        worker: &mut WorkerRegistration,
+        node_index: NodeIndex,
+    ) -> Result<Option<NodeIndex>> {
+        let Some(highest_executable) = self
+            .get_executable_node_indices()
+            .into_iter()
+            .filter(|i| {
+                self[*i].gang().is_none()
+                    && !self[*i].is_awaiting_approval()
+                    && !self[*i].is_awaiting_event()
+            })
+            .filter(|i| {
+                self[*i]
+                    .mutex()
+                    .is_none_or(|mutex| !self.is_mutex_held(mutex))
+            })
+            .rev() // `max_by_key` returns the last maximum
+            .max_by_key(|i| self[*i].priority())
+        else {
+            return Ok(None);
+        };
+        if self[highest_executable].priority() <= self[node_index].priority() {
+            return Ok(None);
+        }
+        let lowest_running = self
+            .get_node_indices()
+            .filter(|i| {
+                self[*i].execution_status == ExecutionStatus::Executing
+                    && self[*i].isolation() == Isolation::Process
+            })
+            .min_by_key(|i| (self[*i].priority(), Reverse(self.node_id(*i))));
+        if lowest_running != Some(node_index) || worker.idle_worker_count()? > 0 {
+            return Ok(None);
+        }
+        Ok(Some(highest_executable))
+    }
+
+    /// Marks the executed `node_index` as [`ExecutionStatus::Executed`], recording an attempt with `outcome`
+    /// ([`AttemptOutcome::Succeeded`], [`AttemptOutcome::UpToDate`] or [`AttemptOutcome::Cached`]), and its children
+    /// whose parents are all executed (or streaming to them, see [`DirectedAcyclicGraph::is_streaming`]) as
+    /// [`ExecutionStatus::Executable`].
+    fn complete_node(
+        &mut self,
+        shared_memory: &mut PosixSharedMemory,
+        worker: &mut WorkerRegistration,
+        header: &RunHeader,
+        node_index: NodeIndex,
+        started_after: Duration,
+        outcome: AttemptOutcome,
+        output: Option<&CommandOutput>,
+    ) -> Result<()> {
+        // Set `execution_status` for `node_index` to `ExecutionStatus::Executed` and record the execution.
+        let attempt = attempt(worker, header, started_after, outcome, None)?;
+        let execution_record = ExecutionRecord {
+            worker_id: worker.info().id,
+            output_bytes: output_bytes(worker.filename_suffix(), node_index, output),
+            finished_after: attempt.finished_after,
+        };
+        self[node_index].execution_status = ExecutionStatus::Executed;
+        self[node_index].execution_record = Some(execution_record);
+        self[node_index].attempts.push(attempt.clone());
+        if let Some(new_dag_in_shm) = shared_memory
+            .shm_compare_node_execution_status_and_update_with_record(
+                node_index,
+                ExecutionStatus::Executed,
+                Some(execution_record),
+                Some(attempt.clone()),
+            )?
+        {
+            // The run was cancelled while executing a node which can't be stopped, its result is discarded
+            if new_dag_in_shm[node_index].execution_status == ExecutionStatus::Cancelled {
+                let attempt = Attempt {
+                    outcome: AttemptOutcome::Cancelled,
+                    ..attempt
+                };
+                *self = shared_memory.shm_update_graph(|graph: &mut DirectedAcyclicGraph| {
+                    graph[node_index].attempts.push(attempt)
+                })?;
+                return worker.set_current_node(None);
+            }
+            // If a `DirectedAcyclicGraph` is returned, then the `node_index`' `execution_status` was changed by another process.
+            return Err(anyhow!(
+                "Execution status of {:?} changed: {} by another process.",
+                node_index,
+                new_dag_in_shm[node_index]
+            ));
+        };
+        worker.node_completed()?;
+        worker.publish(LifecycleEvent::NodeFinished {
+            node_index: node_index.index(),
+            status: ExecutionStatus::Executed,
+        })?;
+
+        // Get indeces of `Node`s that are now executable (due to all their parent nodes having been executed).
+        let mut children_indeces: VecDeque<NodeIndex> =
+            self.get_child_node_indices(node_index).collect();
+        // Iterate through all child nodes of `node_index`.
+        while !children_indeces.is_empty() {
+            // Get first `child_index` from queue.
+            let child_index = children_indeces.pop_front().ok_or(anyhow!(
+                "No child index despite queue having more than 0 elements"
+            ))?;
+
+            // Read graph from shared memory to learn newest execution statuses.
+            *self = shared_memory.read_graph()?;
+
+            // Determine whether all parent nodes `p` of child node are executed or executing. Teardown nodes also
+            // execute after parents which failed or were skipped or cancelled.
+            let is_teardown = self[child_index].role() == Some(NodeRole::Teardown);
+            let (all_executed, all_executed_or_executing) = {
+                let (mut all_executed, mut all_executed_or_executing) = (true, true);
+                for parent_index in self.get_parent_node_indices(child_index) {
+                    let execution_status = self[parent_index].execution_status;
+                    // Parents streaming to the child count as executed
+                    if self.is_streaming(parent_index, child_index) {
+                        continue;
+                    }
+                    // If some node is executing, then not all parent nodes are executed
+                    if execution_status == ExecutionStatus::Executing {
+                        all_executed = false;
+                    }
+                    // If some node is neither executed nor executing, then not all parent nodes are executed or executing
+                    else if execution_status != ExecutionStatus::Executed
+                        && !(is_teardown && execution_status.is_finished())
+                    {
+                        (all_executed, all_executed_or_executing) = (false, false);
+                        break;
+                    }
+                }
+                (all_executed, all_executed_or_executing)
+            };
+
+            // If all parent nodes (`parent_index`) of `child_index` are executed, then `child_index` is executable.
+            if all_executed {
+                // Write execution status to shared memory.
+                // Return value must be written immediately back to `current_graph`, because child node may be a parent of another child node.
+                match shared_memory.shm_compare_node_execution_status_and_update(
+                    child_index,
+                    ExecutionStatus::Executable,
+                )? {
+                    Some(new_dag_in_shm) => {
+                        self[child_index].execution_status =
+                            new_dag_in_shm[child_index].execution_status
+                    }
+                    None => self[child_index].execution_status = ExecutionStatus::Executable,
+                }
+            } else if all_executed_or_executing {
+                // Keep child index in queue to check parent execution status later to make sure node is set to executable.
+                children_indeces.push_back(child_index);
+            }
+        }
+        Ok(())
+    }
+
+    /// Records the failed `attempt` of the claimed `node_index` and requeues the node if it has retries left.
+    /// Otherwise, the node is marked as [`ExecutionStatus::Failed`] with the attempt's error and its descendants as
+    /// [`ExecutionStatus::Skipped`]. Unless `options.keep_going` is set, the rest of the run is then cancelled with
+    /// the same write (see [`DirectedAcyclicGraph::cancel`]), so that all workers stop claiming nodes and terminate
+    /// their running command nodes.
+    fn record_failure(
+        &mut self,
+        shared_memory: &mut PosixSharedMemory,
+        worker: &mut WorkerRegistration,
+        options: &ExecutionOptions,
+        node_index: NodeIndex,
+        attempt: Attempt,
+    ) -> Result<()> {
+        *self = shared_memory.shm_update_graph(|graph: &mut DirectedAcyclicGraph| {
+            let error = attempt.error.clone().unwrap_or_default();
+            let node = &mut graph[node_index];
+            node.attempts.push(attempt);
+            let failed_attempts = node
+                .attempts
+                .iter()
+                .filter(|attempt| {
+                    matches!(
+                        attempt.outcome,
+                        AttemptOutcome::Failed | AttemptOutcome::TimedOut
+                    )
+                })
+                .count();
+            if failed_attempts <= node.retries() as usize
+                && node.execution_status == ExecutionStatus::Executing
+            {
+                node.execution_status = ExecutionStatus::Executable;
+                return;
+            }
+            graph.fail_node(node_index, error);
+            if !options.keep_going {
+                graph.cancel();
+            }
+        })?;
+        if self[node_index].execution_status == ExecutionStatus::Failed {
+            worker.publish(LifecycleEvent::NodeFinished {
+                node_index: node_index.index(),
+                status: ExecutionStatus::Failed,
+            })?;
+        }
+        worker.set_current_node(None)
+    }
+
+    /// Cancels the rest of the run (see [`DirectedAcyclicGraph::cancel`]) once its deadline has passed.
+    /// Records the attempt at executing the cancelled `node_index`, which started `started_after` the start of the
+    /// run, and releases this worker's claim of it.
+    fn record_cancelled_attempt(
+        &mut self,
+        shared_memory: &mut PosixSharedMemory,
+        worker: &mut WorkerRegistration,
+        header: &RunHeader,
+        node_index: NodeIndex,
+        started_after: Duration,
+    ) -> Result<()> {
+        let attempt = attempt(
+            worker,
+            header,
+            started_after,
+            AttemptOutcome::Cancelled,
+            None,
+        )?;
+        *self = shared_memory.shm_update_graph(|graph: &mut DirectedAcyclicGraph| {
+            graph[node_index].attempts.push(attempt)
+        })?;
+        worker.publish(LifecycleEvent::NodeFinished {
+            node_index: node_index.index(),
+            status: ExecutionStatus::Cancelled,
+        })?;
+        worker.set_current_node(None)
+    }
+
+    fn cancel_if_past_deadline(
+        &mut self,
+        shared_memory: &mut PosixSharedMemory,
+        header: &RunHeader,
+        clock: &dyn Clock,
+    ) -> Result<()> {
+        if header.is_past_deadline(clock)? && !self.is_graph_finished() {
+            *self = shared_memory
+                .shm_update_graph(|graph: &mut DirectedAcyclicGraph| graph.cancel())?;
+        }
+        Ok(())
+    }
+
+    /// Builds the error returned by workers leaving the run with `filename_suffix` after `failed_node_indices`
+    /// failed.
+    fn failure_error(&self, filename_suffix: &str, failed_node_indices: &[NodeIndex]) -> Error {
+        let failures: Vec<String> = failed_node_indices
+            .iter()
+            .map(|i| {
+                // Failures of hooks are attributed to them, see `Attempt::hook`
+                let hook = match self[*i].attempts().last().and_then(|a| a.hook) {
+                    Some(hook) => format!(" ({} hook)", hook),
+                    None => String::new(),
+                };
+                format!(
+                    "\n    {}{}: {}",
+                    self.node_id(*i),
+                    hook,
+                    self[*i].error().unwrap_or_default()
+                )
+            })
+            .collect();
+        anyhow!(
+            "Run {} failed, {} node(s) failed:{}",
+            filename_suffix,
+            failures.len(),
+            failures.concat()
+        )
+    }
+
+    /// Barrier waiting until all members of the gang named `gang` were claimed after `node_index` was claimed.
+    /// If this takes longer than [`GANG_BARRIER_TIMEOUT`] (e.g. because workers are blocked in other gangs),
+    /// the claim of `node_index` is released and `false` is returned.
+    fn wait_for_gang(
+        &mut self,
+        shared_memory: &mut PosixSharedMemory,
+        worker: &mut WorkerRegistration,
+        node_index: NodeIndex,
+        gang: &str,
+    ) -> Result<bool> {
+        let is_gang_claimed = |graph: &DirectedAcyclicGraph| {
+            graph.get_gang_node_indices(gang).iter().all(|i| {
+                graph[*i].execution_status == ExecutionStatus::Executing
+                    || graph[*i].execution_status == ExecutionStatus::Executed
+            })
+        };
+        let started = worker.clock().now();
+
+        loop {
+            *self = shared_memory.read_graph()?;
+            if is_gang_claimed(self) {
+                return Ok(true);
+            }
+            if worker.clock().elapsed(started) > GANG_BARRIER_TIMEOUT {
+                // Release the claim unless the gang was completed in the meantime, checked under the write lock
+                *self = shared_memory.shm_update_graph(|graph: &mut DirectedAcyclicGraph| {
+                    if !is_gang_claimed(graph)
+                        && graph[node_index].execution_status == ExecutionStatus::Executing
+                    {
+                        graph[node_index].execution_status = ExecutionStatus::Executable;
+                    }
+                })?;
+                if is_gang_claimed(self) {
+                    return Ok(true);
+                }
+                worker.set_current_node(None)?;
+                // Back off randomly, so that workers blocking each other in different gangs don't collide again
+                worker
+                    .clock()
+                    .sleep(Duration::from_millis(rand::thread_rng().gen_range(0..100)));
+                return Ok(false);
+            }
+            worker.heartbeat()?;
+            worker.clock().sleep(Duration::from_millis(10));
+        }
+    }
+
+    /// Builds the [`ExecutionReport`] of a finished run from its header, the shared memory mapping's and the run's
+    /// resource usage and the number of workers which attached to it.
+    fn execution_report(
+        &self,
+        shared_memory: &PosixSharedMemory,
+        header: RunHeader,
+        mut resource_usage: ResourceUsage,
+        worker_count: usize,
+    ) -> Result<ExecutionReport> {
+        resource_usage.shm_bytes = shared_memory.allocated_bytes();
+        resource_usage.peak_shm_bytes = shared_memory.peak_allocated_bytes();
+        for node_index in self.get_node_indices() {
+            let node_id = self.node_id(node_index);
+            let serialized_node_len = rmp_serde::to_vec(&(node_id, &self[node_index]))?.len();
+            resource_usage
+                .per_node
+                .entry(node_id.to_string())
+                .or_default()
+                .shm_bytes = serialized_node_len;
+        }
+
+        Ok(ExecutionReport {
+            header,
+            is_last_worker: false,
+            timed_out: false,
+            up_to_date: self
+                .get_node_indices()
+                .filter(|i| {
+                    self[*i].attempts().last().map(|attempt| attempt.outcome)
+                        == Some(AttemptOutcome::UpToDate)
+                })
+                .map(|i| self.node_id(i).to_string())
+                .collect(),
+            cache: CacheStatistics::default(),
+            resource_usage,
+            utilization: UtilizationTimeline::new(self, worker_count),
+            results: BTreeMap::new(),
+        })
+    }
+}
+
+/// Resolves the optional `path` of a file recorded in the run's header against the working directory, so that all
+/// workers of the run use the same file.
+fn absolute_path(path: Option<&str>) -> Result<Option<String>> {
+    Ok(path
+        .map(|path| std::path::absolute(path).map(|path| path.display().to_string()))
+        .transpose()?)
+}
+
+/// Watches the inline node at `node_index` of the run with `filename_suffix` on behalf of the worker executing it,
+/// checking every [`COMMAND_CHECK_INTERVAL`] of `clock` until the node's execution `finished`: the progress reported through
+/// its `context` and the node's heartbeats are recorded in the run's graph, and the context's
+/// [cancellation token](ExecutionContext::cancellation) is cancelled once the node was cancelled, exceeded its
+/// [`Node::timeout`] or the run is past its deadline. The run's graph is only mapped once the node ran for an interval, so that short nodes don't pay for
+/// it.
+fn watch_inline_node(
+    filename_suffix: &str,
+    header: &RunHeader,
+    clock: &dyn Clock,
+    node_index: NodeIndex,
+    context: &ExecutionContext,
+    finished: &AtomicBool,
+) {
+    let (mut shared_memory, mut last_check, mut last_heartbeat) = (None, clock.now(), clock.now());
+    let started = clock.now();
+    while !finished.load(Ordering::SeqCst) {
+        clock.sleep(Duration::from_millis(10));
+        if clock.elapsed(last_check) < COMMAND_CHECK_INTERVAL {
+            continue;
+        }
+        last_check = clock.now();
+        let timeout = context.node().timeout();
+        if timeout.is_some_and(|timeout| clock.elapsed(started) >= timeout) {
+            context.cancellation().cancel();
+            return;
+        }
+        if shared_memory.is_none() {
+            shared_memory = open_for_watching(filename_suffix, header).ok();
+        }
+        let Some(shared_memory) = shared_memory.as_mut() else {
+            continue;
+        };
+        // The worker is busy executing the node, so its heartbeats are sent on its behalf
+        let heartbeat = match clock.elapsed(last_heartbeat) >= HEARTBEAT_INTERVAL {
+            true => header.created_at.elapsed_on(clock).ok(),
+            false => None,
+        };
+        let graph = match (context.take_progress(), heartbeat) {
+            (None, None) => shared_memory.read_graph(),
+            (progress, heartbeat) => shared_memory
+                .shm_update_graph(|graph: &mut DirectedAcyclicGraph| {
+                    if let Some(progress) = progress.clone() {
+                        graph.report_progress(node_index, progress);
+                    }
+                    if let Some(after) = heartbeat {
+                        graph.heartbeat(node_index, after);
+                    }
+                })
+                // Storages allocated by the write must outlive this mapping, see `super::watchdog::watch`
+                .and_then(|graph| shared_memory.set_ownership(false).map(|()| graph)),
+        };
+        if heartbeat.is_some() {
+            last_heartbeat = clock.now();
+        }
+        let is_cancelled = graph
+            .is_ok_and(|graph| graph[node_index].execution_status == ExecutionStatus::Cancelled);
+        if is_cancelled || header.is_past_deadline(clock).unwrap_or_default() {
+            context.cancellation().cancel();
+            return;
+        }
+    }
+}
+
+/// Sends the heartbeats of the command node at `node_index` of the run with `filename_suffix` on behalf of the worker
+/// executing it, every [`HEARTBEAT_INTERVAL`] of `clock` until the node is `resumed`, while its command is stopped for
+/// a preempting node (see [`PreemptionPolicy::Stop`]).
+fn watch_stopped_node(
+    filename_suffix: &str,
+    header: &RunHeader,
+    clock: &dyn Clock,
+    node_index: NodeIndex,
+    resumed: &AtomicBool,
+) {
+    let (mut shared_memory, mut last_heartbeat) = (None, clock.now());
+    while !resumed.load(Ordering::SeqCst) {
+        clock.sleep(Duration::from_millis(10));
+        if clock.elapsed(last_heartbeat) < HEARTBEAT_INTERVAL {
+            continue;
+        }
+        last_heartbeat = clock.now();
+        if shared_memory.is_none() {
+            shared_memory = open_for_watching(filename_suffix, header).ok();
+        }
+        let (Some(shared_memory), Ok(after)) =
+            (shared_memory.as_mut(), header.created_at.elapsed_on(clock))
+        else {
+            continue;
+        };
+        // Storages allocated by the write must outlive this mapping, see `super::watchdog::watch`
+        let _ = shared_memory
+            .shm_update_graph(|graph: &mut DirectedAcyclicGraph| {
+                graph.heartbeat(node_index, after);
+            })
+            .and_then(|_| shared_memory.set_ownership(false));
+    }
+}
+
+/// Opens the graph mapping of the run with `filename_suffix` for [`watch_inline_node`], with the run's audit log
+/// observing its writes.
+fn open_for_watching(filename_suffix: &str, header: &RunHeader) -> Result<PosixSharedMemory> {
+    let (mut shared_memory, _) = PosixSharedMemory::open_graph(filename_suffix)?;
+    if let Some(path) = &header.audit_log {
+        shared_memory.set_write_observer(Some(
+            AuditLog::open(path, filename_suffix)?.write_observer(),
+        ));
+    }
+    Ok(shared_memory)
+}
+
+/// Returns the string identifiers of the parents of the node at `node_index` in `graph`'s run identified by
+/// `filename_suffix` with the paths of their result blobs, ordered by string identifier. Parents which wrote no
+/// result blob are left out.
+pub(crate) fn parent_results(
+    graph: &DirectedAcyclicGraph,
+    filename_suffix: &str,
+    node_index: NodeIndex,
+) -> Vec<(String, PathBuf)> {
+    graph
+        .get_parent_node_indices(node_index)
+        .map(|i| {
+            (
+                graph.node_id(i).to_string(),
+                result_blob::result_path(filename_suffix, i),
+            )
+        })
+        .filter(|(_, path)| path.exists())
+        .collect()
+}
+
+/// Returns the number of bytes of output the node at `node_index` produced: the size of its result blob (also if it
+/// was deduplicated or spilled) or, if it wrote none, of its captured standard `output`, zero if it has neither.
+fn output_bytes(
+    filename_suffix: &str,
+    node_index: NodeIndex,
+    output: Option<&CommandOutput>,
+) -> u64 {
+    std::fs::metadata(result_blob::result_path(filename_suffix, node_index))
+        .or_else(|e| match output {
+            Some(output) => std::fs::metadata(output.stdout_path()),
+            None => Err(e),
+        })
+        .map_or(0, |metadata| metadata.len())
+}
+
+/// Stores the result blob the node at `node_index` wrote once it finished, deduplicating it and spilling it to disk
+/// rather than exhausting the run's shared memory budget. Spilled bytes count towards the disk usage of the `worker`'s
+/// run.
+fn store_result(
+    shared_memory: &PosixSharedMemory,
+    worker: &mut WorkerRegistration,
+    node_index: NodeIndex,
+    options: &ExecutionOptions,
+) -> Result<()> {
+    let filename_suffix = worker.filename_suffix();
+    result_blob::deduplicate(filename_suffix, node_index)?;
+    if let Some(max_shm_bytes) = options.max_shm_bytes {
+        let stored_bytes = result_blob::stored_bytes(filename_suffix)?;
+        if shared_memory.allocated_bytes() + stored_bytes > max_shm_bytes {
+            let spilled_bytes = result_blob::spill(
+                filename_suffix,
+                node_index,
+                &options
+                    .spill_directory
+                    .clone()
+                    .unwrap_or_else(std::env::temp_dir),
+            )?;
+            worker.wrote_to_disk(spilled_bytes);
+        }
+    }
+    Ok(())
+}
+
+/// Calls `function`, turning a panic into an error with the panic's message, so that the worker (and the other
+/// workers of its thread scope) survive panicking nodes.
+fn catch_panic<T>(function: impl FnOnce() -> Result<T>) -> Result<T> {
+    panic::catch_unwind(AssertUnwindSafe(function)).unwrap_or_else(|payload| {
+        let message = match (
+            payload.downcast_ref::<&str>(),
+            payload.downcast_ref::<String>(),
+        ) {
+            (Some(message), _) => message.to_string(),
+            (None, Some(message)) => message.clone(),
+            (None, None) => String::from("non-string payload"),
+        };
+        Err(anyhow!("Node panicked: {}", message))
+    })
+}
+
+/// Builds the [`Attempt`] of `worker` at executing a node which started `started_after` the start of the run and
+/// ends now with `outcome`.
+fn attempt(
+    worker: &WorkerRegistration,
+    header: &RunHeader,
+    started_after: Duration,
+    outcome: AttemptOutcome,
+    error: Option<String>,
+) -> Result<Attempt> {
+    Ok(Attempt {
+        worker_id: worker.info().id,
+        started_after,
+        finished_after: header.created_at.elapsed_on(worker.clock())?,
+        outcome,
+        error,
+        hook: None,
+    })
+}
+
+/// Sets up the `process` of the `node`'s command or hook: `options.environment` is added to its environment unless
+/// the node sets the same variables in its `env`, it runs in the node's `workdir` (its `cwd` then being relative to
+/// it) and its output is appended to the node's captured `output`.
+fn prepare_process(
+    process: &mut Command,
+    node: &Node,
+    options: &ExecutionOptions,
+    workdir: Option<&Workdir>,
+    output: Option<&CommandOutput>,
+) -> Result<()> {
+    process.envs(
+        options
+            .environment
+            .iter()
+            .filter(|(name, _)| !node.env().iter().any(|(node_name, _)| node_name == name))
+            .map(|(name, value)| (name, value)),
+    );
+    if let Some(workdir) = workdir {
+        workdir.apply(process);
+        if let Some(cwd) = node.cwd() {
+            process.current_dir(workdir.path().join(cwd));
+        }
+    }
+    if let Some(output) = output {
+        output.apply(process)?;
+    }
+    Ok(())
+}
+
+/// Directory in which the `node`'s command (and its hooks) run, see [`prepare_process`]: its `cwd`, relative to its
+/// `workdir` if it has one, or [`None`] for the working directory of this worker, in which inline nodes run as well.
+pub(crate) fn command_directory(node: &Node, workdir: Option<&Workdir>) -> Option<PathBuf> {
+    if node.isolation() == Isolation::Inline {
+        return None;
+    }
+    match (workdir, node.cwd()) {
+        (Some(workdir), Some(cwd)) => Some(workdir.path().join(cwd)),
+        (Some(workdir), None) => Some(workdir.path().to_path_buf()),
+        (None, Some(cwd)) => Some(PathBuf::from(cwd)),
+        (None, None) => None,
+    }
+}
+
+/// Formats the last line of the captured `output`'s standard error to be appended to the error of a failed command,
+/// empty if the output isn't captured or the command printed no errors.
+fn last_error_line(output: Option<&CommandOutput>) -> String {
+    output
+        .and_then(CommandOutput::last_error_line)
+        .map(|line| format!(" Last line of stderr: {}", line))
+        .unwrap_or_default()
+}
+
+/// Runs the shell command of the `node`'s `hook` (if it has one) to completion like the node's command, sending
+/// heartbeats for the `worker` meanwhile.
+fn run_hook(
+    node: &Node,
+    hook: Hook,
+    options: &ExecutionOptions,
+    worker: &mut WorkerRegistration,
+    workdir: Option<&Workdir>,
+    output: Option<&CommandOutput>,
+) -> Result<()> {
+    let Some(mut process) = node.hook_process(hook) else {
+        return Ok(());
+    };
+    prepare_process(&mut process, node, options, workdir, output)?;
+    let mut child = process.spawn()?;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            if !status.success() {
+                return Err(anyhow!(
+                    "Hook {:?} failed: {}.{}",
+                    node.hook(hook).unwrap_or_default(),
+                    status,
+                    last_error_line(output)
+                ));
+            }
+            return Ok(());
+        }
+        worker.heartbeat()?;
+        worker.clock().sleep(Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,162 @@
+use anyhow::{anyhow, Result};
+use graph_executor_core::graph_structure::{node::Node, node_progress::NodeProgress};
+use serde::de::DeserializeOwned;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// Context of a node executed inline by a worker, passed to its
+/// [`super::inline_functions::InlineFunction`] next to the node.
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionContext {
+    /// The executed node.
+    node: Node,
+    /// Paths of the result blobs of the node's parents, see [`ExecutionContext::parent_results`].
+    parent_results: Vec<PathBuf>,
+    /// String identifiers of the parents which wrote the `parent_results`, in the same order.
+    parent_ids: Vec<String>,
+    /// Token cancelled once the node's execution should stop.
+    cancellation: CancellationToken,
+    /// Latest progress reported by the node which was not yet recorded in the run's graph.
+    progress: Arc<Mutex<Option<NodeProgress>>>,
+}
+
+impl ExecutionContext {
+    /// Creates the context of executing `node`, whose parents wrote the result blobs at `parent_results`, by
+    /// their string identifiers.
+    pub(crate) fn new(node: Node, parent_results: Vec<(String, PathBuf)>) -> Self {
+        let (parent_ids, parent_results) = parent_results.into_iter().unzip();
+        ExecutionContext {
+            node,
+            parent_results,
+            parent_ids,
+            ..Default::default()
+        }
+    }
+
+    /// Get the executed node, e.g. to read its args.
+    pub fn node(&self) -> &Node {
+        &self.node
+    }
+
+    /// Get the paths of the result blobs of the node's parents, ordered by their string identifiers, like
+    /// `$GRAPH_EXECUTOR_PARENT_RESULTS` of command nodes. Parents which wrote no result blob are left out.
+    pub fn parent_results(&self) -> &[PathBuf] {
+        &self.parent_results
+    }
+
+    /// Get the path of the result blob of the parent identified by `parent_id`, if it wrote one.
+    pub fn parent_result(&self, parent_id: &str) -> Option<&Path> {
+        let i = self.parent_ids.iter().position(|id| id == parent_id)?;
+        Some(&self.parent_results[i])
+    }
+
+    /// Reads the output the parent identified by `parent_id` passed with
+    /// [`super::executable_node::NodeOutput::serialized`], e.g. `context.parent_output::<Vec<u64>>("load")?`.
+    /// Fails if it is not a parent of the node, passed no output or passed an output of another type.
+    pub fn parent_output<T: DeserializeOwned>(&self, parent_id: &str) -> Result<T> {
+        let path = self.parent_result(parent_id).ok_or(anyhow!(
+            "Node {} has no output of a parent {}.",
+            self.node.args(),
+            parent_id
+        ))?;
+        rmp_serde::from_slice(&std::fs::read(path)?).map_err(|e| {
+            anyhow!(
+                "Failed reading output of parent {} of node {}: {}",
+                parent_id,
+                self.node.args(),
+                e
+            )
+        })
+    }
+
+    /// Get the token which is cancelled once the run is cancelled (e.g. because another node failed) or reaches its
+    /// deadline, so that long-running functions can stop promptly.
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    /// Reports that the node completed `fraction` (from 0.0 to 1.0) of its work, optionally with a `message`
+    /// describing what it is doing. The latest report is recorded in the run's graph periodically (see
+    /// [`graph_executor_core::graph_structure::graph::DirectedAcyclicGraph::report_progress`]) and shown by
+    /// `status`, so that nodes taking minutes don't look hung.
+    pub fn report_progress(&self, fraction: f64, message: Option<&str>) {
+        *self.progress.lock().unwrap() =
+            Some(NodeProgress::new(fraction, message.map(String::from)));
+    }
+
+    /// Takes the latest progress reported since the last call, if any.
+    pub(crate) fn take_progress(&self) -> Option<NodeProgress> {
+        self.progress.lock().unwrap().take()
+    }
+}
+
+/// Cooperative cancellation of a node's execution: the node's function either polls the token or registers
+/// callbacks on it, e.g. to interrupt a blocking call. Clones share the same state.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<CancellationState>,
+}
+
+/// State shared by the clones of a [`CancellationToken`].
+#[derive(Default)]
+struct CancellationState {
+    /// Whether the token was cancelled.
+    cancelled: AtomicBool,
+    /// Callbacks which are called once the token is cancelled.
+    callbacks: Mutex<Vec<Box<dyn FnOnce() + Send>>>,
+}
+
+impl fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+impl CancellationToken {
+    /// Checks whether the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Returns an error if the token was cancelled, so that functions can stop with `?` at convenient points.
+    pub fn check(&self) -> Result<()> {
+        match self.is_cancelled() {
+            true => Err(anyhow!("Execution was cancelled.")),
+            false => Ok(()),
+        }
+    }
+
+    /// Registers `callback` to be called (by the thread cancelling the token) once the token is cancelled, or calls
+    /// it right away if it already was.
+    pub fn on_cancel(&self, callback: impl FnOnce() + Send + 'static) {
+        let mut callbacks = self.state.callbacks.lock().unwrap();
+        if !self.is_cancelled() {
+            callbacks.push(Box::new(callback));
+            return;
+        }
+        drop(callbacks);
+        callback();
+    }
+
+    /// Cancels the token and calls its callbacks, unless it already was cancelled.
+    pub fn cancel(&self) {
+        let callbacks = {
+            let mut callbacks = self.state.callbacks.lock().unwrap();
+            if self.state.cancelled.swap(true, Ordering::SeqCst) {
+                return;
+            }
+            std::mem::take(&mut *callbacks)
+        };
+        for callback in callbacks {
+            callback();
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use super::{
+    artifact_store::ArtifactStore, environment::EnvironmentManifest, fault_injection::FaultPlan,
+    inline_functions::InlineFunctions, preemption::PreemptionPolicy, realtime::RealtimeOptions,
+};
+use graph_executor_core::{clock::Clock, scheduler::Scheduler};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+/// Options for [`super::execute_graph::ExecuteGraph::execute_with_options`].
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionOptions {
+    /// Optional budget of shared memory bytes of the run on this worker. Writes of the graph exceeding it fail with
+    /// [`crate::shared_memory::posix_shared_memory::SharedMemoryFull`] before anything is written, and result blobs
+    /// of command nodes finishing while the graph and the stored blobs exceed it are spilled to `spill_directory`.
+    pub max_shm_bytes: Option<usize>,
+    /// Directory result blobs are spilled to once the run exceeds `max_shm_bytes`, the system's temporary directory
+    /// if [`None`].
+    pub spill_directory: Option<PathBuf>,
+    /// Whether this worker locks the run's graph mapping into RAM, so that page faults don't delay claims and
+    /// updates of latency-sensitive runs, see
+    /// [`crate::shared_memory::posix_shared_memory::PosixSharedMemory::lock_in_memory`]. If the pages can't be
+    /// locked (e.g. beyond `RLIMIT_MEMLOCK`), a warning is printed and the run continues unlocked.
+    pub lock_memory: bool,
+    /// Optional cap on the bytes written to disk by the run: the captured output of its commands, its spilled result
+    /// blobs and its entries in a remote cache on this machine's disk. The run is cancelled once a node exceeded it
+    /// and fails with [`super::resource_usage::DiskFull`].
+    pub max_disk_bytes: Option<usize>,
+    /// Origin of the graph definition (e.g. the DOT file's path) recorded in the run's header.
+    pub graph_source: Option<String>,
+    /// Policy picking the next node to execute, [`graph_executor_core::scheduler::FifoScheduler`] if [`None`].
+    pub scheduler: Option<Arc<dyn Scheduler>>,
+    /// Cost per unit of node cost when this worker executes a node, see [`graph_executor_core::scheduler::CostAwareScheduler`].
+    pub worker_cost_rate: u64,
+    /// Whether this worker preempts its running command node for higher-priority nodes.
+    pub preemption: PreemptionPolicy,
+    /// Optional cap on the number of nodes executing at the same time across all workers of the run.
+    pub jobs: Option<usize>,
+    /// Whether this worker keeps executing nodes which don't depend on a failed node, instead of leaving the run
+    /// as soon as any node failed. Otherwise, a node failing on this worker cancels the rest of the run.
+    pub keep_going: bool,
+    /// Optional wall-clock limit of the run, set by the worker creating it and enforced by all its workers.
+    pub timeout: Option<Duration>,
+    /// Environment variables set for the commands of nodes executed by this worker, in addition to its own.
+    pub environment: Vec<(String, String)>,
+    /// Optional environment recorded in the header of the run if this worker creates it, see
+    /// [`super::environment::EnvironmentManifest::capture`].
+    pub environment_manifest: Option<EnvironmentManifest>,
+    /// Optional path of the audit log of the run if this worker creates it, see [`super::audit::AuditLog`].
+    /// Relative paths are resolved against the working directory of this worker.
+    pub audit_log: Option<String>,
+    /// Optional path of the manifest written by the last worker leaving the run if this worker creates it, see
+    /// [`super::run_manifest::RunManifest`]. Relative paths are resolved against the working directory of this
+    /// worker.
+    pub run_manifest: Option<String>,
+    /// Optional path of the bundle written by the last worker leaving the run if it failed and this worker creates
+    /// it, see [`super::failure_bundle::FailureBundle`]. Relative paths are resolved against the working directory
+    /// of this worker.
+    pub failure_bundle: Option<String>,
+    /// Optional period and deadline of the iterations of [`super::execute_graph::ExecuteGraph::execute_n`], which
+    /// are executed back to back otherwise.
+    pub realtime: Option<RealtimeOptions>,
+    /// Functions executing this worker's nodes with
+    /// [`graph_executor_core::graph_structure::isolation::Isolation::Inline`] and a `command`, by that command, and
+    /// the payloads executing nodes by their identifier, see [`InlineFunctions::with_payload`].
+    pub inline_functions: InlineFunctions,
+    /// Optional directory in which every attempt at executing a command node gets an isolated working directory of
+    /// its own, see [`super::workdir::Workdir`]. Commands run in the working directory of this worker otherwise.
+    pub workdir_root: Option<PathBuf>,
+    /// Whether the working directories of successful attempts are kept, those of failed attempts always are.
+    pub keep_workdirs: bool,
+    /// Optional directory capturing the standard output and error of every command node (and its hooks) executed by
+    /// this worker in files of their own, see [`super::command_output::CommandOutput`]. Commands write to the
+    /// worker's standard output and error otherwise.
+    pub output_directory: Option<PathBuf>,
+    /// Optional time without a heartbeat after which this worker, while waiting for executable nodes, considers
+    /// another worker of the run dead (immediately if its process on this host no longer exists), detaches it and
+    /// requeues the node it was executing, like [`super::watchdog::watch`]. Dead workers are only reclaimed by a
+    /// watchdog if [`None`].
+    pub reclaim_after: Option<Duration>,
+    /// Optional remote cache shared by the workers of runs on any host, see [`super::remote_cache`]. Before executing
+    /// a node, this worker reads its result blob and declared outputs from the cache if it holds an entry for the
+    /// node's content and inputs, completing the node as
+    /// [`graph_executor_core::graph_structure::attempt::AttemptOutcome::Cached`] without running it. Results of nodes
+    /// it executed are written to the cache. Each worker of a run uses its own.
+    pub remote_cache: Option<Arc<dyn ArtifactStore>>,
+    /// Optional plan of faults this worker injects into its execution for testing, see
+    /// [`super::fault_injection::FaultPlan`].
+    pub faults: Option<FaultPlan>,
+    /// Clock timing this worker's sleeps, timeouts and backoffs as well as the run's deadline and heartbeats,
+    /// [`graph_executor_core::clock::SystemClock`] if [`None`]. Tests pass a
+    /// [`graph_executor_core::clock::VirtualClock`] to run the worker's timing logic without waiting in real time.
+    pub clock: Option<Arc<dyn Clock>>,
+}
@@ -0,0 +1,73 @@
+use super::{
+    remote_cache::CacheStatistics, resource_usage::ResourceUsage, result_blob::ResultBlob,
+    run_header::RunHeader, utilization::UtilizationTimeline,
+};
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+/// Summary of a finished graph execution returned by
+/// [`super::execute_graph::ExecuteGraph::execute_with_options`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecutionReport {
+    /// Header of the finished run.
+    pub header: RunHeader,
+    /// Whether this worker was the last one to leave the run, i.e. the only one reporting the finished run.
+    pub is_last_worker: bool,
+    /// Whether the run's deadline passed before all nodes were executed, so that the remaining ones were cancelled.
+    pub timed_out: bool,
+    /// Identifiers of the nodes which were up to date and therefore not executed, ordered by string identifier.
+    pub up_to_date: Vec<String>,
+    /// Lookups of the run's nodes in the remote cache by all its workers.
+    pub cache: CacheStatistics,
+    /// Shared memory and disk usage of the run.
+    pub resource_usage: ResourceUsage,
+    /// Number of executing nodes over the time of the run, complete only in the report of the last worker.
+    pub utilization: UtilizationTimeline,
+    /// Result blobs of the nodes without children by their identifiers, mapped by the last worker before it removed
+    /// the run's blobs, see [`super::result_blob`]. Empty in the reports of other workers.
+    pub results: BTreeMap<String, Arc<ResultBlob>>,
+}
+
+/// Error of a run which failed because any of its nodes failed or it exceeded its disk cap, returned by the workers
+/// leaving it. It carries the run's report next to the failure, so that callers can still export the failed run, e.g.
+/// to find its flaky nodes, after finding it with [`anyhow::Error::downcast`].
+#[derive(Debug)]
+pub struct RunFailed {
+    /// Report of the failed run.
+    pub report: Box<ExecutionReport>,
+    /// Failure of the run, e.g. a [`super::resource_usage::DiskFull`].
+    pub error: anyhow::Error,
+}
+
+impl fmt::Display for RunFailed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl std::error::Error for RunFailed {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+impl fmt::Display for ExecutionReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Shared memory: {} bytes (peak {} bytes), disk: {} bytes",
+            self.resource_usage.shm_bytes,
+            self.resource_usage.peak_shm_bytes,
+            self.resource_usage.disk_bytes
+        )?;
+        if self.timed_out {
+            write!(f, ", timed out")?;
+        }
+        if !self.up_to_date.is_empty() {
+            write!(f, "\nUp to date: {}", self.up_to_date.join(", "))?;
+        }
+        if self.cache.hits + self.cache.misses > 0 {
+            write!(f, "\n{}", self.cache)?;
+        }
+        write!(f, "\n{}", self.utilization)
+    }
+}
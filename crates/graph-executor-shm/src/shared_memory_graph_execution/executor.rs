@@ -0,0 +1,222 @@
+use super::{
+    execute_graph::{ExecuteGraph, ExecutionSteps},
+    execution_options::ExecutionOptions,
+    execution_report::ExecutionReport,
+};
+use anyhow::{anyhow, Result};
+use graph_executor_core::graph_structure::graph::DirectedAcyclicGraph;
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+/// Number of private runs started by [`Executor`]s of this process, which distinguishes their filename suffixes.
+static PRIVATE_RUN_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Way in which an [`Executor`] executes a graph. Every mode executes the graph in a run in shared memory, the modes
+/// only differ in who executes its nodes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExecutionMode {
+    /// A single worker in the calling thread, executing the nodes one after another.
+    Sequential,
+    /// `workers` workers in threads of this process, one of them the calling thread.
+    InProcessThreads { workers: usize },
+    /// The calling thread as one worker of the run with the filename suffix `prefix`, which workers of other
+    /// processes (e.g. further `graph-executor` binaries) join by the same filename suffix.
+    MultiProcessShm { prefix: String },
+    /// `workers` workers in background threads, while the calling thread only waits for the execution to finish.
+    /// Async callers instead await the [`ExecutionFuture`] of [`Executor::spawn`].
+    Async { workers: usize },
+}
+
+impl fmt::Display for ExecutionMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExecutionMode::Sequential => write!(f, "sequential"),
+            ExecutionMode::InProcessThreads { workers } => write!(f, "{} threads", workers),
+            ExecutionMode::MultiProcessShm { prefix } => write!(f, "multi-process run {}", prefix),
+            ExecutionMode::Async { workers } => write!(f, "async with {} threads", workers),
+        }
+    }
+}
+
+/// Single entry point executing graphs in any [`ExecutionMode`], so that callers switch modes by configuration
+/// instead of calling [`ExecuteGraph`] and spawning workers themselves.
+///
+/// Except for [`ExecutionMode::MultiProcessShm`], graphs are executed in private runs with filename suffixes unique
+/// to this process (`executor-<pid>-<count>`), whose shared memory is removed once they finished.
+#[derive(Clone, Debug)]
+pub struct Executor {
+    /// Mode executing the graphs.
+    pub mode: ExecutionMode,
+    /// Options of every worker started by the executor.
+    pub options: ExecutionOptions,
+}
+
+impl Executor {
+    /// Creates an executor executing graphs in `mode` with the default [`ExecutionOptions`].
+    pub fn new(mode: ExecutionMode) -> Self {
+        Executor {
+            mode,
+            options: ExecutionOptions::default(),
+        }
+    }
+
+    /// Executes `graph` in the executor's mode, leaving it in its final state. Returns the report of the calling
+    /// thread's worker, which in every mode but [`ExecutionMode::MultiProcessShm`] is the last one leaving the run
+    /// (see [`ExecutionReport::is_last_worker`]), or the error listing the failed nodes.
+    pub fn run(&self, graph: &mut DirectedAcyclicGraph) -> Result<ExecutionReport> {
+        match &self.mode {
+            ExecutionMode::Sequential => {
+                execute_in_threads(graph, &private_run(), 1, &self.options)
+            }
+            ExecutionMode::InProcessThreads { workers } => {
+                execute_in_threads(graph, &private_run(), *workers, &self.options)
+            }
+            ExecutionMode::MultiProcessShm { prefix } => {
+                graph.execute_with_options(prefix.clone(), &self.options)
+            }
+            ExecutionMode::Async { .. } => {
+                let (executed, report) = block_on(self.spawn(graph.clone()))?;
+                *graph = executed;
+                Ok(report)
+            }
+        }
+    }
+
+    /// Starts executing `graph` like [`Executor::run`] in a background thread and returns the future resolving to
+    /// the graph in its final state and the report once the execution finished. The future needs no particular
+    /// async runtime: it wakes its task from the background thread.
+    pub fn spawn(&self, mut graph: DirectedAcyclicGraph) -> ExecutionFuture {
+        let state = Arc::new(Mutex::new(FutureState::default()));
+        let executor = match &self.mode {
+            ExecutionMode::Async { workers } => Executor {
+                mode: ExecutionMode::InProcessThreads { workers: *workers },
+                options: self.options.clone(),
+            },
+            _ => self.clone(),
+        };
+        let shared_state = state.clone();
+        thread::spawn(move || {
+            let output = executor.run(&mut graph).map(|report| (graph, report));
+            let mut state = shared_state.lock().unwrap();
+            state.output = Some(output);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+        ExecutionFuture { state }
+    }
+}
+
+/// Execution started by [`Executor::spawn`], resolving to the executed graph and its report.
+pub struct ExecutionFuture {
+    state: Arc<Mutex<FutureState>>,
+}
+
+/// State shared by an [`ExecutionFuture`] and the thread executing its graph.
+#[derive(Default)]
+struct FutureState {
+    /// Result of the execution once it finished.
+    output: Option<Result<(DirectedAcyclicGraph, ExecutionReport)>>,
+    /// Waker of the task which last polled the future before the execution finished.
+    waker: Option<Waker>,
+}
+
+impl Future for ExecutionFuture {
+    type Output = Result<(DirectedAcyclicGraph, ExecutionReport)>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        match state.output.take() {
+            Some(output) => Poll::Ready(output),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Waker unparking the thread blocked in [`block_on`].
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Blocks the calling thread until `future` resolved.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// Filename suffix of a new private run of this process.
+fn private_run() -> String {
+    format!(
+        "executor-{}-{}",
+        std::process::id(),
+        PRIVATE_RUN_COUNT.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Executes `graph` in the run with `filename_suffix` by `workers` workers, the calling thread and `workers - 1`
+/// threads joining the run after it, which the calling thread waits for before leaving the run last. Fails with the
+/// calling thread's error or else with the first error of the other workers.
+fn execute_in_threads(
+    graph: &mut DirectedAcyclicGraph,
+    filename_suffix: &str,
+    workers: usize,
+    options: &ExecutionOptions,
+) -> Result<ExecutionReport> {
+    if workers == 0 {
+        return Err(anyhow!(
+            "At least one worker is required to execute the graph."
+        ));
+    }
+    let template = graph.clone();
+    let mut run = graph.join_run(filename_suffix.to_string(), options)?;
+    let (iteration, worker_results) = thread::scope(|scope| {
+        let handles: Vec<_> = (1..workers)
+            .map(|_| {
+                let (mut graph, filename_suffix) = (template.clone(), filename_suffix.to_string());
+                scope.spawn(move || graph.execute_with_options(filename_suffix, options))
+            })
+            .collect();
+        let iteration = graph.execute_iteration(&mut run, options);
+        let worker_results: Vec<_> = handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| Err(anyhow!("Worker thread panicked.")))
+            })
+            .collect();
+        (iteration, worker_results)
+    });
+    // The run is left even if the calling thread's iteration failed, so that its shared memory is removed. Failed
+    // nodes fail every worker, the calling thread reports them when leaving the run
+    let report = graph.leave_run(run);
+    iteration?;
+    let report = report?;
+    match worker_results.into_iter().find_map(Result::err) {
+        Some(error) => Err(error),
+        None => Ok(report),
+    }
+}
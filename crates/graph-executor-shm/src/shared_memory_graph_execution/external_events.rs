@@ -0,0 +1,99 @@
+use super::iceoryx::{self, MAX_PORTS};
+use anyhow::{anyhow, Result};
+use graph_executor_core::graph_structure::graph::DirectedAcyclicGraph;
+use iceoryx2::{port::listener::Listener, prelude::*, service::port_factory::event::PortFactory};
+use std::collections::BTreeSet;
+
+/// Listeners of a worker on the iceoryx2 event services named by the `await_event` attributes of a graph's nodes.
+/// External components (e.g. a sensor driver or an upstream service) release the nodes awaiting an event by
+/// notifying its service, see [`notify`]. A worker only receives the notifications sent after it joined the run.
+pub(crate) struct ExternalEvents {
+    /// Listener of each awaited event, by name.
+    listeners: Vec<(String, Listener<ipc::Service>)>,
+    /// Node owning the listeners, [`None`] if no node of the graph awaits an event.
+    _node: Option<Node<ipc::Service>>,
+}
+
+impl ExternalEvents {
+    /// Listens on the event services of all events awaited by nodes of `graph`, creating those which don't exist yet.
+    pub(crate) fn listen(graph: &DirectedAcyclicGraph) -> Result<Self> {
+        let events: BTreeSet<&str> = graph
+            .get_node_indices()
+            .filter_map(|i| graph[i].awaited_event())
+            .collect();
+        if events.is_empty() {
+            return Ok(ExternalEvents {
+                listeners: vec![],
+                _node: None,
+            });
+        }
+        let node = iceoryx::create_node()?;
+        let listeners = events
+            .into_iter()
+            .map(|event| {
+                let listener = open_or_create(&node, event)?
+                    .listener_builder()
+                    .create()
+                    .map_err(|e| anyhow!("Failed listening for event {}: {}", event, e))?;
+                Ok((event.to_string(), listener))
+            })
+            .collect::<Result<_>>()?;
+        Ok(ExternalEvents {
+            listeners,
+            _node: Some(node),
+        })
+    }
+
+    /// Names of the events notified since the last call, without waiting for notifications.
+    pub(crate) fn notified(&self) -> Result<Vec<&str>> {
+        let mut notified = vec![];
+        for (event, listener) in &self.listeners {
+            let mut received = false;
+            listener
+                .try_wait_all(|_| received = true)
+                .map_err(|e| anyhow!("Failed receiving event {}: {}", event, e))?;
+            if received {
+                notified.push(event.as_str());
+            }
+        }
+        Ok(notified)
+    }
+}
+
+/// Notifies the iceoryx2 event service `event`, releasing the nodes awaiting it in every run whose workers listen
+/// for it, and returns the number of notified listeners (one per worker).
+pub fn notify(event: &str) -> Result<usize> {
+    let node = iceoryx::create_node()?;
+    let service = node
+        .service_builder(&iceoryx::service_name(event)?)
+        .event()
+        .open()
+        .map_err(|_| anyhow!("No run is listening for event {}.", event))?;
+    let notifier = service
+        .notifier_builder()
+        .create()
+        .map_err(|e| anyhow!("Failed notifying event {}: {}", event, e))?;
+    let listeners = notifier
+        .notify()
+        .map_err(|e| anyhow!("Failed notifying event {}: {}", event, e))?;
+    match listeners {
+        0 => Err(anyhow!("No run is listening for event {}.", event)),
+        listeners => Ok(listeners),
+    }
+}
+
+/// Opens the event service `event`, creating it if it doesn't exist yet. Services created by other components are
+/// opened with their own limits, while those created here admit a listener per worker of [`MAX_PORTS`] workers.
+fn open_or_create(node: &Node<ipc::Service>, event: &str) -> Result<PortFactory<ipc::Service>> {
+    let name = iceoryx::service_name(event)?;
+    if let Ok(service) = node.service_builder(&name).event().open() {
+        return Ok(service);
+    }
+    node.service_builder(&name)
+        .event()
+        .max_listeners(MAX_PORTS)
+        .max_notifiers(MAX_PORTS)
+        .max_nodes(2 * MAX_PORTS)
+        .open_or_create()
+        .map_err(|e| anyhow!("Failed opening event service {}: {}", event, e))
+}
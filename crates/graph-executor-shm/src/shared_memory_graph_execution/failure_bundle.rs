@@ -0,0 +1,187 @@
+use super::{run_header::RunHeader, run_manifest::RunManifest};
+use anyhow::{anyhow, Result};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use graph_executor_core::graph_structure::graph::DirectedAcyclicGraph;
+use std::{collections::BTreeMap, fs::File, io::Read};
+
+/// Artifact users can attach to issues about a failed run, written as a gzipped tarball by the last worker leaving a
+/// run with failures if the run was created with
+/// [`super::execution_options::ExecutionOptions::failure_bundle`]. Its files are placed in a directory named after
+/// the run:
+///
+/// - `graph.dot`: the [anonymized](DirectedAcyclicGraph::anonymized) graph with the final execution statuses,
+/// - `manifest.yaml`: the run's [`RunManifest`] with the anonymized graph and without the graph's source,
+/// - `trace.log`: the run's entries of its [audit log](super::audit::AuditLog), if it has one,
+/// - `logs/<index>.log`: the attempts of every node which was attempted, one per line with its error,
+/// - `environment.txt`: the run's [environment](super::environment::EnvironmentManifest) with the output of its
+///   probes, if one was captured.
+///
+/// Node identifiers, args and commands are redacted from the trace and the logs, nodes are referred to by their
+/// index like in the anonymized graph.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FailureBundle {
+    /// Contents of the bundle's files by their path relative to the bundle's directory.
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+impl FailureBundle {
+    /// Collects the bundle of the run with `filename_suffix` and `header` from its final `graph`.
+    pub fn collect(
+        filename_suffix: &str,
+        header: &RunHeader,
+        graph: &DirectedAcyclicGraph,
+    ) -> Result<Self> {
+        let anonymized = graph.anonymized();
+        let mut files = BTreeMap::new();
+        files.insert(
+            String::from("graph.dot"),
+            anonymized.to_string().into_bytes(),
+        );
+        let manifest = RunManifest {
+            filename_suffix: filename_suffix.to_string(),
+            graph_source: None,
+            graph: anonymized,
+        };
+        files.insert(
+            String::from("manifest.yaml"),
+            serde_yaml::to_string(&manifest)?.into_bytes(),
+        );
+        if let Some(path) = &header.audit_log {
+            let audit_log = std::fs::read_to_string(path)
+                .map_err(|e| anyhow!("Failed reading audit log {}: {}", path, e))?;
+            // Audit logs may be shared by several runs
+            let run = format!(" run={} ", filename_suffix);
+            let trace: String = audit_log
+                .lines()
+                .filter(|entry| entry.contains(&run))
+                .map(|entry| redact(entry, graph) + "\n")
+                .collect();
+            files.insert(String::from("trace.log"), trace.into_bytes());
+        }
+        for i in graph.get_node_indices() {
+            if graph[i].attempts().is_empty() {
+                continue;
+            }
+            let log: String = graph[i]
+                .attempts()
+                .iter()
+                .enumerate()
+                .map(|(number, attempt)| {
+                    let mut line = format!(
+                        "Attempt {}: {} by worker {} from {:.3}s to {:.3}s",
+                        number + 1,
+                        attempt.outcome,
+                        attempt.worker_id,
+                        attempt.started_after.as_secs_f64(),
+                        attempt.finished_after.as_secs_f64()
+                    );
+                    if let Some(hook) = attempt.hook {
+                        line.push_str(&format!(" ({} hook)", hook));
+                    }
+                    if let Some(error) = &attempt.error {
+                        line.push_str(&format!(": {}", redact(error, graph)));
+                    }
+                    line + "\n"
+                })
+                .collect();
+            files.insert(format!("logs/{}.log", i.index()), log.into_bytes());
+        }
+        if let Some(environment) = &header.environment {
+            files.insert(
+                String::from("environment.txt"),
+                format!("{}\n", environment).into_bytes(),
+            );
+        }
+        Ok(FailureBundle { files })
+    }
+
+    /// Get the contents of the bundle's files by their path relative to the bundle's directory.
+    pub fn files(&self) -> &BTreeMap<String, Vec<u8>> {
+        &self.files
+    }
+
+    /// Writes the bundle to `path` as a gzipped tarball whose files are placed in the directory `directory`.
+    pub fn write(&self, path: &str, directory: &str) -> Result<()> {
+        let file =
+            File::create(path).map_err(|e| anyhow!("Failed creating bundle {}: {}", path, e))?;
+        let mut tarball = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        for (name, content) in &self.files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            tarball
+                .append_data(
+                    &mut header,
+                    format!("{}/{}", directory, name),
+                    content.as_slice(),
+                )
+                .map_err(|e| anyhow!("Failed writing bundle {}: {}", path, e))?;
+        }
+        tarball
+            .into_inner()
+            .and_then(|encoder| encoder.finish())
+            .map_err(|e| anyhow!("Failed writing bundle {}: {}", path, e))?;
+        Ok(())
+    }
+
+    /// Reads the bundle written to `path` by [`FailureBundle::write`].
+    pub fn read(path: &str) -> Result<Self> {
+        let file =
+            File::open(path).map_err(|e| anyhow!("Failed opening bundle {}: {}", path, e))?;
+        let mut tarball = tar::Archive::new(GzDecoder::new(file));
+        let mut files = BTreeMap::new();
+        for entry in tarball
+            .entries()
+            .map_err(|e| anyhow!("Invalid bundle {}: {}", path, e))?
+        {
+            let mut entry = entry.map_err(|e| anyhow!("Invalid bundle {}: {}", path, e))?;
+            // Paths are relative to the bundle's directory
+            let name = entry.path()?.iter().skip(1).collect::<std::path::PathBuf>();
+            let mut content = vec![];
+            entry.read_to_end(&mut content)?;
+            files.insert(name.display().to_string(), content);
+        }
+        Ok(FailureBundle { files })
+    }
+}
+
+/// Replaces the identifiers, args and commands of the nodes of `graph` in `text` by placeholders naming the node's
+/// index, e.g. `<node 3>`. Only whole words are replaced, so that short identifiers don't garble the text, and the
+/// longest match wins.
+fn redact(text: &str, graph: &DirectedAcyclicGraph) -> String {
+    let mut secrets: Vec<(&str, String)> = vec![];
+    for i in graph.get_node_indices() {
+        let node = &graph[i];
+        secrets.push((graph.node_id(i), format!("<node {}>", i.index())));
+        secrets.push((node.args(), format!("<args of node {}>", i.index())));
+        if let Some(command) = node.command() {
+            secrets.push((command, format!("<command of node {}>", i.index())));
+        }
+    }
+    secrets.retain(|(secret, _)| !secret.is_empty());
+    secrets.sort_by_key(|(secret, _)| std::cmp::Reverse(secret.len()));
+
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let mut redacted = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let before = text[..text.len() - rest.len()].chars().next_back();
+        let secret = secrets.iter().find(|(secret, _)| {
+            rest.starts_with(secret)
+                && !(is_word(before) && is_word(secret.chars().next()))
+                && !(is_word(rest[secret.len()..].chars().next())
+                    && is_word(secret.chars().next_back()))
+        });
+        match secret {
+            Some((secret, placeholder)) => {
+                redacted.push_str(placeholder);
+                rest = &rest[secret.len()..];
+            }
+            None => {
+                redacted.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    redacted
+}
@@ -0,0 +1,118 @@
+use anyhow::{anyhow, Error, Result};
+use graph_executor_core::clock::Clock;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{str::FromStr, time::Duration};
+
+/// Seeded plan of faults a worker injects into its own execution of a run, so that robustness features like
+/// retries, reclamation of dead workers and recovery from lost claims can be exercised reproducibly: every worker
+/// draws its faults from a random number generator seeded with the plan's `seed` and its id, so that the same
+/// worker makes the same decisions in every run of the plan.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FaultPlan {
+    /// Seed of the faults, combined with the id of the worker injecting them.
+    pub seed: u64,
+    /// Probability with which a claim is delayed before taking the write lock of the run's graph.
+    pub lock_delay_probability: f64,
+    /// Longest delay of a delayed claim, the delay is drawn uniformly up to it.
+    pub max_lock_delay: Duration,
+    /// Probability with which a claim fails as if another worker had changed the graph in the meantime, so that the
+    /// worker rereads the graph and picks again.
+    pub claim_conflict_probability: f64,
+    /// Probability with which the worker crashes after claiming a node: it leaves the run without completing the
+    /// node or detaching, like a killed process, so that the node stays executing until it is reclaimed.
+    pub crash_probability: f64,
+}
+
+impl FromStr for FaultPlan {
+    type Err = Error;
+    /// Parses [`FaultPlan`] from a string like: "seed=42,delay=0.2,max-delay-ms=50,conflict=0.1,crash=0.05".
+    /// Omitted faults are not injected, the longest delay defaults to 100ms.
+    fn from_str(plan: &str) -> Result<Self> {
+        let mut fault_plan = FaultPlan {
+            max_lock_delay: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let probability = |key: &str, value: &str| match value.parse::<f64>() {
+            Ok(probability) if (0.0..=1.0).contains(&probability) => Ok(probability),
+            _ => Err(anyhow!(
+                "FaultPlan::from_str parsing error: Invalid {} probability {}, expected 0 to 1.",
+                key,
+                value
+            )),
+        };
+        for fault in plan.split(',') {
+            let (key, value) = fault.split_once('=').ok_or(anyhow!(
+                "FaultPlan::from_str parsing error: Invalid fault {:?}, expected <key>=<value>.",
+                fault
+            ))?;
+            match key.trim() {
+                "seed" => {
+                    fault_plan.seed = value.parse().map_err(|e| {
+                        anyhow!("FaultPlan::from_str parsing error: Invalid seed {}: {}", value, e)
+                    })?
+                }
+                "delay" => fault_plan.lock_delay_probability = probability(key, value)?,
+                "max-delay-ms" => {
+                    fault_plan.max_lock_delay = Duration::from_millis(value.parse().map_err(|e| {
+                        anyhow!(
+                            "FaultPlan::from_str parsing error: Invalid max-delay-ms {}: {}",
+                            value,
+                            e
+                        )
+                    })?)
+                }
+                "conflict" => fault_plan.claim_conflict_probability = probability(key, value)?,
+                "crash" => fault_plan.crash_probability = probability(key, value)?,
+                key => {
+                    return Err(anyhow!(
+                        "FaultPlan::from_str parsing error: Unknown fault {}, expected seed, delay, max-delay-ms, \
+                         conflict or crash.",
+                        key
+                    ))
+                }
+            }
+        }
+        Ok(fault_plan)
+    }
+}
+
+impl FaultPlan {
+    /// Creates the injector of the faults of the worker with `worker_id`.
+    pub(crate) fn injector(&self, worker_id: usize) -> FaultInjector {
+        FaultInjector {
+            plan: self.clone(),
+            rng: StdRng::seed_from_u64(self.seed.wrapping_add(worker_id as u64)),
+        }
+    }
+}
+
+/// Faults of a [`FaultPlan`] drawn by one worker.
+pub(crate) struct FaultInjector {
+    plan: FaultPlan,
+    rng: StdRng,
+}
+
+impl FaultInjector {
+    /// Draws whether an event with `probability` happens.
+    fn happens(&mut self, probability: f64) -> bool {
+        self.rng.gen::<f64>() < probability
+    }
+
+    /// Sleeps on `clock` before a claim takes the write lock, if the plan delays it.
+    pub(crate) fn delay_lock(&mut self, clock: &dyn Clock) {
+        if self.happens(self.plan.lock_delay_probability) {
+            let delay = self.rng.gen_range(0.0..=1.0) * self.plan.max_lock_delay.as_secs_f64();
+            clock.sleep(Duration::from_secs_f64(delay));
+        }
+    }
+
+    /// Draws whether the next claim fails with a conflict.
+    pub(crate) fn claim_conflict(&mut self) -> bool {
+        self.happens(self.plan.claim_conflict_probability)
+    }
+
+    /// Draws whether the worker crashes after its claim.
+    pub(crate) fn crash(&mut self) -> bool {
+        self.happens(self.plan.crash_probability)
+    }
+}
@@ -0,0 +1,127 @@
+use graph_executor_core::graph_structure::{attempt::AttemptOutcome, graph::DirectedAcyclicGraph};
+use std::{collections::BTreeMap, fmt};
+
+/// Failure rate above which a node is quarantined, see [`QuarantineReport`].
+pub const DEFAULT_FAILURE_RATE_THRESHOLD: f64 = 0.1;
+/// Number of retries quarantined nodes are raised to by [`QuarantineReport::raise_retries`].
+pub const DEFAULT_QUARANTINE_RETRIES: u32 = 2;
+
+/// Attempt history of a node aggregated across runs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NodeFlakiness {
+    /// Number of attempts which either succeeded or failed (preempted, cancelled and abandoned attempts are not
+    /// counted).
+    pub attempts: u64,
+    /// Number of failed attempts.
+    pub failed_attempts: u64,
+}
+
+impl NodeFlakiness {
+    /// Fraction of the counted attempts which failed.
+    pub fn failure_rate(&self) -> f64 {
+        match self.attempts {
+            0 => 0.0,
+            attempts => self.failed_attempts as f64 / attempts as f64,
+        }
+    }
+}
+
+/// Report of the nodes whose attempts failed more often than a threshold across runs, mirroring the quarantine
+/// of flaky tests: quarantined nodes can be retried automatically (see [`QuarantineReport::raise_retries`]) until
+/// they are fixed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuarantineReport {
+    /// Failure rate above which a node is quarantined.
+    pub threshold: f64,
+    /// Aggregated attempt histories of all nodes by node identifier.
+    pub nodes: BTreeMap<String, NodeFlakiness>,
+}
+
+impl QuarantineReport {
+    /// Aggregates the outcomes of attempts (e.g. read from [`super::sqlite_export::attempt_outcomes`]) by node
+    /// identifier, quarantining nodes with a failure rate above `threshold`.
+    pub fn new(
+        attempt_outcomes: impl IntoIterator<Item = (String, AttemptOutcome)>,
+        threshold: f64,
+    ) -> Self {
+        let mut nodes: BTreeMap<String, NodeFlakiness> = BTreeMap::new();
+        for (node_id, outcome) in attempt_outcomes {
+            let failed = match outcome {
+                AttemptOutcome::Succeeded => false,
+                AttemptOutcome::Failed | AttemptOutcome::TimedOut => true,
+                AttemptOutcome::Preempted
+                | AttemptOutcome::Cancelled
+                | AttemptOutcome::Abandoned
+                | AttemptOutcome::UpToDate
+                | AttemptOutcome::Cached => continue,
+            };
+            let node = nodes.entry(node_id).or_default();
+            node.attempts += 1;
+            node.failed_attempts += failed as u64;
+        }
+        QuarantineReport { threshold, nodes }
+    }
+
+    /// Aggregates the attempts of the nodes of the executed `graphs`, see [`QuarantineReport::new`].
+    pub fn from_graphs<'a>(
+        graphs: impl IntoIterator<Item = &'a DirectedAcyclicGraph>,
+        threshold: f64,
+    ) -> Self {
+        QuarantineReport::new(
+            graphs.into_iter().flat_map(|graph| {
+                graph.get_node_indices().flat_map(move |i| {
+                    graph[i]
+                        .attempts()
+                        .iter()
+                        .map(move |attempt| (graph.node_id(i).to_string(), attempt.outcome))
+                })
+            }),
+            threshold,
+        )
+    }
+
+    /// Identifiers and histories of the quarantined nodes.
+    pub fn quarantined(&self) -> impl Iterator<Item = (&str, &NodeFlakiness)> {
+        self.nodes
+            .iter()
+            .filter(|(_, node)| node.failure_rate() > self.threshold)
+            .map(|(node_id, node)| (node_id.as_str(), node))
+    }
+
+    /// Raises the retries of the quarantined nodes of `graph` to at least `retries`, returning the identifiers of
+    /// the nodes whose retries were raised.
+    pub fn raise_retries(&self, graph: &mut DirectedAcyclicGraph, retries: u32) -> Vec<String> {
+        let mut raised = vec![];
+        for (node_id, _) in self.quarantined() {
+            let Some(i) = graph.node_index(node_id) else {
+                continue;
+            };
+            if graph[i].retries() < retries {
+                graph[i] = graph[i].clone().with_retries(retries);
+                raised.push(node_id.to_string());
+            }
+        }
+        raised
+    }
+}
+
+impl fmt::Display for QuarantineReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Quarantined nodes (failure rate above {:.1}%):",
+            self.threshold * 100.0
+        )?;
+        for (node_id, node) in self.quarantined() {
+            writeln!(
+                f,
+                "    {}: {} of {} attempts failed ({:.1}%)",
+                node_id,
+                node.failed_attempts,
+                node.attempts,
+                node.failure_rate() * 100.0
+            )?;
+        }
+        Ok(())
+    }
+}
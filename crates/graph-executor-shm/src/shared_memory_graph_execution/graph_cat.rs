@@ -0,0 +1,182 @@
+use crate::shared_memory::posix_shared_memory::PosixSharedMemory;
+use anyhow::{anyhow, Error, Result};
+use graph_executor_core::graph_structure::{
+    execution_status::ExecutionStatus, graph::DirectedAcyclicGraph, graph_limits::GraphLimits,
+    parse_mode::ParseMode,
+};
+use petgraph::graph::NodeIndex;
+use std::{collections::BTreeSet, path::Path, str::FromStr};
+
+/// All execution statuses, in the order of a node's lifetime.
+const EXECUTION_STATUSES: [ExecutionStatus; 7] = [
+    ExecutionStatus::NonExecutable,
+    ExecutionStatus::Executable,
+    ExecutionStatus::Executing,
+    ExecutionStatus::Executed,
+    ExecutionStatus::Failed,
+    ExecutionStatus::Skipped,
+    ExecutionStatus::Cancelled,
+];
+
+/// Output format of [`cat`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CatFormat {
+    /// One line per node with its execution status, number of attempts, parents and command.
+    #[default]
+    Table,
+    /// The selected nodes and the edges between them as DOT, like the graph is printed when it is saved.
+    Dot,
+}
+
+impl FromStr for CatFormat {
+    type Err = Error;
+    /// Parses [`CatFormat`] from a string like: "table" or "dot".
+    fn from_str(format: &str) -> Result<Self> {
+        match format {
+            "table" => Ok(CatFormat::Table),
+            "dot" => Ok(CatFormat::Dot),
+            _ => Err(anyhow!(
+                "CatFormat::from_str parsing error: Invalid format {}, expected table or dot.",
+                format
+            )),
+        }
+    }
+}
+
+/// Filters selecting the nodes printed by [`cat`]. Nodes are printed if they match all filters.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphFilter {
+    /// Execution statuses of the printed nodes, any status if empty.
+    pub statuses: Vec<ExecutionStatus>,
+    /// Identifier of the node which is printed with all its descendants.
+    pub subtree: Option<String>,
+    /// Whether only nodes without parents are printed.
+    pub roots_only: bool,
+}
+
+impl GraphFilter {
+    /// Adds the execution status parsed case-insensitively from `status` (e.g. `failed`) to the filter.
+    pub fn with_status(mut self, status: &str) -> Result<Self> {
+        let status = EXECUTION_STATUSES
+            .into_iter()
+            .find(|s| s.to_string().eq_ignore_ascii_case(status))
+            .ok_or(anyhow!("Unknown execution status {:?}.", status))?;
+        self.statuses.push(status);
+        Ok(self)
+    }
+
+    /// Get the indices of the nodes of `graph` matching the filter, or an error if the root of the `subtree` is
+    /// not in the graph.
+    pub fn matching_node_indices(
+        &self,
+        graph: &DirectedAcyclicGraph,
+    ) -> Result<BTreeSet<NodeIndex>> {
+        let subtree = match &self.subtree {
+            Some(node_id) => {
+                let root = graph
+                    .node_index(node_id)
+                    .ok_or(anyhow!("No node {} in the graph.", node_id))?;
+                let mut subtree = BTreeSet::new();
+                let mut unvisited = vec![root];
+                while let Some(i) = unvisited.pop() {
+                    if subtree.insert(i) {
+                        unvisited.extend(graph.get_child_node_indices(i));
+                    }
+                }
+                Some(subtree)
+            }
+            None => None,
+        };
+        Ok(graph
+            .get_node_indices()
+            .filter(|i| {
+                self.statuses.is_empty() || self.statuses.contains(&graph[*i].execution_status)
+            })
+            .filter(|i| subtree.as_ref().is_none_or(|subtree| subtree.contains(i)))
+            .filter(|i| !self.roots_only || graph.get_parent_node_indices(*i).next().is_none())
+            .collect())
+    }
+}
+
+/// Reads the graph printed by [`cat`] from the DOT file at `source`, checked against `limits`, or, if there is no
+/// such file, from the shared memory of the live run with the filename suffix `source`.
+pub fn read_graph(source: &str, limits: GraphLimits) -> Result<DirectedAcyclicGraph> {
+    if Path::new(source).is_file() {
+        return DirectedAcyclicGraph::from_file_with_limits(source, ParseMode::Strict, limits);
+    }
+    let (_, graph) = PosixSharedMemory::open_graph(source).map_err(|e| {
+        anyhow!(
+            "No graph file {} and no run {} in shared memory: {}",
+            source,
+            source,
+            e
+        )
+    })?;
+    Ok(graph)
+}
+
+/// Prints the nodes of `graph` (e.g. read from a file or from the shared memory of a live run) selected by `filter`
+/// in `format`, ordered by string identifier. If `anonymize` is set, the nodes are selected before the graph is
+/// [anonymized](DirectedAcyclicGraph::anonymized), so that it can be shared in bug reports.
+pub fn cat(
+    graph: &DirectedAcyclicGraph,
+    filter: &GraphFilter,
+    format: CatFormat,
+    anonymize: bool,
+) -> Result<String> {
+    let selected = filter.matching_node_indices(graph)?;
+    let anonymized;
+    let graph = match anonymize {
+        true => {
+            anonymized = graph.anonymized();
+            &anonymized
+        }
+        false => graph,
+    };
+    if format == CatFormat::Dot {
+        return Ok(graph.to_dot_filtered(|i| selected.contains(&i)));
+    }
+    let mut rows = vec![[
+        String::from("NODE"),
+        String::from("STATUS"),
+        String::from("ATTEMPTS"),
+        String::from("PARENTS"),
+        String::from("COMMAND"),
+    ]];
+    for i in graph.get_node_indices().filter(|i| selected.contains(i)) {
+        let parents: Vec<&str> = graph
+            .get_parent_node_indices(i)
+            .map(|parent| graph.node_id(parent))
+            .collect();
+        rows.push([
+            graph.node_id(i).to_string(),
+            graph[i].execution_status.to_string(),
+            graph[i].attempts().len().to_string(),
+            match parents.is_empty() {
+                true => String::from("-"),
+                false => parents.join(","),
+            },
+            graph[i].command().unwrap_or(graph[i].args()).to_string(),
+        ]);
+    }
+    // Columns are as wide as their widest cell, except for the last one
+    let widths: Vec<usize> = (0..4)
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let lines: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            let mut line: String = (0..4)
+                .map(|column| format!("{:width$}  ", row[column], width = widths[column]))
+                .collect();
+            line.push_str(&row[4]);
+            line.trim_end().to_string()
+        })
+        .collect();
+    Ok(lines.join("\n") + "\n")
+}
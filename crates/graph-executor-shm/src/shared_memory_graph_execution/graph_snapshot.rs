@@ -0,0 +1,121 @@
+use crate::shared_memory::posix_shared_memory::PosixSharedMemory;
+use anyhow::Result;
+use graph_executor_core::graph_structure::{
+    execution_status::ExecutionStatus, graph::DirectedAcyclicGraph, node_progress::NodeProgress,
+};
+use petgraph::graph::NodeIndex;
+use std::{collections::BTreeMap, ops::Index};
+
+/// State of a single node in a [`GraphSnapshot`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeSnapshot {
+    /// Index of the node in the graph.
+    pub index: NodeIndex,
+    /// String identifier of the node.
+    pub node_id: String,
+    /// Execution status of the node when the snapshot was taken.
+    pub execution_status: ExecutionStatus,
+    /// Number of attempts made at executing the node.
+    pub attempt_count: usize,
+    /// Error of the node if it failed.
+    pub error: Option<String>,
+    /// Latest progress the node reported while executing.
+    pub progress: Option<NodeProgress>,
+    /// Indices of the node's parents, ordered by string identifier.
+    pub parents: Vec<NodeIndex>,
+    /// Indices of the node's children, ordered by string identifier.
+    pub children: Vec<NodeIndex>,
+}
+
+/// Immutable snapshot of the execution statuses and topology of a graph, for readers which only observe a run
+/// (e.g. status tools) instead of executing nodes and thus need neither the nodes' payloads nor a mutable graph.
+///
+/// Until the graph is stored in shared memory in a layout which can be read in place, snapshots are derived from
+/// the deserialized graph, so taking one costs as much as reading the graph.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GraphSnapshot {
+    /// Nodes ordered by string identifier.
+    nodes: Vec<NodeSnapshot>,
+    /// Position of each node in `nodes` by its index.
+    positions: BTreeMap<NodeIndex, usize>,
+}
+
+impl GraphSnapshot {
+    /// Takes a snapshot of `graph`.
+    pub fn new(graph: &DirectedAcyclicGraph) -> Self {
+        let nodes: Vec<NodeSnapshot> = graph
+            .get_node_indices()
+            .map(|i| NodeSnapshot {
+                index: i,
+                node_id: graph.node_id(i).to_string(),
+                execution_status: graph[i].execution_status,
+                attempt_count: graph[i].attempts().len(),
+                error: graph[i].error().map(String::from),
+                progress: graph[i].progress.clone(),
+                parents: graph.get_parent_node_indices(i).collect(),
+                children: graph.get_child_node_indices(i).collect(),
+            })
+            .collect();
+        let positions = nodes
+            .iter()
+            .enumerate()
+            .map(|(position, node)| (node.index, position))
+            .collect();
+        GraphSnapshot { nodes, positions }
+    }
+
+    /// All nodes, ordered by string identifier.
+    pub fn nodes(&self) -> &[NodeSnapshot] {
+        &self.nodes
+    }
+
+    /// The node at `index`, if the graph has one.
+    pub fn node(&self, index: NodeIndex) -> Option<&NodeSnapshot> {
+        self.positions
+            .get(&index)
+            .map(|position| &self.nodes[*position])
+    }
+
+    /// Get the [`NodeIndex`] of the node with string identifier `id`.
+    pub fn node_index(&self, id: &str) -> Option<NodeIndex> {
+        self.nodes
+            .binary_search_by(|node| node.node_id.as_str().cmp(id))
+            .ok()
+            .map(|position| self.nodes[position].index)
+    }
+
+    /// Number of nodes with `execution_status`.
+    pub fn count(&self, execution_status: ExecutionStatus) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| node.execution_status == execution_status)
+            .count()
+    }
+}
+
+impl Index<NodeIndex> for GraphSnapshot {
+    type Output = NodeSnapshot;
+
+    fn index(&self, index: NodeIndex) -> &Self::Output {
+        &self.nodes[self.positions[&index]]
+    }
+}
+
+impl PosixSharedMemory {
+    /// Acquire read lock and take a [`GraphSnapshot`] of the graph in shared memory.
+    pub fn shm_snapshot(&mut self) -> Result<GraphSnapshot> {
+        Ok(GraphSnapshot::new(&self.read_graph()?))
+    }
+
+    /// Runs `analysis` (e.g. lints or a critical path calculation) on a copy of the graph in shared memory which is
+    /// pinned for its duration. The copy is read in one piece under the read lock, which is released before
+    /// `analysis` starts, so that long computations neither block the run's writers nor observe their writes,
+    /// let alone a write half-way through.
+    pub fn with_snapshot<R>(
+        &mut self,
+        analysis: impl FnOnce(&DirectedAcyclicGraph) -> R,
+    ) -> Result<R> {
+        let graph = self.read_graph()?;
+        Ok(analysis(&graph))
+    }
+}
@@ -0,0 +1,28 @@
+use anyhow::{anyhow, Result};
+use iceoryx2::prelude::*;
+
+/// Most ports of one kind (e.g. one listener or publisher per worker) on the iceoryx2 services created for runs.
+pub(crate) const MAX_PORTS: usize = 256;
+
+/// Creates an iceoryx2 node of the current process, which owns the ports of a worker or listener on the iceoryx2
+/// services of runs. Unlike iceoryx2's default, the node leaves termination signals to the process, and iceoryx2
+/// only logs errors unless `IOX2_LOG_LEVEL` is set.
+pub(crate) fn create_node() -> Result<Node<ipc::Service>> {
+    set_log_level_from_env_or(LogLevel::Error);
+    NodeBuilder::new()
+        .signal_handling_mode(SignalHandlingMode::Disabled)
+        .create::<ipc::Service>()
+        .map_err(|e| anyhow!("Failed creating iceoryx2 node: {}", e))
+}
+
+/// Converts `name` into the name of an iceoryx2 service.
+pub(crate) fn service_name(name: &str) -> Result<ServiceName> {
+    ServiceName::new(name).map_err(|e| anyhow!("Invalid iceoryx2 service name {:?}: {}", name, e))
+}
+
+/// Removes the ports of iceoryx2 nodes whose processes terminated without dropping them, e.g. workers killed by a
+/// service manager, and with them the services no live process uses anymore.
+pub(crate) fn remove_dead_nodes() {
+    set_log_level_from_env_or(LogLevel::Error);
+    Node::<ipc::Service>::cleanup_dead_nodes(Config::global_config());
+}
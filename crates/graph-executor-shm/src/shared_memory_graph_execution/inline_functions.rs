@@ -0,0 +1,102 @@
+use super::{
+    executable_node::{ExecutableNode, NodeOutput},
+    execution_context::ExecutionContext,
+};
+use anyhow::{anyhow, Result};
+use graph_executor_core::graph_structure::node::Node;
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+/// Computation executing a node with [`graph_executor_core::graph_structure::isolation::Isolation::Inline`] in the
+/// worker's thread, with the [`ExecutionContext`] of its execution.
+pub type InlineFunction = Arc<dyn ExecutableNode>;
+
+/// Functions executing inline nodes, by the name used as their `command`.
+#[derive(Clone, Default)]
+pub struct InlineFunctions {
+    functions: BTreeMap<String, InlineFunction>,
+    /// Payloads executing nodes instead of their `command`, by the nodes' string identifiers, see
+    /// [`InlineFunctions::with_payload`].
+    payloads: BTreeMap<String, InlineFunction>,
+}
+
+impl fmt::Debug for InlineFunctions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set()
+            .entries(self.functions.keys())
+            .entries(self.payloads.keys())
+            .finish()
+    }
+}
+
+/// [`ExecutableNode`] calling a function without output, see [`InlineFunctions::with`].
+struct FunctionNode<F>(F);
+
+impl<F: Fn(&Node, &ExecutionContext) -> Result<()> + Send + Sync> ExecutableNode
+    for FunctionNode<F>
+{
+    fn execute(&self, context: &ExecutionContext) -> Result<NodeOutput> {
+        (self.0)(context.node(), context).map(|()| NodeOutput::default())
+    }
+}
+
+/// [`ExecutableNode`] calling a function whose return value is passed to the node's children, see
+/// [`InlineFunctions::with_output`].
+struct OutputFunctionNode<F>(F);
+
+impl<T: Serialize, F: Fn(&Node, &ExecutionContext) -> Result<T> + Send + Sync> ExecutableNode
+    for OutputFunctionNode<F>
+{
+    fn execute(&self, context: &ExecutionContext) -> Result<NodeOutput> {
+        NodeOutput::serialized(&(self.0)(context.node(), context)?)
+    }
+}
+
+impl InlineFunctions {
+    /// Returns the [`InlineFunctions`] with `function` registered under `name`, replacing any function registered
+    /// under the same name.
+    pub fn with(
+        self,
+        name: &str,
+        function: impl Fn(&Node, &ExecutionContext) -> Result<()> + Send + Sync + 'static,
+    ) -> Self {
+        self.with_node(name, FunctionNode(function))
+    }
+
+    /// Returns the [`InlineFunctions`] with `function` registered under `name` like [`InlineFunctions::with`], passing
+    /// the value it returns to the node's children, which read it with [`ExecutionContext::parent_output`].
+    pub fn with_output<T: Serialize>(
+        self,
+        name: &str,
+        function: impl Fn(&Node, &ExecutionContext) -> Result<T> + Send + Sync + 'static,
+    ) -> Self {
+        self.with_node(name, OutputFunctionNode(function))
+    }
+
+    /// Returns the [`InlineFunctions`] with `node` registered under `name` like [`InlineFunctions::with`], for
+    /// computations passing a result to their children.
+    pub fn with_node(mut self, name: &str, node: impl ExecutableNode + 'static) -> Self {
+        self.functions.insert(name.to_string(), Arc::new(node));
+        self
+    }
+
+    /// Returns the [`InlineFunctions`] with `payload` executing the node identified by `node_id`, whatever its
+    /// `command`, replacing any payload of the same node.
+    pub fn with_payload(mut self, node_id: &str, payload: impl ExecutableNode + 'static) -> Self {
+        self.payloads.insert(node_id.to_string(), Arc::new(payload));
+        self
+    }
+
+    /// Get the payload executing the node identified by `node_id`, see [`InlineFunctions::with_payload`].
+    pub fn payload(&self, node_id: &str) -> Option<&InlineFunction> {
+        self.payloads.get(node_id)
+    }
+
+    /// Calls the function registered under `name` with the `context` of its node.
+    pub fn call(&self, name: &str, context: &ExecutionContext) -> Result<NodeOutput> {
+        match self.functions.get(name) {
+            Some(function) => function.execute(context),
+            None => Err(anyhow!("No inline function {:?} is registered.", name)),
+        }
+    }
+}
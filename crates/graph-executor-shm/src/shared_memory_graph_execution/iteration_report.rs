@@ -0,0 +1,46 @@
+use super::execution_report::ExecutionReport;
+use std::{fmt, time::Duration};
+
+/// Summary of a graph executed repeatedly, returned by [`super::execute_graph::ExecuteGraph::execute_n`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IterationReport {
+    /// Wall-clock latency of every completed iteration (measured from its release in real-time mode), in the order
+    /// they were executed.
+    pub latencies: Vec<Duration>,
+    /// Indices of the iterations which finished after their deadline, see
+    /// [`super::realtime::RealtimeOptions::deadline`].
+    pub deadline_misses: Vec<usize>,
+    /// Report of the run after its last iteration.
+    pub report: ExecutionReport,
+}
+
+impl IterationReport {
+    /// Latency which `percentile` percent (0 to 100) of the completed iterations didn't exceed (nearest rank), or
+    /// [`None`] if no iteration completed.
+    pub fn latency_percentile(&self, percentile: f64) -> Option<Duration> {
+        let mut latencies = self.latencies.clone();
+        latencies.sort();
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * latencies.len() as f64).ceil() as usize;
+        latencies.get(rank.saturating_sub(1)).copied()
+    }
+}
+
+impl fmt::Display for IterationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Iterations: {}", self.latencies.len())?;
+        for (name, percentile) in [("p50", 50.0), ("p90", 90.0), ("p99", 99.0), ("max", 100.0)] {
+            if let Some(latency) = self.latency_percentile(percentile) {
+                write!(f, ", {} {:.3}ms", name, latency.as_secs_f64() * 1000.0)?;
+            }
+        }
+        if !self.deadline_misses.is_empty() {
+            write!(
+                f,
+                ", deadline missed by {} iteration(s): {:?}",
+                self.deadline_misses.len(),
+                self.deadline_misses
+            )?;
+        }
+        write!(f, "\n{}", self.report)
+    }
+}
@@ -0,0 +1,260 @@
+use super::iceoryx::{self, MAX_PORTS};
+use anyhow::{anyhow, Result};
+use graph_executor_core::{
+    clock::{Clock, SystemClock},
+    graph_structure::{execution_status::ExecutionStatus, graph::DirectedAcyclicGraph},
+};
+use iceoryx2::{
+    port::{listener::Listener, notifier::Notifier, publisher::Publisher, subscriber::Subscriber},
+    prelude::*,
+};
+use petgraph::graph::NodeIndex;
+use std::{
+    collections::VecDeque,
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// Number of events buffered for each listener, a listener falling behind misses the oldest events.
+pub const LIFECYCLE_CAPACITY: usize = 256;
+
+/// Most listeners of a run's lifecycle events at the same time.
+const MAX_LISTENERS: usize = 16;
+
+/// Sample of a lifecycle event: the `CLOCK_MONOTONIC` nanoseconds it was published at and the event encoded by
+/// [`LifecycleEvent::encode`].
+type LifecycleSample = [u64; 2];
+
+/// Event in the lifecycle of a run, published by its workers, see [`LifecycleEvents`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LifecycleEvent {
+    /// The first worker joined the run.
+    RunStarted,
+    /// The node at `node_index` of the run's graph finished with `status`: [`ExecutionStatus::Executed`],
+    /// [`ExecutionStatus::Failed`] or [`ExecutionStatus::Cancelled`].
+    NodeFinished {
+        node_index: usize,
+        status: ExecutionStatus,
+    },
+    /// The last worker left the run, `failed` if any of its nodes failed.
+    RunCompleted { failed: bool },
+}
+
+impl fmt::Display for LifecycleEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LifecycleEvent::RunStarted => write!(f, "run-started"),
+            LifecycleEvent::NodeFinished { node_index, status } => {
+                write!(f, "node-finished {} {}", node_index, status)
+            }
+            LifecycleEvent::RunCompleted { failed: false } => write!(f, "run-completed succeeded"),
+            LifecycleEvent::RunCompleted { failed: true } => write!(f, "run-completed failed"),
+        }
+    }
+}
+
+impl LifecycleEvent {
+    /// Encodes the event as the payload of a sample: its kind in the highest byte, a status in the byte below and a
+    /// node index in the lowest four bytes.
+    fn encode(self) -> u64 {
+        match self {
+            LifecycleEvent::RunStarted => 1 << 56,
+            LifecycleEvent::NodeFinished { node_index, status } => {
+                2 << 56 | (u8::from(status) as u64) << 32 | node_index as u32 as u64
+            }
+            LifecycleEvent::RunCompleted { failed } => 3 << 56 | failed as u64,
+        }
+    }
+
+    /// Decodes an event from the payload of a sample encoded by [`LifecycleEvent::encode`].
+    fn decode(payload: u64) -> Result<Self> {
+        match payload >> 56 {
+            1 => Ok(LifecycleEvent::RunStarted),
+            2 => Ok(LifecycleEvent::NodeFinished {
+                node_index: payload as u32 as usize,
+                status: ExecutionStatus::try_from((payload >> 32) as u8)?,
+            }),
+            3 => Ok(LifecycleEvent::RunCompleted {
+                failed: payload & 1 == 1,
+            }),
+            kind => Err(anyhow!("Invalid lifecycle event kind {}.", kind)),
+        }
+    }
+
+    /// Describes the event like its [`fmt::Display`], naming a finished node by its string identifier in the run's
+    /// `graph` instead of its index.
+    pub fn describe(&self, graph: &DirectedAcyclicGraph) -> String {
+        match *self {
+            LifecycleEvent::NodeFinished { node_index, status }
+                if node_index < graph.get_node_indices().count() =>
+            {
+                format!(
+                    "node-finished {} {}",
+                    graph.node_id(NodeIndex::new(node_index)),
+                    status
+                )
+            }
+            _ => self.to_string(),
+        }
+    }
+}
+
+/// Name of the iceoryx2 services of the lifecycle events of the run with `filename_suffix`.
+fn service_name(filename_suffix: &str) -> Result<ServiceName> {
+    iceoryx::service_name(&format!("graph-executor/{}/lifecycle", filename_suffix))
+}
+
+/// Publisher of a worker's lifecycle events on the iceoryx2 publish-subscribe service of its run, on which other
+/// components on the host react to the run starting, its nodes finishing and the run completing without polling its
+/// shared memory or files.
+///
+/// Every event is sent as a sample stamped with the time it was published, followed by a notification on the run's
+/// event service of the same name, on which [`LifecycleListener`]s block. Publishing never waits for listeners: a
+/// listener falling behind by more than [`LIFECYCLE_CAPACITY`] events misses the oldest ones, and so does a listener
+/// which only connects to a worker's publisher once the worker left the run. iceoryx2 removes the services once the
+/// last worker and listener dropped their ports.
+#[derive(Debug)]
+pub struct LifecycleEvents {
+    publisher: Publisher<ipc::Service, LifecycleSample, ()>,
+    notifier: Notifier<ipc::Service>,
+    _node: Node<ipc::Service>,
+}
+
+impl LifecycleEvents {
+    /// Creates the publisher of this worker's lifecycle events of the run with `filename_suffix`, creating the run's
+    /// services if no other worker did yet.
+    pub fn create_or_open(filename_suffix: &str) -> Result<Self> {
+        let error = |e: &dyn fmt::Display| {
+            anyhow!(
+                "Failed publishing lifecycle events of run {}: {}",
+                filename_suffix,
+                e
+            )
+        };
+        let node = iceoryx::create_node()?;
+        let name = service_name(filename_suffix)?;
+        let publisher = node
+            .service_builder(&name)
+            .publish_subscribe::<LifecycleSample>()
+            .max_publishers(MAX_PORTS)
+            .max_subscribers(MAX_LISTENERS)
+            .max_nodes(MAX_PORTS + MAX_LISTENERS)
+            .subscriber_max_buffer_size(LIFECYCLE_CAPACITY)
+            .enable_safe_overflow(true)
+            .open_or_create()
+            .map_err(|e| error(&e))?
+            .publisher_builder()
+            .create()
+            .map_err(|e| error(&e))?;
+        let notifier = node
+            .service_builder(&name)
+            .event()
+            .max_notifiers(MAX_PORTS)
+            .max_listeners(MAX_LISTENERS)
+            .max_nodes(MAX_PORTS + MAX_LISTENERS)
+            .open_or_create()
+            .map_err(|e| error(&e))?
+            .notifier_builder()
+            .create()
+            .map_err(|e| error(&e))?;
+        // Listeners connect to the new publisher when woken up, so that they receive its events even if the worker
+        // left the run by the time they read them
+        notifier.notify().map_err(|e| error(&e))?;
+        Ok(LifecycleEvents {
+            publisher,
+            notifier,
+            _node: node,
+        })
+    }
+
+    /// Publishes `event` and wakes all listeners.
+    pub fn publish(&self, event: LifecycleEvent) -> Result<()> {
+        let published_at = SystemClock.now().as_nanos() as u64;
+        self.publisher
+            .send_copy([published_at, event.encode()])
+            .map_err(|e| anyhow!("Failed publishing lifecycle event {}: {}", event, e))?;
+        self.notifier
+            .notify()
+            .map_err(|e| anyhow!("Failed publishing lifecycle event {}: {}", event, e))?;
+        Ok(())
+    }
+}
+
+/// Listener receiving the [`LifecycleEvent`]s of a run in the order they were published, e.g. to start a
+/// downstream component once the run completed.
+#[derive(Debug)]
+pub struct LifecycleListener {
+    subscriber: Subscriber<ipc::Service, LifecycleSample, ()>,
+    listener: Listener<ipc::Service>,
+    /// Samples received but not returned yet, in the order they were published.
+    pending: VecDeque<LifecycleSample>,
+    _node: Node<ipc::Service>,
+}
+
+impl LifecycleListener {
+    /// Subscribes to the events of the run with `filename_suffix` published from now on.
+    pub fn subscribe(filename_suffix: &str) -> Result<Self> {
+        let error = |e: &dyn fmt::Display| {
+            anyhow!(
+                "Failed subscribing to lifecycle events of run {}: {}",
+                filename_suffix,
+                e
+            )
+        };
+        let node = iceoryx::create_node()?;
+        let name = service_name(filename_suffix)?;
+        let subscriber = node
+            .service_builder(&name)
+            .publish_subscribe::<LifecycleSample>()
+            .open()
+            .map_err(|e| error(&e))?
+            .subscriber_builder()
+            .buffer_size(LIFECYCLE_CAPACITY)
+            .create()
+            .map_err(|e| error(&e))?;
+        let listener = node
+            .service_builder(&name)
+            .event()
+            .open()
+            .map_err(|e| error(&e))?
+            .listener_builder()
+            .create()
+            .map_err(|e| error(&e))?;
+        Ok(LifecycleListener {
+            subscriber,
+            listener,
+            pending: VecDeque::new(),
+            _node: node,
+        })
+    }
+
+    /// Blocks until the next event was published or `timeout` passed, returning [`None`] on timeout.
+    pub fn next_event(&mut self, timeout: Duration) -> Result<Option<LifecycleEvent>> {
+        let started = Instant::now();
+        loop {
+            // Receive before waiting, so that an event published in between is never missed. Samples of different
+            // workers are received per worker, so they are ordered by the time they were published.
+            let mut received = vec![];
+            while let Some(sample) = self
+                .subscriber
+                .receive()
+                .map_err(|e| anyhow!("Failed receiving lifecycle events: {}", e))?
+            {
+                received.push(*sample);
+            }
+            received.sort_unstable();
+            self.pending.extend(received);
+            while let Some([_, payload]) = self.pending.pop_front() {
+                if let Ok(event) = LifecycleEvent::decode(payload) {
+                    return Ok(Some(event));
+                }
+            }
+            let Some(remaining) = timeout.checked_sub(started.elapsed()) else {
+                return Ok(None);
+            };
+            self.listener
+                .timed_wait_all(|_| (), remaining)
+                .map_err(|e| anyhow!("Failed waiting for lifecycle events: {}", e))?;
+        }
+    }
+}
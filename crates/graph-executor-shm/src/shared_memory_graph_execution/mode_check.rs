@@ -0,0 +1,325 @@
+use super::{
+    execute_graph::{ExecuteGraph, ExecutionSteps},
+    execution_options::ExecutionOptions,
+    result_blob::ResultBlob,
+};
+use anyhow::{anyhow, Result};
+use graph_executor_core::graph_structure::{
+    attempt::{Attempt, AttemptOutcome},
+    edge::Dependency,
+    execution_status::ExecutionStatus,
+    graph::DirectedAcyclicGraph,
+};
+use petgraph::graph::NodeIndex;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    process::{Command, Stdio},
+    thread,
+};
+
+/// Way in which [`check_execution_modes`] executes a graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckedMode {
+    /// A single worker in the calling thread.
+    Sequential,
+    /// Workers in threads of the calling process.
+    Threads,
+    /// Workers in processes of their own, started with [`ModeCheckOptions::worker_command`].
+    Processes,
+}
+
+impl fmt::Display for CheckedMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                CheckedMode::Sequential => "sequential",
+                CheckedMode::Threads => "threads",
+                CheckedMode::Processes => "processes",
+            }
+        )
+    }
+}
+
+/// Configuration of [`check_execution_modes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModeCheckOptions {
+    /// Number of workers executing the graph in the multi-threaded and the multi-process mode.
+    pub workers: usize,
+    /// Command line (program and leading arguments) of the worker processes of the multi-process mode, to which
+    /// the graph's DOT file, the run's filename suffix and `--keep-going` are appended, e.g. the path of the
+    /// `graph-executor` binary. The multi-process mode is skipped if [`None`].
+    pub worker_command: Option<Vec<String>>,
+    /// Identifiers of the nodes whose results are not expected to be identical between executions, e.g. because
+    /// they contain timestamps. Their statuses are compared nonetheless.
+    pub nondeterministic_nodes: BTreeSet<String>,
+}
+
+impl Default for ModeCheckOptions {
+    fn default() -> Self {
+        ModeCheckOptions {
+            workers: 3,
+            worker_command: None,
+            nondeterministic_nodes: BTreeSet::new(),
+        }
+    }
+}
+
+/// Execution of a graph in one [`CheckedMode`].
+#[derive(Clone, Debug)]
+pub struct ModeRun {
+    /// Mode the graph was executed in.
+    pub mode: CheckedMode,
+    /// Graph with its final execution statuses and attempts.
+    pub graph: DirectedAcyclicGraph,
+    /// Contents of the result blobs of the nodes without children by their identifiers.
+    pub results: BTreeMap<String, Vec<u8>>,
+}
+
+/// Executions of a graph in every mode and how they diverge, see [`check_execution_modes`].
+#[derive(Clone, Debug)]
+pub struct ModeCheckReport {
+    /// Executions in the order of their modes.
+    pub runs: Vec<ModeRun>,
+    /// Descriptions of the final statuses and results differing from those of the sequential execution and of the
+    /// nodes which started before their parents allowed it.
+    pub divergences: Vec<String>,
+}
+
+impl ModeCheckReport {
+    /// Checks whether all modes executed the graph the same way.
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+impl fmt::Display for ModeCheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for run in &self.runs {
+            let workers: BTreeSet<usize> = run
+                .graph
+                .get_node_indices()
+                .flat_map(|i| run.graph[i].attempts())
+                .map(|attempt| attempt.worker_id)
+                .collect();
+            writeln!(
+                f,
+                "{}: {} executed, {} failed by {} worker(s)",
+                run.mode,
+                run.graph
+                    .get_node_indices()
+                    .filter(|i| run.graph[*i].execution_status == ExecutionStatus::Executed)
+                    .count(),
+                run.graph.get_failed_node_indices().len(),
+                workers.len()
+            )?;
+        }
+        match self.is_consistent() {
+            true => write!(f, "All modes agree."),
+            false => write!(f, "Divergences:\n    {}", self.divergences.join("\n    ")),
+        }
+    }
+}
+
+/// Executes `graph` sequentially, multi-threaded and (with [`ModeCheckOptions::worker_command`]) multi-process in
+/// runs whose filename suffixes are `filename_suffix` followed by the mode (e.g. `-threads`), and reports where the
+/// modes diverge: final statuses or results of deterministic nodes differing from the sequential execution, and
+/// nodes of any mode which started before their parents completed (or, for streaming dependencies, started).
+///
+/// All workers keep going after failures, so that the final statuses don't depend on which nodes were executing
+/// when the first one failed. In every mode, the calling thread is a worker and leaves the run last, so that it
+/// can read the results. Inline nodes are only executed by the calling process, since their functions can't be
+/// registered with the worker processes.
+pub fn check_execution_modes(
+    graph: &DirectedAcyclicGraph,
+    filename_suffix: &str,
+    options: &ModeCheckOptions,
+) -> Result<ModeCheckReport> {
+    if options.workers == 0 {
+        return Err(anyhow!(
+            "At least one worker is required to check execution modes."
+        ));
+    }
+    let mut runs = vec![
+        execute_in_mode(graph, filename_suffix, CheckedMode::Sequential, options)?,
+        execute_in_mode(graph, filename_suffix, CheckedMode::Threads, options)?,
+    ];
+    if options.worker_command.is_some() {
+        runs.push(execute_in_mode(
+            graph,
+            filename_suffix,
+            CheckedMode::Processes,
+            options,
+        )?);
+    }
+
+    let mut divergences = vec![];
+    let reference = &runs[0];
+    for run in &runs {
+        for i in run.graph.get_node_indices() {
+            let node_id = run.graph.node_id(i);
+            let expected = reference
+                .graph
+                .node_index(node_id)
+                .map(|j| &reference.graph[j]);
+            if let Some(expected) =
+                expected.filter(|n| n.execution_status != run.graph[i].execution_status)
+            {
+                divergences.push(format!(
+                    "{}: {} is {}, but {} sequentially",
+                    run.mode, node_id, run.graph[i].execution_status, expected.execution_status
+                ));
+            }
+            divergences.extend(
+                ordering_violations(&run.graph, i)
+                    .into_iter()
+                    .map(|violation| format!("{}: {}", run.mode, violation)),
+            );
+        }
+        for (node_id, result) in &run.results {
+            if options.nondeterministic_nodes.contains(node_id) {
+                continue;
+            }
+            if reference.results.get(node_id) != Some(result) {
+                divergences.push(format!(
+                    "{}: result of {} differs from the sequential execution",
+                    run.mode, node_id
+                ));
+            }
+        }
+        for node_id in reference.results.keys() {
+            if !run.results.contains_key(node_id) {
+                divergences.push(format!("{}: {} has no result", run.mode, node_id));
+            }
+        }
+    }
+    Ok(ModeCheckReport { runs, divergences })
+}
+
+/// Executes `graph` in `mode` in the run with `filename_suffix` followed by the mode, with the calling thread as
+/// the worker leaving the run last.
+fn execute_in_mode(
+    graph: &DirectedAcyclicGraph,
+    filename_suffix: &str,
+    mode: CheckedMode,
+    options: &ModeCheckOptions,
+) -> Result<ModeRun> {
+    let filename_suffix = format!("{}-{}", filename_suffix, mode);
+    let execution_options = ExecutionOptions {
+        keep_going: true,
+        ..Default::default()
+    };
+    let helper_count = match mode {
+        CheckedMode::Sequential => 0,
+        CheckedMode::Threads | CheckedMode::Processes => options.workers - 1,
+    };
+    let mut executed = graph.clone();
+    let mut run = executed.join_run(filename_suffix.clone(), &execution_options)?;
+
+    // Other workers attach to the run created above, this worker waits for them to leave before it does
+    let dot_file = std::env::temp_dir().join(format!("graph-executor-{}.dot", filename_suffix));
+    let execution = thread::scope(|scope| -> Result<()> {
+        let mut processes = vec![];
+        for _ in 0..helper_count {
+            match mode {
+                CheckedMode::Sequential => {}
+                CheckedMode::Threads => {
+                    let (mut graph, filename_suffix) = (graph.clone(), filename_suffix.clone());
+                    let execution_options = &execution_options;
+                    // Workers fail if any node failed, which the comparison reports instead
+                    scope.spawn(move || {
+                        let _ = graph.execute_with_options(filename_suffix, execution_options);
+                    });
+                }
+                CheckedMode::Processes => {
+                    if processes.is_empty() {
+                        std::fs::write(&dot_file, graph.to_string())
+                            .map_err(|e| anyhow!("Failed writing {}: {}", dot_file.display(), e))?;
+                    }
+                    let command = options.worker_command.as_deref().unwrap_or_default();
+                    let (program, args) = command
+                        .split_first()
+                        .ok_or(anyhow!("Worker command is empty."))?;
+                    processes.push(
+                        Command::new(program)
+                            .args(args)
+                            .arg(&dot_file)
+                            .arg(&filename_suffix)
+                            .arg("--keep-going")
+                            .stdout(Stdio::null())
+                            .stderr(Stdio::null())
+                            .spawn()
+                            .map_err(|e| anyhow!("Failed starting worker {}: {}", program, e))?,
+                    );
+                }
+            }
+        }
+        let executed_iteration = executed.execute_iteration(&mut run, &execution_options);
+        for mut process in processes {
+            process.wait()?;
+        }
+        executed_iteration
+    });
+    if mode == CheckedMode::Processes {
+        let _ = std::fs::remove_file(&dot_file);
+    }
+    execution?;
+
+    let final_graph: DirectedAcyclicGraph = run.shared_memory.read_graph()?;
+    let mut results = BTreeMap::new();
+    for i in final_graph
+        .get_node_indices()
+        .filter(|i| final_graph.get_child_node_indices(*i).next().is_none())
+    {
+        if let Some(blob) = ResultBlob::open(&run.filename_suffix, i)? {
+            results.insert(final_graph.node_id(i).to_string(), blob.to_vec());
+        }
+    }
+    // Failed nodes are reported by the comparison rather than as an error
+    if let Err(e) = executed.leave_run(run) {
+        if final_graph.get_failed_node_indices().is_empty() {
+            return Err(e);
+        }
+    }
+    Ok(ModeRun {
+        mode,
+        graph: final_graph,
+        results,
+    })
+}
+
+/// Describes how the successful attempt of the node at `node_index` of `graph` started before one of its parents
+/// completed or, if it streams from the parent, started.
+fn ordering_violations(graph: &DirectedAcyclicGraph, node_index: NodeIndex) -> Vec<String> {
+    let succeeded = |i: NodeIndex| -> Option<&Attempt> {
+        graph[i]
+            .attempts()
+            .iter()
+            .rfind(|attempt| attempt.outcome == AttemptOutcome::Succeeded)
+    };
+    let Some(attempt) = succeeded(node_index) else {
+        return vec![];
+    };
+    graph
+        .get_parent_node_indices(node_index)
+        .filter_map(|parent| {
+            let parent_attempt = succeeded(parent)?;
+            let (allowed_after, event) = match graph.dependency(parent, node_index) {
+                Some(Dependency::Streaming) => (parent_attempt.started_after, "started"),
+                _ => (parent_attempt.finished_after, "completed"),
+            };
+            (attempt.started_after < allowed_after).then(|| {
+                format!(
+                    "{} started at {:.3}s before its parent {} {} at {:.3}s",
+                    graph.node_id(node_index),
+                    attempt.started_after.as_secs_f64(),
+                    graph.node_id(parent),
+                    event,
+                    allowed_after.as_secs_f64()
+                )
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,44 @@
+use anyhow::{anyhow, Error, Result};
+use std::{fmt, str::FromStr};
+
+/// What a worker does with its running command node when a node with a higher priority becomes executable while
+/// no worker is idle. Only the worker running the lowest-priority command node preempts it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PreemptionPolicy {
+    /// Running nodes are never preempted.
+    #[default]
+    Disabled,
+    /// The running command is paused with `SIGSTOP` while the worker executes the higher-priority node and is
+    /// continued with `SIGCONT` afterwards. Its timeout is paused meanwhile, and it is killed if executing the
+    /// higher-priority node returns an error.
+    Stop,
+    /// The running command is terminated with `SIGTERM` and its node is requeued as executable, so that it is
+    /// restarted from scratch once a worker is available.
+    Terminate,
+}
+
+impl fmt::Display for PreemptionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PreemptionPolicy::Disabled => write!(f, "disabled"),
+            PreemptionPolicy::Stop => write!(f, "stop"),
+            PreemptionPolicy::Terminate => write!(f, "terminate"),
+        }
+    }
+}
+
+impl FromStr for PreemptionPolicy {
+    type Err = Error;
+
+    fn from_str(policy: &str) -> Result<Self> {
+        match policy {
+            "disabled" => Ok(PreemptionPolicy::Disabled),
+            "stop" => Ok(PreemptionPolicy::Stop),
+            "terminate" => Ok(PreemptionPolicy::Terminate),
+            other => Err(anyhow!(
+                "Unknown preemption policy {:?}, expected disabled, stop or terminate.",
+                other
+            )),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use anyhow::{anyhow, Result};
+use std::{
+    env, fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+/// Name of the environment variable holding the path of the progress file of a running command node. The command
+/// emits a progress mark by appending a line to it, e.g. `echo >> "$GRAPH_EXECUTOR_PROGRESS"` after writing each
+/// chunk of its output, see [`graph_executor_core::graph_structure::edge::Dependency::Streaming`].
+pub const PROGRESS_VARIABLE: &str = "GRAPH_EXECUTOR_PROGRESS";
+
+/// Progress file of a command node run by this worker, removed once dropped.
+pub struct ProgressFile {
+    /// Path of the file, in the temporary directory.
+    path: PathBuf,
+    /// Number of progress marks already read from the file.
+    marks: u32,
+}
+
+impl ProgressFile {
+    /// Creates the (empty) progress file of the node at `node_index` executed by this process.
+    pub fn create(node_index: usize) -> Result<Self> {
+        let path = env::temp_dir().join(format!(
+            "graph-executor-{}-{}.progress",
+            std::process::id(),
+            node_index
+        ));
+        fs::write(&path, "")
+            .map_err(|e| anyhow!("Failed creating progress file {}: {}", path.display(), e))?;
+        Ok(ProgressFile { path, marks: 0 })
+    }
+
+    /// Get the path of the progress file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the number of progress marks appended to the file since the last call.
+    pub fn new_marks(&mut self) -> Result<u32> {
+        let marks = match fs::read(&self.path) {
+            Ok(content) => content.iter().filter(|byte| **byte == b'\n').count() as u32,
+            // The command may have removed its progress file, no marks are lost as they are counted
+            Err(e) if e.kind() == ErrorKind::NotFound => self.marks,
+            Err(e) => {
+                return Err(anyhow!(
+                    "Failed reading progress file {}: {}",
+                    self.path.display(),
+                    e
+                ))
+            }
+        };
+        let new_marks = marks.saturating_sub(self.marks);
+        self.marks = self.marks.max(marks);
+        Ok(new_marks)
+    }
+}
+
+impl Drop for ProgressFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::{anyhow, Result};
+use serde::de::{Deserializer, IgnoredAny, SeqAccess, Visitor};
+use std::fmt;
+
+/// Major version of the shared memory protocol. Must be incremented whenever the serialized layout of the
+/// run's shared memory mappings changes incompatibly.
+pub const PROTOCOL_MAJOR: u16 = 35;
+/// Minor version of the shared memory protocol. Incremented on backwards compatible additions.
+pub const PROTOCOL_MINOR: u16 = 0;
+
+/// The run's header records provenance (creator pid, hostname, binary version and graph hash).
+pub const FEATURE_PROVENANCE: u64 = 1 << 0;
+/// Workers account the run's shared memory and disk usage.
+pub const FEATURE_RESOURCE_ACCOUNTING: u64 = 1 << 1;
+/// Features supported by this build.
+pub const SUPPORTED_FEATURES: u64 = FEATURE_PROVENANCE | FEATURE_RESOURCE_ACCOUNTING;
+
+/// Protocol version and feature bitmask of a run, stored as the first field of its
+/// [`super::run_header::RunHeader`].
+///
+/// The layout of this struct must never change, so that workers of any version can read it and
+/// refuse to attach to runs they are incompatible with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ProtocolHandshake {
+    /// Major protocol version, workers only attach to runs with the same major version.
+    pub major: u16,
+    /// Minor protocol version.
+    pub minor: u16,
+    /// Bitmask of features the run requires of its workers.
+    pub features: u64,
+}
+
+impl fmt::Display for ProtocolHandshake {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "protocol v{}.{} (features {:#x})",
+            self.major, self.minor, self.features
+        )
+    }
+}
+
+impl Default for ProtocolHandshake {
+    fn default() -> Self {
+        ProtocolHandshake {
+            major: PROTOCOL_MAJOR,
+            minor: PROTOCOL_MINOR,
+            features: SUPPORTED_FEATURES,
+        }
+    }
+}
+
+impl ProtocolHandshake {
+    /// Checks whether a worker of this build can attach to a run with this [`ProtocolHandshake`].
+    pub fn check_compatibility(&self) -> Result<()> {
+        let own = ProtocolHandshake::default();
+        if self.major != own.major {
+            return Err(anyhow!(
+                "Incompatible run: run uses {}, this worker (graph-executor {}) speaks {}.",
+                self,
+                env!("CARGO_PKG_VERSION"),
+                own
+            ));
+        }
+        let missing_features = self.features & !own.features;
+        if missing_features != 0 {
+            return Err(anyhow!(
+                "Incompatible run: run uses {} which requires features {:#x} not supported by this worker (graph-executor {}, {}).",
+                self,
+                missing_features,
+                env!("CARGO_PKG_VERSION"),
+                own
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Deserializes only the leading [`ProtocolHandshake`] of a serialized struct and skips its remaining fields,
+/// so that headers written by other versions can still be probed.
+pub(crate) struct HandshakeProbe(pub ProtocolHandshake);
+
+impl<'de> serde::Deserialize<'de> for HandshakeProbe {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ProbeVisitor;
+
+        impl<'de> Visitor<'de> for ProbeVisitor {
+            type Value = HandshakeProbe;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a sequence starting with a protocol handshake")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let handshake = seq
+                    .next_element::<ProtocolHandshake>()?
+                    .ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
+                while seq.next_element::<IgnoredAny>()?.is_some() {}
+                Ok(HandshakeProbe(handshake))
+            }
+        }
+
+        deserializer.deserialize_seq(ProbeVisitor)
+    }
+}
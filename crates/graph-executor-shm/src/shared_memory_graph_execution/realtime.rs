@@ -0,0 +1,70 @@
+use anyhow::{anyhow, Result};
+use graph_executor_core::clock::Clock;
+use std::time::Duration;
+
+/// Soft real-time timing of the iterations of [`super::execute_graph::ExecuteGraph::execute_n`], e.g. for
+/// robotics pipelines: iterations are released once per `period`, and iterations taking longer than `deadline` are
+/// reported as deadline misses instead of failing the run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RealtimeOptions {
+    /// Interval between the starts (releases) of consecutive iterations.
+    pub period: Duration,
+    /// Latency after its release by which an iteration should be finished, at most `period`.
+    pub deadline: Duration,
+    /// Optional `SCHED_FIFO` priority (1 to 99) of the worker and its commands, which requires `CAP_SYS_NICE`.
+    /// Otherwise, the worker keeps its scheduling policy.
+    pub fifo_priority: Option<i32>,
+}
+
+impl RealtimeOptions {
+    /// Creates [`RealtimeOptions`] whose deadline is the end of the period, without changing the scheduling policy.
+    pub fn new(period: Duration) -> Self {
+        RealtimeOptions {
+            period,
+            deadline: period,
+            fifo_priority: None,
+        }
+    }
+
+    /// Checks that the period is not zero and the deadline lies within the period.
+    pub fn check(&self) -> Result<()> {
+        if self.period.is_zero() {
+            return Err(anyhow!("Period of real-time iterations must not be zero."));
+        }
+        if self.deadline > self.period {
+            return Err(anyhow!(
+                "Deadline {:?} of real-time iterations exceeds their period {:?}.",
+                self.deadline,
+                self.period
+            ));
+        }
+        Ok(())
+    }
+
+    /// Switches the calling process to the `SCHED_FIFO` scheduling policy with `fifo_priority`, if set. Commands
+    /// started afterwards inherit the policy.
+    pub fn apply_scheduling_policy(&self) -> Result<()> {
+        let Some(priority) = self.fifo_priority else {
+            return Ok(());
+        };
+        let param = libc::sched_param {
+            sched_priority: priority,
+        };
+        // SAFETY: `param` is a valid `sched_param` for the duration of the call, `0` refers to the calling process.
+        if unsafe { libc::sched_setscheduler(0, libc::SCHED_FIFO, &param) } == -1 {
+            return Err(anyhow!(
+                "Failed setting SCHED_FIFO priority {}: {}",
+                priority,
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Release time on `clock` of the iteration following the one released at `release`. If that time already
+    /// passed (the iteration overran its period), the next iteration is released immediately and the schedule
+    /// restarts from now, instead of releasing the skipped periods in a burst.
+    pub fn next_release(&self, release: Duration, clock: &dyn Clock) -> Duration {
+        (release + self.period).max(clock.now())
+    }
+}
@@ -0,0 +1,214 @@
+use super::{
+    artifact_store::ArtifactStore, execute_graph::parent_results, result_blob,
+    worker_table::WorkerTable,
+};
+use anyhow::{anyhow, Result};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use graph_executor_core::graph_structure::graph::DirectedAcyclicGraph;
+use petgraph::graph::NodeIndex;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+/// Name of the result blob in a cache entry.
+const RESULT_ENTRY: &str = "result";
+/// Directory of the node's declared outputs in a cache entry.
+const OUTPUTS_DIRECTORY: &str = "outputs";
+
+/// Lookups of a run's nodes in the remote cache, see [`super::execution_options::ExecutionOptions::remote_cache`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStatistics {
+    /// Number of nodes whose results were read from the cache instead of executing them.
+    pub hits: u64,
+    /// Number of nodes which were executed after finding no results for them in the cache.
+    pub misses: u64,
+}
+
+impl fmt::Display for CacheStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Remote cache: {} hits, {} misses",
+            self.hits, self.misses
+        )?;
+        if self.hits + self.misses > 0 {
+            write!(f, " ({:.1}% hit rate)", 100.0 * self.hit_rate())?;
+        }
+        Ok(())
+    }
+}
+
+impl CacheStatistics {
+    /// Sums the lookups of all workers of a run in its worker `table`.
+    pub fn from_workers(table: &WorkerTable) -> Self {
+        table
+            .workers
+            .iter()
+            .fold(CacheStatistics::default(), |statistics, worker| {
+                CacheStatistics {
+                    hits: statistics.hits + worker.cache_hits,
+                    misses: statistics.misses + worker.cache_misses,
+                }
+            })
+    }
+
+    /// Fraction of lookups which were hits, 0 without lookups.
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            lookups => self.hits as f64 / lookups as f64,
+        }
+    }
+}
+
+/// Key of the cached results of the node at `node_index` in `graph`'s run identified by `filename_suffix`: the
+/// node's content hash (see [`DirectedAcyclicGraph::node_content_hash`]) combined with the content of its declared
+/// `inputs` (resolved against its command's `directory`, see [`resolve`]) and of its parents' result blobs, so that
+/// the key changes with anything the node's execution reads.
+pub(crate) fn cache_key(
+    graph: &DirectedAcyclicGraph,
+    filename_suffix: &str,
+    node_index: NodeIndex,
+    directory: Option<&Path>,
+) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(graph.node_content_hash(node_index).as_bytes());
+    for input in graph[node_index].inputs() {
+        hasher.update(input.as_bytes());
+        hasher.update([0]);
+        // Missing inputs are hashed as such, the node's command reports them once it is executed
+        match fs::read(resolve(directory, input)) {
+            Ok(content) => hasher.update(Sha256::digest(content)),
+            Err(_) => hasher.update(b"missing"),
+        }
+    }
+    for (parent_id, path) in parent_results(graph, filename_suffix, node_index) {
+        hasher.update(parent_id.as_bytes());
+        hasher.update([0]);
+        hasher.update(Sha256::digest(fs::read(&path).map_err(|e| {
+            anyhow!("Failed reading result of {}: {}", parent_id, e)
+        })?));
+    }
+    let hash: String = hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    Ok(format!("nodes/{}/{}", &hash[..2], hash))
+}
+
+/// Reads the results of the node at `node_index` of `graph`'s run identified by `filename_suffix` from the entry
+/// `key` of `cache`: its result blob is written to the node's result path and its declared outputs to their paths
+/// resolved against its command's `directory`. Returns `false` without writing anything if there is no such entry
+/// or it lacks any of the node's declared outputs.
+pub(crate) fn read_through(
+    cache: &dyn ArtifactStore,
+    key: &str,
+    graph: &DirectedAcyclicGraph,
+    filename_suffix: &str,
+    node_index: NodeIndex,
+    directory: Option<&Path>,
+) -> Result<bool> {
+    let Some(entry) = cache.get(key)? else {
+        return Ok(false);
+    };
+    let invalid = |e: std::io::Error| anyhow!("Invalid cache entry {}: {}", key, e);
+    let mut tarball = tar::Archive::new(GzDecoder::new(entry.as_slice()));
+    let mut files = BTreeMap::new();
+    for file in tarball.entries().map_err(invalid)? {
+        let mut file = file.map_err(invalid)?;
+        let name = file.path().map_err(invalid)?.to_string_lossy().to_string();
+        let mut content = vec![];
+        file.read_to_end(&mut content).map_err(invalid)?;
+        files.insert(name, content);
+    }
+    // Declared outputs are stored by their index, a node restored without all of them is executed instead
+    let mut restored = vec![];
+    for (i, output) in graph[node_index].outputs().iter().enumerate() {
+        match files.remove(&output_entry(i)) {
+            Some(content) => restored.push((resolve(directory, output), content)),
+            None => return Ok(false),
+        }
+    }
+    if let Some(content) = files.remove(RESULT_ENTRY) {
+        restored.push((
+            result_blob::result_path(filename_suffix, node_index),
+            content,
+        ));
+    }
+    for (path, content) in restored {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, content)
+            .map_err(|e| anyhow!("Failed restoring {} from cache: {}", path.display(), e))?;
+    }
+    Ok(true)
+}
+
+/// Writes the results of the executed node at `node_index` of `graph`'s run identified by `filename_suffix` to the
+/// entry `key` of `cache`: its result blob and its declared outputs, resolved against its command's `directory`.
+/// Nodes with neither are not cached, since they are executed for their side effects. Returns the number of bytes of
+/// the written entry, zero if none was written.
+pub(crate) fn write_through(
+    cache: &dyn ArtifactStore,
+    key: &str,
+    graph: &DirectedAcyclicGraph,
+    filename_suffix: &str,
+    node_index: NodeIndex,
+    directory: Option<&Path>,
+) -> Result<usize> {
+    let mut files = vec![];
+    let result_path = result_blob::result_path(filename_suffix, node_index);
+    if result_path.exists() {
+        files.push((String::from(RESULT_ENTRY), fs::read(&result_path)?));
+    }
+    for (i, output) in graph[node_index].outputs().iter().enumerate() {
+        // Nodes which didn't write all their outputs would restore an incomplete result
+        let Ok(content) = fs::read(resolve(directory, output)) else {
+            return Ok(0);
+        };
+        files.push((output_entry(i), content));
+    }
+    if files.is_empty() {
+        return Ok(0);
+    }
+    let failed = |e: std::io::Error| anyhow!("Failed writing cache entry {}: {}", key, e);
+    let mut tarball = tar::Builder::new(GzEncoder::new(vec![], Compression::default()));
+    for (name, content) in &files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        tarball
+            .append_data(&mut header, name, content.as_slice())
+            .map_err(failed)?;
+    }
+    let entry = tarball
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(failed)?;
+    cache.put(key, &entry)?;
+    Ok(entry.len())
+}
+
+/// Name of the node's declared output with index `i` in a cache entry. Outputs are named by index rather than by
+/// path, since archives normalize absolute and `./`-prefixed paths.
+fn output_entry(i: usize) -> String {
+    format!("{}/{}", OUTPUTS_DIRECTORY, i)
+}
+
+/// Resolves the relative `path` of a node's input or output against the `directory` its command runs in, see
+/// [`super::execute_graph::command_directory`], or the working directory of this worker if [`None`].
+fn resolve(directory: Option<&Path>, path: &str) -> PathBuf {
+    match directory {
+        Some(directory) => directory.join(path),
+        None => PathBuf::from(path),
+    }
+}
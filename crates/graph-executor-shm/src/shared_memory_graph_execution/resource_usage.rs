@@ -0,0 +1,79 @@
+use std::{collections::BTreeMap, fmt};
+
+/// Shared memory and disk bytes attributed to a single [`graph_executor_core::graph_structure::node::Node`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct NodeResourceUsage {
+    /// Bytes the node occupies in the serialized graph in shared memory.
+    pub shm_bytes: usize,
+    /// Bytes written to disk while executing the node.
+    pub disk_bytes: usize,
+}
+
+/// Shared memory and disk bytes consumed by a run, in total and per node.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ResourceUsage {
+    /// Shared memory bytes allocated for the graph when the run finished.
+    pub shm_bytes: usize,
+    /// Highest number of shared memory bytes allocated for the graph during the run.
+    pub peak_shm_bytes: usize,
+    /// Bytes written to disk during the run: the captured output of its commands, its spilled result blobs and the
+    /// entries it wrote to a remote cache on this machine's disk.
+    pub disk_bytes: usize,
+    /// Usage per node, keyed by the node's string identifier.
+    pub per_node: BTreeMap<String, NodeResourceUsage>,
+}
+
+/// Error of a run whose workers wrote more bytes to disk than its cap, see
+/// [`super::execution_options::ExecutionOptions::max_disk_bytes`]. The run is cancelled once a node exceeded the cap
+/// and leaving it fails with this error as the [`super::execution_report::RunFailed::error`].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DiskFull {
+    /// String identifier of the node whose writes exceeded the cap.
+    pub node_id: String,
+    /// Number of bytes the node wrote.
+    pub bytes: usize,
+    /// Number of bytes the run had written before.
+    pub written_bytes: usize,
+    /// Cap on the bytes written to disk by the run.
+    pub max_disk_bytes: usize,
+}
+
+impl fmt::Display for DiskFull {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Writing {} bytes for node {} exceeds the disk cap of {} bytes ({} bytes already written).",
+            self.bytes, self.node_id, self.max_disk_bytes, self.written_bytes
+        )
+    }
+}
+
+impl std::error::Error for DiskFull {}
+
+impl ResourceUsage {
+    /// Records `bytes` written to disk on behalf of the node `node_id`, failing without recording them
+    /// if this would exceed `max_disk_bytes`.
+    pub fn record_disk_write(
+        &mut self,
+        node_id: &str,
+        bytes: usize,
+        max_disk_bytes: Option<usize>,
+    ) -> Result<(), DiskFull> {
+        if let Some(max_disk_bytes) = max_disk_bytes {
+            if self.disk_bytes + bytes > max_disk_bytes {
+                return Err(DiskFull {
+                    node_id: node_id.to_string(),
+                    bytes,
+                    written_bytes: self.disk_bytes,
+                    max_disk_bytes,
+                });
+            }
+        }
+        self.disk_bytes += bytes;
+        self.per_node
+            .entry(node_id.to_string())
+            .or_default()
+            .disk_bytes += bytes;
+        Ok(())
+    }
+}
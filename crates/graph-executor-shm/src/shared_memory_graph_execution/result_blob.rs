@@ -0,0 +1,356 @@
+use anyhow::{anyhow, Result};
+use graph_executor_core::graph_structure::graph::DirectedAcyclicGraph;
+use petgraph::graph::NodeIndex;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashSet,
+    fmt,
+    fs::{self, File},
+    io::{self, ErrorKind},
+    ops::Deref,
+    os::{fd::AsRawFd, unix::fs::MetadataExt},
+    path::{Path, PathBuf},
+    ptr, slice,
+};
+
+/// Name of the environment variable holding the path a command node writes its result blob to, e.g.
+/// `./extract.sh > "$GRAPH_EXECUTOR_RESULT"`.
+pub const RESULT_VARIABLE: &str = "GRAPH_EXECUTOR_RESULT";
+/// Name of the environment variable holding the space-separated paths of the result blobs of a command node's
+/// parents, ordered by their string identifiers. Parents which wrote no result blob are left out.
+pub const PARENT_RESULTS_VARIABLE: &str = "GRAPH_EXECUTOR_PARENT_RESULTS";
+/// Directory of the result blobs of all runs: a `tmpfs`, so that blobs are kept in memory like the run's shared
+/// memory objects.
+const RESULTS_PARENT_DIRECTORY: &str = "/dev/shm";
+
+/// Returns the directory of the result blobs of the run identified by `filename_suffix`.
+pub fn results_directory(filename_suffix: &str) -> PathBuf {
+    Path::new(RESULTS_PARENT_DIRECTORY).join(format!("graph-executor-{}.results", filename_suffix))
+}
+
+/// Returns the path of the result blob of the node at `node_index` in the run identified by `filename_suffix`.
+pub fn result_path(filename_suffix: &str, node_index: NodeIndex) -> PathBuf {
+    results_directory(filename_suffix).join(node_index.index().to_string())
+}
+
+/// Creates the (possibly existing) directory of the result blobs of the run identified by `filename_suffix`.
+pub(crate) fn create_results_directory(filename_suffix: &str) -> Result<()> {
+    let directory = results_directory(filename_suffix);
+    fs::create_dir_all(&directory).map_err(|e| {
+        anyhow!(
+            "Failed creating result directory {}: {}",
+            directory.display(),
+            e
+        )
+    })
+}
+
+/// Removes the directory of the result blobs of the run identified by `filename_suffix` with all remaining blobs
+/// (including [spilled](spill) ones), when the run is torn down. Blobs mapped by a [`ResultBlob`] stay readable
+/// until it is dropped.
+pub fn remove_results(filename_suffix: &str) -> Result<()> {
+    let directory = results_directory(filename_suffix);
+    if let Ok(entries) = fs::read_dir(&directory) {
+        for entry in entries {
+            remove_spilled(&entry?.path())?;
+        }
+    }
+    match fs::remove_dir_all(&directory) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(anyhow!(
+            "Failed removing result directory {}: {}",
+            directory.display(),
+            e
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Returns the directory of the deduplicated result blobs of the run identified by `filename_suffix`, named by the
+/// SHA-256 hash of their content. The blobs of nodes are hard links to these objects.
+fn objects_directory(filename_suffix: &str) -> PathBuf {
+    results_directory(filename_suffix).join("objects")
+}
+
+/// Replaces the result blob of the node at `node_index` with a hard link to the stored blob with the same content,
+/// or stores its blob if there is none yet, so that identical results (e.g. of the nodes of a matrix expansion) are
+/// only kept once. Every blob of a node referencing an object counts towards its number of links, once it is the
+/// only one left the object is removed, see [`release_consumed_results`].
+pub(crate) fn deduplicate(filename_suffix: &str, node_index: NodeIndex) -> Result<()> {
+    let path = result_path(filename_suffix, node_index);
+    let mut hasher = Sha256::new();
+    match File::open(&path) {
+        Ok(mut file) => io::copy(&mut file, &mut hasher)?,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => {
+            return Err(anyhow!(
+                "Failed reading result blob {}: {}",
+                path.display(),
+                e
+            ))
+        }
+    };
+    let hash: String = hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    fs::create_dir_all(objects_directory(filename_suffix))?;
+    let object = objects_directory(filename_suffix).join(hash);
+    // Another worker may store or remove an object with the same content at the same time, in which case linking
+    // is retried once. Blobs which could not be deduplicated are kept as they are.
+    for _ in 0..2 {
+        match fs::hard_link(&path, &object) {
+            Ok(()) => return Ok(()),
+            Err(e) if e.kind() != ErrorKind::AlreadyExists => return Err(e.into()),
+            Err(_) => (),
+        }
+        // The node's blob is replaced atomically, so that readers never miss it
+        let link = path.with_extension("link");
+        match fs::hard_link(&object, &link) {
+            Ok(()) => return Ok(fs::rename(&link, &path)?),
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+            Err(_) => (),
+        }
+    }
+    Ok(())
+}
+
+/// Removes the result blob of the node at `node_index`, e.g. before it is executed again, so that the new blob does
+/// not overwrite the one mapped by readers of the previous attempt.
+pub(crate) fn remove_result(filename_suffix: &str, node_index: NodeIndex) -> Result<()> {
+    let path = result_path(filename_suffix, node_index);
+    remove_spilled(&path)?;
+    match fs::remove_file(&path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(anyhow!(
+            "Failed removing result blob {}: {}",
+            path.display(),
+            e
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Removes the result blobs of `graph`'s nodes whose children all finished, i.e. which are referenced by no
+/// consumer anymore. Blobs of nodes without children are kept until the run is torn down.
+pub(crate) fn release_consumed_results(
+    filename_suffix: &str,
+    graph: &DirectedAcyclicGraph,
+) -> Result<()> {
+    let entries = match fs::read_dir(results_directory(filename_suffix)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(anyhow!("Failed reading result directory: {}", e)),
+    };
+    for entry in entries {
+        let Some(node_index) = entry?
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<usize>().ok())
+            .map(NodeIndex::new)
+            .filter(|i| graph.get_node_indices().any(|j| j == *i))
+        else {
+            continue;
+        };
+        let mut children = graph.get_child_node_indices(node_index).peekable();
+        if children.peek().is_some() && children.all(|i| graph[i].execution_status.is_finished()) {
+            // Other workers may release the same blob at the same time
+            remove_result(filename_suffix, node_index)?;
+        }
+    }
+    // Objects only linked by the objects directory are referenced by no node's blob anymore
+    let objects = match fs::read_dir(objects_directory(filename_suffix)) {
+        Ok(objects) => objects,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(anyhow!("Failed reading result objects directory: {}", e)),
+    };
+    for object in objects {
+        let object = object?;
+        if object.metadata()?.nlink() == 1 {
+            match fs::remove_file(object.path()) {
+                Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+                _ => (),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the number of bytes of the result blobs of the run identified by `filename_suffix` which are kept in
+/// shared memory, counting deduplicated blobs once and [spilled](spill) ones not at all.
+pub(crate) fn stored_bytes(filename_suffix: &str) -> Result<usize> {
+    let mut inodes = HashSet::new();
+    let mut bytes = 0;
+    for directory in [
+        results_directory(filename_suffix),
+        objects_directory(filename_suffix),
+    ] {
+        let entries = match fs::read_dir(&directory) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(anyhow!("Failed reading result directory: {}", e)),
+        };
+        for entry in entries {
+            // Blobs may be released by other workers at the same time
+            let Ok(metadata) = fs::symlink_metadata(entry?.path()) else {
+                continue;
+            };
+            if metadata.is_file() && inodes.insert(metadata.ino()) {
+                bytes += metadata.len() as usize;
+            }
+        }
+    }
+    Ok(bytes)
+}
+
+/// Moves the result blob of the node at `node_index` of the run identified by `filename_suffix` out of shared memory
+/// into `spill_directory`, e.g. once the run exceeds its shared memory budget. The blob's path is replaced with a
+/// symbolic link to the spilled blob, so that consumers read it like any other blob. Returns the number of bytes
+/// written to `spill_directory`.
+pub(crate) fn spill(
+    filename_suffix: &str,
+    node_index: NodeIndex,
+    spill_directory: &Path,
+) -> Result<usize> {
+    let path = result_path(filename_suffix, node_index);
+    let directory = spill_directory.join(format!("graph-executor-{}.results", filename_suffix));
+    fs::create_dir_all(&directory).map_err(|e| {
+        anyhow!(
+            "Failed creating spill directory {}: {}",
+            directory.display(),
+            e
+        )
+    })?;
+    let spilled = directory.join(node_index.index().to_string());
+    let bytes = fs::copy(&path, &spilled).map_err(|e| {
+        anyhow!(
+            "Failed spilling result blob {} to {}: {}",
+            path.display(),
+            spilled.display(),
+            e
+        )
+    })?;
+    // The node's blob is replaced atomically, so that readers never miss it
+    let link = path.with_extension("spill");
+    std::os::unix::fs::symlink(&spilled, &link)?;
+    fs::rename(&link, &path)?;
+    Ok(bytes as usize)
+}
+
+/// Removes the blob a [spilled](spill) result blob at `path` links to, if it is one, and the run's spill directory
+/// once it is empty.
+fn remove_spilled(path: &Path) -> Result<()> {
+    if !fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_symlink()) {
+        return Ok(());
+    }
+    let Ok(spilled) = fs::read_link(path) else {
+        return Ok(());
+    };
+    match fs::remove_file(&spilled) {
+        Err(e) if e.kind() != ErrorKind::NotFound => {
+            return Err(anyhow!(
+                "Failed removing spilled result blob {}: {}",
+                spilled.display(),
+                e
+            ))
+        }
+        _ => (),
+    }
+    // Other blobs of the run may still be spilled
+    if let Some(directory) = spilled.parent() {
+        let _ = fs::remove_dir(directory);
+    }
+    Ok(())
+}
+
+/// Read-only memory mapping of a node's result blob, which is not copied into the graph's shared memory. The
+/// mapping stays valid after the blob was released or the run torn down, until it is dropped.
+pub struct ResultBlob {
+    /// Start of the mapping, null for empty blobs, which cannot be mapped.
+    address: *mut libc::c_void,
+    /// Length of the blob in bytes.
+    len: usize,
+}
+
+// SAFETY: the mapping is read-only and only unmapped when the blob is dropped.
+unsafe impl Send for ResultBlob {}
+unsafe impl Sync for ResultBlob {}
+
+impl ResultBlob {
+    /// Maps the result blob of the node at `node_index` of the run identified by `filename_suffix`, or returns
+    /// [`None`] if the node wrote none or it was already released.
+    pub fn open(filename_suffix: &str, node_index: NodeIndex) -> Result<Option<Self>> {
+        let path = result_path(filename_suffix, node_index);
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(anyhow!(
+                    "Failed opening result blob {}: {}",
+                    path.display(),
+                    e
+                ))
+            }
+        };
+        let len = file.metadata()?.len() as usize;
+        if len == 0 {
+            return Ok(Some(ResultBlob {
+                address: ptr::null_mut(),
+                len,
+            }));
+        }
+        // SAFETY: maps `len` bytes of the open file read-only, the mapping outlives the file descriptor.
+        let address = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if address == libc::MAP_FAILED {
+            return Err(anyhow!(
+                "Failed mapping result blob {}: {}",
+                path.display(),
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(Some(ResultBlob { address, len }))
+    }
+}
+
+impl Deref for ResultBlob {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self.len {
+            0 => &[],
+            // SAFETY: `address` is the start of a readable mapping of `len` bytes.
+            len => unsafe { slice::from_raw_parts(self.address as *const u8, len) },
+        }
+    }
+}
+
+impl PartialEq for ResultBlob {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for ResultBlob {}
+
+impl fmt::Debug for ResultBlob {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ResultBlob({} bytes)", self.len)
+    }
+}
+
+impl Drop for ResultBlob {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            // SAFETY: `address` is the start of a mapping of `len` bytes created by `ResultBlob::open`.
+            unsafe { libc::munmap(self.address, self.len) };
+        }
+    }
+}
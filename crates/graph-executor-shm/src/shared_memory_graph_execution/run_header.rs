@@ -0,0 +1,207 @@
+use super::{
+    environment::EnvironmentManifest,
+    protocol::{HandshakeProbe, ProtocolHandshake},
+    worker_table::WorkerTable,
+};
+use crate::shared_memory::posix_shared_memory::PosixSharedMemory;
+use anyhow::{anyhow, Result};
+use graph_executor_core::{
+    clock::Clock, graph_structure::graph::DirectedAcyclicGraph,
+    monotonic_timestamp::MonotonicTimestamp,
+};
+use std::{ffi::CStr, fmt, time::Duration};
+
+/// Run-wide metadata stored in its own shared memory mapping next to the graph, identifying
+/// which process, host, binary and graph definition created the run.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RunHeader {
+    /// Protocol version and features of the run. Must remain the first field, see [`ProtocolHandshake`].
+    pub protocol: ProtocolHandshake,
+    /// Point in time the run was created by the first process.
+    pub created_at: MonotonicTimestamp,
+    /// Process ID of the process which created the run.
+    pub creator_pid: u32,
+    /// Hostname of the machine the run was created on.
+    pub hostname: String,
+    /// Version of the graph executor binary which created the run.
+    pub binary_version: String,
+    /// [`DirectedAcyclicGraph::content_hash`] of the executed graph.
+    pub graph_hash: String,
+    /// Origin of the graph definition, e.g. the path of the DOT file.
+    pub graph_source: Option<String>,
+    /// Optional time after `created_at` at which the run's remaining nodes are cancelled, see
+    /// [`RunHeader::is_past_deadline`].
+    pub deadline: Option<Duration>,
+    /// Environment captured by the worker creating the run, see [`EnvironmentManifest::capture`].
+    pub environment: Option<EnvironmentManifest>,
+    /// Optional absolute path of the run's audit log, which all workers and the watchdog append to, see
+    /// [`super::audit::AuditLog`].
+    pub audit_log: Option<String>,
+    /// Optional absolute path of the run's manifest, written by the last worker leaving the run, see
+    /// [`super::run_manifest::RunManifest`].
+    pub run_manifest: Option<String>,
+    /// Optional absolute path of the bundle written by the last worker leaving the run if it failed, see
+    /// [`super::failure_bundle::FailureBundle`].
+    pub failure_bundle: Option<String>,
+}
+
+impl fmt::Display for RunHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Created by:  pid {} on {} (graph-executor {}, {})\nGraph:       {} ({})",
+            self.creator_pid,
+            self.hostname,
+            self.binary_version,
+            self.protocol,
+            self.graph_hash,
+            self.graph_source.as_deref().unwrap_or("unknown source")
+        )?;
+        if let Some(deadline) = self.deadline {
+            write!(
+                f,
+                "\nDeadline:    {:.1}s after creation",
+                deadline.as_secs_f64()
+            )?;
+        }
+        if let Some(audit_log) = &self.audit_log {
+            write!(f, "\nAudit log:   {}", audit_log)?;
+        }
+        if let Some(run_manifest) = &self.run_manifest {
+            write!(f, "\nManifest:    {}", run_manifest)?;
+        }
+        if let Some(failure_bundle) = &self.failure_bundle {
+            write!(f, "\nBundle:      {}", failure_bundle)?;
+        }
+        if let Some(environment) = &self.environment {
+            write!(
+                f,
+                "\nEnvironment: {} variable(s), {} tool(s)",
+                environment.variables.len(),
+                environment.tools.len()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl RunHeader {
+    /// Creates a new [`RunHeader`] for a run of `graph` starting now in this process.
+    pub fn new(graph: &DirectedAcyclicGraph, graph_source: Option<String>) -> Result<Self> {
+        Ok(RunHeader {
+            protocol: ProtocolHandshake::default(),
+            created_at: MonotonicTimestamp::now()?,
+            creator_pid: std::process::id(),
+            hostname: hostname()?,
+            binary_version: env!("CARGO_PKG_VERSION").to_string(),
+            graph_hash: graph.content_hash(),
+            graph_source,
+            deadline: None,
+            environment: None,
+            audit_log: None,
+            run_manifest: None,
+            failure_bundle: None,
+        })
+    }
+
+    /// Checks whether the run's deadline (if any) has passed on `clock`.
+    pub fn is_past_deadline(&self, clock: &dyn Clock) -> Result<bool> {
+        match self.deadline {
+            Some(deadline) => Ok(self.created_at.elapsed_on(clock)? >= deadline),
+            None => Ok(false),
+        }
+    }
+
+    /// Filename suffix of the header's shared memory mapping for the run with `filename_suffix`.
+    pub fn filename_suffix(filename_suffix: &str) -> String {
+        format!("{}_header", filename_suffix)
+    }
+
+    /// Creates the header mapping of the run with `filename_suffix` from `header` or, if the run already exists,
+    /// attaches to its header after checking that this worker is compatible with the run's protocol.
+    pub fn create_or_attach(
+        filename_suffix: &str,
+        header: &RunHeader,
+    ) -> Result<(PosixSharedMemory, RunHeader)> {
+        check_filename_suffix(filename_suffix)?;
+        let header_suffix = RunHeader::filename_suffix(filename_suffix);
+        let attached = PosixSharedMemory::create_or_open(&header_suffix, header, None);
+        RunHeader::check_attached(&header_suffix, attached)
+    }
+
+    /// Attaches to the header mapping of the existing run with `filename_suffix`, checking that this worker
+    /// is compatible with the run's protocol.
+    pub fn open(filename_suffix: &str) -> Result<(PosixSharedMemory, RunHeader)> {
+        check_filename_suffix(filename_suffix)?;
+        let header_suffix = RunHeader::filename_suffix(filename_suffix);
+        let attached = PosixSharedMemory::open::<RunHeader>(&header_suffix);
+        RunHeader::check_attached(&header_suffix, attached)
+    }
+
+    /// Checks the protocol of an attached header. If the header could not be deserialized at all
+    /// (e.g. because it was written by an incompatible version), only its [`ProtocolHandshake`] is
+    /// probed to give a descriptive error.
+    fn check_attached(
+        header_suffix: &str,
+        attached: Result<(PosixSharedMemory, RunHeader)>,
+    ) -> Result<(PosixSharedMemory, RunHeader)> {
+        match attached {
+            Ok((shm_mapping, header)) => {
+                header.protocol.check_compatibility()?;
+                Ok((shm_mapping, header))
+            }
+            Err(e) => match PosixSharedMemory::open::<HandshakeProbe>(header_suffix) {
+                Ok((_, HandshakeProbe(handshake))) => {
+                    handshake.check_compatibility()?;
+                    Err(e)
+                }
+                Err(_) => Err(e),
+            },
+        }
+    }
+}
+
+/// Checks that `filename_suffix` identifies a run whose shared memory mappings cannot collide with those of
+/// another run: it may only contain ASCII letters, digits, `-`, `.` and `_` (a `/` would otherwise be replaced by
+/// `_`) and must not end like the mappings of a run's header or worker table, since e.g. the graph segment of a
+/// run `a_header` is the header segment of the run `a`.
+pub fn check_filename_suffix(filename_suffix: &str) -> Result<()> {
+    if filename_suffix.is_empty()
+        || !filename_suffix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_'))
+    {
+        return Err(anyhow!(
+            "Invalid filename suffix {:?}: only ASCII letters, digits, '-', '.' and '_' are allowed.",
+            filename_suffix
+        ));
+    }
+    for reserved in [
+        RunHeader::filename_suffix(""),
+        WorkerTable::filename_suffix(""),
+    ] {
+        if filename_suffix.ends_with(&reserved) {
+            return Err(anyhow!(
+                "Invalid filename suffix {:?}: suffixes ending in {:?} are reserved for the mappings of other runs.",
+                filename_suffix,
+                reserved
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Retrieves the hostname of the machine.
+pub(crate) fn hostname() -> Result<String> {
+    let mut buffer = [0 as libc::c_char; 256];
+    if unsafe { libc::gethostname(buffer.as_mut_ptr(), buffer.len()) } == -1 {
+        return Err(anyhow!(
+            "Failed reading hostname: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    buffer[buffer.len() - 1] = 0; // Hostname may be truncated without null terminator
+    Ok(unsafe { CStr::from_ptr(buffer.as_ptr()) }
+        .to_string_lossy()
+        .to_string())
+}
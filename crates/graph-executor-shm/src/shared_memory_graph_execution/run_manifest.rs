@@ -0,0 +1,147 @@
+use super::{
+    audit::AuditLog, execute_graph::ExecuteGraph, execution_options::ExecutionOptions,
+    execution_report::ExecutionReport, run_header::RunHeader,
+};
+use crate::shared_memory::posix_shared_memory::PosixSharedMemory;
+use anyhow::{anyhow, Result};
+use graph_executor_core::graph_structure::graph::DirectedAcyclicGraph;
+use std::{path::Path, thread};
+
+/// Final state of a run, written by the last worker leaving it if the run was created with
+/// [`ExecutionOptions::run_manifest`], so that a run which finished with failures can be resumed with
+/// [`retry_failed`] after its shared memory was removed.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RunManifest {
+    /// Filename suffix of the run.
+    pub filename_suffix: String,
+    /// Origin of the graph definition recorded in the run's header, see [`RunHeader::graph_source`].
+    pub graph_source: Option<String>,
+    /// The run's graph with the execution statuses and attempts of its nodes.
+    pub graph: DirectedAcyclicGraph,
+}
+
+impl RunManifest {
+    /// Reads the manifest at `path`.
+    pub fn read(path: &str) -> Result<Self> {
+        let manifest = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed reading run manifest {}: {}", path, e))?;
+        serde_yaml::from_str(&manifest).map_err(|e| anyhow!("Invalid run manifest {}: {}", path, e))
+    }
+
+    /// Writes the manifest to `path` as YAML.
+    pub fn write(&self, path: &str) -> Result<()> {
+        std::fs::write(path, serde_yaml::to_string(self)?)
+            .map_err(|e| anyhow!("Failed writing run manifest {}: {}", path, e))
+    }
+}
+
+/// Resumes the run identified by `suffix_or_manifest` which finished with failures, e.g. after fixing what made
+/// its nodes fail: only its failed nodes and those left unexecuted because of them are reset (see
+/// [`DirectedAcyclicGraph::reset_failed_nodes`]), and `workers` workers are started in this process to execute them
+/// like [`ExecuteGraph::execute_with_options`] with `options`. Returns the report of the last worker leaving the run.
+///
+/// - If a run with the filename suffix `suffix_or_manifest` is still in shared memory (e.g. because its last worker
+///   died), it must have finished. Its graph is reset in place, so that further workers can join it as usual.
+/// - Otherwise, `suffix_or_manifest` must be the path of the run's [`RunManifest`], which is created again from the
+///   reset graph and updated by its last worker (unless `options` sets another manifest).
+pub fn retry_failed(
+    suffix_or_manifest: &str,
+    workers: usize,
+    options: &ExecutionOptions,
+) -> Result<ExecutionReport> {
+    if workers == 0 {
+        return Err(anyhow!("At least one worker is required to retry a run."));
+    }
+    let mut options = options.clone();
+    let manifest = match Path::new(suffix_or_manifest).is_file() {
+        true => {
+            let mut manifest = RunManifest::read(suffix_or_manifest)?;
+            // Workers would join the existing run instead of creating it from the reset graph
+            if RunHeader::open(&manifest.filename_suffix).is_ok() {
+                return Err(anyhow!(
+                    "Run {} is still in shared memory, retry it by its filename suffix instead.",
+                    manifest.filename_suffix
+                ));
+            }
+            if manifest.graph.reset_failed_nodes().is_empty() {
+                return Err(anyhow!(
+                    "Run {} has no failed nodes to retry.",
+                    manifest.filename_suffix
+                ));
+            }
+            options.run_manifest = options
+                .run_manifest
+                .or(Some(suffix_or_manifest.to_string()));
+            manifest
+        }
+        false => reset_failed_run_in_shared_memory(suffix_or_manifest)?,
+    };
+    options.graph_source = options.graph_source.or(manifest.graph_source);
+
+    let results: Vec<Result<ExecutionReport>> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..workers)
+            .map(|_| {
+                let (mut graph, options) = (manifest.graph.clone(), &options);
+                let filename_suffix = manifest.filename_suffix.clone();
+                scope.spawn(move || graph.execute_with_options(filename_suffix, options))
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|_| Err(anyhow!("Worker panicked.")))
+            })
+            .collect()
+    });
+    let mut last_report = None;
+    for result in results {
+        let report = result?;
+        if report.is_last_worker || last_report.is_none() {
+            last_report = Some(report);
+        }
+    }
+    last_report.ok_or(anyhow!("No worker reported the run."))
+}
+
+/// Resets the failed nodes of the finished run with `filename_suffix` in its shared memory, see [`retry_failed`].
+fn reset_failed_run_in_shared_memory(filename_suffix: &str) -> Result<RunManifest> {
+    let (_, header) = RunHeader::open(filename_suffix).map_err(|e| {
+        anyhow!(
+            "{} is neither a run manifest nor a run in shared memory: {}",
+            filename_suffix,
+            e
+        )
+    })?;
+    let (mut graph_shm, graph) = PosixSharedMemory::open_graph(filename_suffix)?;
+    if !graph.is_graph_finished() {
+        return Err(anyhow!(
+            "Run {} is still executing, only finished runs can be retried.",
+            filename_suffix
+        ));
+    }
+    if let Some(path) = &header.audit_log {
+        graph_shm.set_write_observer(Some(
+            AuditLog::open(path, filename_suffix)?.write_observer(),
+        ));
+        AuditLog::open(path, filename_suffix)?.record("retry-failed", None, "")?;
+    }
+    let mut reset = vec![];
+    let graph = graph_shm.shm_update_graph(|graph: &mut DirectedAcyclicGraph| {
+        reset = graph.reset_failed_nodes();
+    })?;
+    // Storages allocated by the write must outlive this process' mapping, see `super::watchdog::watch`
+    graph_shm.set_ownership(false)?;
+    if reset.is_empty() {
+        return Err(anyhow!(
+            "Run {} has no failed nodes to retry.",
+            filename_suffix
+        ));
+    }
+    Ok(RunManifest {
+        filename_suffix: filename_suffix.to_string(),
+        graph_source: header.graph_source,
+        graph,
+    })
+}
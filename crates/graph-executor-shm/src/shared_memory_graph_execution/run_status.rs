@@ -0,0 +1,81 @@
+use super::{graph_snapshot::GraphSnapshot, run_header::RunHeader, worker_table::WorkerTable};
+use crate::shared_memory::posix_shared_memory::PosixSharedMemory;
+use anyhow::{anyhow, Result};
+use graph_executor_core::graph_structure::execution_status::ExecutionStatus;
+use std::{fmt, time::Duration};
+
+/// Snapshot of a live run read from shared memory.
+#[derive(Clone, Debug)]
+pub struct RunStatus {
+    /// Filename suffix identifying the run.
+    pub filename_suffix: String,
+    /// The run's header.
+    pub header: RunHeader,
+    /// Time since the run was created, if the run was created during the current boot.
+    pub age: Option<Duration>,
+    /// Execution statuses and topology of the graph.
+    pub snapshot: GraphSnapshot,
+    /// Shared memory bytes currently allocated for the graph.
+    pub shm_bytes: usize,
+    /// Bytes the run's workers wrote to disk so far, see [`super::resource_usage::ResourceUsage::disk_bytes`].
+    pub disk_bytes: usize,
+}
+
+impl RunStatus {
+    /// Reads the status of the run identified by `filename_suffix` from shared memory.
+    pub fn read(filename_suffix: &str) -> Result<Self> {
+        let (_, header) = RunHeader::open(filename_suffix)
+            .map_err(|e| anyhow!("Failed reading run {}: {}", filename_suffix, e))?;
+        let (shared_memory, graph) =
+            PosixSharedMemory::open_graph(filename_suffix).map_err(|e| {
+                anyhow!(
+                    "No graph of run {} in shared memory: {}",
+                    filename_suffix,
+                    e
+                )
+            })?;
+        let workers = WorkerTable::read(filename_suffix)?;
+
+        Ok(RunStatus {
+            filename_suffix: filename_suffix.to_string(),
+            age: header.created_at.elapsed().ok(),
+            header,
+            snapshot: GraphSnapshot::new(&graph),
+            shm_bytes: shared_memory.allocated_bytes(),
+            disk_bytes: workers.disk_usage.disk_bytes,
+        })
+    }
+}
+
+impl fmt::Display for RunStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Run:         {}", self.filename_suffix)?;
+        match self.age {
+            Some(age) => writeln!(f, "Age:         {:.1}s", age.as_secs_f64())?,
+            None => writeln!(f, "Age:         unknown (created during a previous boot)")?,
+        }
+        writeln!(f, "{}", self.header)?;
+        writeln!(
+            f,
+            "Resources:   shared memory {} bytes, disk {} bytes",
+            self.shm_bytes, self.disk_bytes
+        )?;
+        writeln!(f, "Nodes:")?;
+        for node in self.snapshot.nodes() {
+            write!(f, "    {}: {}", node.node_id, node.execution_status)?;
+            if let (ExecutionStatus::Executing, Some(progress)) =
+                (node.execution_status, &node.progress)
+            {
+                write!(f, " {}", progress)?;
+            }
+            if node.attempt_count > 1 {
+                write!(f, " after {} attempts", node.attempt_count)?;
+            }
+            match &node.error {
+                Some(error) => writeln!(f, " ({})", error)?,
+                None => writeln!(f)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,295 @@
+use super::{audit::AuditLog, run_header::RunHeader};
+use crate::shared_memory::posix_shared_memory::PosixSharedMemory;
+use anyhow::{anyhow, Result};
+use graph_executor_core::graph_structure::{
+    execution_status::ExecutionStatus, graph::DirectedAcyclicGraph,
+};
+use petgraph::graph::NodeIndex;
+use std::io::{BufRead, Write};
+
+/// Commands understood by [`RunShell::execute`].
+pub const SHELL_HELP: &str = "\
+ls                 List all nodes with their execution status
+show <node>        Show a node's definition, parents, children and attempts
+approve [<node>]   Approve a node (or all nodes) awaiting approval
+signal <event>     Post an external event, releasing the nodes awaiting it
+retry <node>       Reset a finished node and its descendants to execute them again
+cancel [<node>]    Cancel a node (stopping its command) and skip its descendants, or the whole run
+dump dot           Print the graph with its current state as DOT
+help               Print this help
+exit               Leave the shell";
+
+/// Shell attached to a live run, e.g. to debug a stuck run: it inspects the run's graph in shared memory and
+/// changes it like a worker would, without registering as one. Changes are audited like those of the run's
+/// workers (see [`AuditLog`]), and the shell's actions explicitly.
+pub struct RunShell {
+    /// Filename suffix of the run.
+    filename_suffix: String,
+    /// Mapping of the run's graph, kept open like the watchdog's, see [`super::watchdog::watch`].
+    graph_shm: PosixSharedMemory,
+    /// Audit log of the run, if it has one.
+    audit_log: Option<AuditLog>,
+}
+
+impl RunShell {
+    /// Attaches a [`RunShell`] to the run identified by `filename_suffix`.
+    pub fn attach(filename_suffix: &str) -> Result<Self> {
+        let attach_error = |e| anyhow!("Failed attaching to run {}: {}", filename_suffix, e);
+        let (_, header) = RunHeader::open(filename_suffix).map_err(attach_error)?;
+        let (mut graph_shm, _) =
+            PosixSharedMemory::open_graph(filename_suffix).map_err(attach_error)?;
+        let audit_log = match &header.audit_log {
+            Some(path) => {
+                graph_shm.set_write_observer(Some(
+                    AuditLog::open(path, filename_suffix)?.write_observer(),
+                ));
+                Some(AuditLog::open(path, filename_suffix)?)
+            }
+            None => None,
+        };
+        Ok(RunShell {
+            filename_suffix: filename_suffix.to_string(),
+            graph_shm,
+            audit_log,
+        })
+    }
+
+    /// Reads commands from `input` and writes their output to `output` until `exit` or the end of `input`. Failing
+    /// commands print their error and don't end the shell.
+    pub fn run(&mut self, mut input: impl BufRead, mut output: impl Write) -> Result<()> {
+        loop {
+            write!(output, "{}> ", self.filename_suffix)?;
+            output.flush()?;
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                return Ok(writeln!(output)?);
+            }
+            match line.trim() {
+                "exit" | "quit" => return Ok(()),
+                "" => (),
+                line => match self.execute(line) {
+                    Ok(result) => writeln!(output, "{}", result)?,
+                    Err(e) => writeln!(output, "Error: {}", e)?,
+                },
+            }
+        }
+    }
+
+    /// Executes a single command (see [`SHELL_HELP`]) against the live run and returns its output.
+    pub fn execute(&mut self, line: &str) -> Result<String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if words.as_slice() == ["help"] {
+            return Ok(SHELL_HELP.to_string());
+        }
+        // The run ended once its mappings were removed by the last worker
+        if RunHeader::open(&self.filename_suffix).is_err() {
+            return Err(anyhow!("Run {} has ended.", self.filename_suffix));
+        }
+        let graph = self.graph_shm.read_graph()?;
+        match words.as_slice() {
+            ["ls"] => Ok(list_nodes(&graph)),
+            ["show", node_id] => Ok(show_node(&graph, node_index(&graph, node_id)?)),
+            ["dump", "dot"] => Ok(graph.to_string().trim_end().to_string()),
+            ["approve"] => {
+                let awaiting: Vec<String> = graph
+                    .get_node_indices()
+                    .filter(|i| graph[*i].is_awaiting_approval())
+                    .map(|i| graph.node_id(i).to_string())
+                    .collect();
+                if awaiting.is_empty() {
+                    return Err(anyhow!("No node is awaiting approval."));
+                }
+                for node_id in &awaiting {
+                    self.update("approve", node_id, approve)?;
+                }
+                Ok(format!("Approved {}.", awaiting.join(", ")))
+            }
+            ["approve", node_id] => self.update("approve", node_id, approve),
+            ["signal", event] => self.signal(event),
+            ["retry", node_id] => self.update("retry", node_id, retry),
+            ["cancel"] => self.cancel_run(&graph),
+            ["cancel", node_id] => self.update("cancel", node_id, cancel),
+            _ => Err(anyhow!("Unknown command {:?}, see help.", line)),
+        }
+    }
+
+    /// Applies `action` (named `name`) to the node identified by `node_id` under the graph's write lock. Actions
+    /// check the node's state before changing the graph, so that nothing is written if the action fails.
+    fn update(
+        &mut self,
+        name: &str,
+        node_id: &str,
+        action: impl Fn(&mut DirectedAcyclicGraph, NodeIndex) -> Result<String>,
+    ) -> Result<String> {
+        let mut graph = self.graph_shm.read_graph()?;
+        let i = node_index(&graph, node_id)?;
+        action(&mut graph, i)?;
+        let mut result = Err(anyhow!("Node {} was not updated.", node_id));
+        self.graph_shm
+            .shm_update_graph(|graph: &mut DirectedAcyclicGraph| result = action(graph, i))?;
+        // Storages allocated by the write must outlive the shell, see `super::watchdog::watch`
+        self.graph_shm.set_ownership(false)?;
+        if let (Ok(_), Some(audit_log)) = (&result, &self.audit_log) {
+            audit_log.record(name, Some(node_id), "source=shell")?;
+        }
+        result
+    }
+
+    /// Cancels the rest of the unfinished run like a failure does, see [`DirectedAcyclicGraph::cancel`].
+    fn cancel_run(&mut self, graph: &DirectedAcyclicGraph) -> Result<String> {
+        if graph.is_graph_finished() {
+            return Err(anyhow!("Run {} already finished.", self.filename_suffix));
+        }
+        self.graph_shm
+            .shm_update_graph(|graph: &mut DirectedAcyclicGraph| graph.cancel())?;
+        // Storages allocated by the write must outlive the shell, see `super::watchdog::watch`
+        self.graph_shm.set_ownership(false)?;
+        if let Some(audit_log) = &self.audit_log {
+            audit_log.record("cancel", None, "source=shell")?;
+        }
+        Ok(format!("Cancelled run {}.", self.filename_suffix))
+    }
+
+    /// Posts the external event `event` to the run under the graph's write lock, see
+    /// [`DirectedAcyclicGraph::receive_event`]. Like actions, it checks that a node is awaiting the event before
+    /// changing the graph.
+    fn signal(&mut self, event: &str) -> Result<String> {
+        let awaiting = |graph: &DirectedAcyclicGraph| {
+            graph
+                .get_node_indices()
+                .any(|i| graph[i].is_awaiting_event() && graph[i].awaited_event() == Some(event))
+        };
+        if !awaiting(&self.graph_shm.read_graph()?) {
+            return Err(anyhow!("No node is awaiting event {}.", event));
+        }
+        let mut received = vec![];
+        self.graph_shm
+            .shm_update_graph(|graph: &mut DirectedAcyclicGraph| {
+                received = graph
+                    .receive_event(event)
+                    .into_iter()
+                    .map(|i| graph.node_id(i).to_string())
+                    .collect();
+            })?;
+        // Storages allocated by the write must outlive the shell, see `super::watchdog::watch`
+        self.graph_shm.set_ownership(false)?;
+        if received.is_empty() {
+            return Err(anyhow!("No node is awaiting event {}.", event));
+        }
+        if let Some(audit_log) = &self.audit_log {
+            audit_log.record("signal", None, &format!("event={} source=shell", event))?;
+        }
+        Ok(format!(
+            "Event {} received by {}.",
+            event,
+            received.join(", ")
+        ))
+    }
+}
+
+/// Get the index of the node identified by `node_id`.
+fn node_index(graph: &DirectedAcyclicGraph, node_id: &str) -> Result<NodeIndex> {
+    graph
+        .node_index(node_id)
+        .ok_or(anyhow!("No node {} in the run.", node_id))
+}
+
+/// Lists all nodes ordered by string identifier with their execution status.
+fn list_nodes(graph: &DirectedAcyclicGraph) -> String {
+    let mut lines = vec![];
+    for i in graph.get_node_indices() {
+        let mut line = format!("{}: {}", graph.node_id(i), graph[i].execution_status);
+        if let (ExecutionStatus::Executing, Some(progress)) =
+            (graph[i].execution_status, &graph[i].progress)
+        {
+            line.push_str(&format!(" {}", progress));
+        }
+        if graph[i].is_awaiting_approval() {
+            line.push_str(" (awaiting approval)");
+        }
+        if let (true, Some(event)) = (graph[i].is_awaiting_event(), graph[i].awaited_event()) {
+            line.push_str(&format!(" (awaiting event {})", event));
+        }
+        if graph[i].attempts().len() > 1 {
+            line.push_str(&format!(" after {} attempts", graph[i].attempts().len()));
+        }
+        if let Some(error) = graph[i].error() {
+            line.push_str(&format!(" ({})", error));
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+/// Shows the node at `i` with its parents, children and attempts.
+fn show_node(graph: &DirectedAcyclicGraph, i: NodeIndex) -> String {
+    let ids = |indices: &mut dyn Iterator<Item = NodeIndex>| {
+        indices
+            .map(|i| graph.node_id(i).to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let mut shown = format!(
+        "{}: {}\nParents:  {}\nChildren: {}",
+        graph.node_id(i),
+        graph[i],
+        ids(&mut graph.get_parent_node_indices(i)),
+        ids(&mut graph.get_child_node_indices(i))
+    );
+    if let Some(progress) = &graph[i].progress {
+        shown.push_str(&format!("\nProgress: {}", progress));
+    }
+    for (n, attempt) in graph[i].attempts().iter().enumerate() {
+        shown.push_str(&format!(
+            "\nAttempt {}: {} by worker {} from {:.3}s to {:.3}s",
+            n + 1,
+            attempt.outcome,
+            attempt.worker_id,
+            attempt.started_after.as_secs_f64(),
+            attempt.finished_after.as_secs_f64()
+        ));
+        if let Some(error) = &attempt.error {
+            shown.push_str(&format!(" ({})", error));
+        }
+    }
+    shown
+}
+
+/// Approves the node at `i`, which must be awaiting approval.
+fn approve(graph: &mut DirectedAcyclicGraph, i: NodeIndex) -> Result<String> {
+    if !graph[i].is_awaiting_approval() {
+        return Err(anyhow!(
+            "Node {} is not awaiting approval.",
+            graph.node_id(i)
+        ));
+    }
+    graph[i].approved = true;
+    Ok(format!("Approved {}.", graph.node_id(i)))
+}
+
+/// Resets the finished node at `i` and its descendants, see [`DirectedAcyclicGraph::reset_nodes`].
+fn retry(graph: &mut DirectedAcyclicGraph, i: NodeIndex) -> Result<String> {
+    if !graph[i].execution_status.is_finished() {
+        return Err(anyhow!(
+            "Node {} is {} and cannot be retried before it finished.",
+            graph.node_id(i),
+            graph[i].execution_status
+        ));
+    }
+    let reset = graph.reset_nodes([i]);
+    let reset: Vec<&str> = reset.into_iter().map(|i| graph.node_id(i)).collect();
+    Ok(format!("Reset {}.", reset.join(", ")))
+}
+
+/// Cancels the unfinished node at `i`, see [`DirectedAcyclicGraph::cancel_node`].
+fn cancel(graph: &mut DirectedAcyclicGraph, i: NodeIndex) -> Result<String> {
+    if graph[i].execution_status.is_finished() {
+        return Err(anyhow!(
+            "Node {} is already {}.",
+            graph.node_id(i),
+            graph[i].execution_status
+        ));
+    }
+    graph.cancel_node(i);
+    Ok(format!("Cancelled {}.", graph.node_id(i)))
+}
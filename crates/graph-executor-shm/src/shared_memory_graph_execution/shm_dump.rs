@@ -0,0 +1,360 @@
+use super::{protocol::HandshakeProbe, run_header::RunHeader, worker_table::WorkerTable};
+use crate::shared_memory::{
+    attach_counter::AttachCounter,
+    event::SharedEvent,
+    semaphore::Semaphore,
+    shared_counter::{SharedCounter, MAX_READER_COUNT},
+    shared_segment::SharedSegment,
+    status_slots::SharedStatusSlots,
+};
+use anyhow::{anyhow, Result};
+use graph_executor_core::graph_structure::{
+    execution_status::ExecutionStatus, graph::DirectedAcyclicGraph,
+};
+use std::fmt;
+
+/// Number of bytes in front of the payload in a data segment, holding the length of both in big endian.
+const LENGTH_PREFIX_LEN: usize = usize::BITS as usize / 8;
+
+/// Number of bytes per line of a hexdump.
+const HEXDUMP_WIDTH: usize = 16;
+
+/// Raw contents of the shared memory objects of a run, read without deserializing them as a whole and without
+/// taking their locks, so that runs can be inspected when deserialization fails or a dead process holds a lock.
+/// Concurrent writes may tear the dumped data.
+#[derive(Clone, Debug)]
+pub struct ShmDump {
+    /// Filename suffix identifying the run.
+    pub filename_suffix: String,
+    /// The run's header, graph and worker table mappings, in this order.
+    pub mappings: Vec<MappingDump>,
+}
+
+/// Raw contents of the shared memory objects of one [`crate::shared_memory::posix_shared_memory::PosixSharedMemory`].
+#[derive(Clone, Debug)]
+pub struct MappingDump {
+    /// Filename suffix of the mapping.
+    pub filename_suffix: String,
+    /// Value of the write lock: 1 if no writer holds it, 0 if one does.
+    pub write_lock: Result<u32, String>,
+    /// Number of readers holding the read lock.
+    pub read_count: Result<u32, String>,
+    /// Number of attached processes, whether the only one locks the mapping with the lock word instead of the
+    /// semaphores and the lock word, see [`AttachCounter`].
+    pub attach_counter: Result<(u32, bool, u32), String>,
+    /// Bytes of the data segment: the length prefix, the payload and the unused rest of the segment.
+    pub segment: Result<Vec<u8>, String>,
+    /// Generation counter and status with generation stamp of every status slot, if the mapping has any.
+    pub status_slots: Option<(u64, Vec<(u8, u64)>)>,
+    /// Sequence number of the change event, if the mapping has one.
+    pub change_sequence: Option<u32>,
+    /// Summary of the deserialized payload, or why it failed to deserialize.
+    pub decoded: Result<String, String>,
+    /// Identifiers of the nodes the status slots belong to, if the payload is a graph which deserialized.
+    pub node_ids: Vec<String>,
+}
+
+impl ShmDump {
+    /// Reads the raw contents of the mappings of the run identified by `filename_suffix` from shared memory. Fails
+    /// only if none of them exists.
+    pub fn read(filename_suffix: &str) -> Result<Self> {
+        let mappings = vec![
+            MappingDump::read(&RunHeader::filename_suffix(filename_suffix), |payload| {
+                let HandshakeProbe(handshake) = rmp_serde::from_slice(payload)?;
+                Ok(match rmp_serde::from_slice::<RunHeader>(payload) {
+                    Ok(_) => format!("run header with {}", handshake),
+                    Err(e) => format!("run header with {}, other fields failed: {}", handshake, e),
+                })
+            }),
+            MappingDump::read(filename_suffix, |payload| {
+                let graph = rmp_serde::from_slice::<DirectedAcyclicGraph>(payload)?;
+                Ok(format!(
+                    "graph of {} nodes at generation {}",
+                    graph.get_node_indices().count(),
+                    graph.generation()
+                ))
+            }),
+            MappingDump::read(&WorkerTable::filename_suffix(filename_suffix), |payload| {
+                let table = rmp_serde::from_slice::<WorkerTable>(payload)?;
+                Ok(format!("worker table of {} workers", table.workers.len()))
+            }),
+        ];
+        if mappings.iter().all(|mapping| mapping.segment.is_err()) {
+            return Err(anyhow!(
+                "No shared memory of run {} found.",
+                filename_suffix
+            ));
+        }
+        Ok(ShmDump {
+            filename_suffix: filename_suffix.to_string(),
+            mappings,
+        })
+    }
+}
+
+impl MappingDump {
+    /// Reads the shared memory objects of the mapping with `filename_suffix`, summarizing its payload with
+    /// `describe`.
+    pub fn read(filename_suffix: &str, describe: impl Fn(&[u8]) -> Result<String>) -> Self {
+        let filename_suffix = filename_suffix.replace("/", "_"); // Handle slash in filename
+        let segment = SharedSegment::open(&format!("/{}_data", filename_suffix))
+            .map(|segment| segment.as_slice().to_vec());
+        let decoded = match &segment {
+            Ok(segment) => payload(segment)
+                .ok_or("no length prefix of a payload".to_string())
+                .and_then(|payload| describe(payload).map_err(|e| e.to_string())),
+            Err(e) => Err(e.clone()),
+        };
+        let node_ids = match &segment {
+            Ok(segment) => payload(segment)
+                .and_then(|payload| rmp_serde::from_slice::<DirectedAcyclicGraph>(payload).ok())
+                .map(|graph| {
+                    graph
+                        .get_node_indices()
+                        .map(|i| graph.node_id(i).to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            Err(_) => Vec::new(),
+        };
+        MappingDump {
+            write_lock: Semaphore::open(&format!("/{}_write_lock", filename_suffix))
+                .and_then(|write_lock| write_lock.get_value()),
+            read_count: SharedCounter::open(
+                &format!("/{}_read_count", filename_suffix),
+                MAX_READER_COUNT,
+            )
+            .map(|read_count| read_count.get_value()),
+            attach_counter: AttachCounter::open(&format!("/{}_attach", filename_suffix)).map(
+                |attach_counter| {
+                    (
+                        attach_counter.processes(),
+                        attach_counter.is_exclusive(),
+                        attach_counter.lock_state(),
+                    )
+                },
+            ),
+            segment,
+            status_slots: SharedStatusSlots::open(&format!("/{}_statuses", filename_suffix))
+                .ok()
+                .map(|slots| {
+                    (
+                        slots.generation(),
+                        (0..slots.len()).map(|slot| slots.load(slot)).collect(),
+                    )
+                }),
+            change_sequence: SharedEvent::open(&format!("/{}_events", filename_suffix))
+                .ok()
+                .map(|event| event.sequence()),
+            decoded,
+            node_ids,
+            filename_suffix,
+        }
+    }
+}
+
+/// Get the payload of a data segment, [`None`] if its length prefix doesn't fit the segment.
+fn payload(segment: &[u8]) -> Option<&[u8]> {
+    let total_len = usize::from_be_bytes(segment.get(..LENGTH_PREFIX_LEN)?.try_into().ok()?);
+    segment.get(LENGTH_PREFIX_LEN..total_len)
+}
+
+/// Get the number of bytes of the MessagePack value at the start of `bytes`, [`None`] if it is truncated or invalid.
+fn msgpack_value_len(bytes: &[u8]) -> Option<usize> {
+    let length = |len: usize| -> Option<usize> {
+        let len_bytes = bytes.get(1..1 + len)?;
+        Some(
+            len_bytes
+                .iter()
+                .fold(0, |n, byte| (n << 8) | *byte as usize),
+        )
+    };
+    // Number of bytes of the marker and length, number of bytes of data and number of nested values
+    let (header_len, data_len, value_count) = match *bytes.first()? {
+        0x00..=0x7f | 0xc0 | 0xc2 | 0xc3 | 0xe0..=0xff => (1, 0, 0),
+        marker @ 0x80..=0x8f => (1, 0, 2 * (marker & 0x0f) as usize),
+        marker @ 0x90..=0x9f => (1, 0, (marker & 0x0f) as usize),
+        marker @ 0xa0..=0xbf => (1, (marker & 0x1f) as usize, 0),
+        0xc4 | 0xd9 => (2, length(1)?, 0),
+        0xc5 | 0xda => (3, length(2)?, 0),
+        0xc6 | 0xdb => (5, length(4)?, 0),
+        0xc7 => (3, length(1)?, 0),
+        0xc8 => (4, length(2)?, 0),
+        0xc9 => (6, length(4)?, 0),
+        0xcc | 0xd0 => (2, 0, 0),
+        0xcd | 0xd1 => (3, 0, 0),
+        0xca | 0xce | 0xd2 => (5, 0, 0),
+        0xcb | 0xcf | 0xd3 => (9, 0, 0),
+        marker @ 0xd4..=0xd8 => (2 + (1 << (marker - 0xd4)), 0, 0),
+        0xdc => (3, 0, length(2)?),
+        0xdd => (5, 0, length(4)?),
+        0xde => (3, 0, 2 * length(2)?),
+        0xdf => (5, 0, 2 * length(4)?),
+        0xc1 => return None,
+    };
+    let mut len = header_len + data_len;
+    for _ in 0..value_count {
+        len += msgpack_value_len(bytes.get(len..)?)?;
+    }
+    (len <= bytes.len()).then_some(len)
+}
+
+/// Splits `payload` into its annotated regions: the header of the top-level array and each of its elements, i.e.
+/// the fields of the serialized struct. Payloads which aren't an array, or whose elements don't parse, are a single
+/// region.
+fn payload_regions(payload: &[u8]) -> Vec<(String, &[u8])> {
+    let regions = || -> Option<Vec<(String, &[u8])>> {
+        let (header_len, field_count) = match *payload.first()? {
+            marker @ 0x90..=0x9f => (1, (marker & 0x0f) as usize),
+            0xdc => (
+                3,
+                u16::from_be_bytes(payload.get(1..3)?.try_into().ok()?) as usize,
+            ),
+            0xdd => (
+                5,
+                u32::from_be_bytes(payload.get(1..5)?.try_into().ok()?) as usize,
+            ),
+            _ => return None,
+        };
+        let mut regions = vec![(
+            format!("array of {} fields", field_count),
+            &payload[..header_len],
+        )];
+        let mut offset = header_len;
+        for field in 0..field_count {
+            let len = msgpack_value_len(payload.get(offset..)?)?;
+            regions.push((format!("field {}", field), &payload[offset..offset + len]));
+            offset += len;
+        }
+        if offset < payload.len() {
+            regions.push(("trailing bytes".to_string(), &payload[offset..]));
+        }
+        Some(regions)
+    };
+    regions().unwrap_or_else(|| vec![("value".to_string(), payload)])
+}
+
+/// Writes a hexdump of `bytes` located at `offset` of the data segment, labeling its first line with `label`.
+fn hexdump(f: &mut fmt::Formatter, offset: usize, bytes: &[u8], label: &str) -> fmt::Result {
+    for (i, line) in bytes.chunks(HEXDUMP_WIDTH).enumerate() {
+        let hex: Vec<String> = line.iter().map(|byte| format!("{:02x}", byte)).collect();
+        let ascii: String = line
+            .iter()
+            .map(|byte| match byte.is_ascii_graphic() || *byte == b' ' {
+                true => *byte as char,
+                false => '.',
+            })
+            .collect();
+        write!(
+            f,
+            "    {:08x}  {:<47}  |{:<16}|",
+            offset + i * HEXDUMP_WIDTH,
+            hex.join(" "),
+            ascii
+        )?;
+        match i {
+            0 => writeln!(f, "  {}", label)?,
+            _ => writeln!(f)?,
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for ShmDump {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Run: {}", self.filename_suffix)?;
+        for mapping in &self.mappings {
+            writeln!(f)?;
+            write!(f, "{}", mapping)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MappingDump {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Mapping {}:", self.filename_suffix)?;
+        match &self.write_lock {
+            Ok(1) => writeln!(f, "  Write lock:      free")?,
+            Ok(0) => writeln!(f, "  Write lock:      held")?,
+            Ok(value) => writeln!(f, "  Write lock:      invalid value {}", value)?,
+            Err(e) => writeln!(f, "  Write lock:      {}", e)?,
+        }
+        match &self.read_count {
+            Ok(read_count) => writeln!(f, "  Readers:         {}", read_count)?,
+            Err(e) => writeln!(f, "  Readers:         {}", e)?,
+        }
+        match &self.attach_counter {
+            Ok((processes, true, lock_state)) => writeln!(
+                f,
+                "  Attached:        {} process, locking with lock word {:#010x}",
+                processes, lock_state
+            )?,
+            Ok((processes, false, _)) => writeln!(
+                f,
+                "  Attached:        {} processes, locking with semaphores",
+                processes
+            )?,
+            Err(e) => writeln!(f, "  Attached:        {}", e)?,
+        }
+        if let Some(change_sequence) = self.change_sequence {
+            writeln!(f, "  Change sequence: {}", change_sequence)?;
+        }
+        match (&self.segment, &self.decoded) {
+            (Err(_), _) => {}
+            (Ok(_), Ok(decoded)) => writeln!(f, "  Payload:         {}", decoded)?,
+            (Ok(_), Err(e)) => writeln!(f, "  Payload:         failed to deserialize: {}", e)?,
+        }
+        if let Some((generation, slots)) = &self.status_slots {
+            writeln!(
+                f,
+                "  Status slots:    {} at generation {}",
+                slots.len(),
+                generation
+            )?;
+            for (slot, (status, stamp)) in slots.iter().enumerate() {
+                let node_id = self.node_ids.get(slot).map_or("?", String::as_str);
+                match ExecutionStatus::try_from(*status) {
+                    Ok(status) => {
+                        writeln!(f, "    {} {}: {} (stamp {})", slot, node_id, status, stamp)?
+                    }
+                    Err(_) => writeln!(
+                        f,
+                        "    {} {}: invalid status byte {:#04x} (stamp {})",
+                        slot, node_id, status, stamp
+                    )?,
+                }
+            }
+        }
+        let segment = match &self.segment {
+            Ok(segment) => segment,
+            Err(e) => return writeln!(f, "  Data segment:    {}", e),
+        };
+        writeln!(f, "  Data segment:    {} bytes", segment.len())?;
+        let prefix_len = LENGTH_PREFIX_LEN.min(segment.len());
+        let total_len = match payload(segment) {
+            Some(payload) => LENGTH_PREFIX_LEN + payload.len(),
+            None => prefix_len,
+        };
+        hexdump(
+            f,
+            0,
+            &segment[..prefix_len],
+            &match payload(segment) {
+                Some(payload) => format!("length prefix: {} bytes of payload", payload.len()),
+                None => "length prefix: invalid".to_string(),
+            },
+        )?;
+        let mut offset = LENGTH_PREFIX_LEN;
+        for (label, region) in payload_regions(&segment[prefix_len..total_len]) {
+            hexdump(f, offset, region, &label)?;
+            offset += region.len();
+        }
+        writeln!(
+            f,
+            "    {:08x}  {} bytes beyond the payload",
+            total_len,
+            segment.len() - total_len
+        )
+    }
+}
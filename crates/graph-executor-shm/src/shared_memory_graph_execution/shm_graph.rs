@@ -0,0 +1,304 @@
+use crate::shared_memory::posix_shared_memory::PosixSharedMemory;
+use anyhow::{anyhow, Result};
+use graph_executor_core::graph_structure::{
+    attempt::Attempt, execution_record::ExecutionRecord, execution_status::ExecutionStatus,
+    graph::DirectedAcyclicGraph,
+};
+use petgraph::graph::NodeIndex;
+use std::time::Duration;
+
+impl PosixSharedMemory {
+    /// Like [`PosixSharedMemory::create_or_open`] for the graph of a run, creating one status slot per node (see
+    /// [`PosixSharedMemory::compare_exchange_status_slot`]) holding the execution statuses of `graph`. Returns the
+    /// graph in shared memory, whose execution statuses are those in the slots.
+    pub fn create_or_open_graph(
+        filename_suffix: &str,
+        graph: &DirectedAcyclicGraph,
+        max_allocated_bytes: Option<usize>,
+    ) -> Result<(Self, DirectedAcyclicGraph)> {
+        let statuses: Vec<u8> = graph
+            .get_node_indices()
+            .map(|i| graph[i].execution_status.into())
+            .collect();
+        let (mut shared_memory, _) = PosixSharedMemory::create_or_open_with_status_slots(
+            filename_suffix,
+            graph,
+            max_allocated_bytes,
+            Some(&statuses),
+        )?;
+        let graph = shared_memory.read_graph()?;
+        Ok((shared_memory, graph))
+    }
+
+    /// Like [`PosixSharedMemory::open`] for the graph of a run, see [`PosixSharedMemory::read_graph`].
+    pub fn open_graph(filename_suffix: &str) -> Result<(Self, DirectedAcyclicGraph)> {
+        let (mut shared_memory, _) =
+            PosixSharedMemory::open::<serde::de::IgnoredAny>(filename_suffix)?;
+        let graph = shared_memory.read_graph()?;
+        Ok((shared_memory, graph))
+    }
+
+    /// Acquire read lock and read the graph of a run, with the execution statuses of its status slots, which
+    /// transitions change without writing the graph.
+    pub fn read_graph(&mut self) -> Result<DirectedAcyclicGraph> {
+        // Acquire read lock
+        self.read_lock()?;
+
+        // Read graph while holding the lock, releasing it on errors as well
+        let graph = self.read_graph_from_shm();
+
+        // Release read lock
+        self.read_unlock()?;
+
+        graph
+    }
+
+    /// Read the graph of a run with the execution statuses and enqueue times of its status slots. The caller holds the
+    /// lock.
+    fn read_graph_from_shm(&mut self) -> Result<DirectedAcyclicGraph> {
+        let graph_bytes = self.read_from_shm()?;
+        let mut graph = rmp_serde::from_slice::<DirectedAcyclicGraph>(graph_bytes.as_slice())?;
+        if let Some(status_slots) = self.status_slots() {
+            let indices: Vec<NodeIndex> = graph.get_node_indices().collect();
+            for i in indices.into_iter().take(status_slots.len()) {
+                let (status, generation) = status_slots.load(i.index());
+                graph.record_status_change(i, ExecutionStatus::try_from(status)?, generation);
+                if let Some(enqueued_after) = status_slots.load_enqueued(i.index()) {
+                    graph[i].enqueued_after = Some(enqueued_after);
+                }
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Write the graph of a run and store its execution statuses, generations and enqueue times in its status slots.
+    /// The caller holds the write lock.
+    fn write_graph_to_shm(&mut self, graph: &DirectedAcyclicGraph) -> Result<()> {
+        self.write_to_shm(graph)?;
+        if let Some(status_slots) = self.status_slots() {
+            status_slots.store_all(
+                graph.generation(),
+                graph.get_node_indices().map(|i| {
+                    (
+                        graph[i].execution_status.into(),
+                        graph.changed_in(i),
+                        graph[i].enqueued_after,
+                    )
+                }),
+            );
+        }
+        Ok(())
+    }
+
+    /// Like [`PosixSharedMemory::shm_update`] for the graph of a run, additionally advancing its generation if
+    /// `update` changed any of its nodes, see [`DirectedAcyclicGraph::stamp_changes`]. All writes of a run's graph
+    /// go through this or the conditional updates below, so that [`PosixSharedMemory::read_changes`] sees them.
+    pub fn shm_update_graph(
+        &mut self,
+        update: impl FnOnce(&mut DirectedAcyclicGraph),
+    ) -> Result<DirectedAcyclicGraph> {
+        // Acquire exclusive (write) lock
+        self.write_lock()?;
+
+        // Read, update and write graph while holding the lock, releasing it on errors as well
+        let result = self.read_graph_from_shm().and_then(|mut graph| {
+            let before = graph.clone();
+            update(&mut graph);
+            graph.stamp_changes(&before);
+            self.write_graph_to_shm(&graph)?;
+            Ok(graph)
+        });
+
+        // Release write lock
+        self.write_unlock()?;
+
+        result
+    }
+
+    /// Records `elapsed` as the time at which the executable nodes of `graph` without one were enqueued (see
+    /// [`DirectedAcyclicGraph::record_enqueue_times`]) in their status slots, without writing the graph. Returns the
+    /// graph in shared memory, holding the enqueue time of every node.
+    pub fn shm_record_enqueue_times(
+        &mut self,
+        graph: &DirectedAcyclicGraph,
+        elapsed: Duration,
+    ) -> Result<DirectedAcyclicGraph> {
+        let slots = graph
+            .get_executable_node_indices()
+            .into_iter()
+            .filter(|i| graph[*i].enqueued_after.is_none())
+            .map(|i| i.index());
+        match self.enqueue_status_slots(slots, elapsed)? {
+            true => self.read_graph(),
+            false => self.shm_update_graph(|graph: &mut DirectedAcyclicGraph| {
+                graph.record_enqueue_times(elapsed)
+            }),
+        }
+    }
+
+    /// Acquire read lock and return the current generation of the graph in shared memory with the string identifier
+    /// and execution status of every node changed after `since_generation`, see
+    /// [`DirectedAcyclicGraph::changes_since`]. Monitors pass the returned generation to their next call, so that
+    /// they only process the nodes changed in between.
+    pub fn read_changes(
+        &mut self,
+        since_generation: u64,
+    ) -> Result<(u64, Vec<(String, ExecutionStatus)>)> {
+        let graph = self.read_graph()?;
+        Ok((graph.generation(), graph.changes_since(since_generation)))
+    }
+
+    /// Advance execution status to the next in [`graph_executor_core::graph_structure::node::Node`]'s execution life
+    /// cycle with a single `compare_exchange` of the node's status slot, see
+    /// [`PosixSharedMemory::compare_exchange_status_slot`]. Returns the graph in shared memory if its execution status
+    /// was not the previous one (anymore).
+    pub fn shm_compare_node_execution_status_and_update(
+        &mut self,
+        node_index: NodeIndex,
+        new_execution_status: ExecutionStatus,
+    ) -> Result<Option<DirectedAcyclicGraph>> {
+        self.shm_compare_node_execution_status_and_update_with_record(
+            node_index,
+            new_execution_status,
+            None,
+            None,
+        )
+    }
+
+    /// Like [`PosixSharedMemory::shm_compare_node_execution_status_and_update`], additionally storing
+    /// `execution_record` in the node and appending `attempt` to its attempts with the same write, which takes the
+    /// write lock. Without either, only the node's status slot is changed.
+    pub fn shm_compare_node_execution_status_and_update_with_record(
+        &mut self,
+        node_index: NodeIndex,
+        new_execution_status: ExecutionStatus,
+        execution_record: Option<ExecutionRecord>,
+        attempt: Option<Attempt>,
+    ) -> Result<Option<DirectedAcyclicGraph>> {
+        // Old execution status for conditional write
+        let old_execution_status = match new_execution_status {
+            ExecutionStatus::NonExecutable
+            | ExecutionStatus::Skipped
+            | ExecutionStatus::Cancelled => {
+                return Err(anyhow!(
+                    "New execution status cannot be ExecutionStatus::{}.",
+                    new_execution_status
+                ))
+            }
+            ExecutionStatus::Executable => ExecutionStatus::NonExecutable,
+            ExecutionStatus::Executing => ExecutionStatus::Executable,
+            ExecutionStatus::Executed | ExecutionStatus::Failed => ExecutionStatus::Executing,
+        };
+
+        // Change only the status slot if nothing else is stored
+        if execution_record.is_none() && attempt.is_none() {
+            match self.compare_exchange_status_slot(
+                node_index.index(),
+                old_execution_status.into(),
+                new_execution_status.into(),
+            )? {
+                Some(true) => return Ok(None),
+                Some(false) => return self.read_graph().map(Some),
+                None => {}
+            }
+        }
+
+        // Acquire exclusive (write) lock
+        self.write_lock()?;
+
+        // Write data to shared memory if `data_condition` is equal to current state of data in shared memory
+        let mut graph_in_shm = match self.read_graph_from_shm() {
+            Ok(graph_in_shm) => graph_in_shm,
+            Err(e) => {
+                self.write_unlock()?;
+                return Err(e);
+            }
+        };
+        match graph_in_shm[node_index].execution_status == old_execution_status {
+            true => {
+                // Release write lock and return None on successful write
+                let before = graph_in_shm.clone();
+                graph_in_shm[node_index].execution_status = new_execution_status;
+                if execution_record.is_some() {
+                    graph_in_shm[node_index].execution_record = execution_record;
+                }
+                graph_in_shm[node_index].attempts.extend(attempt);
+                graph_in_shm.stamp_changes(&before);
+                let result = self.write_graph_to_shm(&graph_in_shm);
+                self.write_unlock()?;
+                result.map(|()| None)
+            }
+            false => {
+                // Release write lock and if `data_condition` no longer matches return `data_in_shm`
+                self.write_unlock()?;
+                Ok(Some(graph_in_shm))
+            }
+        }
+    }
+
+    /// Like [`PosixSharedMemory::shm_compare_node_execution_status_and_update`] with [`ExecutionStatus::Executing`],
+    /// but only claims `node_index` while fewer than `max_executing_nodes` nodes of the run are executing and no
+    /// other node with the same concurrency key (see [`DirectedAcyclicGraph::is_mutex_held`]) is executing, the keys
+    /// being those of the caller's copy of the run's `graph`. Both are checked with the claim of the node's status
+    /// slot (see [`PosixSharedMemory::compare_exchange_status_slot_if`]), or under the write lock if the change must
+    /// be made by writing the graph, so that they hold across all processes of the run. Without a concurrency key and
+    /// `max_executing_nodes`, only the node's status slot is changed.
+    pub fn shm_claim_node(
+        &mut self,
+        graph: &DirectedAcyclicGraph,
+        node_index: NodeIndex,
+        max_executing_nodes: Option<usize>,
+    ) -> Result<Option<DirectedAcyclicGraph>> {
+        let mutex = graph[node_index].mutex();
+        if max_executing_nodes.is_none() && mutex.is_none() {
+            return self.shm_compare_node_execution_status_and_update(
+                node_index,
+                ExecutionStatus::Executing,
+            );
+        }
+        let mutex_slots: Vec<usize> = graph
+            .get_node_indices()
+            .filter(|i| mutex.is_some() && *i != node_index && graph[*i].mutex() == mutex)
+            .map(|i| i.index())
+            .collect();
+        match self.compare_exchange_status_slot_if(
+            node_index.index(),
+            ExecutionStatus::Executable.into(),
+            ExecutionStatus::Executing.into(),
+            |executing| {
+                max_executing_nodes.is_none_or(|max| executing.len() < max)
+                    && !executing.iter().any(|slot| mutex_slots.contains(slot))
+            },
+        )? {
+            Some(true) => return Ok(None),
+            Some(false) => return self.read_graph().map(Some),
+            None => {}
+        }
+
+        // Acquire exclusive (write) lock
+        self.write_lock()?;
+
+        // Claim the node while holding the lock, releasing it on errors as well
+        let result = self.read_graph_from_shm().and_then(|mut graph_in_shm| {
+            if graph_in_shm[node_index].execution_status != ExecutionStatus::Executable
+                || max_executing_nodes
+                    .is_some_and(|max| graph_in_shm.get_executing_node_count() >= max)
+                || graph_in_shm[node_index]
+                    .mutex()
+                    .is_some_and(|mutex| graph_in_shm.is_mutex_held(mutex))
+            {
+                return Ok(Some(graph_in_shm));
+            }
+            let before = graph_in_shm.clone();
+            graph_in_shm[node_index].execution_status = ExecutionStatus::Executing;
+            graph_in_shm.stamp_changes(&before);
+            self.write_graph_to_shm(&graph_in_shm)?;
+            Ok(None)
+        });
+
+        // Release write lock
+        self.write_unlock()?;
+
+        result
+    }
+}
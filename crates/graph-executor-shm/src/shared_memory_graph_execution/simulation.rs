@@ -0,0 +1,196 @@
+use anyhow::{anyhow, Result};
+use graph_executor_core::{
+    graph_structure::{
+        execution_record::ExecutionRecord, execution_status::ExecutionStatus,
+        graph::DirectedAcyclicGraph,
+    },
+    monotonic_timestamp::MonotonicTimestamp,
+    scheduler::{GraphView, Scheduler},
+    worker_info::WorkerInfo,
+};
+use petgraph::graph::NodeIndex;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+    fmt,
+    time::Duration,
+};
+
+/// Duration of nodes without an estimate.
+pub const DEFAULT_NODE_DURATION: Duration = Duration::from_secs(1);
+
+/// Result of a [`simulate`]d run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimulationReport {
+    /// Time from the start of the run until the last node finished.
+    pub makespan: Duration,
+    /// Time each worker spent executing nodes.
+    pub busy_per_worker: Vec<Duration>,
+    /// Identifiers of the executed nodes with their start time and worker, in the order they were started.
+    pub schedule: Vec<(String, Duration, usize)>,
+    /// Sum of the executed nodes' costs times the cost rate of the worker executing them.
+    pub total_cost: u64,
+}
+
+impl SimulationReport {
+    /// Fraction of the available worker time (worker count times makespan) spent executing nodes.
+    pub fn utilization(&self) -> f64 {
+        let available = self.makespan.as_secs_f64() * self.busy_per_worker.len() as f64;
+        match available > 0.0 {
+            true => {
+                self.busy_per_worker
+                    .iter()
+                    .map(Duration::as_secs_f64)
+                    .sum::<f64>()
+                    / available
+            }
+            false => 0.0,
+        }
+    }
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Makespan:    {:.3}s", self.makespan.as_secs_f64())?;
+        writeln!(f, "Utilization: {:.1}%", self.utilization() * 100.0)?;
+        writeln!(f, "Total cost:  {}", self.total_cost)?;
+        for (node_id, start, worker_id) in &self.schedule {
+            writeln!(
+                f,
+                "    {:>10.3}s  worker {}: {}",
+                start.as_secs_f64(),
+                worker_id,
+                node_id
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Simulates executing `graph` on `worker_count` workers with the nodes claimed by `scheduler`, without executing
+/// any node. Each node takes its estimate in `durations` (keyed by node identifier) or [`DEFAULT_NODE_DURATION`].
+pub fn simulate(
+    graph: &DirectedAcyclicGraph,
+    durations: &BTreeMap<String, Duration>,
+    worker_count: usize,
+    scheduler: &dyn Scheduler,
+) -> Result<SimulationReport> {
+    simulate_with_cost_rates(graph, durations, &vec![0; worker_count], scheduler)
+}
+
+/// Like [`simulate`], but with one worker per entry of `worker_cost_rates` executing nodes at that cost rate.
+pub fn simulate_with_cost_rates(
+    graph: &DirectedAcyclicGraph,
+    durations: &BTreeMap<String, Duration>,
+    worker_cost_rates: &[u64],
+    scheduler: &dyn Scheduler,
+) -> Result<SimulationReport> {
+    let worker_count = worker_cost_rates.len();
+    if worker_count == 0 {
+        return Err(anyhow!("Simulation requires at least one worker."));
+    }
+    let mut graph = graph.clone();
+    let now = MonotonicTimestamp::now()?;
+    let mut workers: Vec<WorkerInfo> = (0..worker_count)
+        .map(|worker_id| WorkerInfo {
+            id: worker_id,
+            pid: worker_id as u32,
+            hostname: String::from("simulated"),
+            started_at: now.clone(),
+            capabilities: 0,
+            cost_rate: worker_cost_rates[worker_id],
+            current_node: None,
+            nodes_completed: 0,
+            cache_hits: 0,
+            cache_misses: 0,
+            last_heartbeat: now.clone(),
+            detached: false,
+        })
+        .collect();
+    let mut idle_workers: Vec<usize> = (0..worker_count).collect();
+    // Running nodes as (finish time, worker, node), earliest finish first
+    let mut running: BinaryHeap<Reverse<(Duration, usize, NodeIndex)>> = BinaryHeap::new();
+    let mut time = Duration::ZERO;
+    let mut report = SimulationReport {
+        makespan: Duration::ZERO,
+        busy_per_worker: vec![Duration::ZERO; worker_count],
+        schedule: vec![],
+        total_cost: 0,
+    };
+
+    loop {
+        graph.record_enqueue_times(time);
+        // Let every idle worker claim a node
+        let mut still_idle = vec![];
+        let mut idle_worker_count = idle_workers.len();
+        for worker_id in idle_workers.drain(..) {
+            match scheduler.pick(
+                &GraphView::new(&graph, time, idle_worker_count),
+                &workers[worker_id],
+            ) {
+                Some(node_index)
+                    if graph[node_index].execution_status == ExecutionStatus::Executable =>
+                {
+                    graph[node_index].execution_status = ExecutionStatus::Executing;
+                    let node_id = graph.node_id(node_index).to_string();
+                    let duration = durations
+                        .get(&node_id)
+                        .copied()
+                        .unwrap_or(DEFAULT_NODE_DURATION);
+                    report.busy_per_worker[worker_id] += duration;
+                    report.total_cost +=
+                        graph[node_index].cost().unwrap_or(0) * workers[worker_id].cost_rate;
+                    report.schedule.push((node_id, time, worker_id));
+                    running.push(Reverse((time + duration, worker_id, node_index)));
+                    idle_worker_count -= 1;
+                }
+                Some(node_index) => {
+                    return Err(anyhow!(
+                        "Scheduler picked node {} which is not executable but {}.",
+                        graph.node_id(node_index),
+                        graph[node_index].execution_status
+                    ))
+                }
+                None => still_idle.push(worker_id),
+            }
+        }
+        idle_workers = still_idle;
+
+        // Advance to the next node finishing
+        let Some(Reverse((finish, worker_id, node_index))) = running.pop() else {
+            if graph.is_graph_executed() {
+                report.makespan = time;
+                return Ok(report);
+            }
+            return Err(anyhow!(
+                "Simulation stalled at {:.3}s: no node is running and the scheduler picks no node.",
+                time.as_secs_f64()
+            ));
+        };
+        time = finish;
+        graph[node_index].execution_status = ExecutionStatus::Executed;
+        // Simulated nodes produce no output, the size recorded by a previous execution of the node is kept
+        graph[node_index].execution_record = Some(ExecutionRecord {
+            worker_id,
+            output_bytes: graph[node_index]
+                .execution_record()
+                .map_or(0, |record| record.output_bytes),
+            finished_after: time,
+        });
+        workers[worker_id].nodes_completed += 1;
+        idle_workers.push(worker_id);
+
+        // Children whose parents are all executed become executable
+        let children: Vec<NodeIndex> = graph.get_child_node_indices(node_index).collect();
+        for child_index in children {
+            if graph
+                .get_parent_node_indices(child_index)
+                .all(|parent_index| {
+                    graph[parent_index].execution_status == ExecutionStatus::Executed
+                })
+            {
+                graph[child_index].execution_status = ExecutionStatus::Executable;
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use super::execution_report::ExecutionReport;
+use anyhow::{anyhow, Result};
+use graph_executor_core::graph_structure::{attempt::AttemptOutcome, graph::DirectedAcyclicGraph};
+use rusqlite::{params, Connection, OpenFlags};
+use std::{
+    path::Path,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Version of the database schema, stored as the database's `user_version`. Columns and tables are only ever
+/// added, so that queries written against an older schema keep working.
+///
+/// - Version 2 added the `attempts` table.
+/// - Version 3 added the `hook` column of the `attempts` table.
+pub const SCHEMA_VERSION: i32 = 3;
+
+/// Schema of the database: one row per exported run, its nodes (with their results and timings), their attempts
+/// and edges.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    run_id          INTEGER PRIMARY KEY,
+    filename_suffix TEXT NOT NULL,
+    exported_at     INTEGER NOT NULL, -- Unix time in seconds
+    hostname        TEXT NOT NULL,
+    creator_pid     INTEGER NOT NULL,
+    binary_version  TEXT NOT NULL,
+    protocol        TEXT NOT NULL,
+    graph_hash      TEXT NOT NULL,
+    graph_source    TEXT,
+    makespan_ms     INTEGER NOT NULL,
+    shm_bytes       INTEGER NOT NULL,
+    peak_shm_bytes  INTEGER NOT NULL,
+    disk_bytes      INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS nodes (
+    run_id            INTEGER NOT NULL REFERENCES runs (run_id),
+    node_id           TEXT NOT NULL,
+    args              TEXT NOT NULL,
+    command           TEXT,
+    priority          INTEGER NOT NULL,
+    cost              INTEGER,
+    gang              TEXT,
+    execution_status  TEXT NOT NULL,
+    worker_id         INTEGER,
+    output_bytes      INTEGER,
+    finished_after_ms INTEGER,
+    shm_bytes         INTEGER NOT NULL,
+    disk_bytes        INTEGER NOT NULL,
+    PRIMARY KEY (run_id, node_id)
+);
+CREATE TABLE IF NOT EXISTS attempts (
+    run_id            INTEGER NOT NULL REFERENCES runs (run_id),
+    node_id           TEXT NOT NULL,
+    attempt           INTEGER NOT NULL, -- 1 for the first attempt
+    worker_id         INTEGER NOT NULL,
+    started_after_ms  INTEGER NOT NULL,
+    finished_after_ms INTEGER NOT NULL,
+    outcome           TEXT NOT NULL,
+    error             TEXT,
+    hook              TEXT, -- `pre` or `post` if a hook failed the attempt
+    PRIMARY KEY (run_id, node_id, attempt)
+);
+CREATE TABLE IF NOT EXISTS edges (
+    run_id    INTEGER NOT NULL REFERENCES runs (run_id),
+    parent_id TEXT NOT NULL,
+    child_id  TEXT NOT NULL,
+    PRIMARY KEY (run_id, parent_id, child_id)
+);
+";
+
+/// Writes the run with `filename_suffix`, i.e. its executed `graph` and `report`, into the SQLite database at
+/// `database_path` (created if necessary) and returns the run's `run_id`.
+pub fn export_run(
+    database_path: &Path,
+    filename_suffix: &str,
+    graph: &DirectedAcyclicGraph,
+    report: &ExecutionReport,
+) -> Result<i64> {
+    let mut connection = Connection::open(database_path)?;
+    let user_version: i32 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if user_version > SCHEMA_VERSION {
+        return Err(anyhow!(
+            "Database {} has schema version {}, this build only knows version {}.",
+            database_path.display(),
+            user_version,
+            SCHEMA_VERSION
+        ));
+    }
+
+    let transaction = connection.transaction()?;
+    transaction.execute_batch(SCHEMA)?;
+    // Tables created by version 2 lack the `hook` column
+    let has_hook_column: bool = transaction.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('attempts') WHERE name = 'hook'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_hook_column {
+        transaction.execute("ALTER TABLE attempts ADD COLUMN hook TEXT", [])?;
+    }
+    transaction.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+
+    let header = &report.header;
+    let usage = &report.resource_usage;
+    let makespan = graph
+        .get_node_indices()
+        .filter_map(|i| graph[i].execution_record().map(|r| r.finished_after))
+        .max()
+        .unwrap_or_default();
+    transaction.execute(
+        "INSERT INTO runs (filename_suffix, exported_at, hostname, creator_pid, binary_version, protocol, \
+         graph_hash, graph_source, makespan_ms, shm_bytes, peak_shm_bytes, disk_bytes) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            filename_suffix,
+            SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+            header.hostname,
+            header.creator_pid,
+            header.binary_version,
+            header.protocol.to_string(),
+            header.graph_hash,
+            header.graph_source,
+            makespan.as_millis() as i64,
+            usage.shm_bytes as i64,
+            usage.peak_shm_bytes as i64,
+            usage.disk_bytes as i64,
+        ],
+    )?;
+    let run_id = transaction.last_insert_rowid();
+
+    for i in graph.get_node_indices() {
+        let node = &graph[i];
+        let node_id = graph.node_id(i);
+        let node_usage = usage.per_node.get(node_id).cloned().unwrap_or_default();
+        let record = node.execution_record();
+        transaction.execute(
+            "INSERT INTO nodes (run_id, node_id, args, command, priority, cost, gang, execution_status, \
+             worker_id, output_bytes, finished_after_ms, shm_bytes, disk_bytes) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                run_id,
+                node_id,
+                node.args(),
+                node.command(),
+                node.priority(),
+                node.cost().map(|cost| cost as i64),
+                node.gang(),
+                node.execution_status.to_string(),
+                record.map(|r| r.worker_id as i64),
+                record.map(|r| r.output_bytes as i64),
+                record.map(|r| r.finished_after.as_millis() as i64),
+                node_usage.shm_bytes as i64,
+                node_usage.disk_bytes as i64,
+            ],
+        )?;
+        for (attempt_number, attempt) in node.attempts().iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO attempts (run_id, node_id, attempt, worker_id, started_after_ms, finished_after_ms, \
+                 outcome, error, hook) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    run_id,
+                    node_id,
+                    attempt_number as i64 + 1,
+                    attempt.worker_id as i64,
+                    attempt.started_after.as_millis() as i64,
+                    attempt.finished_after.as_millis() as i64,
+                    attempt.outcome.to_string(),
+                    attempt.error,
+                    attempt.hook.map(|hook| hook.to_string()),
+                ],
+            )?;
+        }
+        for child_index in graph.get_child_node_indices(i) {
+            transaction.execute(
+                "INSERT INTO edges (run_id, parent_id, child_id) VALUES (?1, ?2, ?3)",
+                params![run_id, node_id, graph.node_id(child_index)],
+            )?;
+        }
+    }
+
+    transaction.commit()?;
+    Ok(run_id)
+}
+
+/// Reads the node identifiers and outcomes of all attempts of all runs exported into the SQLite database at
+/// `database_path`, e.g. for a [`super::flakiness::QuarantineReport`].
+pub fn attempt_outcomes(database_path: &Path) -> Result<Vec<(String, AttemptOutcome)>> {
+    let connection = Connection::open_with_flags(database_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let user_version: i32 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    // Attempts are exported since schema version 2
+    if user_version < 2 {
+        return Ok(vec![]);
+    }
+
+    let mut statement = connection.prepare("SELECT node_id, outcome FROM attempts")?;
+    let rows = statement.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+    let mut attempt_outcomes = vec![];
+    for row in rows {
+        let (node_id, outcome) = row?;
+        attempt_outcomes.push((node_id, AttemptOutcome::from_str(&outcome)?));
+    }
+    Ok(attempt_outcomes)
+}
@@ -0,0 +1,139 @@
+use anyhow::{anyhow, Result};
+use std::{
+    os::{
+        linux::net::SocketAddrExt,
+        unix::net::{SocketAddr, UnixDatagram},
+    },
+    time::Duration,
+};
+
+/// Sends `state` (e.g. `READY=1` or `WATCHDOG=1`) to systemd if the process runs as a `Type=notify` service,
+/// returning whether it did. See `sd_notify(3)`.
+pub fn notify(state: &str) -> Result<bool> {
+    match std::env::var("NOTIFY_SOCKET") {
+        Ok(socket) if !socket.is_empty() => notify_socket(&socket, state).map(|()| true),
+        _ => Ok(false),
+    }
+}
+
+/// Sends `state` to the notification socket at `socket`, which is in the abstract namespace if it starts with `@`.
+pub(crate) fn notify_socket(socket: &str, state: &str) -> Result<()> {
+    let address = match socket.strip_prefix('@') {
+        Some(name) => SocketAddr::from_abstract_name(name.as_bytes())?,
+        None => SocketAddr::from_pathname(socket)?,
+    };
+    UnixDatagram::unbound()?
+        .send_to_addr(state.as_bytes(), &address)
+        .map_err(|e| anyhow!("Failed notifying systemd via {}: {}", socket, e))?;
+    Ok(())
+}
+
+/// Generator of systemd units running a fleet of workers and a [`super::watchdog::watch`]dog for one run. The
+/// units are grouped by a target, so that the whole fleet is started and stopped together:
+///
+/// - `graph-executor-<suffix>-worker@.service` is instantiated once per worker and restarted if it crashes or
+///   stops notifying systemd's watchdog, but not if the run failed.
+/// - `graph-executor-<suffix>-watchdog.service` supervises the run once the first worker created it and removes
+///   its shared memory once stopped.
+/// - `graph-executor-<suffix>.target` wants all workers and the watchdog.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SystemdUnits {
+    /// Absolute path of the graph executor binary.
+    pub binary: String,
+    /// Absolute path of the executed graph's file.
+    pub digraph_file: String,
+    /// Filename suffix identifying the run.
+    pub filename_suffix: String,
+    /// Number of worker instances wanted by the target.
+    pub worker_count: usize,
+    /// Additional arguments of the workers, e.g. `--keep-going`.
+    pub worker_args: Vec<String>,
+    /// Time after which systemd restarts a worker or the watchdog which stopped notifying it.
+    pub watchdog_sec: Duration,
+}
+
+impl SystemdUnits {
+    /// Name of the target starting and stopping the whole fleet.
+    pub fn target_name(&self) -> String {
+        format!("graph-executor-{}.target", self.filename_suffix)
+    }
+
+    /// Unit files as pairs of file name and content.
+    pub fn unit_files(&self) -> Vec<(String, String)> {
+        let prefix = format!("graph-executor-{}", self.filename_suffix);
+        let target = self.target_name();
+        let watchdog_sec = self.watchdog_sec.as_secs().max(1);
+        let worker_command: Vec<String> = [&self.binary, &self.digraph_file, &self.filename_suffix]
+            .into_iter()
+            .chain(&self.worker_args)
+            .map(|arg| quote(arg))
+            .collect();
+        let watchdog_command = [&self.binary, "watchdog", &self.filename_suffix].map(quote);
+        let teardown_command = [&self.binary, "teardown", &self.filename_suffix].map(quote);
+        let workers: Vec<String> = (1..=self.worker_count)
+            .map(|i| format!("{}-worker@{}.service", prefix, i))
+            .collect();
+
+        vec![
+            (
+                format!("{}-worker@.service", prefix),
+                format!(
+                    "[Unit]\n\
+                     Description=graph-executor worker %i of run {suffix}\n\
+                     PartOf={target}\n\
+                     \n\
+                     [Service]\n\
+                     Type=notify\n\
+                     ExecStart={command}\n\
+                     WatchdogSec={watchdog_sec}\n\
+                     Restart=on-abnormal\n",
+                    suffix = self.filename_suffix,
+                    command = worker_command.join(" "),
+                ),
+            ),
+            (
+                format!("{}-watchdog.service", prefix),
+                format!(
+                    "[Unit]\n\
+                     Description=graph-executor watchdog of run {suffix}\n\
+                     PartOf={target}\n\
+                     After={prefix}-worker@1.service\n\
+                     \n\
+                     [Service]\n\
+                     Type=notify\n\
+                     ExecStart={command}\n\
+                     ExecStopPost={teardown}\n\
+                     WatchdogSec={watchdog_sec}\n\
+                     Restart=on-abnormal\n",
+                    suffix = self.filename_suffix,
+                    command = watchdog_command.join(" "),
+                    teardown = teardown_command.join(" "),
+                ),
+            ),
+            (
+                target.clone(),
+                format!(
+                    "[Unit]\n\
+                     Description=graph-executor fleet of run {suffix}\n\
+                     Wants={prefix}-watchdog.service {workers}\n\
+                     \n\
+                     [Install]\n\
+                     WantedBy=multi-user.target\n",
+                    suffix = self.filename_suffix,
+                    workers = workers.join(" "),
+                ),
+            ),
+        ]
+    }
+}
+
+/// Quotes `arg` for a unit file's command line, escaping systemd's specifiers and variables.
+fn quote(arg: &str) -> String {
+    format!(
+        "\"{}\"",
+        arg.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('%', "%%")
+            .replace('$', "$$")
+    )
+}
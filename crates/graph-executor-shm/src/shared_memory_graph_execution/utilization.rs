@@ -0,0 +1,104 @@
+use graph_executor_core::graph_structure::graph::DirectedAcyclicGraph;
+use std::{fmt, time::Duration};
+
+/// Characters of a sparkline from no to full utilization.
+const SPARKLINE_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+/// Number of characters of the sparkline printed by [`UtilizationTimeline`]'s `Display`.
+pub const DEFAULT_SPARKLINE_WIDTH: usize = 60;
+
+/// Number of executing nodes over the time of a run compared to its available workers, computed from the recorded
+/// attempts of its nodes. Shows where the shape of the graph limits parallelism.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UtilizationTimeline {
+    /// Points in time after the start of the run at which the number of executing nodes changed, with the number
+    /// of nodes executing from then on. Ordered by time, the last step is at the end of the last attempt.
+    pub steps: Vec<(Duration, usize)>,
+    /// Number of workers which attached to the run.
+    pub worker_count: usize,
+}
+
+impl UtilizationTimeline {
+    /// Computes the timeline of the executed `graph` from the attempts of its nodes.
+    pub fn new(graph: &DirectedAcyclicGraph, worker_count: usize) -> Self {
+        // Attempts start (+1) and end (-1), ends before starts at the same time
+        let mut events: Vec<(Duration, i64)> = graph
+            .get_node_indices()
+            .flat_map(|i| graph[i].attempts())
+            .flat_map(|attempt| [(attempt.started_after, 1), (attempt.finished_after, -1)])
+            .collect();
+        events.sort();
+
+        let mut steps: Vec<(Duration, usize)> = vec![];
+        let mut executing: i64 = 0;
+        for (time, change) in events {
+            executing += change;
+            match steps.last_mut() {
+                Some((last_time, last_executing)) if *last_time == time => {
+                    *last_executing = executing.max(0) as usize
+                }
+                _ => steps.push((time, executing.max(0) as usize)),
+            }
+        }
+        UtilizationTimeline {
+            steps,
+            worker_count,
+        }
+    }
+
+    /// Time from the start of the run until the last attempt ended.
+    pub fn makespan(&self) -> Duration {
+        self.steps.last().map(|(time, _)| *time).unwrap_or_default()
+    }
+
+    /// Average number of nodes executing between `from` and `to`.
+    pub fn average_executing_between(&self, from: Duration, to: Duration) -> f64 {
+        if to <= from {
+            return 0.0;
+        }
+        let mut area = 0.0;
+        for (step, next) in self.steps.iter().zip(self.steps.iter().skip(1)) {
+            let (start, end) = (step.0.max(from), next.0.min(to));
+            if start < end {
+                area += (end - start).as_secs_f64() * step.1 as f64;
+            }
+        }
+        area / (to - from).as_secs_f64()
+    }
+
+    /// Average number of nodes executing over the whole run.
+    pub fn average_executing(&self) -> f64 {
+        self.average_executing_between(Duration::ZERO, self.makespan())
+    }
+
+    /// Sparkline of `width` characters, each showing the average number of executing nodes during its share of
+    /// the run relative to the number of workers.
+    pub fn sparkline(&self, width: usize) -> String {
+        let makespan = self.makespan();
+        if makespan.is_zero() || width == 0 {
+            return String::new();
+        }
+        let capacity = self.worker_count.max(1) as f64;
+        (0..width)
+            .map(|bucket| {
+                let from = makespan.mul_f64(bucket as f64 / width as f64);
+                let to = makespan.mul_f64((bucket + 1) as f64 / width as f64);
+                let utilization = (self.average_executing_between(from, to) / capacity).min(1.0);
+                SPARKLINE_LEVELS
+                    [(utilization * (SPARKLINE_LEVELS.len() - 1) as f64).round() as usize]
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for UtilizationTimeline {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Utilization: {} {:.1} of {} workers executing on average over {:.3}s",
+            self.sparkline(DEFAULT_SPARKLINE_WIDTH),
+            self.average_executing(),
+            self.worker_count,
+            self.makespan().as_secs_f64()
+        )
+    }
+}
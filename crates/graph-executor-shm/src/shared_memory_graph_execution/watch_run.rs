@@ -0,0 +1,243 @@
+use super::{
+    execute_graph::ExecuteGraph, execution_options::ExecutionOptions,
+    execution_report::ExecutionReport,
+};
+use anyhow::{anyhow, Result};
+use graph_executor_core::graph_structure::{
+    execution_status::ExecutionStatus, graph::DirectedAcyclicGraph,
+};
+use petgraph::graph::NodeIndex;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::{CString, OsStr},
+    mem::size_of,
+    ops::ControlFlow,
+    os::{
+        fd::{AsRawFd, FromRawFd, OwnedFd},
+        unix::ffi::OsStrExt,
+    },
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Time without further changes after which changed files are considered saved, e.g. by editors writing a file in
+/// several steps.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Events of a watched directory which change the files in it.
+const WATCH_MASK: u32 =
+    libc::IN_CLOSE_WRITE | libc::IN_MOVED_TO | libc::IN_CREATE | libc::IN_DELETE;
+
+/// Watches files for changes using inotify. Files are watched through their parent directories, so that they may
+/// be created after the watcher or replaced by editors renaming a temporary file.
+pub struct FileWatcher {
+    /// inotify instance, read without blocking.
+    inotify: OwnedFd,
+    /// Watched directories by their watch descriptor.
+    directories: BTreeMap<i32, PathBuf>,
+    /// Watched files, see [`watched_path`].
+    paths: BTreeSet<PathBuf>,
+}
+
+impl FileWatcher {
+    /// Creates a [`FileWatcher`] watching `paths`, whose parent directories must exist.
+    pub fn new<'a>(paths: impl IntoIterator<Item = &'a str>) -> Result<Self> {
+        // SAFETY: `inotify_init1` takes no pointers, the returned descriptor is owned by the `FileWatcher`.
+        let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC | libc::IN_NONBLOCK) };
+        if fd == -1 {
+            return Err(anyhow!(
+                "Failed initializing inotify: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        let mut watcher = FileWatcher {
+            // SAFETY: `fd` is a valid descriptor which is not owned elsewhere.
+            inotify: unsafe { OwnedFd::from_raw_fd(fd) },
+            directories: BTreeMap::new(),
+            paths: BTreeSet::new(),
+        };
+        for path in paths {
+            watcher.add(path)?;
+        }
+        Ok(watcher)
+    }
+
+    /// Watches `path` and, unless already watched, its parent directory.
+    fn add(&mut self, path: &str) -> Result<()> {
+        let path = watched_path(path)?;
+        let directory = path.parent().unwrap_or(Path::new("/"));
+        if !self.directories.values().any(|d| d == directory) {
+            let c_directory = CString::new(directory.as_os_str().as_bytes())?;
+            // SAFETY: `c_directory` is a valid C string for the duration of the call.
+            let wd = unsafe {
+                libc::inotify_add_watch(self.inotify.as_raw_fd(), c_directory.as_ptr(), WATCH_MASK)
+            };
+            if wd == -1 {
+                return Err(anyhow!(
+                    "Failed watching {}: {}",
+                    directory.display(),
+                    std::io::Error::last_os_error()
+                ));
+            }
+            self.directories.insert(wd, directory.to_path_buf());
+        }
+        self.paths.insert(path);
+        Ok(())
+    }
+
+    /// Blocks until watched files changed and no further change followed within `debounce`, and returns the
+    /// changed files.
+    pub fn wait(&mut self, debounce: Duration) -> Result<BTreeSet<PathBuf>> {
+        let mut changed = BTreeSet::new();
+        while changed.is_empty() {
+            self.poll(None)?;
+            self.read_events(&mut changed)?;
+        }
+        while self.poll(Some(debounce))? {
+            self.read_events(&mut changed)?;
+        }
+        Ok(changed)
+    }
+
+    /// Discards the changes which happened since the last call to [`FileWatcher::wait`].
+    pub fn discard_pending(&mut self) -> Result<()> {
+        self.read_events(&mut BTreeSet::new())
+    }
+
+    /// Waits up to `timeout` (forever if [`None`]) for events, returns whether there are any.
+    fn poll(&self, timeout: Option<Duration>) -> Result<bool> {
+        let mut pollfd = libc::pollfd {
+            fd: self.inotify.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let timeout = timeout.map_or(-1, |timeout| {
+            timeout.as_millis().min(i32::MAX as u128) as i32
+        });
+        loop {
+            // SAFETY: `pollfd` is a valid `pollfd` for the duration of the call.
+            let ready = unsafe { libc::poll(&mut pollfd, 1, timeout) };
+            if ready != -1 {
+                return Ok(ready > 0);
+            }
+            let error = std::io::Error::last_os_error();
+            if error.kind() != std::io::ErrorKind::Interrupted {
+                return Err(anyhow!("Failed waiting for file changes: {}", error));
+            }
+        }
+    }
+
+    /// Reads all pending events and adds the watched files they changed to `changed`.
+    fn read_events(&mut self, changed: &mut BTreeSet<PathBuf>) -> Result<()> {
+        let mut buffer =
+            [0u8; 64 * (size_of::<libc::inotify_event>() + libc::NAME_MAX as usize + 1)];
+        loop {
+            // SAFETY: `buffer` is valid for writes of its length.
+            let length = unsafe {
+                libc::read(
+                    self.inotify.as_raw_fd(),
+                    buffer.as_mut_ptr().cast(),
+                    buffer.len(),
+                )
+            };
+            if length == -1 {
+                let error = std::io::Error::last_os_error();
+                match error.kind() {
+                    std::io::ErrorKind::WouldBlock => return Ok(()),
+                    std::io::ErrorKind::Interrupted => continue,
+                    _ => return Err(anyhow!("Failed reading file changes: {}", error)),
+                }
+            }
+            let mut offset = 0;
+            while offset < length as usize {
+                // SAFETY: the kernel only returns whole events, each followed by its `len` bytes long name.
+                let event: libc::inotify_event =
+                    unsafe { std::ptr::read_unaligned(buffer[offset..].as_ptr().cast()) };
+                let name_start = offset + size_of::<libc::inotify_event>();
+                offset = name_start + event.len as usize;
+                // Without the events lost in an overflow, any watched file may have changed
+                if event.mask & libc::IN_Q_OVERFLOW != 0 {
+                    changed.extend(self.paths.iter().cloned());
+                    continue;
+                }
+                let name = &buffer[name_start..offset];
+                let name = &name[..name.iter().position(|b| *b == 0).unwrap_or(name.len())];
+                if let Some(directory) = self.directories.get(&event.wd) {
+                    let path = directory.join(OsStr::from_bytes(name));
+                    if self.paths.contains(&path) {
+                        changed.insert(path);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Path under which `path` is watched: its canonical parent directory joined with its file name, so that the file
+/// doesn't need to exist.
+pub fn watched_path(path: &str) -> Result<PathBuf> {
+    let path = Path::new(path);
+    let file_name = path
+        .file_name()
+        .ok_or(anyhow!("Cannot watch {}: not a file.", path.display()))?;
+    let directory = match path.parent() {
+        Some(directory) if !directory.as_os_str().is_empty() => directory,
+        _ => Path::new("."),
+    };
+    let directory = std::fs::canonicalize(directory)
+        .map_err(|e| anyhow!("Cannot watch {}: {}", path.display(), e))?;
+    Ok(directory.join(file_name))
+}
+
+/// Get the indices of the nodes reading any of the `changed` files, ordered by string identifier.
+pub fn input_node_indices(
+    graph: &DirectedAcyclicGraph,
+    changed: &BTreeSet<PathBuf>,
+) -> Result<Vec<NodeIndex>> {
+    let mut indices = vec![];
+    for i in graph.get_node_indices() {
+        for input in graph[i].inputs() {
+            if changed.contains(&watched_path(input)?) {
+                indices.push(i);
+                break;
+            }
+        }
+    }
+    Ok(indices)
+}
+
+/// Executes `graph` like [`ExecuteGraph::execute_with_options`] and re-executes it whenever files read by its
+/// nodes (see [`graph_executor_core::graph_structure::node::Node::inputs`]) change, making it an incremental task
+/// runner: only the nodes reading changed files, the nodes which didn't execute successfully and their descendants
+/// are reset (see [`DirectedAcyclicGraph::reset_nodes`]) and executed again.
+///
+/// `on_run` is called with the graph and the result of every run and decides whether to keep watching. Files
+/// changed while a run executes are attributed to the run's own commands and don't trigger another run.
+pub fn watch_run(
+    graph: &mut DirectedAcyclicGraph,
+    filename_suffix: &str,
+    options: &ExecutionOptions,
+    debounce: Duration,
+    mut on_run: impl FnMut(&DirectedAcyclicGraph, Result<ExecutionReport>) -> ControlFlow<()>,
+) -> Result<()> {
+    let inputs: Vec<String> = graph
+        .get_node_indices()
+        .flat_map(|i| graph[i].inputs().to_vec())
+        .collect();
+    let mut watcher = FileWatcher::new(inputs.iter().map(String::as_str))?;
+    loop {
+        let result = graph.execute_with_options(filename_suffix.to_string(), options);
+        if on_run(graph, result).is_break() {
+            return Ok(());
+        }
+        watcher.discard_pending()?;
+        let changed = watcher.wait(debounce)?;
+        let mut indices = input_node_indices(graph, &changed)?;
+        indices.extend(
+            graph
+                .get_node_indices()
+                .filter(|i| graph[*i].execution_status != ExecutionStatus::Executed),
+        );
+        graph.reset_nodes(indices);
+    }
+}
@@ -0,0 +1,342 @@
+use super::{
+    audit::AuditLog,
+    iceoryx, result_blob,
+    run_header::{hostname, RunHeader},
+    systemd,
+    worker_table::WorkerTable,
+};
+use crate::shared_memory::posix_shared_memory::PosixSharedMemory;
+use anyhow::{anyhow, Result};
+use graph_executor_core::{
+    clock::{Clock, SystemClock},
+    graph_structure::{
+        attempt::{Attempt, AttemptOutcome},
+        execution_status::ExecutionStatus,
+        graph::DirectedAcyclicGraph,
+    },
+    worker_info::WorkerInfo,
+};
+use std::{
+    fmt,
+    process::{Child, Command},
+    sync::Arc,
+    time::Duration,
+};
+
+/// Configuration of [`watch`].
+#[derive(Clone, Debug)]
+pub struct WatchdogOptions {
+    /// Time without a heartbeat after which a worker is considered dead. Heartbeats of the node a worker executes
+    /// count as its own, so that workers busy with long-running nodes are not. Workers on the watchdog's host whose
+    /// process no longer exists are considered dead immediately.
+    pub stale_after: Duration,
+    /// Time between two checks of the run.
+    pub poll_interval: Duration,
+    /// Shell command started for every dead worker to replace it, e.g. the command line of a worker.
+    pub restart_command: Option<String>,
+    /// Maximum number of workers restarted over the lifetime of the watchdog.
+    pub max_restarts: usize,
+    /// Clock timing the watchdog's checks, the staleness of heartbeats and the run's deadline, see
+    /// [`super::execution_options::ExecutionOptions::clock`].
+    pub clock: Arc<dyn Clock>,
+}
+
+impl Default for WatchdogOptions {
+    fn default() -> Self {
+        WatchdogOptions {
+            stale_after: Duration::from_secs(10),
+            poll_interval: Duration::from_secs(1),
+            restart_command: None,
+            max_restarts: 3,
+            clock: Arc::new(SystemClock),
+        }
+    }
+}
+
+/// Actions taken by [`watch`] while supervising a run.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WatchdogSummary {
+    /// Identifiers of the workers which were found dead and detached from the run.
+    pub dead_workers: Vec<usize>,
+    /// Identifiers of the nodes of dead workers which were requeued.
+    pub reclaimed_nodes: Vec<String>,
+    /// Number of workers restarted with [`WatchdogOptions::restart_command`].
+    pub restarts: usize,
+    /// Whether the watchdog cancelled the run's remaining nodes at its deadline.
+    pub cancelled_at_deadline: bool,
+    /// Whether the watchdog removed the run's shared memory mappings because no worker was left to do so.
+    pub cleaned_up: bool,
+}
+
+impl fmt::Display for WatchdogSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Dead workers: {:?}, reclaimed nodes: {:?}, restarts: {}",
+            self.dead_workers, self.reclaimed_nodes, self.restarts
+        )?;
+        if self.cancelled_at_deadline {
+            write!(f, ", cancelled at deadline")?;
+        }
+        if self.cleaned_up {
+            write!(f, ", cleaned up")?;
+        }
+        Ok(())
+    }
+}
+
+/// Supervises the run identified by `filename_suffix` from a separate process until it ends, so that workers only
+/// execute nodes: dead workers (see [`WatchdogOptions::stale_after`]) are detached from the run, their nodes
+/// requeued and the workers optionally restarted, the run's remaining nodes are cancelled at its deadline and,
+/// if the last worker died, the run's shared memory mappings are removed.
+pub fn watch(filename_suffix: &str, options: &WatchdogOptions) -> Result<WatchdogSummary> {
+    let mut summary = WatchdogSummary::default();
+    let mut restarted_workers: Vec<Child> = vec![];
+    let clock = options.clock.as_ref();
+    let mut last_restart: Option<Duration> = None;
+    // Whether all workers had already left the run at the previous check
+    let mut was_abandoned = false;
+
+    // Mappings are kept open and never own their shared memory objects, which are removed by the last worker or,
+    // once the watchdog took them over, when it returns.
+    let attach_error = |e| anyhow!("Failed attaching to run {}: {}", filename_suffix, e);
+    let (mut header_shm, header) = RunHeader::open(filename_suffix).map_err(attach_error)?;
+    let (mut graph_shm, _) =
+        PosixSharedMemory::open_graph(filename_suffix).map_err(attach_error)?;
+    let (mut workers_shm, _) =
+        PosixSharedMemory::open::<WorkerTable>(&WorkerTable::filename_suffix(filename_suffix))
+            .map_err(attach_error)?;
+    // Changes of the graph are audited like those of the workers, administrative actions explicitly
+    let audit_log = match &header.audit_log {
+        Some(path) => {
+            graph_shm.set_write_observer(Some(
+                AuditLog::open(path, filename_suffix)?.write_observer(),
+            ));
+            Some(AuditLog::open(path, filename_suffix)?)
+        }
+        None => None,
+    };
+    systemd::notify("READY=1")?;
+
+    loop {
+        systemd::notify("WATCHDOG=1")?;
+        restarted_workers.retain_mut(|child| matches!(child.try_wait(), Ok(None)));
+
+        // The run ended once its mappings were removed by the last worker
+        if RunHeader::open(filename_suffix).is_err() {
+            return Ok(summary);
+        }
+        let (graph, mut table) = match (graph_shm.read_graph(), workers_shm.read::<WorkerTable>()) {
+            (Ok(graph), Ok(table)) => (graph, table),
+            // The last worker removed the mappings since the check above
+            _ if RunHeader::open(filename_suffix).is_err() => return Ok(summary),
+            (graph, table) => (graph?, table?),
+        };
+
+        // Detach dead workers and requeue the nodes they were executing
+        let (dead_workers, reclaimed_nodes) = reclaim_dead_workers(
+            filename_suffix,
+            &header,
+            &graph,
+            &mut graph_shm,
+            &mut workers_shm,
+            &mut table,
+            options.stale_after,
+            clock,
+        )?;
+        graph_shm.set_ownership(false)?;
+        workers_shm.set_ownership(false)?;
+        summary.reclaimed_nodes.extend(reclaimed_nodes);
+        summary
+            .dead_workers
+            .extend(dead_workers.iter().map(|worker| worker.id));
+
+        // Replace dead workers
+        if let Some(restart_command) = &options.restart_command {
+            for _ in &dead_workers {
+                if summary.restarts >= options.max_restarts {
+                    break;
+                }
+                restarted_workers.push(
+                    Command::new("sh")
+                        .arg("-c")
+                        .arg(restart_command)
+                        .spawn()
+                        .map_err(|e| anyhow!("Failed restarting worker: {}", e))?,
+                );
+                summary.restarts += 1;
+                last_restart = Some(clock.now());
+            }
+        }
+
+        // Cancel the run at its deadline, its workers terminate their command nodes
+        if !summary.cancelled_at_deadline
+            && header.is_past_deadline(clock)?
+            && !graph.is_graph_finished()
+        {
+            if let Some(audit_log) = &audit_log {
+                audit_log.record("cancel", None, "reason=deadline")?;
+            }
+            graph_shm.shm_update_graph(|graph: &mut DirectedAcyclicGraph| graph.cancel())?;
+            graph_shm.set_ownership(false)?;
+            summary.cancelled_at_deadline = true;
+        }
+
+        // The last worker leaving the run removes its mappings, unless it died. Restarted workers get as long as
+        // dead workers to attach.
+        let is_abandoned =
+            !table.workers.is_empty() && table.workers.iter().all(|worker| worker.detached);
+        let is_restart_pending =
+            last_restart.is_some_and(|restart| clock.elapsed(restart) < options.stale_after);
+        if is_abandoned && !is_restart_pending && (!dead_workers.is_empty() || was_abandoned) {
+            if let Some(audit_log) = &audit_log {
+                audit_log.record("teardown", None, "reason=abandoned")?;
+            }
+            graph_shm.set_ownership(true)?;
+            header_shm.set_ownership(true)?;
+            workers_shm.set_ownership(true)?;
+            summary.cleaned_up = true;
+            return Ok(summary);
+        }
+        was_abandoned = is_abandoned;
+        clock.sleep(options.poll_interval);
+    }
+}
+
+/// Detaches the workers of `table` which are dead (see [`is_dead`]) from the run identified by `filename_suffix`
+/// and requeues the nodes they were executing as [`ExecutionStatus::Executable`], recording an
+/// [`AttemptOutcome::Abandoned`] attempt. Workers are rechecked under the write lock of the worker table in case
+/// they just sent a heartbeat, and nodes under the write lock of the graph in case they just finished. `table` is
+/// updated in place. Returns the dead workers as they were found and the identifiers of the requeued nodes.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn reclaim_dead_workers(
+    filename_suffix: &str,
+    header: &RunHeader,
+    graph: &DirectedAcyclicGraph,
+    graph_shm: &mut PosixSharedMemory,
+    workers_shm: &mut PosixSharedMemory,
+    table: &mut WorkerTable,
+    stale_after: Duration,
+    clock: &dyn Clock,
+) -> Result<(Vec<WorkerInfo>, Vec<String>)> {
+    let mut dead_workers: Vec<WorkerInfo> = vec![];
+    let mut reclaimed_nodes: Vec<String> = vec![];
+    if !table
+        .workers
+        .iter()
+        .any(|worker| is_dead(worker, graph, header, stale_after, clock))
+    {
+        return Ok((dead_workers, reclaimed_nodes));
+    }
+    *table = workers_shm.shm_update(|table: &mut WorkerTable| {
+        for worker in table.workers.iter_mut() {
+            if is_dead(worker, graph, header, stale_after, clock) {
+                dead_workers.push(worker.clone());
+                worker.detached = true;
+                worker.current_node = None;
+            }
+        }
+    })?;
+
+    if dead_workers
+        .iter()
+        .any(|worker| worker.current_node.is_some())
+    {
+        let finished_after = header.created_at.elapsed_on(clock)?;
+        graph_shm.shm_update_graph(|graph: &mut DirectedAcyclicGraph| {
+            for worker in &dead_workers {
+                let Some(i) = worker
+                    .current_node
+                    .as_deref()
+                    .and_then(|id| graph.node_index(id))
+                else {
+                    continue;
+                };
+                if graph[i].execution_status != ExecutionStatus::Executing {
+                    continue;
+                }
+                graph[i].execution_status = ExecutionStatus::Executable;
+                graph[i].heartbeat_after = None;
+                graph[i].attempts.push(Attempt {
+                    worker_id: worker.id,
+                    started_after: worker
+                        .last_heartbeat
+                        .duration_since(&header.created_at)
+                        .unwrap_or_default()
+                        .min(finished_after),
+                    finished_after,
+                    outcome: AttemptOutcome::Abandoned,
+                    error: Some(format!(
+                        "Worker {} (pid {} on {}) stopped sending heartbeats.",
+                        worker.id, worker.pid, worker.hostname
+                    )),
+                    hook: None,
+                });
+                reclaimed_nodes.push(graph.node_id(i).to_string());
+            }
+        })?;
+    }
+    if let Some(path) = &header.audit_log {
+        let audit_log = AuditLog::open(path, filename_suffix)?;
+        for worker in &dead_workers {
+            if let Some(node_id) = &worker.current_node {
+                let detail = format!("worker={} pid={}", worker.id, worker.pid);
+                audit_log.record("reclaim", Some(node_id), &detail)?;
+            }
+        }
+    }
+    Ok((dead_workers, reclaimed_nodes))
+}
+
+/// Checks whether `worker` is attached to the run but neither it nor the node of `graph` it executes (see
+/// [`DirectedAcyclicGraph::heartbeat`]) sent a heartbeat for `stale_after` on `clock` or, if it runs on this host,
+/// its process no longer exists.
+fn is_dead(
+    worker: &WorkerInfo,
+    graph: &DirectedAcyclicGraph,
+    header: &RunHeader,
+    stale_after: Duration,
+    clock: &dyn Clock,
+) -> bool {
+    if worker.detached {
+        return false;
+    }
+    let is_process_gone = hostname().is_ok_and(|hostname| hostname == worker.hostname)
+        && unsafe { libc::kill(worker.pid as libc::pid_t, 0) } == -1
+        && std::io::Error::last_os_error().raw_os_error() == Some(libc::ESRCH);
+    let is_node_alive = worker
+        .current_node
+        .as_deref()
+        .and_then(|id| graph.node_index(id))
+        .and_then(|i| graph[i].heartbeat_after)
+        .zip(header.created_at.elapsed_on(clock).ok())
+        .is_some_and(|(heartbeat_after, now)| now.saturating_sub(heartbeat_after) <= stale_after);
+    is_process_gone
+        || (!is_node_alive
+            && worker
+                .last_heartbeat
+                .elapsed_on(clock)
+                .map_or(true, |elapsed| elapsed > stale_after))
+}
+
+/// Removes the shared memory mappings of the run identified by `filename_suffix` regardless of its workers, e.g.
+/// once a service manager stopped all of them, and the iceoryx2 ports its workers left behind. Mappings which do not
+/// exist are skipped.
+pub fn teardown(filename_suffix: &str) -> Result<()> {
+    if let Ok((mut shared_memory, header)) = RunHeader::open(filename_suffix) {
+        if let Some(path) = &header.audit_log {
+            AuditLog::open(path, filename_suffix)?.record("teardown", None, "")?;
+        }
+        shared_memory.set_ownership(true)?;
+    }
+    if let Ok((mut shared_memory, _)) = PosixSharedMemory::open_graph(filename_suffix) {
+        shared_memory.set_ownership(true)?;
+    }
+    if let Ok((mut shared_memory, _)) =
+        PosixSharedMemory::open::<WorkerTable>(&WorkerTable::filename_suffix(filename_suffix))
+    {
+        shared_memory.set_ownership(true)?;
+    }
+    iceoryx::remove_dead_nodes();
+    result_blob::remove_results(filename_suffix)
+}
@@ -0,0 +1,75 @@
+use anyhow::{anyhow, Result};
+use petgraph::graph::NodeIndex;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Name of the environment variable holding the path of the working directory of a command node and its hooks, see
+/// [`super::execution_options::ExecutionOptions::workdir_root`].
+pub const WORKDIR_VARIABLE: &str = "GRAPH_EXECUTOR_WORKDIR";
+
+/// Isolated working directory of one attempt at executing a command node, removed once dropped unless it is kept.
+pub struct Workdir {
+    /// Path of the directory, below the configured root.
+    path: PathBuf,
+    /// Whether the directory is kept once dropped, e.g. for inspecting a failed attempt.
+    keep: bool,
+}
+
+impl Workdir {
+    /// Creates the empty working directory of the `attempt`-th attempt at executing the node at `node_index` in the
+    /// run identified by `filename_suffix`, below `root`. The directory is kept once dropped if `keep` is set.
+    pub(crate) fn create(
+        root: &Path,
+        filename_suffix: &str,
+        node_index: NodeIndex,
+        attempt: usize,
+        keep: bool,
+    ) -> Result<Self> {
+        let path = root.join(format!(
+            "graph-executor-{}-{}-{}",
+            filename_suffix,
+            node_index.index(),
+            attempt
+        ));
+        // Leftovers of a previous run with the same filename suffix
+        if path.exists() {
+            fs::remove_dir_all(&path)?;
+        }
+        fs::create_dir_all(&path).map_err(|e| {
+            anyhow!(
+                "Failed creating working directory {}: {}",
+                path.display(),
+                e
+            )
+        })?;
+        Ok(Workdir { path, keep })
+    }
+
+    /// Get the path of the working directory.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Runs `process` in the working directory, passing its path in [`WORKDIR_VARIABLE`].
+    pub(crate) fn apply(&self, process: &mut Command) {
+        process
+            .current_dir(&self.path)
+            .env(WORKDIR_VARIABLE, &self.path);
+    }
+
+    /// Keeps the working directory once dropped, e.g. because its attempt failed.
+    pub(crate) fn keep(&mut self) {
+        self.keep = true;
+    }
+}
+
+impl Drop for Workdir {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use super::{
+    lifecycle::{LifecycleEvent, LifecycleEvents},
+    protocol::SUPPORTED_FEATURES,
+    remote_cache::CacheStatistics,
+    resource_usage::{DiskFull, ResourceUsage},
+    run_header::{hostname, RunHeader},
+    systemd, watchdog,
+};
+use crate::shared_memory::posix_shared_memory::PosixSharedMemory;
+use anyhow::{anyhow, Result};
+use graph_executor_core::{
+    clock::Clock, graph_structure::graph::DirectedAcyclicGraph,
+    monotonic_timestamp::MonotonicTimestamp, worker_info::WorkerInfo,
+};
+use std::{fmt, sync::Arc, time::Duration};
+
+/// Minimum time between two heartbeats of an idle worker.
+pub(crate) const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Table of all workers which attached to a run, stored in its own shared memory mapping.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct WorkerTable {
+    /// Workers in the order they attached to the run.
+    pub workers: Vec<WorkerInfo>,
+    /// Bytes the run's workers wrote to disk, in total and per node. Shared memory bytes are only accounted once the
+    /// run finished, see [`super::execution_report::ExecutionReport::resource_usage`].
+    pub disk_usage: ResourceUsage,
+    /// Write which exceeded the run's disk cap, failing the run, see [`WorkerRegistration::record_disk_write`].
+    pub disk_full: Option<DiskFull>,
+}
+
+impl fmt::Display for WorkerTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for worker in &self.workers {
+            writeln!(f, "{}", worker)?;
+        }
+        Ok(())
+    }
+}
+
+impl WorkerTable {
+    /// Filename suffix of the worker table's shared memory mapping for the run with `filename_suffix`.
+    pub fn filename_suffix(filename_suffix: &str) -> String {
+        format!("{}_workers", filename_suffix)
+    }
+
+    /// Reads the worker table of the run identified by `filename_suffix` from shared memory.
+    pub fn read(filename_suffix: &str) -> Result<Self> {
+        let (_, table) =
+            PosixSharedMemory::open::<WorkerTable>(&WorkerTable::filename_suffix(filename_suffix))
+                .map_err(|e| anyhow!("Failed reading workers of run {}: {}", filename_suffix, e))?;
+        Ok(table)
+    }
+}
+
+/// Registration of the current worker in a run's [`WorkerTable`], used to report its progress.
+pub(crate) struct WorkerRegistration {
+    /// Shared memory mapping of the [`WorkerTable`].
+    shared_memory: PosixSharedMemory,
+    /// Index of this worker in [`WorkerTable::workers`].
+    worker_id: usize,
+    /// Local copy of this worker's entry.
+    info: WorkerInfo,
+    /// Filename suffix of the run the worker is registered in.
+    filename_suffix: String,
+    /// Bytes written to disk for the worker's current node which were not recorded in the worker table yet, see
+    /// [`WorkerRegistration::wrote_to_disk`].
+    unrecorded_disk_bytes: usize,
+    /// Clock timing the worker's heartbeats.
+    clock: Arc<dyn Clock>,
+    /// Lifecycle events of the run, published by its workers.
+    lifecycle: LifecycleEvents,
+}
+
+impl WorkerRegistration {
+    /// Adds the current worker with its `cost_rate` to the worker table of the run identified by `filename_suffix`,
+    /// timing its heartbeats on `clock`.
+    pub(crate) fn register(
+        filename_suffix: &str,
+        cost_rate: u64,
+        clock: Arc<dyn Clock>,
+    ) -> Result<Self> {
+        let now = MonotonicTimestamp::now_on(clock.as_ref())?;
+        let mut worker = WorkerInfo {
+            id: 0,
+            pid: std::process::id(),
+            hostname: hostname()?,
+            started_at: now.clone(),
+            capabilities: SUPPORTED_FEATURES,
+            cost_rate,
+            current_node: None,
+            nodes_completed: 0,
+            cache_hits: 0,
+            cache_misses: 0,
+            last_heartbeat: now.clone(),
+            detached: false,
+        };
+
+        let (mut shared_memory, _) = PosixSharedMemory::create_or_open(
+            &WorkerTable::filename_suffix(filename_suffix),
+            &WorkerTable::default(),
+            None,
+        )?;
+        // The worker's id is its index in the table, determined while holding the write lock
+        let table = shared_memory.shm_update(|table: &mut WorkerTable| {
+            worker.id = table.workers.len();
+            table.workers.push(worker.clone())
+        })?;
+        let info = table
+            .workers
+            .last()
+            .filter(|w| w.id == worker.id)
+            .cloned()
+            .ok_or(anyhow!("Worker was not added to the worker table."))?;
+        let lifecycle = LifecycleEvents::create_or_open(filename_suffix)?;
+        if info.id == 0 {
+            lifecycle.publish(LifecycleEvent::RunStarted)?;
+        }
+
+        Ok(WorkerRegistration {
+            shared_memory,
+            worker_id: info.id,
+            info,
+            filename_suffix: filename_suffix.to_string(),
+            unrecorded_disk_bytes: 0,
+            clock,
+            lifecycle,
+        })
+    }
+
+    /// This worker's entry in the worker table.
+    pub(crate) fn info(&self) -> &WorkerInfo {
+        &self.info
+    }
+
+    /// Filename suffix of the run the worker is registered in.
+    pub(crate) fn filename_suffix(&self) -> &str {
+        &self.filename_suffix
+    }
+
+    /// Clock timing the worker, see [`super::execution_options::ExecutionOptions::clock`].
+    pub(crate) fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    /// Clock timing the worker, for threads watching its nodes on its behalf.
+    pub(crate) fn shared_clock(&self) -> Arc<dyn Clock> {
+        Arc::clone(&self.clock)
+    }
+
+    /// Publishes `event` to the listeners of the run's lifecycle events, see [`LifecycleEvents`].
+    pub(crate) fn publish(&self, event: LifecycleEvent) -> Result<()> {
+        self.lifecycle.publish(event)
+    }
+
+    /// Records that the worker completed its current node.
+    pub(crate) fn node_completed(&mut self) -> Result<()> {
+        self.update(|worker| {
+            worker.nodes_completed += 1;
+            worker.current_node = None;
+        })
+    }
+
+    /// Records a lookup of a node in the run's remote cache, see [`super::remote_cache`].
+    pub(crate) fn cache_lookup(&mut self, hit: bool) -> Result<()> {
+        self.update(|worker| match hit {
+            true => worker.cache_hits += 1,
+            false => worker.cache_misses += 1,
+        })
+    }
+
+    /// Records the node the worker is executing, [`None`] if it is idle.
+    pub(crate) fn set_current_node(&mut self, node_id: Option<&str>) -> Result<()> {
+        self.update(|worker| worker.current_node = node_id.map(str::to_string))
+    }
+
+    /// Marks the worker as having left the run and returns whether it was the last attached worker, which
+    /// is then responsible for removing the run's shared memory mappings.
+    pub(crate) fn detach(&mut self) -> Result<bool> {
+        self.update(|worker| worker.detached = true)?;
+        systemd::notify("STOPPING=1")?;
+        let table = self.shared_memory.read::<WorkerTable>()?;
+        Ok(table.workers.iter().all(|worker| worker.detached))
+    }
+
+    /// Sets whether the worker table's shared memory mapping is removed when this registration is dropped.
+    pub(crate) fn set_ownership(&mut self, owner: bool) -> Result<()> {
+        self.shared_memory.set_ownership(owner)
+    }
+
+    /// Number of workers of the run which are not executing a node.
+    ///
+    /// Workers which terminated without executing all nodes are counted as idle.
+    pub(crate) fn idle_worker_count(&mut self) -> Result<usize> {
+        let table = self.shared_memory.read::<WorkerTable>()?;
+        Ok(table
+            .workers
+            .iter()
+            .filter(|worker| worker.current_node.is_none() && !worker.detached)
+            .count())
+    }
+
+    /// Number of workers which attached to the run, including those which already left it.
+    pub(crate) fn worker_count(&mut self) -> Result<usize> {
+        Ok(self.shared_memory.read::<WorkerTable>()?.workers.len())
+    }
+
+    /// Adds `bytes` written to disk for the worker's current node, which are recorded in the run's disk usage with
+    /// the node's next [`WorkerRegistration::record_disk_write`].
+    pub(crate) fn wrote_to_disk(&mut self, bytes: usize) {
+        self.unrecorded_disk_bytes += bytes;
+    }
+
+    /// Records `bytes` and the bytes added by [`WorkerRegistration::wrote_to_disk`] as written to disk on behalf of
+    /// the node `node_id` in the run's disk usage. If this would exceed `max_disk_bytes`, they are not recorded and
+    /// the [`DiskFull`] error is kept in the worker table and returned.
+    pub(crate) fn record_disk_write(
+        &mut self,
+        node_id: &str,
+        bytes: usize,
+        max_disk_bytes: Option<usize>,
+    ) -> Result<()> {
+        let bytes = bytes + std::mem::take(&mut self.unrecorded_disk_bytes);
+        let mut result = Ok(());
+        self.shared_memory.shm_update(|table: &mut WorkerTable| {
+            result = table
+                .disk_usage
+                .record_disk_write(node_id, bytes, max_disk_bytes);
+            if let Err(disk_full) = &result {
+                table.disk_full.get_or_insert(disk_full.clone());
+            }
+        })?;
+        Ok(result?)
+    }
+
+    /// Bytes written to disk by all workers of the run and the write which exceeded its disk cap, if any.
+    pub(crate) fn disk_usage(&mut self) -> Result<(ResourceUsage, Option<DiskFull>)> {
+        let table = self.shared_memory.read::<WorkerTable>()?;
+        Ok((table.disk_usage, table.disk_full))
+    }
+
+    /// Lookups of all workers of the run, including those which already left it, in its remote cache.
+    pub(crate) fn cache_statistics(&mut self) -> Result<CacheStatistics> {
+        Ok(CacheStatistics::from_workers(
+            &self.shared_memory.read::<WorkerTable>()?,
+        ))
+    }
+
+    /// Detaches the other workers of the run which sent no heartbeat for `stale_after` or whose process died, and
+    /// requeues the nodes of `graph` they were executing in its mapping `graph_shm` like the watchdog, see
+    /// [`watchdog::watch`]. Returns the identifiers of the requeued nodes.
+    pub(crate) fn reclaim_dead_workers(
+        &mut self,
+        header: &RunHeader,
+        graph: &DirectedAcyclicGraph,
+        graph_shm: &mut PosixSharedMemory,
+        stale_after: Duration,
+    ) -> Result<Vec<String>> {
+        let mut table = self.shared_memory.read::<WorkerTable>()?;
+        let (_, reclaimed_nodes) = watchdog::reclaim_dead_workers(
+            &self.filename_suffix,
+            header,
+            graph,
+            graph_shm,
+            &mut self.shared_memory,
+            &mut table,
+            stale_after,
+            self.clock.as_ref(),
+        )?;
+        Ok(reclaimed_nodes)
+    }
+
+    /// Reports that the worker is alive, if the last heartbeat is older than [`HEARTBEAT_INTERVAL`].
+    pub(crate) fn heartbeat(&mut self) -> Result<()> {
+        match self.info.last_heartbeat.elapsed_on(self.clock.as_ref()) {
+            Ok(elapsed) if elapsed < HEARTBEAT_INTERVAL => Ok(()),
+            _ => self.update(|_| ()),
+        }
+    }
+
+    /// Applies `update` to this worker's entry and refreshes its heartbeat, also towards systemd's watchdog if the
+    /// worker runs as a service.
+    fn update(&mut self, update: impl FnOnce(&mut WorkerInfo)) -> Result<()> {
+        update(&mut self.info);
+        self.info.last_heartbeat = MonotonicTimestamp::now_on(self.clock.as_ref())?;
+        let (worker_id, info) = (self.worker_id, &self.info);
+        self.shared_memory.shm_update(|table: &mut WorkerTable| {
+            if let Some(worker) = table.workers.get_mut(worker_id) {
+                *worker = info.clone();
+            }
+        })?;
+        systemd::notify("WATCHDOG=1")?;
+        Ok(())
+    }
+}